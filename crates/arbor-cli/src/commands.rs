@@ -1,18 +1,105 @@
 //! CLI command implementations.
 
+use crate::table;
 use arbor_graph::compute_centrality;
 use arbor_server::{ArborServer, ServerConfig};
-use arbor_watcher::{index_directory, IndexOptions};
+use arbor_watcher::{
+    dry_run_directory, find_project_root, git_churn, index_directory, index_file_list, FileChange,
+    FileWatcher, IndexOptions, LiveIndex,
+};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
 use std::path::Path;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+/// Direct callers at or above which a complex function is flagged as risky.
+const HIGH_FAN_IN: usize = 5;
+
+/// Ecosystem presets for `arbor init --template`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum InitTemplate {
+    /// TypeScript/JavaScript with npm build output ignored
+    Node,
+    /// Cargo projects
+    Rust,
+    /// Python with virtualenvs and caches ignored
+    Python,
+    /// Dart/Flutter with build and tool directories ignored
+    Flutter,
+}
+
+impl InitTemplate {
+    fn languages(self) -> &'static [&'static str] {
+        match self {
+            Self::Node => &["typescript", "javascript"],
+            Self::Rust => &["rust"],
+            Self::Python => &["python"],
+            Self::Flutter => &["dart"],
+        }
+    }
+
+    fn ignore(self) -> &'static [&'static str] {
+        match self {
+            Self::Node => &["node_modules/", "dist/", "build/", "coverage/", ".next/"],
+            Self::Rust => &["target/"],
+            Self::Python => &[
+                "__pycache__/",
+                ".venv/",
+                "venv/",
+                ".tox/",
+                "build/",
+                "dist/",
+            ],
+            Self::Flutter => &["build/", ".dart_tool/", ".fvm/", "ios/Pods/"],
+        }
+    }
+}
+
+/// Builds the initial `.arbor/config.json`.
+///
+/// With no template and no languages this is the long-standing default.
+/// Extra `languages` are appended to the template's own list.
+fn init_config(languages: &[String], template: Option<InitTemplate>) -> serde_json::Value {
+    let (mut langs, ignore): (Vec<String>, Vec<String>) = match template {
+        Some(template) => (
+            template.languages().iter().map(|s| s.to_string()).collect(),
+            template.ignore().iter().map(|s| s.to_string()).collect(),
+        ),
+        None if !languages.is_empty() => (
+            Vec::new(),
+            ["node_modules", "target", "dist", "__pycache__"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        ),
+        None => {
+            return serde_json::json!({
+                "version": "1.0",
+                "languages": ["typescript", "rust", "python"],
+                "ignore": ["node_modules", "target", "dist", "__pycache__"]
+            });
+        }
+    };
+
+    for language in languages {
+        let language = language.to_lowercase();
+        if !langs.contains(&language) {
+            langs.push(language);
+        }
+    }
+
+    serde_json::json!({
+        "version": "1.0",
+        "languages": langs,
+        "ignore": ignore
+    })
+}
+
 /// Initialize Arbor in a directory.
-pub fn init(path: &Path) -> Result<()> {
+pub fn init(path: &Path, languages: &[String], template: Option<InitTemplate>) -> Result<()> {
     let arbor_dir = path.join(".arbor");
 
     if arbor_dir.exists() {
@@ -22,15 +109,11 @@ pub fn init(path: &Path) -> Result<()> {
 
     fs::create_dir_all(&arbor_dir)?;
 
-    // Create a default config file
+    // Create the config file
     let config_path = arbor_dir.join("config.json");
-    let default_config = serde_json::json!({
-        "version": "1.0",
-        "languages": ["typescript", "rust", "python"],
-        "ignore": ["node_modules", "target", "dist", "__pycache__"]
-    });
+    let config = init_config(languages, template);
 
-    fs::write(&config_path, serde_json::to_string_pretty(&default_config)?)?;
+    fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
 
     println!("{} Initialized Arbor in {}", "✓".green(), path.display());
     println!("  Run {} to index your codebase", "arbor index".cyan());
@@ -39,12 +122,24 @@ pub fn init(path: &Path) -> Result<()> {
 }
 
 /// Index a directory and build the code graph.
+#[allow(clippy::too_many_arguments)]
 pub fn index(
     path: &Path,
     output: Option<&Path>,
     follow_symlinks: bool,
     no_cache: bool,
+    dry_run: bool,
+    no_vendored: bool,
+    jobs: Option<usize>,
+    profile: bool,
+    incremental: bool,
+    config_files: bool,
+    files_from: Option<&Path>,
 ) -> Result<()> {
+    if dry_run {
+        return index_dry_run(path, follow_symlinks, no_vendored, config_files);
+    }
+
     println!("{}", "Indexing codebase...".cyan());
 
     let spinner = ProgressBar::new_spinner();
@@ -62,8 +157,16 @@ pub fn index(
     let options = IndexOptions {
         follow_symlinks,
         cache_path,
+        skip_vendored: no_vendored,
+        threads: jobs,
+        // Centrality isn't needed here, but a profile should show its cost
+        compute_centrality: profile,
+        config_files,
+    };
+    let result = match files_from {
+        Some(list) => index_file_list(path, &read_file_list(list)?, options)?,
+        None => index_directory(path, options)?,
     };
-    let result = index_directory(path, options)?;
 
     spinner.finish_and_clear();
 
@@ -82,6 +185,13 @@ pub fn index(
         result.duration_ms
     );
 
+    if incremental {
+        print_cache_stats(&result);
+    }
+    if profile {
+        print_profile(&result);
+    }
+
     // Warn if graph is empty
     if result.nodes_extracted == 0 {
         eprintln!("\n{} No nodes extracted. Check:", "⚠ Warning:".yellow());
@@ -101,40 +211,206 @@ pub fn index(
         }
     }
 
+    // Files with syntax errors were indexed, but maybe not completely
+    if !result.partial_files.is_empty() {
+        println!(
+            "\n{} files with syntax errors (partially indexed):",
+            "⚠".yellow()
+        );
+        for (file, line) in result.partial_files.iter().take(5) {
+            println!("  {}:{}", file.yellow(), line);
+        }
+        if result.partial_files.len() > 5 {
+            println!("  ... and {} more", result.partial_files.len() - 5);
+        }
+    }
+
     // Export if requested
     if let Some(out_path) = output {
-        export_graph(&result.graph, out_path)?;
+        export_graph(&result.graph, out_path, true)?;
+    }
+
+    Ok(())
+}
+
+/// Reads newline-separated paths from a file, or from stdin for `-`.
+fn read_file_list(list: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let text = if list == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        fs::read_to_string(list)
+            .map_err(|e| format!("cannot read file list {}: {}", list.display(), e))?
+    };
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(std::path::PathBuf::from)
+        .collect())
+}
+
+/// Print what `index` would parse without parsing anything.
+fn index_dry_run(
+    path: &Path,
+    follow_symlinks: bool,
+    no_vendored: bool,
+    config_files: bool,
+) -> Result<()> {
+    let options = IndexOptions {
+        follow_symlinks,
+        cache_path: None,
+        skip_vendored: no_vendored,
+        config_files,
+        ..Default::default()
+    };
+    let result = dry_run_directory(path, options);
+
+    println!(
+        "{} Dry run: {} files would be indexed in {}",
+        "✓".green(),
+        result.total_files.to_string().cyan(),
+        path.display()
+    );
+
+    let mut languages: Vec<_> = result.files_by_language.iter().collect();
+    // Sort by count descending
+    languages.sort_by(|a, b| b.1.cmp(a.1));
+
+    for (language, count) in languages {
+        println!("  {} {}", format!("{}:", language).dimmed(), count);
     }
 
     Ok(())
 }
 
-fn export_graph(graph: &arbor_graph::ArborGraph, path: &Path) -> Result<()> {
+fn export_graph(graph: &arbor_graph::ArborGraph, path: &Path, pretty: bool) -> Result<()> {
+    fs::write(path, graph_export_string(graph, pretty)?)?;
+    println!("{} Exported to {}", "✓".green(), path.display());
+
+    Ok(())
+}
+
+/// Serializes the export document, indented for humans or compact for
+/// tools (about half the size on large graphs).
+fn graph_export_string(graph: &arbor_graph::ArborGraph, pretty: bool) -> Result<String> {
+    let export = graph_export_json(graph);
+    Ok(if pretty {
+        serde_json::to_string_pretty(&export)?
+    } else {
+        serde_json::to_string(&export)?
+    })
+}
+
+/// Builds the JSON document written by `export` and `index --output`.
+fn graph_export_json(graph: &arbor_graph::ArborGraph) -> serde_json::Value {
     let nodes: Vec<_> = graph.nodes().collect();
 
-    let export = serde_json::json!({
+    serde_json::json!({
         "version": "1.0",
         "stats": {
             "nodeCount": graph.node_count(),
             "edgeCount": graph.edge_count()
         },
         "nodes": nodes
-    });
+    })
+}
 
-    fs::write(path, serde_json::to_string_pretty(&export)?)?;
-    println!("{} Exported to {}", "✓".green(), path.display());
+/// Writes JSON to `path` via a temp file and rename.
+///
+/// The rename is atomic on the same filesystem, so a reader opening `path`
+/// sees either the previous document or the new one, never a partial write.
+fn write_json_atomic(path: &Path, value: &serde_json::Value) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("invalid output path: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    {
+        let mut file = fs::File::create(&tmp_path)?;
+        serde_json::to_writer_pretty(&mut file, value)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)?;
 
     Ok(())
 }
 
+/// Sort order for `arbor query` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum QuerySort {
+    /// Search-index order
+    Relevance,
+    /// Most central nodes first
+    Centrality,
+    /// Alphabetical by name
+    Name,
+    /// By file path, then line
+    #[value(alias = "file:line")]
+    File,
+}
+
+/// How to bucket `arbor query` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum QueryGroupBy {
+    /// One section per file
+    File,
+    /// One section per node kind
+    Kind,
+}
+
+/// Turns a `--timeout` in milliseconds into a deadline counted from now.
+///
+/// Only the graph traversal is bounded; indexing runs to completion first.
+fn deadline(timeout_ms: Option<u64>) -> Option<Instant> {
+    timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms))
+}
+
+/// Warns that `--timeout` cut an analysis short.
+fn warn_timed_out(timeout_ms: Option<u64>) {
+    eprintln!(
+        "\n{} Timed out after {} ms: results are partial",
+        "⚠".yellow(),
+        timeout_ms.unwrap_or_default()
+    );
+    eprintln!("  Use a longer --timeout or a smaller --depth for complete results.");
+}
+
+/// Returns `root` if given, else the project root above the current directory.
+fn project_root(root: Option<&Path>) -> Result<std::path::PathBuf> {
+    match root {
+        Some(root) => Ok(root.to_path_buf()),
+        None => Ok(find_project_root(&std::env::current_dir()?)),
+    }
+}
+
 /// Query the code graph.
-pub fn query(query: &str, limit: usize) -> Result<()> {
+pub fn query(
+    query: &str,
+    limit: usize,
+    sort: QuerySort,
+    group_by: Option<QueryGroupBy>,
+    json_output: bool,
+    root: Option<&Path>,
+) -> Result<()> {
     // For now, we need to re-index. In a real implementation,
     // we'd load from a persisted graph or connect to the server.
-    let path = std::env::current_dir()?;
+    let path = project_root(root)?;
     let result = index_directory(&path, IndexOptions::default())?;
+    let mut graph = result.graph;
+
+    // Centrality isn't computed during indexing
+    if sort == QuerySort::Centrality || json_output {
+        let scores = compute_centrality(&graph, 20, 0.85);
+        graph.set_centrality(scores.into_map());
+    }
+
+    let matches = top_matches(&graph, query, sort, limit);
 
-    let matches: Vec<_> = result.graph.search(query).into_iter().take(limit).collect();
+    if json_output {
+        let output = matches_json(&graph, &matches);
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
     if matches.is_empty() {
         println!("No matches found for \"{}\"", query);
@@ -143,23 +419,202 @@ pub fn query(query: &str, limit: usize) -> Result<()> {
 
     println!("Found {} matches:\n", matches.len());
 
-    for node in matches {
+    let group_by = match group_by {
+        Some(group_by) => group_by,
+        None => {
+            println!("{}", match_table(&matches));
+            return Ok(());
+        }
+    };
+
+    for (header, nodes) in group_matches(&matches, group_by) {
         println!(
-            "  {} {} {}",
-            node.kind.to_string().yellow(),
-            node.qualified_name.cyan(),
-            format!("({}:{})", node.file, node.line_start).dimmed()
+            "{} {}",
+            header.bold(),
+            format!("({})", nodes.len()).dimmed()
         );
-        if let Some(ref sig) = node.signature {
-            println!("    {}", sig.dimmed());
-        }
+        println!("{}", match_table(&nodes));
+        println!();
     }
 
     Ok(())
 }
 
+/// Resolves a node ID or symbol name to a node, taking the first name match.
+///
+/// With `ignore_case`, `userservice` finds `UserService`; an exact-case
+/// match is still preferred when several casings exist.
+fn resolve_symbol(
+    graph: &arbor_graph::ArborGraph,
+    symbol: &str,
+    ignore_case: bool,
+) -> Option<arbor_graph::NodeId> {
+    graph.get_index(symbol).or_else(|| {
+        let matches = if ignore_case {
+            graph.find_by_name_ci(symbol)
+        } else {
+            graph.find_by_name(symbol)
+        };
+        matches.first().and_then(|n| graph.get_index(&n.id))
+    })
+}
+
+/// Turns a `--limit` value into a count, with 0 meaning unlimited (as
+/// with `--depth`).
+fn limit_or_all(limit: usize) -> usize {
+    if limit == 0 {
+        usize::MAX
+    } else {
+        limit
+    }
+}
+
+/// Searches the graph and keeps the first `limit` matches in `sort` order.
+fn top_matches<'a>(
+    graph: &'a arbor_graph::ArborGraph,
+    query: &str,
+    sort: QuerySort,
+    limit: usize,
+) -> Vec<&'a arbor_core::CodeNode> {
+    let mut matches = graph.search(query);
+    sort_matches(graph, &mut matches, sort);
+    matches.truncate(limit_or_all(limit));
+    matches
+}
+
+/// Serializes query matches for `query --json`, in match order.
+fn matches_json(
+    graph: &arbor_graph::ArborGraph,
+    matches: &[&arbor_core::CodeNode],
+) -> serde_json::Value {
+    matches
+        .iter()
+        .map(|node| {
+            let centrality = graph
+                .get_index(&node.id)
+                .map(|idx| graph.centrality_or_default(idx))
+                .unwrap_or(0.0);
+            serde_json::json!({
+                "id": node.id,
+                "name": node.name,
+                "qualified_name": node.qualified_name,
+                "kind": node.kind.to_string(),
+                "file": node.file,
+                "line_start": node.line_start,
+                "signature": node.signature,
+                "centrality": centrality
+            })
+        })
+        .collect()
+}
+
+/// Warns on stderr when output depends on the centrality of a graph whose
+/// scores were never computed, so every score reads 0.00. `consequence`
+/// says what that does to the output.
+fn warn_if_centrality_missing(graph: &arbor_graph::ArborGraph, consequence: &str) {
+    if !graph.centrality_computed() {
+        eprintln!("{} Centrality not computed; {}", "⚠".yellow(), consequence);
+    }
+}
+
+/// Lays out query matches as an aligned table.
+fn match_table(nodes: &[&arbor_core::CodeNode]) -> String {
+    use comfy_table::Color;
+
+    let mut table = table::new_table(&["Kind", "Name", "Location", "Complexity", "Signature"]);
+    for node in nodes {
+        let complexity = match node.complexity {
+            0 => ("-".to_string(), Some(Color::DarkGrey)),
+            c if c >= arbor_core::HIGH_COMPLEXITY => (c.to_string(), Some(Color::Red)),
+            c => (c.to_string(), None),
+        };
+        table.add_row(table::row([
+            (node.kind.to_string(), Some(Color::Yellow)),
+            (node.qualified_name.clone(), Some(Color::Cyan)),
+            (
+                format!("{}:{}", node.file, node.line_start),
+                Some(Color::DarkGrey),
+            ),
+            complexity,
+            (
+                node.signature.clone().unwrap_or_default(),
+                Some(Color::DarkGrey),
+            ),
+        ]));
+    }
+    table::render(&table)
+}
+
+/// Buckets query matches by file or kind, keeping match order within
+/// each bucket. Buckets are returned sorted by header.
+fn group_matches<'a>(
+    matches: &[&'a arbor_core::CodeNode],
+    group_by: QueryGroupBy,
+) -> Vec<(String, Vec<&'a arbor_core::CodeNode>)> {
+    let mut groups: std::collections::BTreeMap<String, Vec<&arbor_core::CodeNode>> =
+        std::collections::BTreeMap::new();
+
+    for node in matches {
+        let key = match group_by {
+            QueryGroupBy::File => node.file.clone(),
+            QueryGroupBy::Kind => node.kind.to_string(),
+        };
+        groups.entry(key).or_default().push(node);
+    }
+
+    groups.into_iter().collect()
+}
+
+/// Orders query matches in place. Relevance keeps the search order.
+fn sort_matches(
+    graph: &arbor_graph::ArborGraph,
+    matches: &mut [&arbor_core::CodeNode],
+    sort: QuerySort,
+) {
+    match sort {
+        QuerySort::Relevance => {}
+        QuerySort::Centrality => {
+            let score = |node: &arbor_core::CodeNode| {
+                graph
+                    .get_index(&node.id)
+                    .map(|idx| graph.centrality_or_default(idx))
+                    .unwrap_or(0.0)
+            };
+            matches.sort_by(|a, b| score(b).total_cmp(&score(a)));
+        }
+        QuerySort::Name => matches.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+        }),
+        QuerySort::File => matches.sort_by(|a, b| {
+            a.file
+                .cmp(&b.file)
+                .then_with(|| a.line_start.cmp(&b.line_start))
+        }),
+    }
+}
+
 /// Start the Arbor server.
-pub async fn serve(port: u16, headless: bool, path: &Path, follow_symlinks: bool) -> Result<()> {
+#[allow(clippy::too_many_arguments)]
+pub async fn serve(
+    port: u16,
+    headless: bool,
+    path: &Path,
+    follow_symlinks: bool,
+    no_vendored: bool,
+    config_files: bool,
+    metrics_port: Option<u16>,
+    read_only_fs: bool,
+    tls: Option<arbor_server::TlsConfig>,
+    profile: bool,
+    reindex_interval: Duration,
+    graph_file: Option<&Path>,
+    cors_origins: Option<Vec<String>>,
+    idle_timeout: Option<Duration>,
+    snapshot_on_exit: Option<&Path>,
+    auth_token: Option<String>,
+) -> Result<()> {
     let bind_addr = if headless { "0.0.0.0" } else { "127.0.0.1" };
 
     if headless {
@@ -168,40 +623,275 @@ pub async fn serve(port: u16, headless: bool, path: &Path, follow_symlinks: bool
         println!("{}", "Starting Arbor server...".cyan());
     }
 
-    // Index the codebase first
-    let options = IndexOptions {
+    // The periodic re-index walks with the same options
+    let index_options = IndexOptions {
         follow_symlinks,
-        cache_path: None,
+        skip_vendored: no_vendored,
+        config_files,
+        compute_centrality: true,
+        ..Default::default()
     };
-    let result = index_directory(path, options)?;
-    let mut graph = result.graph;
 
-    // Compute centrality
-    let scores = compute_centrality(&graph, 20, 0.85);
-    graph.set_centrality(scores.into_map());
+    // Serve a prebuilt graph as-is, or index the codebase first
+    let start = std::time::Instant::now();
+    let graph = match graph_file {
+        Some(graph_file) => {
+            let graph = load_graph_file(graph_file)?;
+            println!(
+                "{} Loaded {} ({} nodes, {} edges)",
+                "✓".green(),
+                graph_file.display(),
+                graph.node_count(),
+                graph.edge_count()
+            );
+            graph
+        }
+        None => {
+            let result = index_directory(path, index_options.clone())?;
 
-    println!(
-        "{} Indexed {} files ({} nodes)",
-        "✓".green(),
-        result.files_indexed,
-        result.nodes_extracted
-    );
+            println!(
+                "{} Indexed {} files ({} nodes)",
+                "✓".green(),
+                result.files_indexed,
+                result.nodes_extracted
+            );
+            if profile {
+                print_profile(&result);
+            }
+            result.graph
+        }
+    };
 
     let addr = format!("{}:{}", bind_addr, port).parse()?;
-    let config = ServerConfig { addr };
+    let metrics_addr = match metrics_port {
+        Some(metrics_port) => Some(format!("{}:{}", bind_addr, metrics_port).parse()?),
+        None => None,
+    };
+    // Publicly reachable servers never hand out file contents
+    let expose_source = !(headless || read_only_fs);
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    let snapshots_disabled = headless && auth_token.is_none();
+    let config = ServerConfig {
+        addr,
+        metrics_addr,
+        expose_source,
+        auth_token,
+        tls,
+        cors_origins,
+        idle_timeout,
+        ..Default::default()
+    };
     let server = ArborServer::new(graph, config);
+    server.metrics().record_reindex(start.elapsed());
+
+    let reindex_options = IndexOptions {
+        cache_path: Some(path.join(".arbor").join("cache")),
+        ..index_options
+    };
+    let reindexing = server
+        .spawn_reindex(path.to_path_buf(), reindex_options, reindex_interval)
+        .is_some();
 
-    println!("{} Listening on ws://{}:{}", "✓".green(), bind_addr, port);
+    println!(
+        "{} Listening on {}://{}:{}",
+        "✓".green(),
+        scheme,
+        bind_addr,
+        port
+    );
+    if let Some(metrics_port) = metrics_port {
+        println!(
+            "{} Metrics on http://{}:{}/metrics",
+            "✓".green(),
+            bind_addr,
+            metrics_port
+        );
+    }
     if headless {
         println!("  Headless mode: accepting connections from any host");
     }
+    if !expose_source {
+        println!("  Read-only: source access disabled, graph structure only");
+    }
+    if snapshots_disabled {
+        println!("  Snapshots disabled: pass --auth-token to allow graph.export/graph.import");
+    }
+    if reindexing {
+        println!(
+            "  Re-indexing every {}s",
+            reindex_interval.as_secs().to_string().cyan()
+        );
+    }
+    if let Some(snapshot) = snapshot_on_exit {
+        println!(
+            "  Snapshot to {} on exit",
+            snapshot.display().to_string().cyan()
+        );
+    }
     println!("  Press {} to stop", "Ctrl+C".cyan());
 
-    server.run().await.map_err(|e| e.to_string())?;
+    server
+        .run_until(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(snapshot) = snapshot_on_exit {
+        save_exit_snapshot(&server.graph(), snapshot, true).await?;
+    }
+
+    Ok(())
+}
 
+/// Writes the `--snapshot-on-exit` snapshot and reports it, with a hint
+/// to load it via `--graph` when `load_hint` is set (only `serve` has it).
+async fn save_exit_snapshot(
+    graph: &arbor_server::SharedGraph,
+    path: &Path,
+    load_hint: bool,
+) -> Result<()> {
+    let bytes = arbor_server::write_snapshot(graph, path)
+        .await
+        .map_err(|e| format!("failed to write snapshot {}: {}", path.display(), e))?;
+    eprint!(
+        "{} Wrote snapshot {} ({} KB)",
+        "✓".green(),
+        path.display(),
+        bytes.div_ceil(1024)
+    );
+    if load_hint {
+        eprint!("; start with --graph {} to load it", path.display());
+    }
+    eprintln!();
     Ok(())
 }
 
+/// Loads the graph for `--no-index` without parsing anything.
+///
+/// Tries, under `root/.arbor/`: `graph.arbg` (`arbor export --format
+/// arbg`), `graph.json` (`arbor watch-export`), then the cache `arbor
+/// index` keeps in `cache/`. Whatever is found is used as-is, even if
+/// files changed since. Fails with instructions when there is nothing.
+fn load_prebuilt_graph(root: &Path) -> Result<arbor_graph::ArborGraph> {
+    let arbor_dir = root.join(".arbor");
+    for name in ["graph.arbg", "graph.json"] {
+        let file = arbor_dir.join(name);
+        if file.is_file() {
+            return load_graph_file(&file);
+        }
+    }
+
+    // Opening creates a store, so only open one that already exists
+    let cache = arbor_dir.join("cache");
+    if cache.is_dir() {
+        let store = arbor_graph::GraphStore::open(&cache)?;
+        if !store.list_cached_files()?.is_empty() {
+            let mut graph = store.load_graph()?;
+            let scores = compute_centrality(&graph, 20, 0.85);
+            graph.set_centrality(scores.into_map());
+            return Ok(graph);
+        }
+    }
+
+    Err(format!(
+        "--no-index: no prebuilt graph or index cache in {}\n  \
+         Run `arbor index {}` first (or `arbor export --format arbg --output {}`), \
+         or drop --no-index to index now",
+        arbor_dir.display(),
+        root.display(),
+        arbor_dir.join("graph.arbg").display()
+    )
+    .into())
+}
+
+/// Loads a graph written by `arbor export`: an `.arbg` snapshot as-is,
+/// or a `.json` export, whose edges are re-resolved from node references
+/// and whose centrality is recomputed.
+fn load_graph_file(path: &Path) -> Result<arbor_graph::ArborGraph> {
+    let bytes =
+        fs::read(path).map_err(|e| format!("failed to read graph {}: {}", path.display(), e))?;
+
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let mut doc: serde_json::Value = serde_json::from_slice(&bytes)?;
+        let nodes = doc
+            .get_mut("nodes")
+            .map(serde_json::Value::take)
+            .ok_or_else(|| format!("{} has no \"nodes\" array", path.display()))?;
+        let nodes: Vec<arbor_core::CodeNode> = serde_json::from_value(nodes)?;
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(nodes);
+        let mut graph = builder.build();
+        let scores = compute_centrality(&graph, 20, 0.85);
+        graph.set_centrality(scores.into_map());
+        return Ok(graph);
+    }
+
+    arbor_graph::ArborGraph::from_snapshot(&bytes)
+        .map_err(|e| format!("failed to load graph {}: {}", path.display(), e).into())
+}
+
+/// Prints how much of an index run the cache served.
+fn print_cache_stats(result: &arbor_watcher::IndexResult) {
+    let walked = result.cache_hits + result.files_indexed;
+    let hit_rate = if walked == 0 {
+        0.0
+    } else {
+        result.cache_hits as f64 * 100.0 / walked as f64
+    };
+
+    let mut stats = table::new_table(&["Files", "Count"]);
+    for (label, value) in [
+        ("Cache hits", result.cache_hits),
+        ("Re-parsed", result.files_indexed),
+        ("Removed (deleted)", result.files_removed),
+    ] {
+        stats.add_row(table::row([
+            (label.to_string(), None),
+            (value.to_string(), Some(comfy_table::Color::Cyan)),
+        ]));
+    }
+    println!("\n{}", table::render(&stats));
+    println!("  {} {:.0}% cache hits", "Hit rate:".dimmed(), hit_rate);
+
+    // Estimate what the hits would have cost from this run's own parses
+    let parse_time: Duration = result.language_timings.values().sum();
+    if result.files_indexed > 0 && result.cache_hits > 0 {
+        let per_file = parse_time / result.files_indexed as u32;
+        println!(
+            "  {} ~{}ms",
+            "Time saved:".dimmed(),
+            (per_file * result.cache_hits as u32).as_millis()
+        );
+    }
+}
+
+/// Prints per-phase and per-language index timings.
+fn print_profile(result: &arbor_watcher::IndexResult) {
+    let ms = |d: &Duration| format!("{:.1}ms", d.as_secs_f64() * 1000.0);
+
+    let mut phases = table::new_table(&["Phase", "Time"]);
+    for (phase, elapsed) in &result.phase_timings {
+        phases.add_row(table::row([
+            (phase.to_string(), None),
+            (ms(elapsed), Some(comfy_table::Color::Cyan)),
+        ]));
+    }
+    println!("\n{}", table::render(&phases));
+
+    if !result.language_timings.is_empty() {
+        let mut languages = table::new_table(&["Language", "Parse time"]);
+        for (language, elapsed) in &result.language_timings {
+            languages.add_row(table::row([
+                (language.clone(), None),
+                (ms(elapsed), Some(comfy_table::Color::Cyan)),
+            ]));
+        }
+        println!("{}", table::render(&languages));
+    }
+}
+
 /// Start the Arbor Visualizer.
 pub async fn viz(path: &Path, follow_symlinks: bool) -> Result<()> {
     println!("{}", "Starting Arbor Visualizer stack...".cyan());
@@ -209,7 +899,7 @@ pub async fn viz(path: &Path, follow_symlinks: bool) -> Result<()> {
     // 1. Index Codebase
     let options = IndexOptions {
         follow_symlinks,
-        cache_path: None,
+        ..Default::default()
     };
     let result = index_directory(path, options)?;
     let mut graph = result.graph;
@@ -229,7 +919,10 @@ pub async fn viz(path: &Path, follow_symlinks: bool) -> Result<()> {
     // 2. Start API Server (JSON-RPC)
     let rpc_port = 7433;
     let rpc_addr = format!("127.0.0.1:{}", rpc_port).parse()?;
-    let rpc_config = ServerConfig { addr: rpc_addr };
+    let rpc_config = ServerConfig {
+        addr: rpc_addr,
+        ..Default::default()
+    };
     let arbor_server = ArborServer::new(graph, rpc_config);
     let shared_graph = arbor_server.graph();
 
@@ -329,31 +1022,248 @@ pub async fn viz(path: &Path, follow_symlinks: bool) -> Result<()> {
     Ok(())
 }
 
-/// Export the graph to JSON.
-pub fn export(path: &Path, output: &Path) -> Result<()> {
+/// Export the graph to JSON, a matrix, DOT or a rendered SVG.
+///
+/// `pretty` only affects JSON.
+pub fn export(
+    path: &Path,
+    output: &Path,
+    format: ExportFormat,
+    pretty: bool,
+    open: bool,
+    no_imports: bool,
+) -> Result<()> {
     let result = index_directory(path, IndexOptions::default())?;
-    export_graph(&result.graph, output)?;
+    let graph = if no_imports {
+        result.graph.without_imports()
+    } else {
+        result.graph
+    };
+    match format {
+        ExportFormat::Json => export_graph(&graph, output, pretty)?,
+        ExportFormat::Matrix => export_matrix(&graph, output)?,
+        ExportFormat::Dot => {
+            fs::write(output, graph_dot(&graph))?;
+            println!("{} Exported to {}", "✓".green(), output.display());
+        }
+        ExportFormat::Svg => {
+            render_svg(&graph_dot(&graph), output)?;
+            println!("{} Rendered to {}", "✓".green(), output.display());
+        }
+        ExportFormat::Arbg => {
+            let mut graph = graph;
+            let scores = compute_centrality(&graph, 20, 0.85);
+            graph.set_centrality(scores.into_map());
+            fs::write(output, graph.to_snapshot()?)?;
+            println!("{} Exported to {}", "✓".green(), output.display());
+        }
+    }
+
+    if open {
+        opener::open(output).map_err(|e| format!("failed to open {}: {}", output.display(), e))?;
+    }
     Ok(())
 }
 
-/// Show index status.
-pub fn status(path: &Path, show_files: bool) -> Result<()> {
-    let arbor_dir = path.join(".arbor");
+/// Output formats for `arbor export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Nodes and stats as JSON
+    Json,
+    /// Weighted sparse adjacency matrix (Matrix Market) plus node list
+    Matrix,
+    /// Graphviz DOT source
+    Dot,
+    /// SVG rendered from DOT (requires Graphviz `dot` on PATH)
+    Svg,
+    /// Binary `.arbg` snapshot with edges and centrality, for `serve --graph`
+    Arbg,
+}
 
-    if !arbor_dir.exists() {
-        println!("{} Arbor not initialized in this directory", "✗".red());
-        println!("  Run {} to initialize", "arbor init".cyan());
-        return Ok(());
+/// Renders the whole graph as Graphviz DOT, one box per node.
+fn graph_dot(graph: &arbor_graph::ArborGraph) -> String {
+    use std::fmt::Write as _;
+
+    let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph arbor {{");
+    let _ = writeln!(out, "  rankdir=LR;");
+    let _ = writeln!(out, "  node [shape=box, fontname=\"Helvetica\"];");
+    for node in graph.nodes() {
+        let _ = writeln!(
+            out,
+            "  \"{}\" [label=\"{}\", tooltip=\"{}\"];",
+            quote(&node.id),
+            quote(&node.name),
+            quote(&node.file)
+        );
+    }
+    for edge in graph.edges_iter() {
+        let _ = writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [label=\"{}\"];",
+            quote(&edge.source),
+            quote(&edge.target),
+            edge.kind
+        );
     }
+    let _ = writeln!(out, "}}");
+    out
+}
 
-    // Quick index to get stats
-    let result = index_directory(path, IndexOptions::default())?;
+/// Pipes DOT source through Graphviz `dot -Tsvg` into `path`.
+fn render_svg(dot: &str, path: &Path) -> Result<()> {
+    use std::io::Write as _;
 
-    // Collect unique files from indexed nodes
-    let files: std::collections::HashSet<_> =
-        result.graph.nodes().map(|n| n.file.clone()).collect();
+    let mut child = match std::process::Command::new("dot")
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(path)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(
+                "Graphviz `dot` not found on PATH. Install Graphviz, or use --format dot and render it yourself."
+                    .into(),
+            );
+        }
+        Err(e) => return Err(e.into()),
+    };
 
-    // Collect unique extensions from indexed files
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(dot.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(format!("`dot -Tsvg` failed ({})", status).into());
+    }
+    Ok(())
+}
+
+/// Writes a ctags file so editors can jump to definitions without an LSP.
+pub fn tags(path: &Path, output: &Path) -> Result<()> {
+    let result = index_directory(path, IndexOptions::default())?;
+    let tags = ctags_file(&result.graph);
+    fs::write(output, &tags)?;
+
+    let count = tags.lines().filter(|l| !l.starts_with("!_")).count();
+    println!(
+        "{} Wrote {} tags to {}",
+        "✓".green(),
+        count,
+        output.display()
+    );
+    Ok(())
+}
+
+/// ctags kind letter for a node kind, or `None` for kinds that
+/// aren't definitions (imports and exports).
+fn ctags_kind(kind: arbor_core::NodeKind) -> Option<char> {
+    use arbor_core::NodeKind;
+
+    match kind {
+        NodeKind::Function => Some('f'),
+        NodeKind::Method | NodeKind::Constructor => Some('m'),
+        NodeKind::Class => Some('c'),
+        NodeKind::Interface => Some('i'),
+        NodeKind::Struct => Some('s'),
+        NodeKind::Enum => Some('g'),
+        NodeKind::Variable => Some('v'),
+        NodeKind::Constant => Some('C'),
+        NodeKind::TypeAlias => Some('t'),
+        NodeKind::Module => Some('n'),
+        NodeKind::Field => Some('F'),
+        NodeKind::Import | NodeKind::Export => None,
+    }
+}
+
+/// Renders the graph in extended ctags format.
+///
+/// Each line is `name<TAB>file<TAB>line;"<TAB>kind<TAB>line:N`, sorted
+/// bytewise so Vim can binary-search it. The address is a line number
+/// rather than a search pattern, which stays correct for overloaded names.
+fn ctags_file(graph: &arbor_graph::ArborGraph) -> String {
+    let mut lines: Vec<String> = graph
+        .nodes()
+        .filter_map(|node| {
+            let letter = ctags_kind(node.kind)?;
+            let file = node.file.strip_prefix("./").unwrap_or(&node.file);
+            Some(format!(
+                "{}\t{}\t{};\"\t{}\tline:{}",
+                node.name, file, node.line_start, letter, node.line_start
+            ))
+        })
+        .collect();
+    lines.sort();
+    lines.dedup();
+
+    let mut out = String::from(
+        "!_TAG_FILE_FORMAT\t2\t/extended format; --format=1 will not append ;\" to lines/\n\
+         !_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/\n\
+         !_TAG_PROGRAM_NAME\tarbor\t//\n",
+    );
+    for line in lines {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Writes the adjacency matrix in Matrix Market format.
+///
+/// Row/column `i` is the `i`th line of the `.nodes` file written next to
+/// `path`. Load both with `scipy.io.mmread` and a plain line read.
+fn export_matrix(graph: &arbor_graph::ArborGraph, path: &Path) -> Result<()> {
+    let (nodes, matrix) = graph.adjacency();
+    sprs::io::write_matrix_market(path, &matrix)?;
+
+    let mut node_list = String::new();
+    for id in nodes {
+        if let Some(node) = graph.get(id) {
+            node_list.push_str(&node.id);
+            node_list.push('\n');
+        }
+    }
+    let nodes_path = path.with_extension("nodes");
+    fs::write(&nodes_path, node_list)?;
+
+    println!(
+        "{} Exported {}x{} matrix ({} edges) to {} and {}",
+        "✓".green(),
+        matrix.rows(),
+        matrix.cols(),
+        matrix.nnz(),
+        path.display(),
+        nodes_path.display()
+    );
+    Ok(())
+}
+
+/// Show index status.
+pub fn status(
+    path: &Path,
+    show_files: bool,
+    show_orphans: bool,
+    show_resolution: bool,
+) -> Result<()> {
+    let arbor_dir = path.join(".arbor");
+
+    if !arbor_dir.exists() {
+        println!("{} Arbor not initialized in this directory", "✗".red());
+        println!("  Run {} to initialize", "arbor init".cyan());
+        return Ok(());
+    }
+
+    // Quick index to get stats
+    let result = index_directory(path, IndexOptions::default())?;
+
+    // Collect unique files from indexed nodes
+    let files: std::collections::HashSet<_> =
+        result.graph.nodes().map(|n| n.file.clone()).collect();
+
+    // Collect unique extensions from indexed files
     let mut file_exts: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut ext_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
 
@@ -374,9 +1284,18 @@ pub fn status(path: &Path, show_files: bool) -> Result<()> {
 
     println!("{}", "📊 Arbor Status".cyan().bold());
     println!();
-    println!("  {} {}", "Files indexed:".dimmed(), result.files_indexed);
-    println!("  {} {}", "Nodes:".dimmed(), result.nodes_extracted);
-    println!("  {} {}", "Edges:".dimmed(), result.graph.edge_count());
+    let mut counts = table::new_table(&["Metric", "Count"]);
+    for (label, value) in [
+        ("Files indexed", result.files_indexed),
+        ("Nodes", result.nodes_extracted),
+        ("Edges", result.graph.edge_count()),
+    ] {
+        counts.add_row(table::row([
+            (label.to_string(), None),
+            (value.to_string(), Some(comfy_table::Color::Cyan)),
+        ]));
+    }
+    println!("{}", table::render(&counts));
 
     if show_files {
         println!();
@@ -384,9 +1303,14 @@ pub fn status(path: &Path, show_files: bool) -> Result<()> {
         if ext_list.is_empty() {
             println!("    (none)");
         } else {
+            let mut exts = table::new_table(&["Extension", "Files"]);
             for (ext, count) in ext_list {
-                println!("    .{}: {} files", ext, count);
+                exts.add_row(table::row([
+                    (format!(".{}", ext), None),
+                    (count.to_string(), Some(comfy_table::Color::Cyan)),
+                ]));
             }
+            println!("{}", table::render(&exts));
         }
     } else {
         // Compact view (top 5)
@@ -420,6 +1344,65 @@ pub fn status(path: &Path, show_files: bool) -> Result<()> {
         }
     }
 
+    if show_orphans {
+        let orphans = result.graph.orphan_files();
+        println!();
+        println!("{}", "🍂 Orphan Files".cyan().bold());
+        if orphans.is_empty() {
+            println!(
+                "  {} Every indexed file is connected to the graph",
+                "✓".green()
+            );
+        } else {
+            println!(
+                "  {}",
+                "Only imports or isolated symbols (possible dead modules):".dimmed()
+            );
+            for file in &orphans {
+                println!("  {}", file.yellow());
+            }
+        }
+    }
+
+    if show_resolution {
+        let stats = &result.resolution;
+        println!();
+        println!("{}", "🔗 Edge Resolution".cyan().bold());
+        let mut summary = table::new_table(&["Metric", "Count"]);
+        for (label, value) in [
+            ("References", stats.references.to_string()),
+            ("Resolved", stats.resolved.to_string()),
+            ("Unresolved", stats.unresolved_count().to_string()),
+            (
+                "Resolution rate",
+                format!("{:.1}%", stats.resolution_rate() * 100.0),
+            ),
+        ] {
+            summary.add_row(table::row([
+                (label.to_string(), None),
+                (value, Some(comfy_table::Color::Cyan)),
+            ]));
+        }
+        println!("{}", table::render(&summary));
+
+        let top = stats.top_unresolved(15);
+        if !top.is_empty() {
+            println!();
+            println!(
+                "  {}",
+                "Top unresolved (external libraries or missed resolution):".dimmed()
+            );
+            let mut names = table::new_table(&["Name", "Occurrences"]);
+            for (name, count) in top {
+                names.add_row(table::row([
+                    (name.to_string(), Some(comfy_table::Color::Yellow)),
+                    (count.to_string(), None),
+                ]));
+            }
+            println!("{}", table::render(&names));
+        }
+    }
+
     // Show helpful tip if graph is empty
     if result.nodes_extracted == 0 && result.files_indexed > 0 {
         println!();
@@ -434,7 +1417,12 @@ pub fn status(path: &Path, show_files: bool) -> Result<()> {
 }
 
 /// Start the Agentic Bridge (MCP + Viz).
-pub async fn bridge(path: &Path, launch_viz: bool, follow_symlinks: bool) -> Result<()> {
+pub async fn bridge(
+    path: &Path,
+    launch_viz: bool,
+    follow_symlinks: bool,
+    snapshot_on_exit: Option<&Path>,
+) -> Result<()> {
     use arbor_mcp::McpServer;
 
     eprintln!("{} Arbor Bridge (MCP Mode)", "🔗".bold().cyan());
@@ -448,6 +1436,7 @@ pub async fn bridge(path: &Path, launch_viz: bool, follow_symlinks: bool) -> Res
     let options = IndexOptions {
         follow_symlinks,
         cache_path: Some(path.join(".arbor").join("cache")),
+        ..Default::default()
     };
     eprintln!("{} Starting initial index...", "⏳".yellow());
 
@@ -486,6 +1475,7 @@ pub async fn bridge(path: &Path, launch_viz: bool, follow_symlinks: bool) -> Res
 
     let rpc_config = ServerConfig {
         addr: format!("127.0.0.1:{}", rpc_port).parse()?,
+        ..Default::default()
     };
 
     let arbor_server = ArborServer::new_with_shared(shared_graph.clone(), rpc_config);
@@ -569,14 +1559,28 @@ pub async fn bridge(path: &Path, launch_viz: bool, follow_symlinks: bool) -> Res
 
     // 3. Start MCP Server (Main Thread) WITH Spotlight capability
     // IMPORTANT: All logging MUST be to stderr from here on.
-    let mcp = McpServer::with_spotlight(shared_graph, spotlight_handle);
-    mcp.run_stdio().await?;
+    let mcp = McpServer::with_spotlight(shared_graph.clone(), spotlight_handle);
+    tokio::select! {
+        result = mcp.run_stdio() => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    if let Some(snapshot) = snapshot_on_exit {
+        save_exit_snapshot(&shared_graph, snapshot, false).await?;
+    }
 
     Ok(())
 }
 
+/// Files parsed per language by `doctor --bench-parse`.
+const BENCH_SAMPLES: usize = 3;
+
+/// A language is flagged when its mean parse time exceeds the median
+/// language's by this factor.
+const BENCH_OUTLIER_FACTOR: u32 = 5;
+
 /// Check system health and environment.
-pub async fn check_health() -> Result<()> {
+pub async fn check_health(bench_parse: bool) -> Result<()> {
     use std::net::TcpListener;
 
     println!("{}", "🔍 Arbor Health Check".cyan().bold());
@@ -647,6 +1651,11 @@ pub async fn check_health() -> Result<()> {
         all_ok = false;
     }
 
+    // 6. Parse latency per language
+    if bench_parse {
+        print_parse_bench(&workspace_root);
+    }
+
     println!("{}", "═".repeat(50));
 
     if all_ok {
@@ -658,22 +1667,87 @@ pub async fn check_health() -> Result<()> {
     Ok(())
 }
 
+/// Times a few parses per language and flags languages that are far
+/// slower than the rest.
+fn print_parse_bench(root: &Path) {
+    use comfy_table::Color;
+
+    let benches = arbor_watcher::bench_parse(root, &IndexOptions::default(), BENCH_SAMPLES);
+    if benches.is_empty() {
+        println!("{} No source files to benchmark", "⚠".yellow());
+        return;
+    }
+
+    let outliers: Vec<&str> = arbor_watcher::parse_outliers(&benches, BENCH_OUTLIER_FACTOR)
+        .into_iter()
+        .map(|b| b.language.as_str())
+        .collect();
+
+    let mut table = table::new_table(&["Language", "Files", "Mean", "Slowest"]);
+    for bench in &benches {
+        let slowest = bench
+            .slowest()
+            .map(|(path, size, time)| {
+                format!(
+                    "{:.1?} {} ({} KB)",
+                    time,
+                    path.strip_prefix(root).unwrap_or(path).display(),
+                    size / 1024
+                )
+            })
+            .unwrap_or_default();
+        let color = if outliers.contains(&bench.language.as_str()) {
+            Color::Red
+        } else {
+            Color::Cyan
+        };
+        table.add_row(table::row([
+            (bench.language.clone(), Some(color)),
+            (bench.samples.len().to_string(), None),
+            (format!("{:.1?}", bench.mean()), None),
+            (slowest, Some(Color::DarkGrey)),
+        ]));
+    }
+
+    println!(
+        "{} Parse latency ({} files per language):",
+        "✓".green(),
+        BENCH_SAMPLES
+    );
+    println!("{}", table::render(&table));
+    for language in outliers {
+        println!(
+            "{} {} parses over {}x slower than the median language; check for a giant file or a pathological grammar",
+            "⚠".yellow(),
+            language,
+            BENCH_OUTLIER_FACTOR
+        );
+    }
+}
+
 /// Preview blast radius before refactoring a node.
-pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: bool) -> Result<()> {
-    // Load the graph by indexing current directory
-    let path = std::env::current_dir()?;
+#[allow(clippy::too_many_arguments)]
+pub fn refactor(
+    target: &str,
+    max_depth: usize,
+    show_why: bool,
+    json_output: bool,
+    impact_budget: Option<usize>,
+    diagram: Option<DiagramFormat>,
+    min_severity: Option<arbor_graph::ImpactSeverity>,
+    public_only: bool,
+    confidence_config: arbor_graph::ConfidenceConfig,
+    ignore_case: bool,
+    timeout_ms: Option<u64>,
+    root: Option<&Path>,
+) -> Result<()> {
+    // Load the graph by indexing the project
+    let path = project_root(root)?;
     let result = index_directory(&path, IndexOptions::default())?;
     let graph = result.graph;
 
     // Find the target node
-    let node_idx = graph.get_index(target).or_else(|| {
-        graph
-            .find_by_name(target)
-            .first()
-            .and_then(|n| graph.get_index(&n.id))
-    });
-
-    let node_idx = match node_idx {
+    let node_idx = match resolve_symbol(&graph, target, ignore_case) {
         Some(idx) => idx,
         None => {
             // Smart fallback: suggest similar symbols
@@ -685,7 +1759,34 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
     let target_node = graph.get(node_idx).unwrap();
 
     // Run impact analysis
-    let analysis = graph.analyze_impact(node_idx, max_depth);
+    let mut analysis = graph.analyze_impact_until(node_idx, max_depth, deadline(timeout_ms));
+    if analysis.timed_out {
+        warn_timed_out(timeout_ms);
+    }
+    if let Some(min) = min_severity {
+        analysis.retain_min_severity(min);
+    }
+    if public_only {
+        analysis.retain_public();
+    }
+
+    if let Some(format) = diagram {
+        if matches!(format, DiagramFormat::Markdown | DiagramFormat::Llm) {
+            let opts = arbor_graph::MarkdownOptions {
+                include_reasons: show_why,
+                include_confidence: true,
+                compact: format == DiagramFormat::Llm,
+                confidence: confidence_config,
+            };
+            if !opts.compact {
+                warn_if_centrality_missing(&graph, "impact scores below are placeholders");
+            }
+            print!("{}", analysis.to_markdown(&opts));
+        } else {
+            print!("{}", impact_diagram(&graph, &analysis, format));
+        }
+        return check_impact_budget(analysis.total_affected, impact_budget, analysis.timed_out);
+    }
 
     if json_output {
         // JSON output (keep existing behavior for automation)
@@ -694,7 +1795,8 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
                 "id": analysis.target.id,
                 "name": analysis.target.name,
                 "kind": analysis.target.kind,
-                "file": analysis.target.file
+                "file": analysis.target.file,
+                "complexity": analysis.target.complexity
             },
             "upstream": analysis.upstream.iter().map(|n| serde_json::json!({
                 "id": n.node_info.id,
@@ -711,10 +1813,19 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
                 "entry_edge": n.entry_edge.to_string()
             })).collect::<Vec<_>>(),
             "total_affected": analysis.total_affected,
-            "query_time_ms": analysis.query_time_ms
+            "public_api_affected": graph.closest_public_ancestors(node_idx).iter().map(|n| serde_json::json!({
+                "id": n.id,
+                "name": n.name,
+                "file": n.file,
+                "line": n.line_start
+            })).collect::<Vec<_>>(),
+            "warnings": graph.impact_limitations(&analysis),
+            "impact_budget": impact_budget,
+            "query_time_ms": analysis.query_time_ms,
+            "timed_out": analysis.timed_out
         });
         println!("{}", serde_json::to_string_pretty(&output)?);
-        return Ok(());
+        return check_impact_budget(analysis.total_affected, impact_budget, analysis.timed_out);
     }
 
     // === WARM, OPINIONATED OUTPUT ===
@@ -727,7 +1838,8 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
     println!();
 
     // Compute and display confidence
-    let confidence = arbor_graph::ConfidenceExplanation::from_analysis(&analysis);
+    let confidence =
+        arbor_graph::ConfidenceExplanation::from_analysis_with(&analysis, &confidence_config);
     let role = arbor_graph::NodeRole::from_analysis(&analysis);
 
     let confidence_color = match confidence.level {
@@ -752,6 +1864,32 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
     }
     println!();
 
+    let limitations = graph.impact_limitations(&analysis);
+    if !limitations.is_empty() {
+        for warning in &limitations {
+            println!("{}  {}", "⚠".yellow(), warning.message.yellow());
+            println!("   {}", warning.suggestion.dimmed());
+        }
+        println!();
+    }
+
+    if target_node.complexity > 0 {
+        let callers = graph.get_callers(node_idx).len();
+        println!(
+            "   {} {} | {}",
+            "Complexity:".dimmed(),
+            target_node.complexity,
+            format!("{} direct callers", callers).dimmed()
+        );
+        if target_node.complexity >= arbor_core::HIGH_COMPLEXITY && callers >= HIGH_FAN_IN {
+            println!(
+                "   {} High complexity + high fan-in: changes here are risky",
+                "⚠".yellow()
+            );
+        }
+        println!();
+    }
+
     // ========== --why VERBOSE OUTPUT ==========
     if show_why {
         println!("{}", "═══ Detailed Analysis (--why) ═══".cyan().bold());
@@ -1028,10 +2166,170 @@ pub fn refactor(target: &str, max_depth: usize, show_why: bool, json_output: boo
         }
     }
 
+    // Where breaking a private node becomes externally visible
+    let is_public =
+        target_node.visibility == arbor_core::Visibility::Public || target_node.is_exported;
+    if !is_public {
+        let public_api = graph.closest_public_ancestors(node_idx);
+        if !public_api.is_empty() {
+            println!();
+            println!("{}", "Public API affected:".yellow());
+            for node in public_api.iter().take(10) {
+                println!(
+                    "  • {} ({}) {}",
+                    node.name,
+                    node.kind,
+                    format!("{}:{}", node.file, node.line_start).dimmed()
+                );
+            }
+            if public_api.len() > 10 {
+                println!("  • ... and {} more", public_api.len() - 10);
+            }
+        }
+    }
+
     println!();
     println!("{}", format!("File: {}", target_node.file).dimmed());
 
-    Ok(())
+    check_impact_budget(analysis.total_affected, impact_budget, analysis.timed_out)
+}
+
+/// Output formats for `arbor refactor --output`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiagramFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Markdown "Architectural Brief" (same as the MCP tools)
+    Markdown,
+    /// Terse Markdown summary for LLM prompts
+    Llm,
+}
+
+/// Renders the blast radius of an impact analysis as a diagram.
+///
+/// Only the target and the affected nodes appear, with the edges between
+/// them. Nodes are colored by severity.
+fn impact_diagram(
+    graph: &arbor_graph::ArborGraph,
+    analysis: &arbor_graph::ImpactAnalysis,
+    format: DiagramFormat,
+) -> String {
+    use arbor_graph::ImpactSeverity;
+    use std::fmt::Write as _;
+
+    // (id, label, fill color), target first
+    let mut nodes: Vec<(&str, &str, &str)> = vec![(
+        analysis.target.id.as_str(),
+        analysis.target.name.as_str(),
+        "#4a90d9",
+    )];
+    for affected in analysis.all_affected() {
+        if nodes.iter().any(|(id, _, _)| *id == affected.node_info.id) {
+            continue;
+        }
+        let color = match affected.severity {
+            ImpactSeverity::Direct => "#e74c3c",
+            ImpactSeverity::Transitive => "#f39c12",
+            ImpactSeverity::Distant => "#f1c40f",
+        };
+        nodes.push((
+            affected.node_info.id.as_str(),
+            affected.node_info.name.as_str(),
+            color,
+        ));
+    }
+
+    let position = |id: &str| nodes.iter().position(|(node_id, _, _)| *node_id == id);
+    let edges: Vec<(usize, usize, String)> = graph
+        .edges_iter()
+        .filter_map(|e| {
+            Some((
+                position(&e.source)?,
+                position(&e.target)?,
+                e.kind.to_string(),
+            ))
+        })
+        .collect();
+
+    let mut out = String::new();
+    match format {
+        DiagramFormat::Dot => {
+            let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(out, "digraph impact {{");
+            let _ = writeln!(out, "  rankdir=LR;");
+            let _ = writeln!(
+                out,
+                "  node [shape=box, style=filled, fontname=\"Helvetica\"];"
+            );
+            for (id, label, color) in &nodes {
+                let _ = writeln!(
+                    out,
+                    "  \"{}\" [label=\"{}\", fillcolor=\"{}\"];",
+                    quote(id),
+                    quote(label),
+                    color
+                );
+            }
+            for (from, to, kind) in &edges {
+                let _ = writeln!(
+                    out,
+                    "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                    quote(nodes[*from].0),
+                    quote(nodes[*to].0),
+                    kind
+                );
+            }
+            let _ = writeln!(out, "}}");
+        }
+        DiagramFormat::Markdown | DiagramFormat::Llm => {
+            out.push_str(&analysis.to_markdown(&arbor_graph::MarkdownOptions {
+                compact: format == DiagramFormat::Llm,
+                ..Default::default()
+            }));
+        }
+        DiagramFormat::Mermaid => {
+            let _ = writeln!(out, "graph LR");
+            for (i, (_, label, _)) in nodes.iter().enumerate() {
+                let _ = writeln!(out, "  n{}[\"{}\"]", i, label.replace('"', "#quot;"));
+            }
+            for (from, to, kind) in &edges {
+                let _ = writeln!(out, "  n{} -->|{}| n{}", from, kind, to);
+            }
+            for (i, (_, _, color)) in nodes.iter().enumerate() {
+                let _ = writeln!(out, "  style n{} fill:{}", i, color);
+            }
+        }
+    }
+    out
+}
+
+/// Fails when the blast radius exceeds `--impact-budget`, or can't be
+/// checked against it because `--timeout` cut the analysis short.
+///
+/// The returned error makes `main` exit nonzero, which is what CI gates key on.
+fn check_impact_budget(
+    total_affected: usize,
+    budget: Option<usize>,
+    timed_out: bool,
+) -> Result<()> {
+    match budget {
+        Some(budget) if timed_out => Err(format!(
+            "impact budget not checked: the analysis timed out after {} nodes \
+             (budget is {}); use a longer --timeout",
+            total_affected, budget
+        )
+        .into()),
+        Some(budget) if total_affected > budget => Err(format!(
+            "impact budget exceeded: {} nodes affected, budget is {} ({} over)",
+            total_affected,
+            budget,
+            total_affected - budget
+        )
+        .into()),
+        _ => Ok(()),
+    }
 }
 
 /// Suggest similar symbols when exact match fails
@@ -1119,40 +2417,91 @@ fn suggest_similar_symbols(graph: &arbor_graph::ArborGraph, target: &str) -> Res
 }
 
 /// Explain code using graph-backed context.
-pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: bool) -> Result<()> {
-    // Load the graph by indexing current directory
-    let path = std::env::current_dir()?;
-    let result = index_directory(&path, IndexOptions::default())?;
-    let graph = result.graph;
+#[allow(clippy::too_many_arguments)]
+pub fn explain(
+    questions: &[String],
+    max_tokens: usize,
+    max_depth: usize,
+    show_why: bool,
+    json_output: bool,
+    include_source: bool,
+    edge_kinds: &[arbor_graph::EdgeKind],
+    ignore_case: bool,
+    no_index: bool,
+    component: bool,
+    timeout_ms: Option<u64>,
+    root: Option<&Path>,
+) -> Result<()> {
+    // Load a prebuilt graph, or index the project
+    let path = project_root(root)?;
+    let graph = if no_index {
+        load_prebuilt_graph(&path)?
+    } else {
+        index_directory(&path, IndexOptions::default())?.graph
+    };
 
-    // Try to find a node matching the question (could be a function name)
-    let node_idx = graph.get_index(question).or_else(|| {
-        graph
-            .find_by_name(question)
-            .first()
-            .and_then(|n| graph.get_index(&n.id))
-    });
+    // Each question could be a function name; all must resolve
+    let mut roots = Vec::with_capacity(questions.len());
+    for question in questions {
+        match resolve_symbol(&graph, question, ignore_case) {
+            Some(idx) => roots.push(idx),
+            None => {
+                return Err(format!("Node '{}' not found in graph", question).into());
+            }
+        }
+    }
 
-    let node_idx = match node_idx {
-        Some(idx) => idx,
-        None => {
-            return Err(format!("Node '{}' not found in graph", question).into());
+    // Other components can't be reached from the first symbol; slicing
+    // them too would mix unrelated services into one context
+    if component {
+        let (kept, dropped) = keep_first_component(&graph, questions, roots);
+        for question in dropped {
+            eprintln!(
+                "{} Skipping '{}': not in the component of '{}'",
+                "⚠".yellow(),
+                question,
+                questions[0]
+            );
         }
-    };
+        roots = kept;
+    }
 
-    // Slice context around the node
-    let slice = graph.slice_context(node_idx, max_tokens, 2, &[]);
+    // The token budget keeps the most central nodes at each depth
+    warn_if_centrality_missing(&graph, "context nodes are ranked by depth only");
+
+    // Slice one shared context around all the nodes
+    let slice = graph.slice_context_until(
+        &roots,
+        max_tokens,
+        max_depth,
+        &[],
+        edge_kinds,
+        deadline(timeout_ms),
+    );
 
     // Warn if context was truncated
-    if slice.truncation_reason != arbor_graph::TruncationReason::Complete {
-        eprintln!(
-            "\n{} Context truncated: {} (limit: {} tokens)",
-            "⚠".yellow(),
-            slice.truncation_reason,
-            max_tokens
-        );
-        eprintln!("  Some nodes were excluded to fit token budget.");
-        eprintln!("  Use --tokens to increase limit, or use pinning for critical nodes.");
+    match slice.truncation_reason {
+        arbor_graph::TruncationReason::TokenBudget => {
+            eprintln!(
+                "\n{} Context truncated: {} (limit: {} tokens)",
+                "⚠".yellow(),
+                slice.truncation_reason,
+                max_tokens
+            );
+            eprintln!("  Some nodes were excluded to fit token budget.");
+            eprintln!("  Use --tokens to increase limit, or use pinning for critical nodes.");
+        }
+        arbor_graph::TruncationReason::MaxDepth => {
+            eprintln!(
+                "\n{} Context truncated: {} (limit: {} hops)",
+                "⚠".yellow(),
+                slice.truncation_reason,
+                max_depth
+            );
+            eprintln!("  Use --depth to reach farther nodes.");
+        }
+        arbor_graph::TruncationReason::Timeout => warn_timed_out(timeout_ms),
+        arbor_graph::TruncationReason::Complete => {}
     }
 
     if json_output {
@@ -1163,11 +2512,19 @@ pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: b
                 "kind": slice.target.kind,
                 "file": slice.target.file
             },
+            "targets": slice.targets.iter().map(|t| serde_json::json!({
+                "id": t.id,
+                "name": t.name,
+                "kind": t.kind,
+                "file": t.file,
+                "docstring": t.doc_summary()
+            })).collect::<Vec<_>>(),
             "context_nodes": slice.nodes.iter().map(|n| serde_json::json!({
                 "id": n.node_info.id,
                 "name": n.node_info.name,
                 "kind": n.node_info.kind,
                 "file": n.node_info.file,
+                "docstring": n.node_info.doc_summary(),
                 "depth": n.depth,
                 "token_estimate": n.token_estimate,
                 "pinned": n.pinned
@@ -1179,11 +2536,12 @@ pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: b
         println!("{}", serde_json::to_string_pretty(&output)?);
     } else {
         println!("{}", "📖 Graph-Backed Context".cyan().bold());
-        println!(
-            "Target: {} ({})",
-            slice.target.name.cyan(),
-            slice.target.kind
-        );
+        for target in &slice.targets {
+            println!("Target: {} ({})", target.name.cyan(), target.kind);
+            if let Some(doc) = target.doc_summary() {
+                println!("  {}", doc.dimmed());
+            }
+        }
         println!();
 
         println!("{}", slice.summary());
@@ -1201,6 +2559,9 @@ pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: b
                     node.token_estimate,
                     pinned_marker.cyan()
                 );
+                if let Some(doc) = node.node_info.doc_summary() {
+                    println!("      {}", doc.dimmed());
+                }
             }
             if slice.nodes.len() > 10 {
                 println!("  ... and {} more nodes", slice.nodes.len() - 10);
@@ -1208,6 +2569,35 @@ pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: b
             println!();
         }
 
+        if include_source {
+            for node in &slice.nodes {
+                let info = &node.node_info;
+                match source_snippet(&path.join(&info.file), info.line_start, info.line_end) {
+                    Ok(snippet) => {
+                        println!(
+                            "{} {}:{}-{}",
+                            info.name.cyan(),
+                            info.file.dimmed(),
+                            info.line_start,
+                            info.line_end
+                        );
+                        println!("```{}", fence_language(&info.file));
+                        println!("{}", snippet);
+                        println!("```");
+                        println!();
+                    }
+                    Err(reason) => {
+                        eprintln!(
+                            "{} Skipping source for {}: {}",
+                            "⚠".yellow(),
+                            info.name,
+                            reason
+                        );
+                    }
+                }
+            }
+        }
+
         println!(
             "Truncation: {} | Query time: {}ms",
             slice.truncation_reason.to_string().yellow(),
@@ -1218,6 +2608,60 @@ pub fn explain(question: &str, max_tokens: usize, show_why: bool, json_output: b
     Ok(())
 }
 
+/// Splits resolved questions into the roots in the connected component
+/// of the first one, and the questions outside it.
+fn keep_first_component<'a>(
+    graph: &arbor_graph::ArborGraph,
+    questions: &'a [String],
+    roots: Vec<arbor_graph::NodeId>,
+) -> (Vec<arbor_graph::NodeId>, Vec<&'a String>) {
+    let first = roots.first().and_then(|&idx| graph.component_of(idx));
+    let mut kept = Vec::new();
+    let mut dropped = Vec::new();
+    for (question, idx) in questions.iter().zip(roots) {
+        if graph.component_of(idx) == first {
+            kept.push(idx);
+        } else {
+            dropped.push(question);
+        }
+    }
+    (kept, dropped)
+}
+
+/// Reads lines `line_start..=line_end` (1-indexed) of a file.
+///
+/// Fails if the file can't be read or is shorter than the recorded range,
+/// which happens when it changed since indexing.
+fn source_snippet(
+    file: &Path,
+    line_start: u32,
+    line_end: u32,
+) -> std::result::Result<String, String> {
+    let text = fs::read_to_string(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let lines: Vec<&str> = text.lines().collect();
+
+    let start = line_start.max(1) as usize;
+    let end = line_end.max(line_start) as usize;
+    if end > lines.len() {
+        return Err(format!(
+            "{} has {} lines, expected at least {}",
+            file.display(),
+            lines.len(),
+            end
+        ));
+    }
+
+    Ok(lines[start - 1..end].join("\n"))
+}
+
+/// Returns the Markdown fence language for a file, i.e. its extension.
+fn fence_language(file: &str) -> &str {
+    Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+}
+
 /// Launch the graphical interface.
 pub fn gui(path: &Path) -> Result<()> {
     println!("{} Launching Arbor GUI...", "🌲".green());
@@ -1311,50 +2755,1078 @@ pub fn pr_summary(symbols: &str, path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Watch for file changes and re-index automatically.
-pub async fn watch(path: &Path) -> Result<()> {
-    use std::time::Duration;
+/// Analyze the impact of several symbols at once and print one JSON report.
+pub fn impact(
+    mut symbols: Vec<String>,
+    batch: Option<&Path>,
+    max_depth: usize,
+    component: bool,
+    root: Option<&Path>,
+) -> Result<()> {
+    if let Some(batch) = batch {
+        let list = fs::read_to_string(batch)
+            .map_err(|e| format!("failed to read {}: {}", batch.display(), e))?;
+        symbols.extend(parse_symbol_list(&list));
+    }
 
-    println!("{}", "👁️  Watch Mode".cyan().bold());
-    println!("Watching: {}", path.display());
-    println!("Press Ctrl+C to stop.\n");
+    let path = project_root(root)?;
+    let result = index_directory(&path, IndexOptions::default())?;
 
-    // Initial index
-    let mut last_result = index_directory(path, IndexOptions::default())?;
-    println!(
-        "✓ Initial index: {} files, {} nodes",
-        last_result.files_indexed, last_result.nodes_extracted
-    );
+    let report = batch_impact_report(&result.graph, &symbols, max_depth, component);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
 
-    loop {
-        tokio::time::sleep(Duration::from_secs(2)).await;
+/// One symbol per line; blank lines and `#` comments are skipped.
+fn parse_symbol_list(list: &str) -> Vec<String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
 
-        // Re-index and check for changes
-        match index_directory(path, IndexOptions::default()) {
-            Ok(result) => {
-                if result.nodes_extracted != last_result.nodes_extracted
-                    || result.files_indexed != last_result.files_indexed
-                {
-                    println!(
-                        "🔄 Updated: {} files, {} nodes (was {} files, {} nodes)",
-                        result.files_indexed,
-                        result.nodes_extracted,
-                        last_result.files_indexed,
-                        last_result.nodes_extracted
-                    );
-                    last_result = result;
-                }
+/// Runs `analyze_impact` for each symbol and merges the results.
+///
+/// Each symbol gets its own breakdown; `union` lists every affected node
+/// once, with the changed symbols that reach it and its shortest hop
+/// distance from any of them.
+///
+/// With `component`, only symbols in the connected component of the first
+/// one found are analyzed; the rest are listed with `outside_component`.
+fn batch_impact_report(
+    graph: &arbor_graph::ArborGraph,
+    symbols: &[String],
+    max_depth: usize,
+    component: bool,
+) -> serde_json::Value {
+    let mut breakdowns = Vec::new();
+    let mut union: std::collections::BTreeMap<String, serde_json::Value> =
+        std::collections::BTreeMap::new();
+    let mut scope: Option<usize> = None;
+
+    for symbol in symbols {
+        let node_idx = graph.get_index(symbol).or_else(|| {
+            graph
+                .find_by_name(symbol)
+                .first()
+                .and_then(|n| graph.get_index(&n.id))
+        });
+        let Some(idx) = node_idx else {
+            breakdowns.push(serde_json::json!({ "symbol": symbol, "found": false }));
+            continue;
+        };
+        if component {
+            let own = graph.component_of(idx).unwrap_or_default();
+            if *scope.get_or_insert(own) != own {
+                breakdowns.push(serde_json::json!({
+                    "symbol": symbol,
+                    "found": true,
+                    "outside_component": true,
+                }));
+                continue;
+            }
+        }
+
+        let analysis = graph.analyze_impact(idx, max_depth);
+        let affected = analysis.all_affected();
+        for node in &affected {
+            let info = &node.node_info;
+            let entry = union.entry(info.id.clone()).or_insert_with(|| {
+                serde_json::json!({
+                    "id": info.id,
+                    "name": info.name,
+                    "kind": info.kind,
+                    "file": info.file,
+                    "hop_distance": node.hop_distance,
+                    "affected_by": [],
+                })
+            });
+            if node.hop_distance < entry["hop_distance"].as_u64().unwrap_or(u64::MAX) as usize {
+                entry["hop_distance"] = node.hop_distance.into();
             }
-            Err(e) => {
-                eprintln!("⚠ Index error: {}", e);
+            if let Some(by) = entry["affected_by"].as_array_mut() {
+                by.push(analysis.target.id.clone().into());
             }
         }
+
+        breakdowns.push(serde_json::json!({
+            "symbol": symbol,
+            "found": true,
+            "target": analysis.target.id,
+            "total_affected": analysis.total_affected,
+            "affected": affected.iter().map(|n| serde_json::json!({
+                "id": n.node_info.id,
+                "name": n.node_info.name,
+                "severity": n.severity.as_str(),
+                "hop_distance": n.hop_distance,
+                "direction": n.direction.to_string(),
+            })).collect::<Vec<_>>(),
+        }));
+    }
+
+    let mut report = serde_json::json!({
+        "symbols": breakdowns,
+        "union": {
+            "total_affected": union.len(),
+            "nodes": union.into_values().collect::<Vec<_>>(),
+        }
+    });
+    if let Some(scope) = scope {
+        report["component"] = scope.into();
     }
+    report
 }
 
-#[cfg(test)]
-mod tests {
-    use std::path::PathBuf;
+/// Show the k-core of the code graph.
+pub fn core(path: &Path, k: usize) -> Result<()> {
+    let result = index_directory(path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let mut core: Vec<_> = graph
+        .k_core(k)
+        .into_iter()
+        .filter_map(|idx| graph.get(idx))
+        .collect();
+
+    if core.is_empty() {
+        println!("No nodes with at least {} neighbors in the {}-core", k, k);
+        return Ok(());
+    }
+
+    core.sort_by(|a, b| a.file.cmp(&b.file).then(a.line_start.cmp(&b.line_start)));
+
+    println!(
+        "{} {} of {} nodes are in the {}-core:\n",
+        "✓".green(),
+        core.len().to_string().cyan(),
+        graph.node_count(),
+        k
+    );
+
+    for node in core {
+        println!(
+            "  {} {} {}",
+            node.kind.to_string().yellow(),
+            node.qualified_name.cyan(),
+            format!("({}:{})", node.file, node.line_start).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Print fan-in/fan-out per node and flag over-coupled functions.
+pub fn metrics(path: &Path, max_fan_in: usize, max_fan_out: usize, limit: usize) -> Result<()> {
+    use comfy_table::Color;
+
+    let result = index_directory(path, IndexOptions::default())?;
+    let graph = result.graph;
+    let limit = limit_or_all(limit);
+
+    let report = graph.fan_in_fan_out(max_fan_in, max_fan_out);
+    if report.entries.is_empty() {
+        println!("No call edges found");
+        return Ok(());
+    }
+
+    println!(
+        "{} Most coupled nodes ({} of {} with calls):
+",
+        "✓".green(),
+        report.entries.len().min(limit),
+        report.entries.len()
+    );
+
+    let mut table = table::new_table(&["Fan-in", "Fan-out", "Name", "Location"]);
+    for entry in report.entries.iter().take(limit) {
+        let count = |value: usize, max: usize| {
+            let color = if value > max { Some(Color::Red) } else { None };
+            (value.to_string(), color)
+        };
+        table.add_row(table::row([
+            count(entry.fan_in, max_fan_in),
+            count(entry.fan_out, max_fan_out),
+            (entry.node.qualified_name.clone(), Some(Color::Cyan)),
+            (
+                format!("{}:{}", entry.node.file, entry.node.line_start),
+                Some(Color::DarkGrey),
+            ),
+        ]));
+    }
+    println!("{}", table::render(&table));
+
+    for (label, flagged, limit_label) in [
+        (
+            "God functions",
+            report.god_functions(),
+            format!("fan-in > {}", max_fan_in),
+        ),
+        (
+            "Brittle orchestrators",
+            report.orchestrators(),
+            format!("fan-out > {}", max_fan_out),
+        ),
+    ] {
+        println!();
+        if flagged.is_empty() {
+            println!(
+                "{} No {} ({})",
+                "✓".green(),
+                label.to_lowercase(),
+                limit_label
+            );
+            continue;
+        }
+        println!(
+            "{} {} ({}): {}",
+            "⚠".yellow(),
+            label,
+            limit_label,
+            flagged.len()
+        );
+        for entry in flagged {
+            println!(
+                "  {} {}",
+                entry.node.qualified_name.cyan(),
+                format!("({}:{})", entry.node.file, entry.node.line_start).dimmed()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Rank functions by centrality, weighted by git churn with `changed`.
+pub fn hotspots(
+    path: &Path,
+    changed: bool,
+    since: Option<&str>,
+    max_commits: usize,
+    limit: usize,
+    json_output: bool,
+) -> Result<()> {
+    use comfy_table::Color;
+
+    let churn = if changed {
+        let churn = git_churn(path, since, max_commits)?;
+        if churn.is_none() {
+            eprintln!(
+                "{} Not a git repository (or git is not installed); ranking by centrality only",
+                "⚠".yellow()
+            );
+        }
+        churn
+    } else {
+        None
+    };
+
+    let result = index_directory(
+        path,
+        IndexOptions {
+            compute_centrality: true,
+            ..Default::default()
+        },
+    )?;
+    let hotspots = result.graph.hotspots(churn.as_ref(), path);
+    let limit = limit_or_all(limit);
+
+    if json_output {
+        let shown: Vec<_> = hotspots.iter().take(limit).collect();
+        println!("{}", serde_json::to_string_pretty(&shown)?);
+        return Ok(());
+    }
+
+    if hotspots.is_empty() {
+        match churn {
+            Some(_) => println!("No functions changed in the selected history"),
+            None => println!("No functions found"),
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} Top hotspots ({} of {}):
+",
+        "✓".green(),
+        hotspots.len().min(limit),
+        hotspots.len()
+    );
+
+    let mut headers = vec!["Score", "Centrality"];
+    if churn.is_some() {
+        headers.extend(["Lines changed", "Commits"]);
+    }
+    headers.extend(["Name", "Location"]);
+
+    let mut table = table::new_table(&headers);
+    for hotspot in hotspots.iter().take(limit) {
+        let mut cells = vec![
+            (format!("{:.2}", hotspot.score), None),
+            (format!("{:.3}", hotspot.node.centrality), None),
+        ];
+        if churn.is_some() {
+            cells.push((hotspot.lines_changed.to_string(), None));
+            cells.push((hotspot.commits.to_string(), None));
+        }
+        cells.push((hotspot.node.qualified_name.clone(), Some(Color::Cyan)));
+        cells.push((
+            format!("{}:{}", hotspot.node.file, hotspot.node.line_start),
+            Some(Color::DarkGrey),
+        ));
+        table.add_row(table::row(cells));
+    }
+    println!("{}", table::render(&table));
+
+    Ok(())
+}
+
+/// Diff the local working tree's graph against a running server's graph.
+pub async fn diff(
+    server_url: &str,
+    token: Option<&str>,
+    impact: Option<&str>,
+    json_output: bool,
+    path: &Path,
+) -> Result<()> {
+    let mut server_graph = arbor_server::fetch_graph(server_url, token)
+        .await
+        .map_err(|e| format!("Could not fetch graph from {}: {}", server_url, e))?;
+    let mut graph = index_directory(path, IndexOptions::default())?.graph;
+
+    // Node IDs hash the path as indexed, so compare root-relative paths
+    graph.strip_root(path);
+    if let Some(root) = indexed_root(&server_graph, &graph) {
+        server_graph.strip_root(&root);
+    }
+
+    if let Some(symbol) = impact {
+        return print_impact_delta(&server_graph.diff_impact(&graph, symbol), json_output);
+    }
+
+    let diff = server_graph.diff(&graph);
+
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} (server) → {} (working tree)",
+        "Diff:".cyan().bold(),
+        server_url,
+        path.display()
+    );
+    if diff.is_empty() {
+        println!("{} No structural changes", "✓".green());
+        return Ok(());
+    }
+
+    for node in &diff.added {
+        println!(
+            "  {} {} ({}) {}",
+            "+".green(),
+            node.qualified_name,
+            node.kind,
+            node.file.dimmed()
+        );
+    }
+    for node in &diff.removed {
+        println!(
+            "  {} {} ({}) {}",
+            "-".red(),
+            node.qualified_name,
+            node.kind,
+            node.file.dimmed()
+        );
+    }
+    for node in &diff.modified {
+        println!(
+            "  {} {} ({}) {}",
+            "~".yellow(),
+            node.qualified_name,
+            node.kind,
+            node.file.dimmed()
+        );
+    }
+    println!();
+    println!(
+        "{} added, {} removed, {} modified | edges: +{} -{}",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.modified.len(),
+        diff.added_edges.len(),
+        diff.removed_edges.len()
+    );
+
+    Ok(())
+}
+
+/// Guesses the root `graph` was indexed from, given a graph of the same
+/// tree with root-relative paths: the directory that most often leaves one
+/// of `relative`'s files when stripped from one of `graph`'s.
+fn indexed_root(
+    graph: &arbor_graph::ArborGraph,
+    relative: &arbor_graph::ArborGraph,
+) -> Option<std::path::PathBuf> {
+    let relative_files: std::collections::HashSet<&str> =
+        relative.nodes().map(|n| n.file.as_str()).collect();
+    let files: std::collections::BTreeSet<&str> = graph.nodes().map(|n| n.file.as_str()).collect();
+
+    let mut counts: std::collections::BTreeMap<&Path, usize> = std::collections::BTreeMap::new();
+    for file in files {
+        let file = Path::new(file);
+        // The outermost match: `src/a.rs` rather than just `a.rs`
+        let root = file.ancestors().skip(1).filter(|ancestor| {
+            file.strip_prefix(ancestor).is_ok_and(|rest| {
+                relative_files.contains(rest.to_string_lossy().replace('\\', "/").as_str())
+            })
+        });
+        if let Some(root) = root.last() {
+            *counts.entry(root).or_default() += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(root, _)| root.to_path_buf())
+}
+
+/// Prints how a symbol's blast radius changed, for `diff --impact`.
+fn print_impact_delta(delta: &arbor_graph::ImpactDelta, json_output: bool) -> Result<()> {
+    if json_output {
+        println!("{}", serde_json::to_string_pretty(delta)?);
+        return Ok(());
+    }
+
+    let count = |n: Option<usize>| n.map_or("not found".to_string(), |n| n.to_string());
+    let change = match delta.delta {
+        d if d < 0 => d.to_string().green(),
+        d if d > 0 => format!("+{}", d).red(),
+        _ => "±0".normal(),
+    };
+    println!(
+        "{} {}: {} (server) → {} (working tree), {}",
+        "Impact:".cyan().bold(),
+        delta.symbol,
+        count(delta.before),
+        count(delta.after),
+        change
+    );
+
+    for node in &delta.added {
+        println!(
+            "  {} {} ({}) {}",
+            "+".red(),
+            node.qualified_name,
+            node.kind,
+            node.file.dimmed()
+        );
+    }
+    for node in &delta.removed {
+        println!(
+            "  {} {} ({}) {}",
+            "-".green(),
+            node.qualified_name,
+            node.kind,
+            node.file.dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Show the innermost symbol defined at `<file>:<line>`.
+pub fn at(location: &str, root: Option<&Path>) -> Result<()> {
+    let (file, line) = location
+        .rsplit_once(':')
+        .and_then(|(file, line)| Some((file, line.parse::<u32>().ok()?)))
+        .ok_or_else(|| format!("expected <file>:<line>, got \"{}\"", location))?;
+
+    let path = project_root(root)?;
+    let result = index_directory(&path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let mut files: Vec<&str> = graph.nodes().map(|n| n.file.as_str()).collect();
+    files.sort_unstable();
+    files.dedup();
+    let indexed = resolve_indexed_file(&files, &path, file)?;
+
+    match indexed.and_then(|indexed| graph.node_at(indexed, line)) {
+        Some(node) => {
+            println!(
+                "{} {} {}",
+                node.kind.to_string().yellow(),
+                node.qualified_name.cyan(),
+                format!("({}:{}-{})", node.file, node.line_start, node.line_end).dimmed()
+            );
+            if let Some(signature) = &node.signature {
+                println!("  {}", signature.dimmed());
+            }
+        }
+        None => println!("{} No symbol found at {}", "⚠".yellow(), location),
+    }
+
+    Ok(())
+}
+
+/// Picks the indexed file that `file` names.
+///
+/// Indexed paths carry the root prefix, so `file` is first resolved on disk,
+/// against the working directory and then `root`. Otherwise the indexed
+/// path ending in `file` is taken; several of those are an error listing
+/// the candidates.
+fn resolve_indexed_file<'a>(files: &[&'a str], root: &Path, file: &str) -> Result<Option<&'a str>> {
+    if let Some(exact) = files.iter().find(|indexed| **indexed == file) {
+        return Ok(Some(exact));
+    }
+
+    let targets = [
+        Path::new(file).canonicalize(),
+        root.join(file).canonicalize(),
+    ];
+    for target in targets.into_iter().flatten() {
+        let same = files
+            .iter()
+            .find(|indexed| Path::new(indexed).canonicalize().ok().as_ref() == Some(&target));
+        if let Some(indexed) = same {
+            return Ok(Some(indexed));
+        }
+    }
+
+    let candidates: Vec<&str> = files
+        .iter()
+        .copied()
+        .filter(|indexed| Path::new(indexed).ends_with(file))
+        .collect();
+    match candidates[..] {
+        [] => Ok(None),
+        [indexed] => Ok(Some(indexed)),
+        _ => Err(format!(
+            "\"{}\" matches {} files, give a longer path:\n  {}",
+            file,
+            candidates.len(),
+            candidates.join("\n  ")
+        )
+        .into()),
+    }
+}
+
+/// List the members of a container symbol as a class outline.
+pub fn members(symbol: &str, root: Option<&Path>) -> Result<()> {
+    let path = project_root(root)?;
+    let result = index_directory(&path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let node_idx = graph.get_index(symbol).or_else(|| {
+        graph
+            .find_by_name(symbol)
+            .first()
+            .and_then(|n| graph.get_index(&n.id))
+    });
+
+    let node_idx = match node_idx {
+        Some(idx) => idx,
+        None => return suggest_similar_symbols(&graph, symbol),
+    };
+
+    let container = graph.get(node_idx).unwrap();
+    let members = graph.members(node_idx);
+
+    println!(
+        "{} {} {}",
+        container.kind.to_string().yellow(),
+        container.qualified_name.cyan().bold(),
+        format!("({}:{})", container.file, container.line_start).dimmed()
+    );
+
+    if members.is_empty() {
+        println!("  {}", "(no members)".dimmed());
+        return Ok(());
+    }
+
+    let mut outline = table::new_table(&["Line", "Kind", "Visibility", "Name", "Signature"]);
+    for member in &members {
+        outline.add_row(table::row([
+            (member.line_start.to_string(), None),
+            (member.kind.to_string(), Some(comfy_table::Color::Yellow)),
+            (format!("{:?}", member.visibility).to_lowercase(), None),
+            (member.name.clone(), Some(comfy_table::Color::Cyan)),
+            (member.signature.clone().unwrap_or_default(), None),
+        ]));
+    }
+    println!("{}", table::render(&outline));
+
+    Ok(())
+}
+
+/// List the tests that transitively call a symbol.
+pub fn affected_tests(symbol: &str, root: Option<&Path>) -> Result<()> {
+    let path = project_root(root)?;
+    let result = index_directory(&path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let node_idx = graph.get_index(symbol).or_else(|| {
+        graph
+            .find_by_name(symbol)
+            .first()
+            .and_then(|n| graph.get_index(&n.id))
+    });
+
+    let node_idx = match node_idx {
+        Some(idx) => idx,
+        None => return suggest_similar_symbols(&graph, symbol),
+    };
+
+    let target = graph.get(node_idx).unwrap();
+    let tests = graph.impacted_tests(node_idx);
+
+    if tests.is_empty() {
+        println!(
+            "{} No tests reach {} through calls",
+            "⚠".yellow(),
+            target.qualified_name.cyan()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {} test(s) cover {}",
+        "✓".green(),
+        tests.len(),
+        target.qualified_name.cyan().bold()
+    );
+    for test in &tests {
+        println!(
+            "  {} {}",
+            test.qualified_name,
+            format!("({}:{})", test.file, test.line_start).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Show how execution reaches a symbol from the nearest entry point.
+pub fn trace(symbol: &str, path: &Path) -> Result<()> {
+    let result = index_directory(path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let node_idx = graph.get_index(symbol).or_else(|| {
+        graph
+            .find_by_name(symbol)
+            .first()
+            .and_then(|n| graph.get_index(&n.id))
+    });
+
+    let node_idx = match node_idx {
+        Some(idx) => idx,
+        None => return suggest_similar_symbols(&graph, symbol),
+    };
+
+    let steps = match graph.path_to_nearest_entrypoint(node_idx) {
+        Some(steps) => steps,
+        None => {
+            println!(
+                "{} No entry point reaches {} (every caller chain is a cycle)",
+                "⚠".yellow(),
+                symbol.cyan()
+            );
+            return Ok(());
+        }
+    };
+
+    if steps.len() == 1 {
+        println!("{} {} is itself an entry point", "✓".green(), symbol.cyan());
+        return Ok(());
+    }
+
+    println!(
+        "{} {} is reached in {} hop{}:\n",
+        "✓".green(),
+        symbol.cyan(),
+        steps.len() - 1,
+        if steps.len() == 2 { "" } else { "s" }
+    );
+
+    for step in &steps {
+        println!(
+            "  {} {}",
+            step.node_info.qualified_name.cyan(),
+            format!("({}:{})", step.node_info.file, step.node_info.line_start).dimmed()
+        );
+        if let Some(edge) = step.edge {
+            println!("    {} {}", "↓".dimmed(), edge.to_string().dimmed());
+        }
+    }
+
+    Ok(())
+}
+
+/// List nodes that cannot be reached from the given entry points.
+pub fn reachable(from: &str, path: &Path) -> Result<()> {
+    let result = index_directory(path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let mut roots = Vec::new();
+    for name in from.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let matched: Vec<_> = match graph.get_index(name) {
+            Some(idx) => vec![idx],
+            None => graph
+                .find_by_name(name)
+                .iter()
+                .filter_map(|n| graph.get_index(&n.id))
+                .collect(),
+        };
+        if matched.is_empty() {
+            return Err(format!("entry point not found: {}", name).into());
+        }
+        roots.extend(matched);
+    }
+
+    if roots.is_empty() {
+        return Err("no entry points given (use --from main,test_main)".into());
+    }
+
+    let live = graph.reachable_from(&roots);
+    let mut unreachable: Vec<_> = graph
+        .node_indexes()
+        .filter(|idx| !live.contains(idx))
+        .filter_map(|idx| graph.get(idx))
+        .collect();
+
+    if unreachable.is_empty() {
+        println!(
+            "{} All {} nodes are reachable from {}",
+            "✓".green(),
+            graph.node_count(),
+            from.cyan()
+        );
+        return Ok(());
+    }
+
+    unreachable.sort_by(|a, b| a.file.cmp(&b.file).then(a.line_start.cmp(&b.line_start)));
+
+    println!(
+        "{} {} of {} nodes are unreachable from {}:\n",
+        "⚠".yellow(),
+        unreachable.len().to_string().cyan(),
+        graph.node_count(),
+        from.cyan()
+    );
+
+    for node in unreachable {
+        println!(
+            "  {} {} {}",
+            node.kind.to_string().yellow(),
+            node.qualified_name.cyan(),
+            format!("({}:{})", node.file, node.line_start).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Show the downstream dependencies two symbols have in common.
+pub fn common(a: &str, b: &str, max_depth: usize, path: &Path) -> Result<()> {
+    let result = index_directory(path, IndexOptions::default())?;
+    let graph = result.graph;
+
+    let resolve = |symbol: &str| {
+        graph.get_index(symbol).or_else(|| {
+            graph
+                .find_by_name(symbol)
+                .first()
+                .and_then(|n| graph.get_index(&n.id))
+        })
+    };
+
+    let a_idx = match resolve(a) {
+        Some(idx) => idx,
+        None => return suggest_similar_symbols(&graph, a),
+    };
+    let b_idx = match resolve(b) {
+        Some(idx) => idx,
+        None => return suggest_similar_symbols(&graph, b),
+    };
+
+    let common = graph.common_dependencies(a_idx, b_idx, max_depth);
+
+    if common.is_empty() {
+        println!(
+            "No shared dependencies between {} and {}",
+            a.cyan(),
+            b.cyan()
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {} and {} share {} dependenc{}:\n",
+        "✓".green(),
+        a.cyan(),
+        b.cyan(),
+        common.len(),
+        if common.len() == 1 { "y" } else { "ies" }
+    );
+
+    for node in common {
+        println!(
+            "  {} {} {}",
+            node.kind.yellow(),
+            node.qualified_name.cyan(),
+            format!("({}:{})", node.file, node.line_start).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// Check the graph's edges against layering rules.
+///
+/// Each violation is printed with the file:line of the offending call
+/// site, and the nodes in between for a transitive (`->>`) rule; any
+/// violation makes the command fail, for CI.
+pub fn lint(rules_path: &Path, path: &Path) -> Result<()> {
+    let text = fs::read_to_string(rules_path)
+        .map_err(|e| format!("cannot read rules file {}: {}", rules_path.display(), e))?;
+    let rules = arbor_graph::parse_layer_rules(&text)
+        .map_err(|e| format!("{}: {}", rules_path.display(), e))?;
+
+    let result = index_directory(path, IndexOptions::default())?;
+    let violations = result.graph.layer_violations(&rules, path);
+
+    if violations.is_empty() {
+        println!(
+            "{} No violations of {} rule{} in {} edges",
+            "✓".green(),
+            rules.len(),
+            if rules.len() == 1 { "" } else { "s" },
+            result.graph.edge_count()
+        );
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!(
+            "{}:{} {} {} {} {}",
+            violation.file,
+            violation.line,
+            violation.from.qualified_name.cyan(),
+            violation.kind.to_string().dimmed(),
+            violation.to.qualified_name.cyan(),
+            format!("({}:{})", violation.to.file, violation.to.line_start).dimmed()
+        );
+        if !violation.via.is_empty() {
+            let via: Vec<&str> = violation
+                .via
+                .iter()
+                .map(|n| n.qualified_name.as_str())
+                .collect();
+            println!("  {} {}", "via".dimmed(), via.join(" -> "));
+        }
+        println!("  {} {}", "violates".red(), violation.rule);
+    }
+
+    Err(format!(
+        "{} layering violation{}",
+        violations.len(),
+        if violations.len() == 1 { "" } else { "s" }
+    )
+    .into())
+}
+
+/// Parse one file and print its extracted nodes, or its syntax tree.
+pub fn parse(file: &Path, tree: bool, max_depth: Option<usize>) -> Result<()> {
+    if !tree {
+        let nodes = arbor_core::parse_file(file)?;
+        for node in &nodes {
+            println!(
+                "  {} {} {}",
+                node.kind.to_string().yellow(),
+                node.qualified_name.cyan(),
+                format!("(lines {}-{})", node.line_start, node.line_end).dimmed()
+            );
+        }
+        println!("\n{} {} nodes", "✓".green(), nodes.len());
+        return Ok(());
+    }
+
+    // Only the <script> blocks of a component are code
+    if arbor_core::parser::is_component_file(file) {
+        let source = fs::read_to_string(file)?;
+        print!("{}", arbor_core::dump_component_tree(&source, max_depth)?);
+        return Ok(());
+    }
+
+    let parser = arbor_core::detect_language(file)
+        .ok_or_else(|| arbor_core::ParseError::UnsupportedLanguage(file.to_path_buf()))?;
+    let source = fs::read_to_string(file)?;
+    print!(
+        "{}",
+        arbor_core::dump_tree(&source, parser.as_ref(), max_depth)?
+    );
+
+    Ok(())
+}
+
+/// Watch for file changes and re-index automatically.
+pub async fn watch(path: &Path) -> Result<()> {
+    use std::time::Duration;
+
+    println!("{}", "👁️  Watch Mode".cyan().bold());
+    println!("Watching: {}", path.display());
+    println!("Press Ctrl+C to stop.\n");
+
+    // Start watching before the initial index so no change slips through
+    let watcher = FileWatcher::new(path)?;
+    let result = index_directory(path, IndexOptions::default())?;
+    println!(
+        "✓ Initial index: {} files, {} nodes",
+        result.files_indexed, result.nodes_extracted
+    );
+    let mut live = LiveIndex::new(path, result.graph);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+
+        let changes = watcher.poll();
+        if changes.is_empty() {
+            continue;
+        }
+        let before = live.graph().stats();
+        if let Err(e) = apply_changes(&mut live, path, &IndexOptions::default(), changes) {
+            eprintln!("⚠ Index error: {}", e);
+            continue;
+        }
+
+        let after = live.graph().stats();
+        if after.node_count != before.node_count || after.files != before.files {
+            println!(
+                "🔄 Updated: {} files, {} nodes (was {} files, {} nodes)",
+                after.files, after.node_count, before.files, before.node_count
+            );
+        }
+    }
+}
+
+/// Applies watcher events to `live`: modified, deleted and renamed files
+/// are reparsed in place, and anything else re-indexes `path`, where the
+/// mtime cache (if `options` has one) keeps only changed files re-parsed.
+///
+/// Returns a summary of what was re-parsed.
+fn apply_changes(
+    live: &mut LiveIndex,
+    path: &Path,
+    options: &IndexOptions,
+    changes: Vec<FileChange>,
+) -> Result<String> {
+    let update = live.apply(changes);
+    if !update.needs_reindex {
+        for (file, error) in &update.errors {
+            eprintln!("⚠ Failed to parse {}: {}", file, error);
+        }
+        return Ok(format!(
+            "{} files re-parsed incrementally, {} renamed, {} removed",
+            update.reparsed, update.renamed, update.removed
+        ));
+    }
+
+    let result = index_directory(path, options.clone())?;
+    let summary = format!(
+        "{} files re-parsed, {} from cache",
+        result.files_indexed, result.cache_hits
+    );
+    live.reset(result.graph);
+    Ok(summary)
+}
+
+/// Watch a directory and keep an exported graph JSON up to date.
+///
+/// Each export carries a `revision` counter that increases by one per
+/// write, so pollers can cheaply tell whether anything changed.
+pub fn watch_export(path: &Path, output: Option<&Path>, debounce_ms: u64) -> Result<()> {
+    let arbor_dir = path.join(".arbor");
+    let output = match output {
+        Some(output) => output.to_path_buf(),
+        None => {
+            fs::create_dir_all(&arbor_dir)?;
+            arbor_dir.join("graph.json")
+        }
+    };
+    let options = IndexOptions {
+        cache_path: Some(arbor_dir.join("cache")),
+        ..Default::default()
+    };
+    let debounce = Duration::from_millis(debounce_ms);
+
+    println!("{}", "👁️  Watch Export".cyan().bold());
+    println!("Watching: {}", path.display());
+    println!("Writing:  {}", output.display());
+    println!("Press Ctrl+C to stop.\n");
+
+    // Start watching before the initial index so no change slips through
+    let watcher = FileWatcher::new(path)?;
+    let mut revision: u64 = 0;
+    let mut live: Option<LiveIndex> = None;
+    let mut changes: Vec<FileChange> = Vec::new();
+
+    loop {
+        let summary = match live.as_mut() {
+            Some(live) => apply_changes(live, path, &options, std::mem::take(&mut changes)),
+            // The mtime cache keeps the initial index incremental too
+            None => index_directory(path, options.clone())
+                .map_err(Into::into)
+                .map(|result| {
+                    let summary = format!(
+                        "{} files re-parsed, {} from cache",
+                        result.files_indexed, result.cache_hits
+                    );
+                    live = Some(LiveIndex::new(path, result.graph));
+                    summary
+                }),
+        };
+        match (summary, live.as_ref()) {
+            (Ok(summary), Some(live)) => {
+                revision += 1;
+                let mut export = graph_export_json(live.graph());
+                export["revision"] = revision.into();
+                write_json_atomic(&output, &export)?;
+                println!(
+                    "{} Revision {}: {} nodes ({})",
+                    "✓".green(),
+                    revision,
+                    live.graph().node_count(),
+                    summary
+                );
+            }
+            (Err(e), _) => eprintln!("⚠ Index error: {}", e),
+            (Ok(_), None) => {}
+        }
+
+        // Block until something changes, then wait for a quiet period
+        loop {
+            if let Some(change) = watcher.recv_timeout(Duration::from_secs(60)) {
+                changes.push(change);
+                break;
+            }
+        }
+        while let Some(change) = watcher.recv_timeout(debounce) {
+            changes.push(change);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        batch_impact_report, check_impact_budget, fence_language, graph_export_json,
+        graph_export_string, group_matches, impact_diagram, indexed_root, init_config,
+        load_graph_file, load_prebuilt_graph, matches_json, parse_symbol_list,
+        resolve_indexed_file, sort_matches, source_snippet, write_json_atomic, DiagramFormat,
+        InitTemplate, QueryGroupBy, QuerySort,
+    };
+    use std::path::PathBuf;
 
     /// Returns the platform-specific bundled visualizer path relative to exe_dir.
     fn get_bundled_visualizer_path(exe_dir: &std::path::Path) -> PathBuf {
@@ -1452,4 +3924,591 @@ mod tests {
             viz_path
         );
     }
+
+    #[test]
+    fn test_impact_budget_exit_behavior() {
+        // No budget never fails
+        assert!(check_impact_budget(1000, None, false).is_ok());
+        assert!(check_impact_budget(1000, None, true).is_ok());
+
+        // At or below the budget passes
+        assert!(check_impact_budget(5, Some(10), false).is_ok());
+        assert!(check_impact_budget(10, Some(10), false).is_ok());
+
+        // Above the budget fails and reports the overflow
+        let err = check_impact_budget(12, Some(10), false).unwrap_err();
+        assert!(err.to_string().contains("2 over"));
+
+        // A timed-out partial count can't pass the gate
+        let err = check_impact_budget(5, Some(10), true).unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn test_source_snippet_line_ranges() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let file = dir.join("lib.rs");
+        std::fs::write(
+            &file,
+            "// header\nfn first() {\n    1\n}\n\nfn second() {}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            source_snippet(&file, 2, 4).unwrap(),
+            "fn first() {\n    1\n}"
+        );
+        assert_eq!(source_snippet(&file, 6, 6).unwrap(), "fn second() {}");
+
+        // File shorter than recorded, or gone, is reported rather than printed
+        assert!(source_snippet(&file, 6, 9).unwrap_err().contains("6 lines"));
+        assert!(source_snippet(&dir.join("missing.rs"), 1, 1).is_err());
+
+        assert_eq!(fence_language("src/lib.rs"), "rs");
+        assert_eq!(fence_language("Makefile"), "");
+    }
+
+    #[test]
+    fn test_batch_impact_deduplicates_overlapping_symbols() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("validate", "validate", NodeKind::Function, "auth.rs"),
+            CodeNode::new("save", "save", NodeKind::Function, "store.rs"),
+            CodeNode::new("handler", "handler", NodeKind::Function, "api.rs")
+                .with_references(vec!["validate".to_string(), "save".to_string()]),
+        ]);
+        let graph = builder.build();
+
+        let symbols = parse_symbol_list("# changed in this release\nvalidate\n\nsave\nmissing\n");
+        assert_eq!(symbols, ["validate", "save", "missing"]);
+
+        let report = batch_impact_report(&graph, &symbols, 5, false);
+
+        let per_symbol = report["symbols"].as_array().unwrap();
+        assert_eq!(per_symbol.len(), 3);
+        assert_eq!(per_symbol[0]["total_affected"], 1);
+        assert_eq!(per_symbol[1]["total_affected"], 1);
+        assert_eq!(per_symbol[2]["found"], false);
+
+        // handler calls both, but is counted once in the union
+        assert_eq!(report["union"]["total_affected"], 1);
+        let handler = &report["union"]["nodes"][0];
+        assert_eq!(handler["name"], "handler");
+        assert_eq!(handler["affected_by"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_load_graph_file_rebuilds_edges_from_json_export() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("validate", "validate", NodeKind::Function, "auth.rs"),
+            CodeNode::new("login", "login", NodeKind::Function, "session.rs")
+                .with_references(vec!["validate".to_string()]),
+        ]);
+        let original = builder.build();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let json = dir.join("graph.json");
+        write_json_atomic(&json, &graph_export_json(&original)).unwrap();
+        let arbg = dir.join("graph.arbg");
+        std::fs::write(&arbg, original.to_snapshot().unwrap()).unwrap();
+
+        for path in [&json, &arbg] {
+            let graph = load_graph_file(path).unwrap();
+            assert_eq!(graph.node_count(), 2);
+            assert_eq!(graph.edge_count(), 1);
+            let validate = graph.get_index(&original.find_by_name("validate")[0].id);
+            let callers = graph.get_callers(validate.unwrap());
+            assert_eq!(callers[0].name, "login");
+        }
+        assert!(load_graph_file(&dir.join("missing.arbg")).is_err());
+    }
+
+    #[test]
+    fn test_atomic_export_never_observed_partial() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let target = dir.join("graph.json");
+
+        // Large enough that a non-atomic write would be observable mid-way
+        let payload: Vec<String> = (0..5000).map(|i| format!("node_{}", i)).collect();
+        write_json_atomic(
+            &target,
+            &serde_json::json!({ "revision": 0, "nodes": payload }),
+        )
+        .unwrap();
+
+        let writer_target = target.clone();
+        let writer = std::thread::spawn(move || {
+            for revision in 1..=50u64 {
+                let doc = serde_json::json!({ "revision": revision, "nodes": payload });
+                write_json_atomic(&writer_target, &doc).unwrap();
+            }
+        });
+
+        let mut last_revision = 0;
+        while !writer.is_finished() {
+            let text = std::fs::read_to_string(&target).unwrap();
+            let doc: serde_json::Value =
+                serde_json::from_str(&text).expect("reader observed malformed JSON");
+            let revision = doc["revision"].as_u64().unwrap();
+            assert!(revision >= last_revision, "revision went backwards");
+            last_revision = revision;
+        }
+        writer.join().unwrap();
+
+        let text = std::fs::read_to_string(&target).unwrap();
+        let doc: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(doc["revision"], 50);
+    }
+
+    #[test]
+    fn test_query_sort_by_name() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        for name in ["validate_user", "create_user", "delete_user"] {
+            graph.add_node(CodeNode::new(name, name, NodeKind::Function, "users.rs"));
+        }
+
+        let mut matches = graph.search("user");
+        sort_matches(&graph, &mut matches, QuerySort::Name);
+
+        let names: Vec<_> = matches.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["create_user", "delete_user", "validate_user"]);
+    }
+
+    #[test]
+    fn test_query_group_by_kind() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "load_user",
+            "load_user",
+            NodeKind::Function,
+            "users.rs",
+        ));
+        graph.add_node(CodeNode::new(
+            "save_user",
+            "save_user",
+            NodeKind::Function,
+            "users.rs",
+        ));
+        graph.add_node(CodeNode::new("User", "User", NodeKind::Class, "models.rs"));
+
+        let matches = graph.search("user");
+        let groups = group_matches(&matches, QueryGroupBy::Kind);
+
+        assert_eq!(groups.len(), 2);
+        let count = |kind: NodeKind| {
+            groups
+                .iter()
+                .find(|(header, _)| *header == kind.to_string())
+                .map(|(_, nodes)| nodes.len())
+        };
+        assert_eq!(count(NodeKind::Function), Some(2));
+        assert_eq!(count(NodeKind::Class), Some(1));
+    }
+
+    #[test]
+    fn test_query_json_matches_table_results() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        for name in ["validate_user", "create_user", "parse_config"] {
+            graph.add_node(
+                CodeNode::new(name, name, NodeKind::Function, "users.rs")
+                    .with_lines(3, 9)
+                    .with_signature(format!("fn {}()", name)),
+            );
+        }
+
+        let mut matches = graph.search("user");
+        sort_matches(&graph, &mut matches, QuerySort::Name);
+
+        let text = serde_json::to_string(&matches_json(&graph, &matches)).unwrap();
+        let parsed: Vec<serde_json::Value> = serde_json::from_str(&text).unwrap();
+
+        let json_ids: Vec<&str> = parsed.iter().map(|v| v["id"].as_str().unwrap()).collect();
+        let table_ids: Vec<&str> = matches.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(json_ids, table_ids);
+
+        assert_eq!(parsed[0]["name"], "create_user");
+        assert_eq!(parsed[0]["qualified_name"], "create_user");
+        assert_eq!(parsed[0]["kind"], "function");
+        assert_eq!(parsed[0]["line_start"], 3);
+        assert_eq!(parsed[0]["signature"], "fn create_user()");
+        assert!(parsed[0]["centrality"].is_number());
+    }
+
+    #[test]
+    fn test_init_flutter_template() {
+        let config = init_config(&[], Some(InitTemplate::Flutter));
+
+        let ignore: Vec<&str> = config["ignore"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert!(ignore.contains(&"build/"));
+        assert!(ignore.contains(&".dart_tool/"));
+        assert_eq!(config["languages"], serde_json::json!(["dart"]));
+
+        // Extra languages are appended once
+        let config = init_config(
+            &["Kotlin".to_string(), "dart".to_string()],
+            Some(InitTemplate::Flutter),
+        );
+        assert_eq!(config["languages"], serde_json::json!(["dart", "kotlin"]));
+    }
+
+    #[test]
+    fn test_impact_diagram_contains_only_affected_nodes() {
+        use arbor_core::{CodeNode, NodeKind};
+        use arbor_graph::{Edge, EdgeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        let mut add =
+            |name: &str| graph.add_node(CodeNode::new(name, name, NodeKind::Function, "app.rs"));
+        let handler = add("handler");
+        let validate = add("validate");
+        let parse = add("parse");
+        let unrelated = add("unrelated");
+        let other = add("other");
+        graph.add_edge(handler, validate, Edge::new(EdgeKind::Calls));
+        graph.add_edge(validate, parse, Edge::new(EdgeKind::Calls));
+        graph.add_edge(unrelated, other, Edge::new(EdgeKind::Calls));
+
+        let analysis = graph.analyze_impact(validate, 5);
+        let dot = impact_diagram(&graph, &analysis, DiagramFormat::Dot);
+
+        let mut declared: Vec<&str> = dot
+            .lines()
+            .filter(|l| l.contains("fillcolor="))
+            .map(|l| l.trim().split('"').nth(1).unwrap())
+            .collect();
+        declared.sort();
+        let mut expected: Vec<&str> = [&analysis.target.id]
+            .into_iter()
+            .chain(
+                analysis
+                    .upstream
+                    .iter()
+                    .chain(&analysis.downstream)
+                    .map(|n| &n.node_info.id),
+            )
+            .map(|s| s.as_str())
+            .collect();
+        expected.sort();
+
+        assert_eq!(declared, expected);
+        assert_eq!(declared.len(), 3);
+        assert_eq!(dot.matches(" -> ").count(), 2);
+    }
+
+    #[test]
+    fn test_tags_file_points_at_definition() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        std::fs::write(
+            dir.join("auth.rs"),
+            "use std::fmt;\n\npub fn validate(token: &str) -> bool {\n    !token.is_empty()\n}\n",
+        )
+        .unwrap();
+
+        let output = dir.join("tags");
+        tags(dir, &output).unwrap();
+        let tags = std::fs::read_to_string(&output).unwrap();
+
+        assert!(tags.starts_with("!_TAG_FILE_FORMAT\t2\t"));
+        let file = dir.join("auth.rs").display().to_string();
+        let line = tags
+            .lines()
+            .find(|l| l.starts_with("validate\t"))
+            .expect("validate should be tagged");
+        assert_eq!(line, format!("validate\t{}\t3;\"\tf\tline:3", file));
+        // Imports aren't definitions
+        assert!(!tags.lines().any(|l| l.starts_with("std::fmt")));
+    }
+
+    fn dot_fixture() -> arbor_graph::ArborGraph {
+        use arbor_core::{CodeNode, NodeKind};
+        use arbor_graph::{Edge, EdgeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        let login = graph.add_node(CodeNode::new(
+            "login",
+            "login",
+            NodeKind::Function,
+            "src\\auth.rs",
+        ));
+        let validate = graph.add_node(CodeNode::new(
+            "validate \"token\"",
+            "validate",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+        graph.add_edge(login, validate, Edge::new(EdgeKind::Calls));
+        graph
+    }
+
+    #[test]
+    fn test_graph_dot_lists_nodes_and_edges() {
+        let graph = dot_fixture();
+        let dot = graph_dot(&graph);
+
+        assert!(dot.starts_with("digraph arbor {\n"));
+        assert!(dot.trim_end().ends_with('}'));
+        // Quotes and backslashes are escaped inside DOT strings
+        assert!(dot.contains(r#"[label="validate \"token\"", tooltip="auth.rs"];"#));
+        assert!(dot.contains(r#"tooltip="src\\auth.rs""#));
+
+        let id = |name: &str| graph.find_by_name(name)[0].id.replace('\\', "\\\\");
+        let (login, validate) = (id("login"), id("validate \"token\""));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [label=\"calls\"];",
+            login, validate
+        )));
+        assert_eq!(dot.matches(" -> ").count(), graph.edge_count());
+    }
+
+    #[test]
+    fn test_render_svg_writes_svg_or_explains_missing_graphviz() {
+        let tmp = tempfile::tempdir().unwrap();
+        let output = tmp.path().join("graph.svg");
+
+        match render_svg(&graph_dot(&dot_fixture()), &output) {
+            Ok(()) => {
+                let svg = std::fs::read_to_string(&output).unwrap();
+                assert!(svg.contains("<svg"));
+                assert!(svg.contains("login"));
+                // Graphviz is installed, so bad input must be reported
+                let broken = tmp.path().join("broken.svg");
+                assert!(render_svg("digraph {", &broken).is_err());
+            }
+            Err(e) => assert!(e.to_string().contains("Graphviz `dot` not found"), "{}", e),
+        }
+    }
+
+    #[test]
+    fn test_resolve_symbol_ignore_case() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "UserService",
+            "UserService",
+            NodeKind::Class,
+            "user.ts",
+        ));
+
+        assert!(resolve_symbol(&graph, "userservice", false).is_none());
+        let found = resolve_symbol(&graph, "userservice", true).unwrap();
+        assert_eq!(graph.get(found).unwrap().name, "UserService");
+    }
+
+    #[test]
+    fn test_query_limit_zero_returns_all_matches() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = arbor_graph::ArborGraph::new();
+        for i in 0..15 {
+            let name = format!("handler_{}", i);
+            graph.add_node(CodeNode::new(&name, &name, NodeKind::Function, "routes.rs"));
+        }
+        graph.add_node(CodeNode::new("main", "main", NodeKind::Function, "main.rs"));
+
+        assert_eq!(
+            top_matches(&graph, "handler", QuerySort::Name, 10).len(),
+            10
+        );
+        assert_eq!(top_matches(&graph, "handler", QuerySort::Name, 0).len(), 15);
+    }
+
+    #[test]
+    fn test_no_index_requires_prebuilt_graph() {
+        use arbor_watcher::{index_directory, IndexOptions};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        std::fs::write(
+            dir.join("lib.rs"),
+            "fn validate() {}\nfn login() { validate(); }\n",
+        )
+        .unwrap();
+
+        // Nothing prebuilt: a clear error instead of an index run
+        let err = load_prebuilt_graph(dir).unwrap_err().to_string();
+        assert!(err.contains("no prebuilt graph or index cache"), "{}", err);
+        assert!(err.contains("arbor index"), "{}", err);
+        assert!(!dir.join(".arbor").exists());
+
+        // What `arbor index` leaves behind is enough
+        let options = IndexOptions {
+            cache_path: Some(dir.join(".arbor").join("cache")),
+            ..Default::default()
+        };
+        index_directory(dir, options).unwrap();
+        let graph = load_prebuilt_graph(dir).unwrap();
+        assert_eq!(graph.find_by_name("validate").len(), 1);
+        assert!(graph.edge_count() > 0);
+        assert!(graph.centrality_computed());
+    }
+
+    #[test]
+    fn test_compact_export_is_valid_and_smaller() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("validate", "auth::validate", NodeKind::Function, "auth.rs")
+                .with_signature("fn validate(token: &str) -> bool"),
+            CodeNode::new("login", "session::login", NodeKind::Function, "session.rs")
+                .with_references(vec!["validate".to_string()]),
+        ]);
+        let graph = builder.build();
+
+        let pretty = graph_export_string(&graph, true).unwrap();
+        let compact = graph_export_string(&graph, false).unwrap();
+        assert!(compact.len() < pretty.len());
+        assert!(!compact.contains('\n'));
+
+        // Same document either way
+        let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(compact, pretty);
+        assert_eq!(compact["nodes"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_indexed_root_aligns_server_paths() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let graph = |files: &[&str]| {
+            let mut builder = arbor_graph::GraphBuilder::new();
+            builder.add_nodes(
+                files
+                    .iter()
+                    .map(|file| CodeNode::new("f", "f", NodeKind::Function, file))
+                    .collect(),
+            );
+            builder.build()
+        };
+        // The working tree has a top-level lib.rs too, so `src/lib.rs`
+        // must not be taken as `lib.rs` under /srv/repo/src
+        let server = graph(&[
+            "/srv/repo/src/lib.rs",
+            "/srv/repo/lib.rs",
+            "/srv/repo/build.rs",
+        ]);
+        let local = graph(&["src/lib.rs", "lib.rs", "build.rs"]);
+
+        assert_eq!(
+            indexed_root(&server, &local),
+            Some(PathBuf::from("/srv/repo"))
+        );
+        assert_eq!(indexed_root(&server, &graph(&["other.rs"])), None);
+    }
+
+    #[test]
+    fn test_resolve_indexed_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        for file in ["a/mod.rs", "b/mod.rs"] {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "fn f() {}\n").unwrap();
+        }
+        let (a, b) = (
+            dir.join("a/mod.rs").to_string_lossy().to_string(),
+            dir.join("b/mod.rs").to_string_lossy().to_string(),
+        );
+        let files = [a.as_str(), b.as_str()];
+
+        // Resolved against the root on disk, not by the first suffix match
+        let resolved = resolve_indexed_file(&files, dir, "b/mod.rs").unwrap();
+        assert_eq!(resolved, Some(b.as_str()));
+        assert_eq!(
+            resolve_indexed_file(&files, dir, &a).unwrap(),
+            Some(a.as_str())
+        );
+        assert_eq!(resolve_indexed_file(&files, dir, "c.rs").unwrap(), None);
+
+        // A bare suffix that isn't on disk under the root is ambiguous
+        let err = resolve_indexed_file(&files, dir, "mod.rs").unwrap_err();
+        assert!(err.to_string().contains(&a) && err.to_string().contains(&b));
+    }
+
+    #[test]
+    fn test_batch_impact_component_skips_other_services() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new(
+                "validate",
+                "validate",
+                NodeKind::Function,
+                "billing/auth.rs",
+            ),
+            CodeNode::new("charge", "charge", NodeKind::Function, "billing/api.rs")
+                .with_references(vec!["validate".to_string()]),
+            CodeNode::new("rank", "rank", NodeKind::Function, "search/rank.rs"),
+            CodeNode::new("query", "query", NodeKind::Function, "search/api.rs")
+                .with_references(vec!["rank".to_string()]),
+        ]);
+        let graph = builder.build();
+        let symbols = ["validate".to_string(), "rank".to_string()];
+
+        let report = batch_impact_report(&graph, &symbols, 5, true);
+        assert_eq!(report["symbols"][0]["total_affected"], 1);
+        assert_eq!(report["symbols"][1]["outside_component"], true);
+        assert_eq!(report["union"]["total_affected"], 1);
+        assert_eq!(report["union"]["nodes"][0]["name"], "charge");
+
+        // Without the filter both services are analyzed
+        let report = batch_impact_report(&graph, &symbols, 5, false);
+        assert_eq!(report["union"]["total_affected"], 2);
+        assert!(report.get("component").is_none());
+    }
+
+    #[test]
+    fn test_explain_component_drops_other_services() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new(
+                "validate",
+                "validate",
+                NodeKind::Function,
+                "billing/auth.rs",
+            ),
+            CodeNode::new("charge", "charge", NodeKind::Function, "billing/api.rs")
+                .with_references(vec!["validate".to_string()]),
+            CodeNode::new("rank", "rank", NodeKind::Function, "search/rank.rs"),
+        ]);
+        let graph = builder.build();
+        let questions = [
+            "charge".to_string(),
+            "rank".to_string(),
+            "validate".to_string(),
+        ];
+        let roots: Vec<_> = questions
+            .iter()
+            .map(|q| resolve_symbol(&graph, q, false).unwrap())
+            .collect();
+
+        let (kept, dropped) = keep_first_component(&graph, &questions, roots.clone());
+        assert_eq!(kept, [roots[0], roots[2]]);
+        assert_eq!(dropped, [&questions[1]]);
+    }
 }
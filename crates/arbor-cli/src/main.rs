@@ -3,12 +3,14 @@
 //! This is the main entry point for users interacting with Arbor.
 //! It provides commands for indexing, querying, and serving the code graph.
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use colored::Colorize;
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod commands;
+mod table;
 
 #[derive(Parser)]
 #[command(name = "arbor")]
@@ -20,6 +22,14 @@ struct Cli {
     #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// When to use colors in output
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    color: clap::ColorChoice,
+
+    /// Write logs to stderr as JSON lines, for log pipelines
+    #[arg(long, global = true)]
+    log_json: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -31,6 +41,14 @@ enum Commands {
         /// Path to initialize (defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
+
+        /// Language to index (repeatable)
+        #[arg(long = "language", value_name = "LANG")]
+        languages: Vec<String>,
+
+        /// Ecosystem preset for languages and ignore lists
+        #[arg(long, value_enum)]
+        template: Option<commands::InitTemplate>,
     },
 
     /// Index the codebase and build the graph
@@ -50,6 +68,35 @@ enum Commands {
         /// Disable caching (force full re-index)
         #[arg(long)]
         no_cache: bool,
+
+        /// List what would be indexed (files per language) without parsing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip vendored/generated directories (node_modules, vendor, target, ...)
+        #[arg(long)]
+        no_vendored: bool,
+
+        /// Number of parser threads (defaults to available cores)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Print time spent in each indexing phase
+        #[arg(long)]
+        profile: bool,
+
+        /// Use the cache and report hits, re-parsed and removed files
+        #[arg(long, conflicts_with = "no_cache")]
+        incremental: bool,
+
+        /// Also index TOML and YAML config files (sections and keys)
+        #[arg(long)]
+        config_files: bool,
+
+        /// Index only the files listed in FILE (one path per line, `-` for
+        /// stdin), taking the rest from the cache instead of walking
+        #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+        files_from: Option<PathBuf>,
     },
 
     /// Search the code graph
@@ -57,9 +104,26 @@ enum Commands {
         /// Search query
         query: String,
 
-        /// Maximum results to return
+        /// Maximum results to return (0 = unlimited)
         #[arg(short, long, default_value = "10")]
         limit: usize,
+
+        /// Order results before applying --limit
+        #[arg(long, value_enum, default_value = "relevance")]
+        sort: commands::QuerySort,
+
+        /// Print matches under per-file or per-kind headers
+        #[arg(long, value_enum)]
+        group_by: Option<commands::QueryGroupBy>,
+
+        /// Output as a JSON array instead of a table
+        #[arg(long, conflicts_with = "group_by")]
+        json: bool,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
     },
 
     /// Start the Arbor server
@@ -79,14 +143,107 @@ enum Commands {
         /// Follow symbolic links when walking directories
         #[arg(long)]
         follow_symlinks: bool,
+
+        /// Skip vendored/generated directories (node_modules, vendor, target, ...)
+        #[arg(long)]
+        no_vendored: bool,
+
+        /// Also index TOML and YAML config files (sections and keys)
+        #[arg(long)]
+        config_files: bool,
+
+        /// Serve Prometheus metrics at /metrics
+        #[arg(long)]
+        metrics: bool,
+
+        /// Port for the metrics endpoint (with --metrics)
+        #[arg(long, default_value = "9464")]
+        metrics_port: u16,
+
+        /// Serve graph structure only; never return file contents
+        /// (implied by --headless)
+        #[arg(long)]
+        read_only_fs: bool,
+
+        /// PEM certificate chain; serves wss:// (with --tls-key)
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+
+        /// PEM private key for --tls-cert
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+
+        /// Print time spent in each phase of the initial index
+        #[arg(long)]
+        profile: bool,
+
+        /// Re-index (incrementally) every N seconds, even without file
+        /// events; 0 disables
+        #[arg(long, value_name = "SECS", default_value = "0")]
+        reindex_interval: u64,
+
+        /// Serve a prebuilt graph (.arbg or .json from `arbor export`)
+        /// instead of indexing PATH. It is served as-is: re-indexing would
+        /// replace it with PATH's graph, so it can't be combined with
+        /// --reindex-interval
+        #[arg(long, value_name = "FILE", conflicts_with = "reindex_interval")]
+        graph: Option<PathBuf>,
+
+        /// Browser origins allowed to connect, comma-separated, or `*`
+        /// (default: localhost pages only)
+        #[arg(long, value_name = "ORIGINS", value_delimiter = ',')]
+        cors: Option<Vec<String>>,
+
+        /// Close connections idle for N seconds; 0 disables
+        #[arg(long, value_name = "SECS", default_value = "0")]
+        idle_timeout: u64,
+
+        /// On Ctrl+C, write the served graph to FILE as an .arbg snapshot
+        /// for a later `--graph FILE`
+        #[arg(long, value_name = "FILE")]
+        snapshot_on_exit: Option<PathBuf>,
+
+        /// Token clients must send to graph.export and graph.import
+        /// (default: $ARBOR_TOKEN); without one, --headless refuses both
+        #[arg(long, value_name = "TOKEN")]
+        auth_token: Option<String>,
     },
 
-    /// Export the graph to JSON
+    /// Export the graph to JSON, a matrix, DOT, SVG or an .arbg snapshot
     Export {
         /// Output file
         #[arg(short, long, default_value = "arbor-graph.json")]
         output: PathBuf,
 
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: commands::ExportFormat,
+
+        /// Indent JSON output; `--pretty=false` writes compact JSON, about
+        /// half the size, for feeding other tools
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        pretty: bool,
+
+        /// Open the exported file in the system's default viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Drop import nodes, linking each symbol that uses an import
+        /// straight to the definition it names
+        #[arg(long)]
+        no_imports: bool,
+
+        /// Path to index (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Write a ctags-format tags file for editor jump-to-definition
+    Tags {
+        /// Output file
+        #[arg(short, long, default_value = "tags")]
+        output: PathBuf,
+
         /// Path to index (defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
@@ -101,6 +258,15 @@ enum Commands {
         /// List all indexed files
         #[arg(long)]
         files: bool,
+
+        /// List files whose symbols are all imports or unconnected
+        #[arg(long)]
+        orphans: bool,
+
+        /// Report how many references resolved to edges, and the most
+        /// common names that didn't
+        #[arg(long)]
+        resolution: bool,
     },
 
     /// Start the Arbor Visualizer
@@ -127,11 +293,20 @@ enum Commands {
         /// Follow symbolic links when walking directories
         #[arg(long)]
         follow_symlinks: bool,
+
+        /// On Ctrl+C or when the client disconnects, write the graph to
+        /// FILE as an .arbg snapshot
+        #[arg(long, value_name = "FILE")]
+        snapshot_on_exit: Option<PathBuf>,
     },
 
     /// Check system health and environment
-    #[command(hide = true)]
-    CheckHealth,
+    #[command(hide = true, alias = "doctor")]
+    CheckHealth {
+        /// Also time parsing a few files per language and flag slow ones
+        #[arg(long)]
+        bench_parse: bool,
+    },
 
     /// Preview blast radius before refactoring a node
     Refactor {
@@ -149,17 +324,64 @@ enum Commands {
         /// Output as JSON instead of formatted text
         #[arg(long)]
         json: bool,
+
+        /// Exit nonzero if more than N nodes are affected (for CI gates),
+        /// or if --timeout cut the analysis short
+        #[arg(long, value_name = "N")]
+        impact_budget: Option<usize>,
+
+        /// Emit the blast radius as a diagram, Markdown brief or terse LLM
+        /// summary instead of text
+        #[arg(long, alias = "format", value_enum, conflicts_with = "json")]
+        output: Option<commands::DiagramFormat>,
+
+        /// Only show and count affected nodes at least this severe
+        /// (direct, transitive or distant; default: all)
+        #[arg(long, value_name = "SEVERITY")]
+        min_severity: Option<arbor_graph::ImpactSeverity>,
+
+        /// Only show and count public affected nodes (externally observable breakage)
+        #[arg(long)]
+        public_only: bool,
+
+        /// Affected nodes above which confidence drops to Medium
+        #[arg(long, value_name = "N", default_value = "20")]
+        large_impact: usize,
+
+        /// Affected nodes above which confidence drops to Low
+        #[arg(long, value_name = "N", default_value = "50")]
+        very_large_impact: usize,
+
+        /// Match the symbol name ignoring case (an exact-case match still wins)
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+
+        /// Stop the impact traversal after MS milliseconds and report the
+        /// partial result, flagged as timed out
+        #[arg(long, value_name = "MS")]
+        timeout: Option<u64>,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
     },
 
     /// Explain code using graph-backed context
     Explain {
-        /// The question or code path to explain
-        question: String,
+        /// Symbols to explain; several are combined into one context
+        #[arg(required = true)]
+        question: Vec<String>,
 
         /// Maximum tokens for context (default: 4000)
         #[arg(short, long, default_value = "4000")]
         tokens: usize,
 
+        /// Maximum hops from the symbol to include (0 = unlimited). Depth
+        /// bounds how far the context reaches; --tokens still caps the total
+        #[arg(short, long, default_value = "2")]
+        depth: usize,
+
         /// Show detailed reasoning for context selection
         #[arg(long)]
         why: bool,
@@ -167,6 +389,39 @@ enum Commands {
         /// Output as JSON instead of formatted text
         #[arg(long)]
         json: bool,
+
+        /// Print the source of each context node
+        #[arg(long)]
+        include_source: bool,
+
+        /// Only follow these edge kinds when slicing (comma-separated,
+        /// e.g. calls,inherits; default: all)
+        #[arg(long, value_delimiter = ',', value_name = "KINDS")]
+        edge_kinds: Vec<arbor_graph::EdgeKind>,
+
+        /// Match the symbol name ignoring case (an exact-case match still wins)
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+
+        /// Never index: use .arbor/graph.arbg, .arbor/graph.json or the
+        /// index cache, and fail if none exists
+        #[arg(long)]
+        no_index: bool,
+
+        /// Keep the slice within the connected component of the first
+        /// symbol, dropping symbols that live elsewhere
+        #[arg(long)]
+        component: bool,
+
+        /// Stop collecting context after MS milliseconds and return the
+        /// nodes gathered so far, flagged as timed out
+        #[arg(long, value_name = "MS")]
+        timeout: Option<u64>,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
     },
 
     /// Launch the graphical interface
@@ -186,46 +441,358 @@ enum Commands {
         path: PathBuf,
     },
 
+    /// Combined JSON impact report for several changed symbols
+    Impact {
+        /// Symbols to analyze (function name, class name, or qualified path)
+        #[arg(required_unless_present = "batch")]
+        symbols: Vec<String>,
+
+        /// Read symbols from FILE, one per line (# starts a comment)
+        #[arg(long, value_name = "FILE")]
+        batch: Option<PathBuf>,
+
+        /// Maximum depth to search (default: 5)
+        #[arg(short, long, default_value = "5")]
+        depth: usize,
+
+        /// Stay within the connected component of the first symbol;
+        /// symbols elsewhere (another service in a monorepo) are skipped
+        #[arg(long)]
+        component: bool,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+
+    /// Show the densely interconnected core of the codebase (k-core)
+    Core {
+        /// Minimum number of distinct neighbors each core node must have
+        #[arg(short, default_value = "2")]
+        k: usize,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Report fan-in/fan-out and flag over-coupled functions
+    Metrics {
+        /// Flag nodes with more distinct callers than this
+        #[arg(long, default_value = "10")]
+        max_fan_in: usize,
+
+        /// Flag nodes with more distinct callees than this
+        #[arg(long, default_value = "10")]
+        max_fan_out: usize,
+
+        /// Number of most-coupled nodes to list (0 = unlimited)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Rank functions by risk: centrality, combined with git churn
+    Hotspots {
+        /// Weight centrality by lines changed in git history, so only
+        /// central code that also changes often ranks high
+        #[arg(long)]
+        changed: bool,
+
+        /// Count churn in commits after this ref (e.g. main, v1.2.0)
+        #[arg(long, requires = "changed")]
+        since: Option<String>,
+
+        /// Read at most this many commits of history (0 = unlimited)
+        #[arg(long, default_value = "100", requires = "changed")]
+        commits: usize,
+
+        /// Number of hotspots to list (0 = unlimited)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Diff the working tree's graph against a running server's
+    Diff {
+        /// WebSocket URL of the server, e.g. ws://127.0.0.1:7432
+        #[arg(long)]
+        against_server: String,
+
+        /// Auth token, if the server requires one for snapshots
+        /// (default: $ARBOR_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+
+        /// Compare this symbol's blast radius instead of the whole structure
+        #[arg(long, value_name = "SYMBOL")]
+        impact: Option<String>,
+
+        /// Output as JSON instead of formatted text
+        #[arg(long)]
+        json: bool,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Show the symbol defined at a file position (e.g. src/lib.rs:42)
+    At {
+        /// Position as <file>:<line>
+        location: String,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+
+    /// List the members of a class, struct, enum or interface
+    Members {
+        /// The container symbol (name or ID)
+        symbol: String,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+
+    /// List the tests that transitively call a symbol
+    AffectedTests {
+        /// The symbol whose covering tests to find (name or ID)
+        symbol: String,
+
+        /// Project root to index (defaults to the nearest ancestor with
+        /// .arbor/, .git/ or a manifest file)
+        #[arg(long)]
+        root: Option<PathBuf>,
+    },
+
+    /// Show the call path from the nearest entry point to a symbol
+    Trace {
+        /// The symbol to trace (function name or qualified path)
+        symbol: String,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// List nodes unreachable from the given entry points (dead-code candidates)
+    Reachable {
+        /// Live entry points (comma-separated names or IDs)
+        #[arg(long, default_value = "main")]
+        from: String,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Show downstream dependencies shared by two symbols
+    Common {
+        /// First symbol
+        a: String,
+
+        /// Second symbol
+        b: String,
+
+        /// Maximum depth to search (0 = unlimited)
+        #[arg(short, long, default_value = "5")]
+        depth: usize,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Check dependencies against architecture rules (exits nonzero on violations)
+    Lint {
+        /// Rules file, one `forbid: ui/** -> db/**` or `allow: ...` per line
+        /// (`->>` also forbids indirect paths)
+        #[arg(long, default_value = ".arbor/rules")]
+        rules: PathBuf,
+
+        /// Path to analyze (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Parse a single file and show what the extractor sees
+    Parse {
+        /// Source file to parse
+        file: PathBuf,
+
+        /// Print the Tree-sitter syntax tree instead of extracted nodes
+        /// (for Vue/Svelte, the trees of their <script> blocks)
+        #[arg(long)]
+        tree: bool,
+
+        /// Stop the tree dump below this depth
+        #[arg(long, requires = "tree")]
+        max_depth: Option<usize>,
+    },
+
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
     /// Watch for file changes and re-index automatically
     Watch {
         /// Path to watch (defaults to current directory)
         #[arg(default_value = ".")]
         path: PathBuf,
     },
+
+    /// Keep an exported graph JSON fresh, rewriting it atomically on change
+    WatchExport {
+        /// Path to watch (defaults to current directory)
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output file (defaults to .arbor/graph.json under the watched path)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Quiet period after the last change before re-exporting
+        #[arg(long, default_value = "500")]
+        debounce_ms: u64,
+    },
 }
 
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    table::set_color(cli.color);
 
-    // Set up logging
+    // Set up logging. Both formats write to stderr so `bridge` keeps
+    // stdout for MCP messages.
     let filter = if cli.verbose { "debug" } else { "info" };
     tracing_subscriber::registry()
-        .with(
+        .with(cli.log_json.then(|| json_log_layer(std::io::stderr)))
+        .with((!cli.log_json).then(|| {
             tracing_subscriber::fmt::layer()
                 .with_writer(std::io::stderr)
-                .with_target(false),
-        )
+                .with_target(false)
+        }))
         .with(tracing_subscriber::EnvFilter::new(filter))
         .init();
 
     let result = match cli.command {
-        Commands::Init { path } => commands::init(&path),
+        Commands::Init {
+            path,
+            languages,
+            template,
+        } => commands::init(&path, &languages, template),
         Commands::Index {
             path,
             output,
             follow_symlinks,
             no_cache,
-        } => commands::index(&path, output.as_deref(), follow_symlinks, no_cache),
-        Commands::Query { query, limit } => commands::query(&query, limit),
+            dry_run,
+            no_vendored,
+            jobs,
+            profile,
+            incremental,
+            config_files,
+            files_from,
+        } => commands::index(
+            &path,
+            output.as_deref(),
+            follow_symlinks,
+            no_cache,
+            dry_run,
+            no_vendored,
+            jobs,
+            profile,
+            incremental,
+            config_files,
+            files_from.as_deref(),
+        ),
+        Commands::Query {
+            query,
+            limit,
+            sort,
+            group_by,
+            json,
+            root,
+        } => commands::query(&query, limit, sort, group_by, json, root.as_deref()),
         Commands::Serve {
             port,
             headless,
             path,
             follow_symlinks,
-        } => commands::serve(port, headless, &path, follow_symlinks).await,
-        Commands::Export { output, path } => commands::export(&path, &output),
-        Commands::Status { path, files } => commands::status(&path, files),
+            no_vendored,
+            config_files,
+            metrics,
+            metrics_port,
+            read_only_fs,
+            tls_cert,
+            tls_key,
+            profile,
+            reindex_interval,
+            graph,
+            cors,
+            idle_timeout,
+            snapshot_on_exit,
+            auth_token,
+        } => {
+            let metrics_port = metrics.then_some(metrics_port);
+            let auth_token = auth_token.or_else(|| std::env::var("ARBOR_TOKEN").ok());
+            let tls = tls_cert
+                .zip(tls_key)
+                .map(|(cert, key)| arbor_server::TlsConfig { cert, key });
+            commands::serve(
+                port,
+                headless,
+                &path,
+                follow_symlinks,
+                no_vendored,
+                config_files,
+                metrics_port,
+                read_only_fs,
+                tls,
+                profile,
+                Duration::from_secs(reindex_interval),
+                graph.as_deref(),
+                cors,
+                (idle_timeout > 0).then_some(Duration::from_secs(idle_timeout)),
+                snapshot_on_exit.as_deref(),
+                auth_token,
+            )
+            .await
+        }
+        Commands::Export {
+            output,
+            format,
+            pretty,
+            open,
+            no_imports,
+            path,
+        } => commands::export(&path, &output, format, pretty, open, no_imports),
+        Commands::Tags { output, path } => commands::tags(&path, &output),
+        Commands::Status {
+            path,
+            files,
+            orphans,
+            resolution,
+        } => commands::status(&path, files, orphans, resolution),
         Commands::Viz {
             path,
             follow_symlinks,
@@ -234,23 +801,133 @@ async fn main() {
             path,
             viz,
             follow_symlinks,
-        } => commands::bridge(&path, viz, follow_symlinks).await,
-        Commands::CheckHealth => commands::check_health().await,
+            snapshot_on_exit,
+        } => commands::bridge(&path, viz, follow_symlinks, snapshot_on_exit.as_deref()).await,
+        Commands::CheckHealth { bench_parse } => commands::check_health(bench_parse).await,
         Commands::Refactor {
             target,
             depth,
             why,
             json,
-        } => commands::refactor(&target, depth, why, json),
+            impact_budget,
+            output,
+            min_severity,
+            public_only,
+            large_impact,
+            very_large_impact,
+            ignore_case,
+            timeout,
+            root,
+        } => commands::refactor(
+            &target,
+            depth,
+            why,
+            json,
+            impact_budget,
+            output,
+            min_severity,
+            public_only,
+            arbor_graph::ConfidenceConfig {
+                large_blast_radius: large_impact,
+                very_large_blast_radius: very_large_impact,
+                ..Default::default()
+            },
+            ignore_case,
+            timeout,
+            root.as_deref(),
+        ),
         Commands::Explain {
             question,
             tokens,
+            depth,
+            why,
+            json,
+            include_source,
+            edge_kinds,
+            ignore_case,
+            no_index,
+            component,
+            timeout,
+            root,
+        } => commands::explain(
+            &question,
+            tokens,
+            depth,
             why,
             json,
-        } => commands::explain(&question, tokens, why, json),
+            include_source,
+            &edge_kinds,
+            ignore_case,
+            no_index,
+            component,
+            timeout,
+            root.as_deref(),
+        ),
         Commands::Gui { path } => commands::gui(&path),
         Commands::PrSummary { symbols, path } => commands::pr_summary(&symbols, &path),
+        Commands::Impact {
+            symbols,
+            batch,
+            depth,
+            component,
+            root,
+        } => commands::impact(symbols, batch.as_deref(), depth, component, root.as_deref()),
+        Commands::Core { k, path } => commands::core(&path, k),
+        Commands::Metrics {
+            max_fan_in,
+            max_fan_out,
+            limit,
+            path,
+        } => commands::metrics(&path, max_fan_in, max_fan_out, limit),
+        Commands::Hotspots {
+            changed,
+            since,
+            commits,
+            limit,
+            json,
+            path,
+        } => commands::hotspots(&path, changed, since.as_deref(), commits, limit, json),
+        Commands::Diff {
+            against_server,
+            token,
+            impact,
+            json,
+            path,
+        } => {
+            let token = token.or_else(|| std::env::var("ARBOR_TOKEN").ok());
+            commands::diff(
+                &against_server,
+                token.as_deref(),
+                impact.as_deref(),
+                json,
+                &path,
+            )
+            .await
+        }
+        Commands::At { location, root } => commands::at(&location, root.as_deref()),
+        Commands::Members { symbol, root } => commands::members(&symbol, root.as_deref()),
+        Commands::AffectedTests { symbol, root } => {
+            commands::affected_tests(&symbol, root.as_deref())
+        }
+        Commands::Trace { symbol, path } => commands::trace(&symbol, &path),
+        Commands::Reachable { from, path } => commands::reachable(&from, &path),
+        Commands::Common { a, b, depth, path } => commands::common(&a, &b, depth, &path),
+        Commands::Lint { rules, path } => commands::lint(&rules, &path),
+        Commands::Parse {
+            file,
+            tree,
+            max_depth,
+        } => commands::parse(&file, tree, max_depth),
+        Commands::Completions { shell } => {
+            write_completions(shell, &mut std::io::stdout());
+            Ok(())
+        }
         Commands::Watch { path } => commands::watch(&path).await,
+        Commands::WatchExport {
+            path,
+            output,
+            debounce_ms,
+        } => commands::watch_export(&path, output.as_deref(), debounce_ms),
     };
 
     if let Err(e) = result {
@@ -258,3 +935,88 @@ async fn main() {
         std::process::exit(1);
     }
 }
+
+/// Formats log events as one JSON object per line, with `level`,
+/// `message` and any other fields at the top level.
+fn json_log_layer<S, W>(writer: W) -> impl tracing_subscriber::Layer<S>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_target(false)
+        .with_writer(writer)
+}
+
+/// Writes the completion script for `shell`, generated from the `Cli` definition.
+fn write_completions(shell: clap_complete::Shell, out: &mut dyn std::io::Write) {
+    clap_complete::generate(shell, &mut Cli::command(), "arbor", out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_completions() {
+        let mut out = Vec::new();
+        write_completions(clap_complete::Shell::Bash, &mut out);
+
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("arbor"));
+        assert!(script.contains("refactor"));
+    }
+
+    #[test]
+    fn test_serve_graph_conflicts_with_reindex_interval() {
+        let parse = |args: &[&str]| Cli::try_parse_from(["arbor", "serve"].iter().chain(args));
+        assert!(parse(&["--graph", "g.arbg"]).is_ok());
+        assert!(parse(&["--reindex-interval", "30"]).is_ok());
+        let err = parse(&["--graph", "g.arbg", "--reindex-interval", "30"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_json_log_lines_parse() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber =
+            tracing_subscriber::registry().with(json_log_layer(move || writer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("server started");
+            tracing::warn!(port = 7432, "port in use");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["message"], "server started");
+        assert_eq!(lines[1]["level"], "WARN");
+        assert_eq!(lines[1]["port"], 7432);
+    }
+}
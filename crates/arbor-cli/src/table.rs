@@ -0,0 +1,100 @@
+//! Aligned terminal tables for CLI output.
+//!
+//! Wraps comfy-table so every command lays out columns the same way.
+//! Color follows the global `--color` flag; MCP output is unaffected and
+//! keeps its markdown tables.
+
+use clap::ColorChoice;
+use comfy_table::{presets, Cell, Color, ContentArrangement, Table};
+use std::sync::OnceLock;
+
+static COLOR: OnceLock<ColorChoice> = OnceLock::new();
+
+/// Applies the `--color` choice to tables and to `colored` output.
+///
+/// Call once at startup; later calls are ignored.
+pub fn set_color(choice: ColorChoice) {
+    match choice {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {}
+    }
+    let _ = COLOR.set(choice);
+}
+
+/// Creates a borderless table with the given column headers.
+pub fn new_table(header: &[&str]) -> Table {
+    let mut table = Table::new();
+    table
+        .load_preset(presets::NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled)
+        .set_header(header.iter().map(|h| Cell::new(h).fg(Color::DarkGrey)));
+
+    match COLOR.get().copied().unwrap_or(ColorChoice::Auto) {
+        ColorChoice::Always => {
+            table.enforce_styling();
+        }
+        ColorChoice::Never => {
+            table.force_no_tty();
+        }
+        ColorChoice::Auto => {}
+    }
+
+    table
+}
+
+/// Builds a table row of plain and colored cells.
+pub fn row<I>(cells: I) -> Vec<Cell>
+where
+    I: IntoIterator<Item = (String, Option<Color>)>,
+{
+    cells
+        .into_iter()
+        .map(|(text, color)| match color {
+            Some(color) => Cell::new(text).fg(color),
+            None => Cell::new(text),
+        })
+        .collect()
+}
+
+/// Renders a table, indented to sit under a command's heading.
+pub fn render(table: &Table) -> String {
+    table
+        .lines()
+        .map(|line| format!("  {}", line.trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_align_for_varying_widths() {
+        let mut table = new_table(&["Kind", "Name", "Location"]);
+        table.force_no_tty();
+        table.add_row(row([
+            ("function".to_string(), None),
+            ("a".to_string(), None),
+            ("src/lib.rs:1".to_string(), None),
+        ]));
+        table.add_row(row([
+            ("struct".to_string(), None),
+            ("VeryLongTypeName".to_string(), None),
+            ("x.rs:10".to_string(), None),
+        ]));
+
+        let output = render(&table);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+
+        // The third column starts at the same offset on every line
+        let offsets: Vec<usize> = ["Location", "src/lib.rs:1", "x.rs:10"]
+            .iter()
+            .zip(&lines)
+            .map(|(cell, line)| line.find(cell).unwrap())
+            .collect();
+        assert!(offsets.iter().all(|&o| o == offsets[0]));
+    }
+}
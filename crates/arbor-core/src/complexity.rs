@@ -0,0 +1,231 @@
+//! Cyclomatic complexity estimates.
+//!
+//! We don't build control flow graphs. Instead we count decision points
+//! in a function's syntax subtree: branches, loops, `match`/`switch`,
+//! `catch` clauses, ternaries and short-circuit `&&`/`||`. Node kinds are
+//! shared across most Tree-sitter grammars, so one list covers every
+//! supported language well enough for ranking risky code.
+
+use crate::node::{CodeNode, NodeKind};
+use tree_sitter::{Node, Tree};
+
+/// Complexity at or above which a function is considered hard to change.
+pub const HIGH_COMPLEXITY: u32 = 10;
+
+/// Syntax node kinds that each add one decision point.
+const DECISION_KINDS: &[&str] = &[
+    // Branches
+    "if_statement",
+    "if_expression",
+    "elif_clause",
+    "if",
+    "unless",
+    "if_modifier",
+    "unless_modifier",
+    "conditional_expression",
+    "ternary_expression",
+    // Loops
+    "for_statement",
+    "for_in_statement",
+    "for_expression",
+    "for_range_loop",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "for_each_statement",
+    "while_statement",
+    "while_expression",
+    "loop_expression",
+    "do_statement",
+    "while",
+    "until",
+    "for",
+    // Multi-way branches
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+    "case",
+    // Exception handlers
+    "catch_clause",
+    "except_clause",
+    "rescue",
+];
+
+/// Estimates the cyclomatic complexity of a function's syntax subtree.
+///
+/// Starts at 1 for the single path through straight-line code and adds
+/// one per decision point.
+pub fn estimate_complexity(node: &Node) -> u32 {
+    1 + count_decisions(node)
+}
+
+/// Fills in `complexity` for every function, method and constructor.
+///
+/// Nodes are matched back to the syntax tree by byte range.
+pub(crate) fn annotate_complexity(nodes: &mut [CodeNode], tree: &Tree) {
+    let root = tree.root_node();
+
+    for node in nodes.iter_mut() {
+        if !matches!(
+            node.kind,
+            NodeKind::Function | NodeKind::Method | NodeKind::Constructor
+        ) {
+            continue;
+        }
+
+        if let Some(syntax) =
+            root.descendant_for_byte_range(node.byte_start as usize, node.byte_end as usize)
+        {
+            node.complexity = estimate_complexity(&syntax);
+        }
+    }
+}
+
+/// Recursively counts decision points below (and including) `node`.
+fn count_decisions(node: &Node) -> u32 {
+    let mut count = u32::from(is_decision(node));
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            count += count_decisions(&child);
+        }
+    }
+
+    count
+}
+
+/// Returns true if the node is a branch, loop or short-circuit operator.
+fn is_decision(node: &Node) -> bool {
+    // Anonymous tokens like the `if` keyword share names with Ruby's
+    // named `if` node, so only named nodes count
+    if !node.is_named() {
+        return false;
+    }
+
+    if DECISION_KINDS.contains(&node.kind()) {
+        return true;
+    }
+
+    match node.kind() {
+        "binary_expression" | "binary" | "boolean_operator" => node
+            .child_by_field_name("operator")
+            .map(|op| matches!(op.kind(), "&&" | "||" | "and" | "or"))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::languages::get_parser;
+    use crate::node::CodeNode;
+    use crate::parser::parse_source;
+
+    fn complexity_of(ext: &str, source: &str, name: &str) -> u32 {
+        let parser = get_parser(ext).unwrap();
+        let nodes: Vec<CodeNode> = parse_source(source, "test", parser.as_ref()).unwrap();
+        nodes.iter().find(|n| n.name == name).unwrap().complexity
+    }
+
+    #[test]
+    fn test_rust_branches_and_match() {
+        let source = r#"
+fn classify(n: i32, flag: bool) -> &'static str {
+    if n < 0 && flag {
+        "negative"
+    } else if n == 0 {
+        "zero"
+    } else {
+        match n {
+            1 => "one",
+            _ => "many",
+        }
+    }
+}
+"#;
+        // if, &&, else if, match
+        assert_eq!(complexity_of("rs", source, "classify"), 5);
+    }
+
+    #[test]
+    fn test_rust_loops() {
+        let source = r#"
+fn total(values: &[i32]) -> i32 {
+    let mut sum = 0;
+    for v in values {
+        if *v > 0 || *v < -10 {
+            sum += v;
+        }
+    }
+    while sum > 100 {
+        sum -= 1;
+    }
+    sum
+}
+
+fn straight() -> i32 {
+    1 + 2
+}
+"#;
+        // for, if, ||, while
+        assert_eq!(complexity_of("rs", source, "total"), 5);
+        assert_eq!(complexity_of("rs", source, "straight"), 1);
+    }
+
+    #[test]
+    fn test_python_elif_and_boolean_operators() {
+        let source = r#"
+def grade(score):
+    if score > 90 and score <= 100:
+        return "A"
+    elif score > 80:
+        return "B"
+    for _ in range(3):
+        pass
+    return "C"
+"#;
+        // if, and, elif, for
+        assert_eq!(complexity_of("py", source, "grade"), 5);
+    }
+
+    #[test]
+    fn test_typescript_switch_and_ternary() {
+        let source = r#"
+function pick(a: number, b: number): number {
+  while (a > 10) {
+    a--;
+  }
+  switch (a) {
+    case 1:
+      return 1;
+    default:
+      break;
+  }
+  return a > b ? a : b;
+}
+"#;
+        // while, switch, ternary
+        assert_eq!(complexity_of("ts", source, "pick"), 4);
+    }
+
+    #[test]
+    fn test_go_if_and_for() {
+        let source = r#"
+package main
+
+func abs(x int) int {
+	for i := 0; i < 3; i++ {
+	}
+	if x < 0 {
+		return -x
+	}
+	return x
+}
+"#;
+        // for, if
+        assert_eq!(complexity_of("go", source, "abs"), 3);
+    }
+}
@@ -0,0 +1,370 @@
+//! Structural extraction for TOML and YAML config files.
+//!
+//! Infra repos wire services together through config, so knowing where a
+//! key like `database.url` is defined helps when code refers to it. This
+//! is a line-based pass, not a full parser: sections (TOML tables, YAML
+//! mappings) become `Module` nodes and keys with values become `Constant`
+//! nodes, named by their dotted path. Values, anchors and list items are
+//! not interpreted.
+
+use crate::node::{CodeNode, NodeKind};
+use std::path::Path;
+
+/// Returns true for `.toml`, `.yaml` and `.yml` files.
+pub fn is_config_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "toml" | "yaml" | "yml"))
+        .unwrap_or(false)
+}
+
+/// Extracts sections and keys from a TOML or YAML file, picked by the
+/// extension of `file_path`.
+pub fn parse_config(source: &str, file_path: &str) -> Vec<CodeNode> {
+    let is_toml = Path::new(file_path)
+        .extension()
+        .map(|e| e.eq_ignore_ascii_case("toml"))
+        .unwrap_or(false);
+
+    if is_toml {
+        parse_toml(source, file_path)
+    } else {
+        parse_yaml(source, file_path)
+    }
+}
+
+/// One source line with its 1-based number and byte range.
+struct Line<'a> {
+    number: u32,
+    start: usize,
+    end: usize,
+    text: &'a str,
+}
+
+fn lines(source: &str) -> Vec<Line<'_>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for (i, raw) in source.split_inclusive('\n').enumerate() {
+        let text = raw.trim_end_matches(['\n', '\r']);
+        out.push(Line {
+            number: i as u32 + 1,
+            start,
+            end: start + text.len(),
+            text,
+        });
+        start += raw.len();
+    }
+    out
+}
+
+/// Builds a node for `path` (the dotted key path) spanning `first..=last`.
+fn config_node(
+    kind: NodeKind,
+    path: &[String],
+    file_path: &str,
+    first: &Line,
+    last: &Line,
+) -> CodeNode {
+    let name = path.last().cloned().unwrap_or_default();
+    let indent = first.text.len() - first.text.trim_start().len();
+    CodeNode::new(&name, &path.join("."), kind, file_path)
+        .with_lines(first.number, last.number)
+        .with_bytes(first.start as u32, last.end as u32)
+        .with_column(indent as u32)
+        .with_signature(first.text.trim())
+}
+
+/// Strips surrounding quotes from a key.
+fn unquote(key: &str) -> String {
+    key.trim().trim_matches('"').trim_matches('\'').to_string()
+}
+
+// ============================================================================
+// TOML
+// ============================================================================
+
+/// Extracts `[tables]`, `[[arrays]]` and `key = value` pairs.
+///
+/// A table spans from its header to the last non-blank line before the
+/// next header. Multi-line arrays and strings are skipped over so their
+/// contents aren't mistaken for keys.
+fn parse_toml(source: &str, file_path: &str) -> Vec<CodeNode> {
+    let lines = lines(source);
+    let mut nodes = Vec::new();
+    let mut table: Vec<String> = Vec::new();
+    // Index of the open table's node and its header line
+    let mut open: Option<(usize, usize)> = None;
+    let mut last_content = 0;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        let text = line.text.trim();
+        if text.is_empty() || text.starts_with('#') {
+            i += 1;
+            continue;
+        }
+
+        if text.starts_with('[') {
+            if let Some((node, header)) = open.take() {
+                nodes[node] = close_span(&nodes[node], &lines[header], &lines[last_content]);
+            }
+            let header = text.split('#').next().unwrap_or(text).trim();
+            let name = header.trim_start_matches('[').trim_end_matches(']');
+            table = name.split('.').map(unquote).collect();
+            open = Some((nodes.len(), i));
+            nodes.push(config_node(NodeKind::Module, &table, file_path, line, line));
+            last_content = i;
+            i += 1;
+            continue;
+        }
+
+        let Some((key, value)) = text.split_once('=') else {
+            i += 1;
+            continue;
+        };
+        let mut path = table.clone();
+        path.extend(key.split('.').map(unquote));
+
+        // Follow the value onto later lines if it's left open
+        let value = value.trim();
+        let mut end = i;
+        if let Some(delim) = ["\"\"\"", "'''"].into_iter().find(|d| value.starts_with(d)) {
+            if value[3..].find(delim).is_none() {
+                while end + 1 < lines.len() && !lines[end + 1].text.contains(delim) {
+                    end += 1;
+                }
+                end = (end + 1).min(lines.len() - 1);
+            }
+        } else if value.starts_with('[') {
+            let mut depth = bracket_depth(value);
+            while depth > 0 && end + 1 < lines.len() {
+                end += 1;
+                depth += bracket_depth(lines[end].text);
+            }
+        }
+
+        nodes.push(config_node(
+            NodeKind::Constant,
+            &path,
+            file_path,
+            line,
+            &lines[end],
+        ));
+        last_content = end;
+        i = end + 1;
+    }
+
+    if let Some((node, header)) = open {
+        nodes[node] = close_span(&nodes[node], &lines[header], &lines[last_content]);
+    }
+    nodes
+}
+
+/// Net `[` minus `]` on a line, ignoring anything after a comment.
+fn bracket_depth(text: &str) -> i32 {
+    let code = text.split('#').next().unwrap_or(text);
+    code.matches('[').count() as i32 - code.matches(']').count() as i32
+}
+
+/// Extends a section node to end at `last`.
+fn close_span(node: &CodeNode, first: &Line, last: &Line) -> CodeNode {
+    node.clone()
+        .with_lines(first.number, last.number)
+        .with_bytes(first.start as u32, last.end as u32)
+}
+
+// ============================================================================
+// YAML
+// ============================================================================
+
+/// Extracts mapping keys, nesting by indentation.
+///
+/// `key:` with nothing after it opens a section; `key: value` is a
+/// constant. List items and the contents of block scalars (`|`, `>`) are
+/// skipped. Each `---` document starts over at the top level.
+fn parse_yaml(source: &str, file_path: &str) -> Vec<CodeNode> {
+    let lines = lines(source);
+    let mut nodes = Vec::new();
+    // Open sections: (indent, key, node index, header line)
+    let mut stack: Vec<(usize, String, usize, usize)> = Vec::new();
+    // Skip lines indented deeper than this (inside a list item or block scalar)
+    let mut skip_deeper_than: Option<usize> = None;
+    let mut last_content = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        let text = line.text.trim();
+        if text.is_empty() || text.starts_with('#') || text.starts_with('%') {
+            continue;
+        }
+        let indent = line.text.len() - line.text.trim_start().len();
+
+        if let Some(limit) = skip_deeper_than {
+            if indent > limit {
+                last_content = i;
+                continue;
+            }
+            skip_deeper_than = None;
+        }
+
+        if text == "---" || text == "..." {
+            for (_, _, node, header) in stack.drain(..).rev() {
+                nodes[node] = close_span(&nodes[node], &lines[header], &lines[last_content]);
+            }
+            continue;
+        }
+
+        // A list item (possibly at the same indent as its key) belongs to
+        // the open section
+        if text == "-" || text.starts_with("- ") {
+            skip_deeper_than = Some(indent);
+            last_content = i;
+            continue;
+        }
+
+        while stack.last().is_some_and(|(open, ..)| *open >= indent) {
+            let (_, _, node, header) = stack.pop().unwrap();
+            nodes[node] = close_span(&nodes[node], &lines[header], &lines[last_content]);
+        }
+
+        let Some((key, value)) = split_yaml_key(text) else {
+            // Continuation of a multi-line plain scalar
+            last_content = i;
+            continue;
+        };
+
+        let mut path: Vec<String> = stack.iter().map(|(_, k, ..)| k.clone()).collect();
+        path.push(key.clone());
+
+        let value = value.split(" #").next().unwrap_or(value).trim();
+        // `key: &anchor` still opens a mapping
+        if value.is_empty() || (value.starts_with('&') && !value.contains(' ')) {
+            stack.push((indent, key, nodes.len(), i));
+            nodes.push(config_node(NodeKind::Module, &path, file_path, line, line));
+        } else {
+            if value.starts_with('|') || value.starts_with('>') {
+                skip_deeper_than = Some(indent);
+            }
+            nodes.push(config_node(
+                NodeKind::Constant,
+                &path,
+                file_path,
+                line,
+                line,
+            ));
+        }
+        last_content = i;
+    }
+
+    for (_, _, node, header) in stack.into_iter().rev() {
+        nodes[node] = close_span(&nodes[node], &lines[header], &lines[last_content]);
+    }
+    nodes
+}
+
+/// Splits `key: value` (or `key:`) into the unquoted key and the rest.
+fn split_yaml_key(text: &str) -> Option<(String, &str)> {
+    let key_end = if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        text[1..].find(quote)? + 2
+    } else {
+        0
+    };
+
+    let rest = &text[key_end..];
+    let colon = rest
+        .match_indices(':')
+        .map(|(pos, _)| pos)
+        .find(|&pos| rest[pos + 1..].is_empty() || rest[pos + 1..].starts_with([' ', '\t']))?;
+
+    let key = unquote(&text[..key_end + colon]);
+    if key.is_empty() {
+        return None;
+    }
+    Some((key, &rest[colon + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_nested_sections() {
+        let source = r#"# deploy config
+services:
+  api:
+    image: registry/api:1.4
+    env:
+      DATABASE_URL: postgres://db/app
+    ports:
+      - "8080:8080"
+      - name: metrics
+        port: 9464
+  worker:
+    command: |
+      run --queue jobs
+      --verbose: true
+logging: info
+"#;
+        let nodes = parse_config(source, "deploy.yaml");
+        let find = |q: &str| nodes.iter().find(|n| n.qualified_name == q);
+
+        let services = find("services").unwrap();
+        assert_eq!(services.kind, NodeKind::Module);
+        assert_eq!((services.line_start, services.line_end), (2, 14));
+
+        let api = find("services.api").unwrap();
+        assert_eq!(api.kind, NodeKind::Module);
+        assert_eq!(api.name, "api");
+        assert_eq!(api.line_end, 10);
+
+        let url = find("services.api.env.DATABASE_URL").unwrap();
+        assert_eq!(url.kind, NodeKind::Constant);
+        assert_eq!(url.line_start, 6);
+
+        assert_eq!(find("services.api.ports").unwrap().kind, NodeKind::Module);
+        assert_eq!(
+            find("services.worker.command").unwrap().kind,
+            NodeKind::Constant
+        );
+        assert_eq!(find("logging").unwrap().kind, NodeKind::Constant);
+
+        // List items and block scalar contents aren't keys
+        assert!(nodes
+            .iter()
+            .all(|n| n.name != "port" && n.name != "--verbose"));
+    }
+
+    #[test]
+    fn test_toml_tables_and_keys() {
+        let source = r#"name = "arbor"
+
+[database]
+url = "postgres://db/app"
+pool.size = 10
+hosts = [
+  "a = 1",
+  "b",
+]
+
+[[workers]]
+queue = "jobs"
+"#;
+        let nodes = parse_config(source, "Config.toml");
+        let find = |q: &str| nodes.iter().find(|n| n.qualified_name == q);
+
+        assert_eq!(find("name").unwrap().kind, NodeKind::Constant);
+
+        let database = find("database").unwrap();
+        assert_eq!(database.kind, NodeKind::Module);
+        assert_eq!((database.line_start, database.line_end), (3, 9));
+
+        assert_eq!(find("database.url").unwrap().line_start, 4);
+        assert_eq!(find("database.pool.size").unwrap().name, "size");
+        assert_eq!(find("database.hosts").unwrap().line_end, 9);
+        assert_eq!(find("workers.queue").unwrap().kind, NodeKind::Constant);
+
+        // Array contents aren't keys
+        assert!(nodes.iter().all(|n| n.name != "\"a"));
+        assert_eq!(nodes.len(), 7);
+    }
+}
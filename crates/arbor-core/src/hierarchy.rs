@@ -0,0 +1,99 @@
+//! Parent links between extracted nodes.
+//!
+//! Language extractors know a method's class only as a prefix of its
+//! qualified name. This pass turns that (or, failing it, byte-range
+//! nesting) into an explicit `parent_id` on each member.
+
+use crate::node::{CodeNode, NodeKind};
+
+/// Returns true for kinds that can own members.
+fn is_container(kind: NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Class | NodeKind::Interface | NodeKind::Struct | NodeKind::Enum
+    )
+}
+
+/// Sets `parent_id` on every node nested in a class, struct, enum or
+/// interface from the same file.
+///
+/// The qualified name decides first (`UserService.save` belongs to
+/// `UserService`), which also covers Rust methods that live in an `impl`
+/// block outside the struct's span. Otherwise the innermost container
+/// whose byte range encloses the node wins.
+pub fn assign_parents(nodes: &mut [CodeNode]) {
+    let containers: Vec<usize> = (0..nodes.len())
+        .filter(|&i| is_container(nodes[i].kind))
+        .collect();
+    if containers.is_empty() {
+        return;
+    }
+
+    for i in 0..nodes.len() {
+        if matches!(nodes[i].kind, NodeKind::Import | NodeKind::Export) {
+            continue;
+        }
+
+        let node = &nodes[i];
+        let by_name = node
+            .qualified_name
+            .rsplit_once("::")
+            .or_else(|| node.qualified_name.rsplit_once('.'))
+            .and_then(|(scope, _)| {
+                containers
+                    .iter()
+                    .copied()
+                    .find(|&c| c != i && nodes[c].qualified_name == scope)
+            });
+
+        let span = |n: &CodeNode| n.byte_end.saturating_sub(n.byte_start);
+        let by_range = || {
+            containers
+                .iter()
+                .copied()
+                .filter(|&c| {
+                    let outer = &nodes[c];
+                    c != i
+                        && outer.byte_start <= node.byte_start
+                        && node.byte_end <= outer.byte_end
+                        && span(outer) > span(node)
+                })
+                .min_by_key(|&c| span(&nodes[c]))
+        };
+
+        if let Some(parent) = by_name.or_else(by_range) {
+            nodes[i].parent_id = Some(nodes[parent].id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::get_parser;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn test_methods_point_at_their_class() {
+        let source = r#"
+class Cart {
+    add(item) { this.items.push(item); }
+    total() { return 0; }
+}
+
+function checkout() {}
+"#;
+        let parser = get_parser("ts").unwrap();
+        let nodes = parse_source(source, "cart.ts", parser.as_ref()).unwrap();
+
+        let cart = nodes.iter().find(|n| n.name == "Cart").unwrap();
+        for name in ["add", "total"] {
+            let method = nodes.iter().find(|n| n.name == name).unwrap();
+            assert_eq!(method.parent_id.as_deref(), Some(cart.id.as_str()));
+        }
+
+        let checkout = nodes.iter().find(|n| n.name == "checkout").unwrap();
+        assert!(checkout.parent_id.is_none());
+        assert!(cart.parent_id.is_none());
+    }
+}
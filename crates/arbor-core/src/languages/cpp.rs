@@ -145,12 +145,17 @@ fn extract_from_node(
 
         // Template declarations
         "template_declaration" => {
-            // Recurse into template body
+            // Recurse into template body; the first node extracted from it
+            // is the templated class or function itself.
+            let first_new = nodes.len();
             for i in 0..node.child_count() {
                 if let Some(child) = node.child(i) {
                     extract_from_node(&child, source, file_path, nodes, context);
                 }
             }
+            if let Some(templated) = nodes.get_mut(first_new) {
+                templated.generics = extract_template_params(node, source);
+            }
             return;
         }
 
@@ -370,6 +375,20 @@ fn extract_using(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
 // Helper functions
 // ============================================================================
 
+/// Extracts template parameters (e.g. `typename T`, `int N`).
+fn extract_template_params(node: &Node, source: &str) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("parameters") else {
+        return Vec::new();
+    };
+
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter(|p| p.kind() != "comment")
+        .map(|p| get_text(&p, source))
+        .collect()
+}
+
 /// Gets text content of a node.
 fn get_text(node: &Node, source: &str) -> String {
     source[node.byte_range()].to_string()
@@ -576,4 +595,35 @@ void myFunction(int x) {
             .iter()
             .any(|n| n.name == "myFunction" && matches!(n.kind, NodeKind::Function)));
     }
+
+    #[test]
+    fn test_template_params_extraction() {
+        let source = r#"
+template <typename T, int N>
+class FixedArray {
+public:
+    T get(int i) { return data[i]; }
+private:
+    T data[N];
+};
+
+template <class K>
+K identity(K value) { return value; }
+"#;
+
+        let parser = CppParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        let nodes = parser.extract_nodes(&tree, source, "fixed_array.hpp");
+
+        let class = nodes.iter().find(|n| n.name == "FixedArray").unwrap();
+        let func = nodes.iter().find(|n| n.name == "identity").unwrap();
+        let method = nodes.iter().find(|n| n.name == "get").unwrap();
+
+        assert_eq!(class.generics, vec!["typename T", "int N"]);
+        assert_eq!(func.generics, vec!["class K"]);
+        assert!(method.generics.is_empty());
+    }
 }
@@ -0,0 +1,356 @@
+//! Groovy language parser implementation.
+//!
+//! Handles .groovy files and Gradle build scripts (.gradle). Extracts
+//! classes, interfaces, methods, closures assigned to names
+//! (`def notify = { ... }`, `ext.bump = { ... }`), and imports.
+//!
+//! Gradle's DSL is mostly nested closure calls (`dependencies { ... }`),
+//! which carry no names worth indexing, so build scripts are best-effort:
+//! we pick up the named closures and methods and skip the rest.
+
+use crate::languages::{self, LanguageParser};
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct GroovyParser;
+
+impl LanguageParser for GroovyParser {
+    fn language(&self) -> Language {
+        languages::from_language_fn(tree_sitter_groovy::LANGUAGE.into_raw())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["groovy", "gradle"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+
+        extract_from_node(&root, source, file_path, &mut nodes, None);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the Groovy AST.
+fn extract_from_node(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    nodes: &mut Vec<CodeNode>,
+    context: Option<&str>,
+) {
+    match node.kind() {
+        "class_declaration" | "interface_declaration" | "enum_declaration" => {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let name = get_text(&name_node, source);
+                let kind = match node.kind() {
+                    "interface_declaration" => NodeKind::Interface,
+                    "enum_declaration" => NodeKind::Enum,
+                    _ => NodeKind::Class,
+                };
+
+                nodes.push(
+                    CodeNode::new(&name, &name, kind, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+                        .with_column(name_node.start_position().column as u32)
+                        .with_visibility(detect_visibility(node, source)),
+                );
+
+                if let Some(body) = node.child_by_field_name("body") {
+                    for i in 0..body.child_count() {
+                        if let Some(child) = body.child(i) {
+                            extract_from_node(&child, source, file_path, nodes, Some(&name));
+                        }
+                    }
+                }
+                return;
+            }
+        }
+
+        "method_declaration" | "function_definition" | "function_declaration" => {
+            if let Some(code_node) = extract_method(node, source, file_path, context) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        // def notify = { ... }
+        "variable_declarator" => {
+            if let (Some(name_node), Some(value)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("value"),
+            ) {
+                if value.kind() == "closure" {
+                    let name = get_text(&name_node, source);
+                    nodes.push(closure_node(
+                        node, &name_node, &name, &value, source, file_path, context,
+                    ));
+                    return;
+                }
+            }
+        }
+
+        // ext.bump = { ... }
+        "assignment_expression" | "assignment" => {
+            if let (Some(left), Some(right)) = (
+                node.child_by_field_name("left"),
+                node.child_by_field_name("right"),
+            ) {
+                if right.kind() == "closure" {
+                    let target = get_text(&left, source);
+                    let name = target.rsplit('.').next().unwrap_or(&target).to_string();
+                    nodes.push(closure_node(
+                        node, &left, &name, &right, source, file_path, context,
+                    ));
+                    return;
+                }
+            }
+        }
+
+        "import_declaration" | "groovy_import" => {
+            if let Some(module) = import_path(node, source) {
+                nodes.push(
+                    CodeNode::new(&module, &module, NodeKind::Import, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32),
+                );
+            }
+            return;
+        }
+
+        _ => {}
+    }
+
+    // Recurse into children
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes, context);
+        }
+    }
+}
+
+/// Extracts a method (or top-level script function) declaration.
+fn extract_method(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    context: Option<&str>,
+) -> Option<CodeNode> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = get_text(&name_node, source);
+
+    let (kind, qualified_name) = match context {
+        Some(ctx) => (NodeKind::Method, format!("{}.{}", ctx, name)),
+        None => (NodeKind::Function, name.clone()),
+    };
+
+    let body = node.child_by_field_name("body");
+    let header_end = body.map(|b| b.start_byte()).unwrap_or(node.end_byte());
+    let references = body
+        .map(|b| extract_call_references(&b, source))
+        .unwrap_or_default();
+
+    let mut code_node = CodeNode::new(&name, &qualified_name, kind, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+        .with_column(name_node.start_position().column as u32)
+        .with_signature(collapse_whitespace(
+            source[node.start_byte()..header_end].trim(),
+        ))
+        .with_visibility(detect_visibility(node, source))
+        .with_references(references);
+
+    if has_modifier(node, source, "static") {
+        code_node = code_node.as_static();
+    }
+
+    Some(code_node)
+}
+
+/// Builds a function node for a closure bound to a name.
+fn closure_node(
+    node: &Node,
+    name_node: &Node,
+    name: &str,
+    closure: &Node,
+    source: &str,
+    file_path: &str,
+    context: Option<&str>,
+) -> CodeNode {
+    let qualified_name = match context {
+        Some(ctx) => format!("{}.{}", ctx, name),
+        None => name.to_string(),
+    };
+
+    CodeNode::new(name, &qualified_name, NodeKind::Function, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+        .with_column(name_node.start_position().column as u32)
+        .with_visibility(Visibility::Public)
+        .with_references(extract_call_references(closure, source))
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Returns true if the node's `modifiers` child contains `keyword`.
+fn has_modifier(node: &Node, source: &str, keyword: &str) -> bool {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .filter(|c| c.kind() == "modifiers")
+        .any(|m| {
+            get_text(&m, source)
+                .split_whitespace()
+                .any(|w| w == keyword)
+        })
+}
+
+/// Groovy members are public unless marked otherwise.
+fn detect_visibility(node: &Node, source: &str) -> Visibility {
+    if has_modifier(node, source, "private") {
+        Visibility::Private
+    } else if has_modifier(node, source, "protected") {
+        Visibility::Protected
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Extracts the imported name from `import a.b.C` or `import static a.b.C.d`.
+fn import_path(node: &Node, source: &str) -> Option<String> {
+    let module = get_text(node, source)
+        .trim()
+        .trim_start_matches("import")
+        .trim()
+        .trim_start_matches("static ")
+        .trim()
+        .trim_end_matches(';')
+        .trim()
+        .to_string();
+
+    if module.is_empty() {
+        None
+    } else {
+        Some(module)
+    }
+}
+
+/// Collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts method call references from a body.
+fn extract_call_references(node: &Node, source: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_calls(node, source, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+/// Recursively collects called method names.
+fn collect_calls(node: &Node, source: &str, refs: &mut Vec<String>) {
+    if matches!(
+        node.kind(),
+        "method_invocation" | "method_call" | "function_call" | "juxt_function_call"
+    ) {
+        if let Some(name) = node
+            .child_by_field_name("name")
+            .or_else(|| node.child_by_field_name("function"))
+        {
+            refs.push(get_text(&name, source));
+        }
+    }
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            collect_calls(&child, source, refs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str, file_path: &str) -> Vec<CodeNode> {
+        let parser = GroovyParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, file_path)
+    }
+
+    #[test]
+    fn test_parse_class_with_method() {
+        let source = r#"
+import groovy.json.JsonSlurper
+
+class Greeter {
+    String greet(String name) {
+        return format(name)
+    }
+
+    private String format(String name) {
+        "Hello, ${name}!"
+    }
+}
+"#;
+        let nodes = parse(source, "Greeter.groovy");
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "Greeter" && n.kind == NodeKind::Class));
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "groovy.json.JsonSlurper" && n.kind == NodeKind::Import));
+
+        let greet = nodes.iter().find(|n| n.name == "greet").unwrap();
+        assert_eq!(greet.kind, NodeKind::Method);
+        assert_eq!(greet.qualified_name, "Greeter.greet");
+        assert!(greet.references.contains(&"format".to_string()));
+
+        let format = nodes.iter().find(|n| n.name == "format").unwrap();
+        assert_eq!(format.visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_parse_gradle_named_closure() {
+        let source = r#"
+def bumpVersion = {
+    println "bumping"
+}
+
+dependencies {
+    implementation 'org.slf4j:slf4j-api:2.0.9'
+}
+"#;
+        let nodes = parse(source, "build.gradle");
+
+        let bump = nodes.iter().find(|n| n.name == "bumpVersion").unwrap();
+        assert_eq!(bump.kind, NodeKind::Function);
+    }
+}
@@ -140,7 +140,9 @@ fn extract_class(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_annotations(node, source)),
     )
 }
 
@@ -158,7 +160,9 @@ fn extract_interface(node: &Node, source: &str, file_path: &str) -> Option<CodeN
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_annotations(node, source)),
     )
 }
 
@@ -176,7 +180,8 @@ fn extract_enum(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_attributes(extract_annotations(node, source)),
     )
 }
 
@@ -209,7 +214,9 @@ fn extract_method(
             .with_column(name_node.start_position().column as u32)
             .with_signature(signature)
             .with_visibility(visibility)
-            .with_references(references),
+            .with_references(references)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_annotations(node, source)),
     )
 }
 
@@ -244,7 +251,9 @@ fn extract_constructor(
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
             .with_signature(signature)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_annotations(node, source)),
     )
 }
 
@@ -257,6 +266,7 @@ fn extract_fields(
     context: Option<&str>,
 ) {
     let visibility = detect_visibility(node, source);
+    let annotations = extract_annotations(node, source);
 
     // Look for variable declarators
     for i in 0..node.child_count() {
@@ -277,7 +287,8 @@ fn extract_fields(
                             )
                             .with_bytes(child.start_byte() as u32, child.end_byte() as u32)
                             .with_column(name_node.start_position().column as u32)
-                            .with_visibility(visibility),
+                            .with_visibility(visibility)
+                            .with_attributes(annotations.clone()),
                     );
                 }
             }
@@ -356,6 +367,40 @@ fn detect_visibility(node: &Node, source: &str) -> Visibility {
     Visibility::Internal
 }
 
+/// Collects the annotations in a declaration's modifiers, without the
+/// `@` (`@GetMapping("/users")` becomes `GetMapping("/users")`).
+fn extract_annotations(node: &Node, source: &str) -> Vec<String> {
+    let mut annotations = Vec::new();
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            if child.kind() == "modifiers" {
+                let mut cursor = child.walk();
+                annotations.extend(
+                    child
+                        .named_children(&mut cursor)
+                        .filter(|m| matches!(m.kind(), "marker_annotation" | "annotation"))
+                        .map(|m| get_text(&m, source).trim_start_matches('@').to_string()),
+                );
+            }
+        }
+    }
+    annotations
+}
+
+/// Extracts declared type parameters (e.g. `<T extends Comparable<T>>`).
+fn extract_generics(node: &Node, source: &str) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return Vec::new();
+    };
+
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter(|p| p.kind() == "type_parameter")
+        .map(|p| get_text(&p, source))
+        .collect()
+}
+
 /// Builds a method signature.
 fn build_method_signature(node: &Node, source: &str, name: &str) -> String {
     let return_type = node
@@ -481,4 +526,59 @@ public class Example {
         assert!(matches!(private_method.visibility, Visibility::Private));
         assert!(matches!(package_method.visibility, Visibility::Internal));
     }
+
+    #[test]
+    fn test_generics_extraction() {
+        let source = r#"
+public class Box<T extends Comparable<T>> {
+    public <K, V> V lookup(K key) { return null; }
+}
+"#;
+
+        let parser = JavaParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        let nodes = parser.extract_nodes(&tree, source, "Box.java");
+
+        let class = nodes.iter().find(|n| n.name == "Box").unwrap();
+        let method = nodes.iter().find(|n| n.name == "lookup").unwrap();
+
+        assert_eq!(class.generics, vec!["T extends Comparable<T>"]);
+        assert_eq!(method.generics, vec!["K", "V"]);
+    }
+
+    #[test]
+    fn test_annotations_extraction() {
+        let source = r#"
+@RestController
+public class UserController {
+    @Autowired
+    private UserService service;
+
+    @Override
+    @GetMapping("/users/{id}")
+    public User get(String id) { return service.find(id); }
+}
+"#;
+
+        let parser = JavaParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        let nodes = parser.extract_nodes(&tree, source, "UserController.java");
+
+        let class = nodes.iter().find(|n| n.name == "UserController").unwrap();
+        let field = nodes.iter().find(|n| n.name == "service").unwrap();
+        let method = nodes.iter().find(|n| n.name == "get").unwrap();
+
+        assert_eq!(class.attributes, vec!["RestController"]);
+        assert_eq!(field.attributes, vec!["Autowired"]);
+        assert_eq!(
+            method.attributes,
+            vec!["Override", "GetMapping(\"/users/{id}\")"]
+        );
+    }
 }
@@ -8,12 +8,20 @@ mod c;
 mod cpp;
 mod dart;
 mod go;
+mod groovy;
 mod java;
+mod nim;
+mod objc;
+mod perl;
+mod proto;
 mod python;
+mod r;
 mod rust;
 mod typescript;
 
 use crate::node::CodeNode;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
 
 /// Trait for language-specific parsing logic.
 ///
@@ -39,10 +47,75 @@ pub trait LanguageParser: Send + Sync {
     ) -> Vec<CodeNode>;
 }
 
+/// Converts a grammar's `LANGUAGE` constant into a tree-sitter 0.22
+/// `Language`.
+///
+/// Newer grammar crates are built on `tree-sitter-language` and export a
+/// `LanguageFn` instead of `language()`. Both wrap the same C
+/// `TSLanguage` pointer, so pass `LANGUAGE.into_raw()` here.
+pub(crate) fn from_language_fn(raw: unsafe extern "C" fn() -> *const ()) -> tree_sitter::Language {
+    // SAFETY: a grammar's language function returns a pointer to its
+    // static, never-freed TSLanguage.
+    unsafe { tree_sitter::Language::from_raw(raw() as *const tree_sitter::ffi::TSLanguage) }
+}
+
+/// Parsers added at runtime via `register`, keyed by lowercase extension.
+type Registry = RwLock<HashMap<String, Arc<dyn LanguageParser>>>;
+
+fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers a parser for a file extension (without the leading dot).
+///
+/// This lets library users plug in languages (or internal DSLs) without
+/// modifying Arbor. Built-in parsers take precedence, so registering a
+/// built-in extension has no effect. Registering the same extension twice
+/// replaces the earlier parser.
+pub fn register(extension: &str, parser: Box<dyn LanguageParser>) {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    registry.insert(extension.to_lowercase(), Arc::from(parser));
+}
+
+/// Forwards to a parser held in the registry.
+///
+/// `get_parser` hands out boxes, but the registry must keep its own copy.
+struct RegisteredParser(Arc<dyn LanguageParser>);
+
+impl LanguageParser for RegisteredParser {
+    fn language(&self) -> tree_sitter::Language {
+        self.0.language()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.0.extensions()
+    }
+
+    fn extract_nodes(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        file_path: &str,
+    ) -> Vec<CodeNode> {
+        self.0.extract_nodes(tree, source, file_path)
+    }
+}
+
 /// Gets a parser for the given file extension.
 ///
+/// Built-in languages are checked first, then parsers added via `register`.
 /// Returns None if we don't support this extension.
 pub fn get_parser(extension: &str) -> Option<Box<dyn LanguageParser>> {
+    get_builtin_parser(extension).or_else(|| {
+        let registry = registry().read().unwrap_or_else(|e| e.into_inner());
+        registry
+            .get(&extension.to_lowercase())
+            .map(|parser| Box::new(RegisteredParser(parser.clone())) as Box<dyn LanguageParser>)
+    })
+}
+
+fn get_builtin_parser(extension: &str) -> Option<Box<dyn LanguageParser>> {
     match extension.to_lowercase().as_str() {
         // TypeScript and JavaScript
         "ts" | "tsx" | "mts" | "cts" => Some(Box::new(typescript::TypeScriptParser)),
@@ -69,6 +142,57 @@ pub fn get_parser(extension: &str) -> Option<Box<dyn LanguageParser>> {
         // Dart
         "dart" => Some(Box::new(dart::DartParser)),
 
+        // R
+        "r" => Some(Box::new(r::RParser)),
+
+        // Perl
+        "pl" | "pm" => Some(Box::new(perl::PerlParser)),
+
+        // Groovy and Gradle build scripts
+        "groovy" | "gradle" => Some(Box::new(groovy::GroovyParser)),
+
+        // Objective-C (ObjC++ parsed with the same grammar)
+        "m" | "mm" => Some(Box::new(objc::ObjCParser)),
+
+        // Nim (and NimScript)
+        "nim" | "nims" => Some(Box::new(nim::NimParser)),
+
+        // Protocol Buffers
+        "proto" => Some(Box::new(proto::ProtoParser)),
+
+        // Vue and Svelte components (parse_file extracts the <script> blocks)
+        "vue" | "svelte" => Some(Box::new(typescript::TypeScriptParser)),
+
+        _ => None,
+    }
+}
+
+/// Returns a human-readable language name for a file extension.
+///
+/// Returns None if we don't support this extension.
+pub fn language_name(extension: &str) -> Option<&'static str> {
+    match extension.to_lowercase().as_str() {
+        "ts" | "tsx" | "mts" | "cts" => Some("TypeScript"),
+        "js" | "jsx" | "mjs" | "cjs" => Some("JavaScript"),
+        "rs" => Some("Rust"),
+        "py" | "pyi" => Some("Python"),
+        "go" => Some("Go"),
+        "java" => Some("Java"),
+        "c" | "h" => Some("C"),
+        "cpp" | "hpp" | "cc" | "hh" | "cxx" | "hxx" => Some("C++"),
+        "dart" => Some("Dart"),
+        "r" => Some("R"),
+        "pl" | "pm" => Some("Perl"),
+        "groovy" => Some("Groovy"),
+        "gradle" => Some("Gradle"),
+        "m" => Some("Objective-C"),
+        "mm" => Some("Objective-C++"),
+        "nim" | "nims" => Some("Nim"),
+        "proto" => Some("Protobuf"),
+        "vue" => Some("Vue"),
+        "svelte" => Some("Svelte"),
+        "toml" => Some("TOML"),
+        "yaml" | "yml" => Some("YAML"),
         _ => None,
     }
 }
@@ -85,6 +209,13 @@ pub fn supported_extensions() -> &'static [&'static str] {
         "c", "h", // C
         "cpp", "hpp", "cc", "hh", "cxx", "hxx",  // C++
         "dart", // Dart
+        "r", "R", // R
+        "pl", "pm", // Perl
+        "groovy", "gradle", // Groovy
+        "m", "mm", // Objective-C
+        "nim", "nims",  // Nim
+        "proto", // Protobuf
+        "vue", "svelte", // Single-file components
     ]
 }
 
@@ -92,3 +223,63 @@ pub fn supported_extensions() -> &'static [&'static str] {
 pub fn is_supported(extension: &str) -> bool {
     get_parser(extension).is_some()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::NodeKind;
+    use crate::parse_file;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Treats every non-empty line as a function named after its text.
+    struct FooParser;
+
+    impl LanguageParser for FooParser {
+        fn language(&self) -> tree_sitter::Language {
+            tree_sitter_python::language()
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["foo"]
+        }
+
+        fn extract_nodes(
+            &self,
+            _tree: &tree_sitter::Tree,
+            source: &str,
+            file_path: &str,
+        ) -> Vec<CodeNode> {
+            source
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| CodeNode::new(line, line, NodeKind::Function, file_path))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_register_external_parser() {
+        assert!(!is_supported("foo"));
+
+        register("foo", Box::new(FooParser));
+        assert!(is_supported("foo"));
+        assert!(is_supported("FOO"));
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("rules.foo");
+        fs::write(&path, "validate\nsubmit\n").unwrap();
+
+        let nodes = parse_file(&path).unwrap();
+        let names: Vec<_> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["validate", "submit"]);
+    }
+
+    #[test]
+    fn test_builtin_parsers_take_precedence() {
+        register("rs", Box::new(FooParser));
+        let parser = get_parser("rs").unwrap();
+        assert!(parser.extensions().contains(&"rs"));
+    }
+}
@@ -0,0 +1,329 @@
+//! Nim language parser implementation.
+//!
+//! Handles .nim and .nims files. Extracts routines (`proc`, `func`,
+//! `iterator`, `template` and `macro` as functions, `method` as methods),
+//! type sections (objects, enums, concepts, aliases) and imports.
+//!
+//! Nim exports with a trailing `*` on the name (`proc greet*()`), so a
+//! marked symbol is public and everything else is module-private.
+
+use crate::languages::{self, LanguageParser};
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct NimParser;
+
+impl LanguageParser for NimParser {
+    fn language(&self) -> Language {
+        languages::from_language_fn(tree_sitter_nim::LANGUAGE.into_raw())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["nim", "nims"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+
+        extract_from_node(&root, source, file_path, &mut nodes);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the Nim AST.
+fn extract_from_node(node: &Node, source: &str, file_path: &str, nodes: &mut Vec<CodeNode>) {
+    match node.kind() {
+        "proc_declaration"
+        | "func_declaration"
+        | "iterator_declaration"
+        | "template_declaration"
+        | "macro_declaration"
+        | "converter_declaration" => {
+            if let Some(code_node) = extract_routine(node, source, file_path, NodeKind::Function) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "method_declaration" => {
+            if let Some(code_node) = extract_routine(node, source, file_path, NodeKind::Method) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "type_declaration" => {
+            if let Some(code_node) = extract_type(node, source, file_path) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "import_statement" | "import_from_statement" | "include_statement" => {
+            for module in import_modules(node, source) {
+                nodes.push(
+                    CodeNode::new(&module, &module, NodeKind::Import, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32),
+                );
+            }
+            return;
+        }
+
+        _ => {}
+    }
+
+    // Recurse into children
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes);
+        }
+    }
+}
+
+/// Extracts a routine declaration (`proc greet*(name: string): string = ...`).
+fn extract_routine(node: &Node, source: &str, file_path: &str, kind: NodeKind) -> Option<CodeNode> {
+    let name_node = node.child_by_field_name("name")?;
+    let (name, visibility) = symbol_name(&name_node, source);
+
+    let body = node.child_by_field_name("body");
+    let header_end = body.map(|b| b.start_byte()).unwrap_or(node.end_byte());
+    let signature = source[node.start_byte()..header_end]
+        .trim()
+        .trim_end_matches('=')
+        .trim();
+    let references = body
+        .map(|b| extract_call_references(&b, source))
+        .unwrap_or_default();
+
+    Some(
+        CodeNode::new(&name, &name, kind, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(collapse_whitespace(signature))
+            .with_visibility(visibility)
+            .with_references(references),
+    )
+}
+
+/// Extracts one entry of a `type` section.
+///
+/// `ref object` and `ptr object` count as objects; anything that isn't an
+/// object, enum or concept is recorded as an alias.
+fn extract_type(node: &Node, source: &str, file_path: &str) -> Option<CodeNode> {
+    let symbol = (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .find(|c| c.kind() == "type_symbol_declaration")?;
+    let name_node = symbol
+        .child_by_field_name("name")
+        .or_else(|| symbol.named_child(0))?;
+    let (name, visibility) = symbol_name(&name_node, source);
+
+    let kind = if has_descendant(node, "object_declaration") {
+        NodeKind::Struct
+    } else if has_descendant(node, "enum_declaration") {
+        NodeKind::Enum
+    } else if has_descendant(node, "concept_declaration") {
+        NodeKind::Interface
+    } else {
+        NodeKind::TypeAlias
+    };
+
+    Some(
+        CodeNode::new(&name, &name, kind, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_visibility(visibility),
+    )
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Splits a declared name into the bare identifier and its visibility.
+///
+/// `greet*` (an `exported_symbol`) is public; a plain `greet` is private.
+fn symbol_name(node: &Node, source: &str) -> (String, Visibility) {
+    let text = get_text(node, source);
+    let name = text.trim().trim_end_matches('*').trim().trim_matches('`');
+    let visibility = if node.kind() == "exported_symbol" || text.trim_end().ends_with('*') {
+        Visibility::Public
+    } else {
+        Visibility::Private
+    };
+    (name.to_string(), visibility)
+}
+
+/// Returns true if any node under `node` has the given kind.
+fn has_descendant(node: &Node, kind: &str) -> bool {
+    (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .any(|c| c.kind() == kind || has_descendant(&c, kind))
+}
+
+/// Lists the modules named by `import a, std/b`, `from a import b` or
+/// `include a`. Bracket groups (`import std/[os, strutils]`) are kept whole.
+fn import_modules(node: &Node, source: &str) -> Vec<String> {
+    if node.kind() == "import_from_statement" {
+        return node
+            .child_by_field_name("module")
+            .map(|m| vec![get_text(&m, source)])
+            .unwrap_or_default();
+    }
+
+    let text = get_text(node, source);
+    let list = text
+        .trim()
+        .trim_start_matches("import")
+        .trim_start_matches("include")
+        .trim();
+    // `import foo except bar` only names foo
+    let list = list.split(" except ").next().unwrap_or(list);
+
+    let mut modules = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                modules.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    modules.push(current);
+
+    modules
+        .into_iter()
+        .map(|m| collapse_whitespace(m.trim()))
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts call references from a routine body.
+fn extract_call_references(node: &Node, source: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_calls(node, source, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+/// Recursively collects called routine names. Method-call syntax
+/// (`user.save()`) records the routine, `save`.
+fn collect_calls(node: &Node, source: &str, refs: &mut Vec<String>) {
+    if node.kind() == "call" {
+        if let Some(function) = node.child_by_field_name("function") {
+            let target = get_text(&function, source);
+            if let Some(name) = target.rsplit('.').next() {
+                refs.push(name.trim().to_string());
+            }
+        }
+    }
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            collect_calls(&child, source, refs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str, file_path: &str) -> Vec<CodeNode> {
+        let parser = NimParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, file_path)
+    }
+
+    #[test]
+    fn test_parse_exported_proc() {
+        let source = r#"
+import std/strutils
+
+proc greet*(name: string): string =
+  result = format(name)
+
+proc format(name: string): string =
+  "Hello, " & name.capitalizeAscii
+"#;
+        let nodes = parse(source, "greeter.nim");
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "std/strutils" && n.kind == NodeKind::Import));
+
+        let greet = nodes.iter().find(|n| n.name == "greet").unwrap();
+        assert_eq!(greet.kind, NodeKind::Function);
+        assert_eq!(greet.visibility, Visibility::Public);
+        assert!(greet.references.contains(&"format".to_string()));
+        assert!(greet
+            .signature
+            .as_deref()
+            .unwrap()
+            .starts_with("proc greet*(name: string): string"));
+
+        let format = nodes.iter().find(|n| n.name == "format").unwrap();
+        assert_eq!(format.visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_parse_object_type() {
+        let source = r#"
+type
+  User* = object
+    name: string
+    age: int
+  Role = enum
+    admin, guest
+
+method describe*(u: User): string {.base.} =
+  u.name
+"#;
+        let nodes = parse(source, "user.nim");
+
+        let user = nodes.iter().find(|n| n.name == "User").unwrap();
+        assert_eq!(user.kind, NodeKind::Struct);
+        assert_eq!(user.visibility, Visibility::Public);
+
+        let role = nodes.iter().find(|n| n.name == "Role").unwrap();
+        assert_eq!(role.kind, NodeKind::Enum);
+        assert_eq!(role.visibility, Visibility::Private);
+
+        let describe = nodes.iter().find(|n| n.name == "describe").unwrap();
+        assert_eq!(describe.kind, NodeKind::Method);
+    }
+}
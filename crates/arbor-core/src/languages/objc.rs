@@ -0,0 +1,425 @@
+//! Objective-C language parser implementation.
+//!
+//! Handles .m files (and .mm on a best-effort basis: the grammar is plain
+//! Objective-C, so C++-only constructs in ObjC++ files are skipped).
+//! Extracts `@interface`/`@implementation` classes, `@protocol`s, method
+//! declarations and definitions, C functions, and `#import`/`@import`.
+
+use crate::languages::{self, LanguageParser};
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct ObjCParser;
+
+impl LanguageParser for ObjCParser {
+    fn language(&self) -> Language {
+        languages::from_language_fn(tree_sitter_objc::LANGUAGE.into_raw())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["m", "mm"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+
+        extract_from_node(&root, source, file_path, &mut nodes, None);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the Objective-C AST.
+fn extract_from_node(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    nodes: &mut Vec<CodeNode>,
+    class: Option<&str>,
+) {
+    match node.kind() {
+        "class_interface" | "class_implementation" | "protocol_declaration" => {
+            if let Some(name_node) = container_name(node) {
+                let name = get_text(&name_node, source);
+                let kind = if node.kind() == "protocol_declaration" {
+                    NodeKind::Interface
+                } else {
+                    NodeKind::Class
+                };
+
+                nodes.push(
+                    CodeNode::new(&name, &name, kind, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+                        .with_column(name_node.start_position().column as u32)
+                        .with_visibility(Visibility::Public),
+                );
+
+                for i in 0..node.child_count() {
+                    if let Some(child) = node.child(i) {
+                        extract_from_node(&child, source, file_path, nodes, Some(&name));
+                    }
+                }
+                return;
+            }
+        }
+
+        "method_declaration" | "method_definition" => {
+            if let Some(code_node) = extract_method(node, source, file_path, class) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "function_definition" => {
+            if let Some(code_node) = extract_function(node, source, file_path) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "preproc_include" | "module_import" => {
+            if let Some(module) = import_path(node, source) {
+                nodes.push(
+                    CodeNode::new(&module, &module, NodeKind::Import, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32),
+                );
+            }
+            return;
+        }
+
+        _ => {}
+    }
+
+    // Recurse into children
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes, class);
+        }
+    }
+}
+
+/// Extracts a `- (void)foo:(int)x bar:(int)y` method as `Class.foo:bar:`.
+///
+/// `+` methods are class methods and marked static.
+fn extract_method(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    class: Option<&str>,
+) -> Option<CodeNode> {
+    let body = find_child(node, "compound_statement");
+    let header_end = body.map(|b| b.start_byte()).unwrap_or(node.end_byte());
+    let header = source[node.start_byte()..header_end]
+        .trim()
+        .trim_end_matches(';')
+        .trim();
+
+    let selector = method_selector(header)?;
+    let qualified_name = match class {
+        Some(class) => format!("{}.{}", class, selector),
+        None => selector.clone(),
+    };
+
+    let references = body
+        .map(|b| extract_call_references(&b, source))
+        .unwrap_or_default();
+
+    let mut code_node = CodeNode::new(&selector, &qualified_name, NodeKind::Method, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+        .with_column(node.start_position().column as u32)
+        .with_signature(collapse_whitespace(header))
+        .with_visibility(Visibility::Public)
+        .with_references(references);
+
+    if header.starts_with('+') {
+        code_node = code_node.as_static();
+    }
+
+    Some(code_node)
+}
+
+/// Extracts a plain C function definition.
+fn extract_function(node: &Node, source: &str, file_path: &str) -> Option<CodeNode> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while declarator.kind() != "function_declarator" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    let name_node = declarator.child_by_field_name("declarator")?;
+    let name = get_text(&name_node, source);
+
+    let references = node
+        .child_by_field_name("body")
+        .map(|body| extract_call_references(&body, source))
+        .unwrap_or_default();
+
+    let is_static = (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .any(|c| c.kind() == "storage_class_specifier" && get_text(&c, source) == "static");
+
+    let header_end = node
+        .child_by_field_name("body")
+        .map(|b| b.start_byte())
+        .unwrap_or(node.end_byte());
+
+    Some(
+        CodeNode::new(&name, &name, NodeKind::Function, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(collapse_whitespace(
+                source[node.start_byte()..header_end].trim(),
+            ))
+            .with_visibility(if is_static {
+                Visibility::Private
+            } else {
+                Visibility::Public
+            })
+            .with_references(references),
+    )
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Finds the first direct child of a given kind.
+fn find_child<'a>(node: &Node<'a>, kind: &str) -> Option<Node<'a>> {
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .find(|c| c.kind() == kind)
+}
+
+/// Finds the name of an `@interface`, `@implementation` or `@protocol`.
+fn container_name<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    node.child_by_field_name("name").or_else(|| {
+        (0..node.named_child_count())
+            .filter_map(|i| node.named_child(i))
+            .find(|c| matches!(c.kind(), "identifier" | "type_identifier"))
+    })
+}
+
+/// Builds the selector from a method header.
+///
+/// `- (void)setName:(NSString *)name age:(int)age` gives `setName:age:`;
+/// `+ (instancetype)shared` gives `shared`.
+fn method_selector(header: &str) -> Option<String> {
+    let header = header.trim_start_matches(['-', '+']);
+
+    // Drop parenthesized return and parameter types
+    let mut stripped = String::with_capacity(header.len());
+    let mut depth = 0usize;
+    for c in header.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                stripped.push(' ');
+            }
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    let keywords: String = stripped
+        .split_whitespace()
+        .filter(|t| t.ends_with(':'))
+        .collect();
+
+    if keywords.is_empty() {
+        stripped.split_whitespace().next().map(str::to_string)
+    } else {
+        Some(keywords)
+    }
+}
+
+/// Extracts the module from `#import <Foo/Foo.h>`, `#include "x.h"` or `@import Foo;`.
+fn import_path(node: &Node, source: &str) -> Option<String> {
+    let text = match node.child_by_field_name("path") {
+        Some(path) => get_text(&path, source),
+        None => get_text(node, source)
+            .trim_start_matches("@import")
+            .trim()
+            .trim_end_matches(';')
+            .to_string(),
+    };
+    let module = text
+        .trim()
+        .trim_matches(|c| c == '<' || c == '>' || c == '"')
+        .to_string();
+
+    if module.is_empty() {
+        None
+    } else {
+        Some(module)
+    }
+}
+
+/// Collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extracts C call and message-send references.
+fn extract_call_references(node: &Node, source: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_calls(node, source, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+/// Recursively collects called function names and message selectors.
+fn collect_calls(node: &Node, source: &str, refs: &mut Vec<String>) {
+    match node.kind() {
+        "call_expression" => {
+            if let Some(func) = node.child_by_field_name("function") {
+                refs.push(get_text(&func, source));
+            }
+        }
+        "message_expression" => {
+            // [receiver selector], or [receiver key:arg other:arg] which
+            // sends `key:other:` like the method header declares it
+            let mut cursor = node.walk();
+            let keywords: Vec<String> = node
+                .children_by_field_name("method", &mut cursor)
+                .map(|keyword| get_text(&keyword, source))
+                .collect();
+            let keyed = (0..node.child_count())
+                .filter_map(|i| node.child(i))
+                .any(|c| c.kind() == ":");
+            let selector = if keyed {
+                Some(keywords.iter().map(|k| format!("{}:", k)).collect())
+            } else {
+                keywords.into_iter().next().or_else(|| {
+                    (1..node.named_child_count())
+                        .filter_map(|i| node.named_child(i))
+                        .find(|c| c.kind() == "identifier")
+                        .map(|c| get_text(&c, source))
+                })
+            };
+            if let Some(selector) = selector {
+                refs.push(selector);
+            }
+        }
+        _ => {}
+    }
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            collect_calls(&child, source, refs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Vec<CodeNode> {
+        let parser = ObjCParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, "Greeter.m")
+    }
+
+    const GREETER: &str = r#"
+#import <Foundation/Foundation.h>
+
+@interface Greeter : NSObject
+- (NSString *)greet:(NSString *)name;
++ (instancetype)shared;
+@end
+
+@implementation Greeter
+
+- (NSString *)greet:(NSString *)name {
+    return [NSString stringWithFormat:@"Hello, %@!", name];
+}
+
++ (instancetype)shared {
+    return [[Greeter alloc] init];
+}
+
+@end
+"#;
+
+    #[test]
+    fn test_parse_class_and_methods() {
+        let nodes = parse(GREETER);
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "Greeter" && n.kind == NodeKind::Class));
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "Foundation/Foundation.h" && n.kind == NodeKind::Import));
+
+        let greet: Vec<_> = nodes.iter().filter(|n| n.name == "greet:").collect();
+        assert!(!greet.is_empty());
+        assert!(greet.iter().all(|n| n.kind == NodeKind::Method));
+        assert!(greet.iter().all(|n| n.qualified_name == "Greeter.greet:"));
+        assert!(greet.iter().all(|n| !n.is_static));
+
+        let shared = nodes
+            .iter()
+            .find(|n| n.name == "shared" && n.line_start > 8)
+            .unwrap();
+        assert_eq!(shared.kind, NodeKind::Method);
+        assert!(shared.is_static);
+        assert!(shared.references.contains(&"alloc".to_string()));
+    }
+
+    #[test]
+    fn test_keyword_message_calls_full_selector() {
+        let nodes = parse(
+            r#"
+@implementation Person
+- (void)rename {
+    [self setName:@"Ada" age:36];
+    [self greet:@"Ada"];
+}
+@end
+"#,
+        );
+
+        let rename = nodes.iter().find(|n| n.name == "rename").unwrap();
+        assert!(rename.references.contains(&"setName:age:".to_string()));
+        assert!(rename.references.contains(&"greet:".to_string()));
+        assert!(!rename.references.contains(&"setName".to_string()));
+    }
+
+    #[test]
+    fn test_method_selector() {
+        assert_eq!(
+            method_selector("- (void)setName:(NSString *)name age:(int)age").as_deref(),
+            Some("setName:age:")
+        );
+        assert_eq!(
+            method_selector("+ (instancetype)shared").as_deref(),
+            Some("shared")
+        );
+    }
+}
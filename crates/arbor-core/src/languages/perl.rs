@@ -0,0 +1,269 @@
+//! Perl language parser implementation.
+//!
+//! Handles .pl and .pm files. Extracts `sub` definitions, `package`
+//! declarations and `use`/`require` imports. Subs are qualified with the
+//! package in effect where they are declared (`main` by default).
+
+use crate::languages::{self, LanguageParser};
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct PerlParser;
+
+impl LanguageParser for PerlParser {
+    fn language(&self) -> Language {
+        languages::from_language_fn(tree_sitter_perl::LANGUAGE.into_raw())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pl", "pm"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+        let mut package = "main".to_string();
+
+        extract_from_node(&root, source, file_path, &mut nodes, &mut package);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the Perl AST.
+///
+/// `package` is the package currently in effect. A `package Foo;`
+/// statement changes it for the rest of the enclosing block.
+fn extract_from_node(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    nodes: &mut Vec<CodeNode>,
+    package: &mut String,
+) {
+    match node.kind() {
+        "package_statement" => {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let name = get_text(&name_node, source);
+                nodes.push(
+                    CodeNode::new(&name, &name, NodeKind::Module, file_path)
+                        .with_lines(
+                            node.start_position().row as u32 + 1,
+                            node.end_position().row as u32 + 1,
+                        )
+                        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+                        .with_column(name_node.start_position().column as u32),
+                );
+
+                // package Foo { ... } scopes the name to its block
+                if let Some(block) = node.child_by_field_name("block") {
+                    let mut inner = name;
+                    extract_from_node(&block, source, file_path, nodes, &mut inner);
+                    return;
+                }
+                *package = name;
+            }
+            return;
+        }
+
+        "subroutine_declaration_statement" => {
+            if let Some(code_node) = extract_sub(node, source, file_path, package) {
+                nodes.push(code_node);
+            }
+        }
+
+        "use_statement" => {
+            if let Some(module) = node.child_by_field_name("module") {
+                nodes.push(import_node(node, &get_text(&module, source), file_path));
+            }
+        }
+
+        "require_expression" => {
+            if let Some(module) = node.named_child(0) {
+                let name = get_text(&module, source)
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .to_string();
+                nodes.push(import_node(node, &name, file_path));
+            }
+        }
+
+        _ => {}
+    }
+
+    // A package statement inside a block only lasts until the block ends
+    let mut scoped;
+    let package = if node.kind() == "block" {
+        scoped = package.clone();
+        &mut scoped
+    } else {
+        package
+    };
+
+    // Recurse into children
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes, package);
+        }
+    }
+}
+
+/// Extracts a `sub name { ... }` definition.
+fn extract_sub(node: &Node, source: &str, file_path: &str, package: &str) -> Option<CodeNode> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = get_text(&name_node, source);
+    let qualified_name = format!("{}::{}", package, name);
+
+    let references = node
+        .child_by_field_name("body")
+        .map(|body| extract_call_references(&body, source))
+        .unwrap_or_default();
+
+    Some(
+        CodeNode::new(&name, &qualified_name, NodeKind::Function, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(build_sub_signature(node, source, &name))
+            .with_visibility(perl_visibility(&name))
+            .with_references(references),
+    )
+}
+
+/// Builds an Import node for `use` or `require`.
+fn import_node(node: &Node, module: &str, file_path: &str) -> CodeNode {
+    CodeNode::new(module, module, NodeKind::Import, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Determines visibility from Perl naming convention.
+///
+/// Subs starting with an underscore are private by convention.
+fn perl_visibility(name: &str) -> Visibility {
+    if name.starts_with('_') {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Builds a sub signature, including a prototype or signature if present.
+fn build_sub_signature(node: &Node, source: &str, name: &str) -> String {
+    let params = ["signature", "prototype"]
+        .iter()
+        .find_map(|field| node.child_by_field_name(field))
+        .map(|n| get_text(&n, source))
+        .unwrap_or_default();
+
+    format!("sub {}{}", name, params)
+}
+
+/// Extracts function and method call references.
+fn extract_call_references(node: &Node, source: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_calls(node, source, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+/// Recursively collects called sub and method names.
+fn collect_calls(node: &Node, source: &str, refs: &mut Vec<String>) {
+    let callee = match node.kind() {
+        "function_call_expression" | "ambiguous_function_call_expression" => {
+            node.child_by_field_name("function")
+        }
+        "method_call_expression" => node.child_by_field_name("method"),
+        _ => None,
+    };
+
+    if let Some(callee) = callee {
+        let name = get_text(&callee, source);
+        // Foo::Bar::baz() refers to the sub `baz`
+        let name = name.rsplit("::").next().unwrap_or(&name).to_string();
+        refs.push(name);
+    }
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            collect_calls(&child, source, refs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Vec<CodeNode> {
+        let parser = PerlParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, "Greeter.pm")
+    }
+
+    const GREETER: &str = r#"
+package Greeter;
+
+use strict;
+use warnings;
+
+sub greet {
+    my ($name) = @_;
+    return _format($name);
+}
+
+sub _format {
+    my ($name) = @_;
+    return "Hello, $name!";
+}
+
+1;
+"#;
+
+    #[test]
+    fn test_parse_package_and_subs() {
+        let nodes = parse(GREETER);
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "Greeter" && n.kind == NodeKind::Module));
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "strict" && n.kind == NodeKind::Import));
+
+        let greet = nodes.iter().find(|n| n.name == "greet").unwrap();
+        assert_eq!(greet.kind, NodeKind::Function);
+        assert_eq!(greet.qualified_name, "Greeter::greet");
+        assert_eq!(greet.visibility, Visibility::Public);
+
+        let format = nodes.iter().find(|n| n.name == "_format").unwrap();
+        assert_eq!(format.qualified_name, "Greeter::_format");
+        assert_eq!(format.visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn test_sub_calls_populate_references() {
+        let nodes = parse(GREETER);
+
+        let greet = nodes.iter().find(|n| n.name == "greet").unwrap();
+        assert!(greet.references.contains(&"_format".to_string()));
+    }
+}
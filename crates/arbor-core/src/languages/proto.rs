@@ -0,0 +1,402 @@
+//! Protocol Buffers parser implementation.
+//!
+//! Handles .proto files, the contracts between gRPC services. Messages
+//! become structs (nested ones qualified by their parent, `Order.Item`),
+//! enums stay enums, services become interfaces and their `rpc` methods
+//! methods (`OrderService.PlaceOrder`). Field types and RPC request and
+//! response types are recorded as references, so a change to a message
+//! shows up in the impact of every message and RPC that uses it.
+//!
+//! Everything in a schema is part of its public contract, so every symbol
+//! is public.
+
+use crate::languages::{self, LanguageParser};
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct ProtoParser;
+
+impl LanguageParser for ProtoParser {
+    fn language(&self) -> Language {
+        languages::from_language_fn(tree_sitter_proto::LANGUAGE.into_raw())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["proto"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+
+        extract_from_node(&root, source, file_path, &mut nodes, None);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the Protobuf AST.
+///
+/// `scope` is the qualified name of the enclosing message or service.
+fn extract_from_node(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    nodes: &mut Vec<CodeNode>,
+    scope: Option<&str>,
+) {
+    match node.kind() {
+        "message" => {
+            if let Some(code_node) = extract_message(node, source, file_path, scope) {
+                let qualified_name = code_node.qualified_name.clone();
+                nodes.push(code_node);
+                recurse(node, source, file_path, nodes, Some(&qualified_name));
+                return;
+            }
+        }
+
+        "enum" => {
+            if let Some(code_node) = extract_type(
+                node,
+                source,
+                file_path,
+                scope,
+                NodeKind::Enum,
+                &["enum_name"],
+            ) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "service" => {
+            if let Some(code_node) = extract_type(
+                node,
+                source,
+                file_path,
+                scope,
+                NodeKind::Interface,
+                &["service_name"],
+            ) {
+                let qualified_name = code_node.qualified_name.clone();
+                nodes.push(code_node);
+                recurse(node, source, file_path, nodes, Some(&qualified_name));
+                return;
+            }
+        }
+
+        "rpc" => {
+            if let Some(code_node) = extract_rpc(node, source, file_path, scope) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        "import" => {
+            if let Some(code_node) = extract_import(node, source, file_path) {
+                nodes.push(code_node);
+            }
+            return;
+        }
+
+        _ => {}
+    }
+
+    recurse(node, source, file_path, nodes, scope);
+}
+
+/// Visits every child of `node`.
+fn recurse(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    nodes: &mut Vec<CodeNode>,
+    scope: Option<&str>,
+) {
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes, scope);
+        }
+    }
+}
+
+/// Extracts a message, referencing the types of its fields.
+///
+/// Fields of nested messages belong to those messages, not this one.
+fn extract_message(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    scope: Option<&str>,
+) -> Option<CodeNode> {
+    let code_node = extract_type(
+        node,
+        source,
+        file_path,
+        scope,
+        NodeKind::Struct,
+        &["message_name"],
+    )?;
+
+    let mut references = Vec::new();
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            collect_type_references(&child, source, &mut references);
+        }
+    }
+    references.sort();
+    references.dedup();
+
+    Some(code_node.with_references(references))
+}
+
+/// Extracts a named declaration: a message, enum or service.
+fn extract_type(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    scope: Option<&str>,
+    kind: NodeKind,
+    name_kinds: &[&str],
+) -> Option<CodeNode> {
+    let name_node = find_name(node, name_kinds)?;
+    let name = get_text(&name_node, source);
+    let qualified_name = qualify(scope, &name);
+    let keyword = node.child(0).map(|k| get_text(&k, source))?;
+
+    Some(
+        CodeNode::new(&name, &qualified_name, kind, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(format!("{} {}", keyword, name))
+            .with_visibility(Visibility::Public),
+    )
+}
+
+/// Extracts an `rpc` as a method of its service.
+///
+/// `rpc PlaceOrder(OrderRequest) returns (stream OrderStatus)` references
+/// both `OrderRequest` and `OrderStatus`.
+fn extract_rpc(
+    node: &Node,
+    source: &str,
+    file_path: &str,
+    scope: Option<&str>,
+) -> Option<CodeNode> {
+    let name_node = find_name(node, &["rpc_name"])?;
+    let name = get_text(&name_node, source);
+
+    let mut references = Vec::new();
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            if child.kind() == "message_or_enum_type" {
+                references.push(type_name(&child, source));
+            }
+        }
+    }
+    references.dedup();
+
+    // The signature is everything before the options block or `;`
+    let text = get_text(node, source);
+    let header = text.split('{').next().unwrap_or(&text);
+    let signature = collapse_whitespace(header.trim().trim_end_matches(';'));
+
+    Some(
+        CodeNode::new(&name, &qualify(scope, &name), NodeKind::Method, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(signature)
+            .with_visibility(Visibility::Public)
+            .with_references(references),
+    )
+}
+
+/// Extracts `import "path/to/file.proto";` (also `import public`/`weak`).
+fn extract_import(node: &Node, source: &str, file_path: &str) -> Option<CodeNode> {
+    let path = (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .find(|c| c.kind() == "string")?;
+    let path = get_text(&path, source)
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string();
+
+    Some(
+        CodeNode::new(&path, &path, NodeKind::Import, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32),
+    )
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Finds the name child of a declaration: the first child of one of
+/// `kinds`, falling back to the first identifier.
+fn find_name<'a>(node: &Node<'a>, kinds: &[&str]) -> Option<Node<'a>> {
+    let children: Vec<Node<'a>> = (0..node.named_child_count())
+        .filter_map(|i| node.named_child(i))
+        .collect();
+    children
+        .iter()
+        .find(|c| kinds.contains(&c.kind()))
+        .or_else(|| children.iter().find(|c| c.kind() == "identifier"))
+        .copied()
+}
+
+/// Joins a name onto its enclosing scope.
+fn qualify(scope: Option<&str>, name: &str) -> String {
+    match scope {
+        Some(scope) => format!("{}.{}", scope, name),
+        None => name.to_string(),
+    }
+}
+
+/// The simple name of a type reference: `.shop.v1.Order.Item` is `Item`.
+fn type_name(node: &Node, source: &str) -> String {
+    let text = get_text(node, source);
+    text.rsplit('.').next().unwrap_or(&text).trim().to_string()
+}
+
+/// Collects message and enum types used by fields under `node`, without
+/// descending into nested messages, enums or services.
+fn collect_type_references(node: &Node, source: &str, refs: &mut Vec<String>) {
+    match node.kind() {
+        "message" | "enum" | "service" => return,
+        "message_or_enum_type" => {
+            refs.push(type_name(node, source));
+            return;
+        }
+        _ => {}
+    }
+
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            collect_type_references(&child, source, refs);
+        }
+    }
+}
+
+/// Collapses runs of whitespace (including newlines) into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str, file_path: &str) -> Vec<CodeNode> {
+        let parser = ProtoParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, file_path)
+    }
+
+    #[test]
+    fn test_parse_service_with_rpcs() {
+        let source = r#"
+syntax = "proto3";
+
+package shop.v1;
+
+import "google/protobuf/timestamp.proto";
+
+service OrderService {
+  rpc PlaceOrder(OrderRequest) returns (OrderReply);
+  rpc WatchOrder(OrderRequest) returns (stream OrderStatus) {
+    option deprecated = true;
+  }
+}
+"#;
+        let nodes = parse(source, "orders.proto");
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "google/protobuf/timestamp.proto" && n.kind == NodeKind::Import));
+
+        let service = nodes.iter().find(|n| n.name == "OrderService").unwrap();
+        assert_eq!(service.kind, NodeKind::Interface);
+        assert_eq!(service.visibility, Visibility::Public);
+
+        let rpcs: Vec<&CodeNode> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Method)
+            .collect();
+        assert_eq!(rpcs.len(), 2);
+
+        let place = rpcs.iter().find(|n| n.name == "PlaceOrder").unwrap();
+        assert_eq!(place.qualified_name, "OrderService.PlaceOrder");
+        assert_eq!(place.references, vec!["OrderRequest", "OrderReply"]);
+        assert_eq!(
+            place.signature.as_deref(),
+            Some("rpc PlaceOrder(OrderRequest) returns (OrderReply)")
+        );
+
+        let watch = rpcs.iter().find(|n| n.name == "WatchOrder").unwrap();
+        assert_eq!(watch.references, vec!["OrderRequest", "OrderStatus"]);
+        assert_eq!(
+            watch.signature.as_deref(),
+            Some("rpc WatchOrder(OrderRequest) returns (stream OrderStatus)")
+        );
+    }
+
+    #[test]
+    fn test_parse_message_with_nested_type_reference() {
+        let source = r#"
+syntax = "proto3";
+
+message Order {
+  message Item {
+    string sku = 1;
+    Money price = 2;
+  }
+
+  enum State {
+    STATE_UNSPECIFIED = 0;
+    STATE_PAID = 1;
+  }
+
+  string id = 1;
+  repeated Item items = 2;
+  State state = 3;
+  .shop.v1.Customer customer = 4;
+}
+"#;
+        let nodes = parse(source, "orders.proto");
+
+        let order = nodes.iter().find(|n| n.name == "Order").unwrap();
+        assert_eq!(order.kind, NodeKind::Struct);
+        assert_eq!(order.signature.as_deref(), Some("message Order"));
+        // Field types link the message to what it uses; the nested
+        // message's own fields belong to it
+        assert_eq!(order.references, vec!["Customer", "Item", "State"]);
+
+        let item = nodes.iter().find(|n| n.name == "Item").unwrap();
+        assert_eq!(item.kind, NodeKind::Struct);
+        assert_eq!(item.qualified_name, "Order.Item");
+        assert_eq!(item.references, vec!["Money"]);
+
+        let state = nodes.iter().find(|n| n.name == "State").unwrap();
+        assert_eq!(state.kind, NodeKind::Enum);
+        assert_eq!(state.qualified_name, "Order.State");
+    }
+}
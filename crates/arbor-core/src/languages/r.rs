@@ -0,0 +1,308 @@
+//! R language parser implementation.
+//!
+//! Handles .r and .R files. R has no declaration syntax, so functions
+//! are found by looking for `name <- function(...)` assignments, S4
+//! definitions via `setClass`/`setGeneric`/`setMethod`, and imports
+//! via `library()`, `require()` and `source()`.
+
+use crate::languages::LanguageParser;
+use crate::node::{CodeNode, NodeKind, Visibility};
+use tree_sitter::{Language, Node, Tree};
+
+pub struct RParser;
+
+impl LanguageParser for RParser {
+    fn language(&self) -> Language {
+        tree_sitter_r::language()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["r", "R"]
+    }
+
+    fn extract_nodes(&self, tree: &Tree, source: &str, file_path: &str) -> Vec<CodeNode> {
+        let mut nodes = Vec::new();
+        let root = tree.root_node();
+
+        extract_from_node(&root, source, file_path, &mut nodes);
+
+        nodes
+    }
+}
+
+/// Recursively extracts nodes from the R AST.
+fn extract_from_node(node: &Node, source: &str, file_path: &str, nodes: &mut Vec<CodeNode>) {
+    match node.kind() {
+        // Assignments: foo <- function(...), foo = function(...), function(...) -> foo
+        "binary_operator" => {
+            if let Some(code_node) = extract_function_assignment(node, source, file_path) {
+                nodes.push(code_node);
+            }
+        }
+
+        // Calls: library(), source(), setClass(), setGeneric(), setMethod()
+        "call" => {
+            if let Some(code_node) = extract_call(node, source, file_path) {
+                nodes.push(code_node);
+            }
+        }
+
+        _ => {}
+    }
+
+    // Recurse into children
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            extract_from_node(&child, source, file_path, nodes);
+        }
+    }
+}
+
+/// Extracts a function bound with `<-`, `<<-`, `=` or `->`.
+fn extract_function_assignment(node: &Node, source: &str, file_path: &str) -> Option<CodeNode> {
+    let operator = node.child_by_field_name("operator")?;
+    let (name_node, value_node) = match get_text(&operator, source).as_str() {
+        "<-" | "<<-" | "=" => (
+            node.child_by_field_name("lhs")?,
+            node.child_by_field_name("rhs")?,
+        ),
+        "->" | "->>" => (
+            node.child_by_field_name("rhs")?,
+            node.child_by_field_name("lhs")?,
+        ),
+        _ => return None,
+    };
+
+    if value_node.kind() != "function_definition" {
+        return None;
+    }
+
+    let name = match name_node.kind() {
+        "identifier" => get_text(&name_node, source),
+        "string" => string_value(&name_node, source),
+        _ => return None,
+    };
+
+    let signature = build_function_signature(&value_node, source, &name);
+    let references = extract_call_references(&value_node, source);
+
+    Some(
+        CodeNode::new(&name, &name, NodeKind::Function, file_path)
+            .with_lines(
+                node.start_position().row as u32 + 1,
+                node.end_position().row as u32 + 1,
+            )
+            .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+            .with_column(name_node.start_position().column as u32)
+            .with_signature(signature)
+            .with_visibility(r_visibility(&name))
+            .with_references(references),
+    )
+}
+
+/// Extracts imports and S4 definitions from well-known calls.
+fn extract_call(node: &Node, source: &str, file_path: &str) -> Option<CodeNode> {
+    let function = node.child_by_field_name("function")?;
+    if function.kind() != "identifier" {
+        return None;
+    }
+
+    let args = call_arguments(node);
+    let first = args.first()?;
+
+    let (name, qualified_name, kind) = match get_text(&function, source).as_str() {
+        "library" | "require" | "requireNamespace" | "source" => {
+            let module = match first.kind() {
+                "identifier" => get_text(first, source),
+                "string" => string_value(first, source),
+                _ => return None,
+            };
+            (module.clone(), module, NodeKind::Import)
+        }
+        "setClass" | "setRefClass" => {
+            let name = literal_string(first, source)?;
+            (name.clone(), name, NodeKind::Class)
+        }
+        "setGeneric" => {
+            let name = literal_string(first, source)?;
+            (name.clone(), name, NodeKind::Function)
+        }
+        "setMethod" => {
+            // setMethod("area", "Circle", function(shape) ...)
+            let name = literal_string(first, source)?;
+            let qualified_name = match args.get(1).and_then(|n| literal_string(n, source)) {
+                Some(class) => format!("{}.{}", class, name),
+                None => name.clone(),
+            };
+            (name, qualified_name, NodeKind::Method)
+        }
+        _ => return None,
+    };
+
+    let mut code_node = CodeNode::new(&name, &qualified_name, kind, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32);
+
+    if kind != NodeKind::Import {
+        code_node = code_node
+            .with_column(first.start_position().column as u32)
+            .with_visibility(r_visibility(&name))
+            .with_references(extract_call_references(node, source));
+    }
+
+    Some(code_node)
+}
+
+// ============================================================================
+// Helper functions
+// ============================================================================
+
+/// Gets text content of a node.
+fn get_text(node: &Node, source: &str) -> String {
+    source[node.byte_range()].to_string()
+}
+
+/// Returns the value nodes of a call's arguments, in order.
+fn call_arguments<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    let mut values = Vec::new();
+    if let Some(arguments) = node.child_by_field_name("arguments") {
+        for i in 0..arguments.child_count() {
+            if let Some(child) = arguments.child(i) {
+                if child.kind() == "argument" {
+                    if let Some(value) = child.child_by_field_name("value") {
+                        values.push(value);
+                    }
+                }
+            }
+        }
+    }
+    values
+}
+
+/// Strips the quotes from a string literal.
+fn string_value(node: &Node, source: &str) -> String {
+    get_text(node, source)
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+/// Returns the contents of a node if it is a string literal.
+fn literal_string(node: &Node, source: &str) -> Option<String> {
+    (node.kind() == "string").then(|| string_value(node, source))
+}
+
+/// Determines visibility from R naming convention.
+///
+/// Names starting with a dot are hidden from `ls()` and treated as internal.
+fn r_visibility(name: &str) -> Visibility {
+    if name.starts_with('.') {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    }
+}
+
+/// Builds a function signature.
+fn build_function_signature(node: &Node, source: &str, name: &str) -> String {
+    let params = node
+        .child_by_field_name("parameters")
+        .map(|n| get_text(&n, source))
+        .unwrap_or_else(|| "()".to_string());
+
+    format!("{} <- function{}", name, params)
+}
+
+/// Extracts function call references.
+fn extract_call_references(node: &Node, source: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    collect_calls(node, source, &mut refs);
+    refs.sort();
+    refs.dedup();
+    refs
+}
+
+/// Recursively collects function call names.
+fn collect_calls(node: &Node, source: &str, refs: &mut Vec<String>) {
+    if node.kind() == "call" {
+        if let Some(func_node) = node.child_by_field_name("function") {
+            let call_name = get_text(&func_node, source);
+            refs.push(call_name);
+        }
+    }
+
+    for i in 0..node.child_count() {
+        if let Some(child) = node.child(i) {
+            collect_calls(&child, source, refs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Vec<CodeNode> {
+        let parser = RParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, "analysis.R")
+    }
+
+    #[test]
+    fn test_parse_function_assignment() {
+        let source = r#"
+summarise_sales <- function(df, by) {
+  aggregate(df$amount, list(df[[by]]), sum)
+}
+"#;
+
+        let nodes = parse(source);
+
+        let func = nodes
+            .iter()
+            .find(|n| n.name == "summarise_sales")
+            .expect("function assignment should be extracted");
+        assert!(matches!(func.kind, NodeKind::Function));
+        assert!(func.references.contains(&"aggregate".to_string()));
+    }
+
+    #[test]
+    fn test_parse_library_import() {
+        let source = r#"
+library(dplyr)
+source("helpers.R")
+"#;
+
+        let nodes = parse(source);
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "dplyr" && matches!(n.kind, NodeKind::Import)));
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "helpers.R" && matches!(n.kind, NodeKind::Import)));
+    }
+
+    #[test]
+    fn test_parse_s4_definitions() {
+        let source = r#"
+setClass("Circle", representation(r = "numeric"))
+setGeneric("area", function(shape) standardGeneric("area"))
+setMethod("area", "Circle", function(shape) pi * shape@r^2)
+"#;
+
+        let nodes = parse(source);
+
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "Circle" && matches!(n.kind, NodeKind::Class)));
+        assert!(nodes
+            .iter()
+            .any(|n| n.qualified_name == "Circle.area" && matches!(n.kind, NodeKind::Method)));
+    }
+}
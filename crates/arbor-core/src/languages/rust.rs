@@ -174,6 +174,8 @@ fn extract_function(
             .with_signature(signature)
             .with_visibility(visibility)
             .with_references(references)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_attributes(node, source))
             .with_async_if(is_async),
     )
 }
@@ -192,7 +194,9 @@ fn extract_struct(node: &Node, source: &str, file_path: &str) -> Option<CodeNode
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_attributes(node, source)),
     )
 }
 
@@ -210,7 +214,9 @@ fn extract_enum(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_attributes(node, source)),
     )
 }
 
@@ -228,7 +234,9 @@ fn extract_trait(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_attributes(node, source)),
     )
 }
 
@@ -282,7 +290,8 @@ fn extract_const(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_attributes(extract_attributes(node, source)),
     )
 }
 
@@ -300,7 +309,9 @@ fn extract_type_alias(node: &Node, source: &str, file_path: &str) -> Option<Code
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
-            .with_visibility(visibility),
+            .with_visibility(visibility)
+            .with_generics(extract_generics(node, source))
+            .with_attributes(extract_attributes(node, source)),
     )
 }
 
@@ -325,6 +336,52 @@ fn find_child_by_kind<'a>(node: &'a Node, kind: &str) -> Option<Node<'a>> {
     None
 }
 
+/// Extracts declared generic parameters (e.g. `<'a, T, U: Clone>`).
+fn extract_generics(node: &Node, source: &str) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return Vec::new();
+    };
+
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter(|p| {
+            !matches!(
+                p.kind(),
+                "line_comment" | "block_comment" | "attribute_item"
+            )
+        })
+        .map(|p| get_text(&p, source))
+        .collect()
+}
+
+/// Collects the outer attributes (`#[...]`) above an item, in source
+/// order. Doc comments between them are skipped.
+fn extract_attributes(node: &Node, source: &str) -> Vec<String> {
+    let mut attributes = Vec::new();
+    let mut sibling = node.prev_named_sibling();
+    while let Some(item) = sibling {
+        match item.kind() {
+            "attribute_item" => {
+                let attribute = find_child_by_kind(&item, "attribute")
+                    .map(|a| get_text(&a, source))
+                    .unwrap_or_else(|| {
+                        let text = get_text(&item, source);
+                        text.trim_start_matches("#[")
+                            .trim_end_matches(']')
+                            .to_string()
+                    });
+                attributes.push(attribute);
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = item.prev_named_sibling();
+    }
+    attributes.reverse();
+    attributes
+}
+
 /// Gets the target type of an impl block (e.g., "UserService" from `impl UserService`).
 fn get_impl_target(node: &Node, source: &str) -> Option<String> {
     // The type being implemented for
@@ -421,3 +478,47 @@ impl CodeNodeExt for CodeNode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Vec<CodeNode> {
+        let parser = RustParser;
+        let mut ts_parser = tree_sitter::Parser::new();
+        ts_parser.set_language(&parser.language()).unwrap();
+        let tree = ts_parser.parse(source, None).unwrap();
+
+        parser.extract_nodes(&tree, source, "user.rs")
+    }
+
+    #[test]
+    fn test_attributes_one_entry_per_item() {
+        let source = r#"
+#[derive(Debug, Clone)]
+/// A user record.
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    name: String,
+}
+
+fn helper() {}
+
+#[test]
+fn test_user() {}
+"#;
+        let nodes = parse(source);
+
+        let user = nodes.iter().find(|n| n.name == "User").unwrap();
+        assert_eq!(
+            user.attributes,
+            vec!["derive(Debug, Clone)", "serde(rename_all = \"camelCase\")"]
+        );
+
+        let helper = nodes.iter().find(|n| n.name == "helper").unwrap();
+        assert!(helper.attributes.is_empty());
+
+        let test = nodes.iter().find(|n| n.name == "test_user").unwrap();
+        assert_eq!(test.attributes, vec!["test"]);
+    }
+}
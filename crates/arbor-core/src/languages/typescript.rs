@@ -102,6 +102,8 @@ fn extract_from_node(
 
         // Export statements (named exports, default exports)
         "export_statement" => {
+            extract_exports(node, source, file_path, nodes);
+
             // The export might wrap a function or class, extract those
             for i in 0..node.child_count() {
                 if let Some(child) = node.child(i) {
@@ -175,6 +177,7 @@ fn extract_function(
                 Visibility::Private
             })
             .with_references(references)
+            .with_generics(extract_generics(node, source))
             .with_async_if(is_async)
             .with_exported_if(is_exported),
     )
@@ -207,6 +210,7 @@ fn extract_arrow_function(node: &Node, source: &str, file_path: &str) -> Option<
                             .with_column(name_node.start_position().column as u32)
                             .with_signature(signature)
                             .with_references(references)
+                            .with_generics(extract_generics(&value_node, source))
                             .with_async_if(is_async)
                             .with_exported_if(is_exported),
                     );
@@ -236,6 +240,7 @@ fn extract_class(node: &Node, source: &str, file_path: &str) -> Option<CodeNode>
             } else {
                 Visibility::Private
             })
+            .with_generics(extract_generics(node, source))
             .with_exported_if(is_exported),
     )
 }
@@ -274,6 +279,7 @@ fn extract_method(
             .with_signature(signature)
             .with_visibility(visibility)
             .with_references(references)
+            .with_generics(extract_generics(node, source))
             .with_async_if(is_async)
             .with_static_if(is_static),
     )
@@ -298,6 +304,7 @@ fn extract_interface(node: &Node, source: &str, file_path: &str) -> Option<CodeN
             } else {
                 Visibility::Private
             })
+            .with_generics(extract_generics(node, source))
             .with_exported_if(is_exported),
     )
 }
@@ -316,6 +323,7 @@ fn extract_type_alias(node: &Node, source: &str, file_path: &str) -> Option<Code
             )
             .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
             .with_column(name_node.start_position().column as u32)
+            .with_generics(extract_generics(node, source))
             .with_exported_if(is_exported),
     )
 }
@@ -339,6 +347,72 @@ fn extract_import(node: &Node, source: &str, file_path: &str) -> Option<CodeNode
     )
 }
 
+/// Extracts export nodes from an `export` statement.
+///
+/// `export { a, b as c }` yields exports `a` and `c`, and `export default x`
+/// yields `default`. Each references the local symbol it exposes so the
+/// graph builder can link them with an `Exports` edge. Declarations like
+/// `export function f` are flagged `is_exported` instead and get no
+/// separate node.
+fn extract_exports(node: &Node, source: &str, file_path: &str, nodes: &mut Vec<CodeNode>) {
+    let is_default = (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .any(|c| c.kind() == "default");
+
+    if is_default {
+        let target = node
+            .child_by_field_name("declaration")
+            .and_then(|d| d.child_by_field_name("name"))
+            .or_else(|| {
+                node.child_by_field_name("value")
+                    .filter(|v| v.kind() == "identifier")
+            })
+            .map(|n| get_text(&n, source));
+
+        nodes.push(export_node(node, "default", target, file_path));
+        return;
+    }
+
+    for i in 0..node.named_child_count() {
+        let Some(clause) = node.named_child(i) else {
+            continue;
+        };
+        if clause.kind() != "export_clause" {
+            continue;
+        }
+
+        for j in 0..clause.named_child_count() {
+            let Some(spec) = clause.named_child(j) else {
+                continue;
+            };
+            let Some(local) = spec.child_by_field_name("name") else {
+                continue;
+            };
+            let local = get_text(&local, source);
+            let exported = spec
+                .child_by_field_name("alias")
+                .map(|a| get_text(&a, source))
+                .unwrap_or_else(|| local.clone());
+
+            nodes.push(export_node(&spec, &exported, Some(local), file_path));
+        }
+    }
+}
+
+/// Builds an `Export` node named after what importers see.
+fn export_node(node: &Node, name: &str, target: Option<String>, file_path: &str) -> CodeNode {
+    CodeNode::new(name, name, NodeKind::Export, file_path)
+        .with_lines(
+            node.start_position().row as u32 + 1,
+            node.end_position().row as u32 + 1,
+        )
+        .with_bytes(node.start_byte() as u32, node.end_byte() as u32)
+        .with_column(node.start_position().column as u32)
+        .with_visibility(Visibility::Public)
+        .with_references(target.into_iter().collect())
+        .as_exported()
+}
+
 // ============================================================================
 // Helper functions
 // ============================================================================
@@ -385,6 +459,20 @@ fn detect_visibility(node: &Node, source: &str) -> Visibility {
     Visibility::Public // Default for class members
 }
 
+/// Extracts declared type parameters (e.g. `<T, K extends keyof T>`).
+fn extract_generics(node: &Node, source: &str) -> Vec<String> {
+    let Some(params) = node.child_by_field_name("type_parameters") else {
+        return Vec::new();
+    };
+
+    let mut cursor = params.walk();
+    params
+        .named_children(&mut cursor)
+        .filter(|p| p.kind() == "type_parameter")
+        .map(|p| get_text(&p, source))
+        .collect()
+}
+
 /// Builds a function signature string.
 fn build_function_signature(node: &Node, source: &str) -> String {
     // Try to extract name, params, and return type
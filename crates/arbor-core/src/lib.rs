@@ -16,14 +16,28 @@
 //! }
 //! ```
 
+pub mod complexity;
+pub mod config;
 pub mod error;
+mod hierarchy;
 pub mod languages;
 pub mod node;
 pub mod parser;
 pub mod parser_v2;
+mod spans;
 
+pub use complexity::{estimate_complexity, HIGH_COMPLEXITY};
+pub use config::{is_config_file, parse_config};
 pub use error::{ParseError, Result};
+pub use hierarchy::assign_parents;
 pub use languages::LanguageParser;
-pub use node::{CodeNode, NodeKind, Visibility};
-pub use parser::{detect_language, parse_file, parse_source};
+pub use node::{CodeNode, NodeKind, Span, Visibility};
+pub use parser::{
+    detect_language, detect_language_from_content, dump_component_tree, dump_tree, edit_between,
+    parse_file, parse_file_checked, parse_source, parse_source_incremental, parse_source_with_tree,
+    syntax_error_lines, ParsedFile,
+};
 pub use parser_v2::{ArborParser, ParseResult, RelationType, SymbolRelation};
+
+// Re-exported for the incremental parsing API
+pub use tree_sitter::{InputEdit, Point, Tree};
@@ -79,6 +79,34 @@ pub enum Visibility {
     Internal,
 }
 
+/// A range of source text.
+///
+/// Lines are 1-indexed like `CodeNode::line_start`; columns are 0-indexed
+/// byte offsets within the line like `CodeNode::column`. The end is
+/// exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub line_start: u32,
+    pub column_start: u32,
+    pub line_end: u32,
+    pub column_end: u32,
+}
+
+impl Span {
+    /// Moves the span down by `lines`, and right by `columns` on the
+    /// first line (for code embedded in a larger file).
+    pub fn shift(&mut self, lines: u32, columns: u32) {
+        if self.line_start == 1 {
+            self.column_start += columns;
+        }
+        if self.line_end == 1 {
+            self.column_end += columns;
+        }
+        self.line_start += lines;
+        self.line_end += lines;
+    }
+}
+
 /// A code entity extracted from source.
 ///
 /// This is the core data type that flows through Arbor. It's designed
@@ -124,6 +152,10 @@ pub struct CodeNode {
     /// Whether this is exported (TS/ES modules).
     pub is_exported: bool,
 
+    /// Whether this is test code. Set by post-build passes.
+    #[serde(default)]
+    pub is_test: bool,
+
     /// Docstring or leading comment.
     pub docstring: Option<String>,
 
@@ -134,6 +166,37 @@ pub struct CodeNode {
     /// Entities this node references (call targets, type refs, etc).
     /// These are names, not IDs - resolution happens in the graph crate.
     pub references: Vec<String>,
+
+    /// Declared generic/template parameters, as written (e.g. `T`, `U: Clone`).
+    #[serde(default)]
+    pub generics: Vec<String>,
+
+    /// Estimated cyclomatic complexity (functions and methods only, 0 otherwise).
+    #[serde(default)]
+    pub complexity: u32,
+
+    /// ID of the enclosing class, struct, enum or interface, if any.
+    #[serde(default)]
+    pub parent_id: Option<String>,
+
+    /// Attributes (Rust) or annotations (Java) on the declaration, one per
+    /// `#[...]` or `@...` as written, minus the delimiters: `#[derive(Debug,
+    /// Clone)]` is the single entry `derive(Debug, Clone)`.
+    #[serde(default)]
+    pub attributes: Vec<String>,
+
+    /// Column just past the last character of the definition, on `line_end`.
+    #[serde(default)]
+    pub end_column: u32,
+
+    /// Exact range of the name identifier.
+    #[serde(default)]
+    pub name_span: Option<Span>,
+
+    /// Range of the body (block, class body, ...). Everything from
+    /// `line_start` up to the body is the header.
+    #[serde(default)]
+    pub body_span: Option<Span>,
 }
 
 impl CodeNode {
@@ -178,10 +241,18 @@ impl CodeNode {
             is_async: false,
             is_static: false,
             is_exported: false,
+            is_test: false,
             docstring: None,
             byte_start: 0,
             byte_end: 0,
             references: Vec::new(),
+            generics: Vec::new(),
+            complexity: 0,
+            parent_id: None,
+            attributes: Vec::new(),
+            end_column: 0,
+            name_span: None,
+            body_span: None,
         }
     }
 
@@ -235,11 +306,35 @@ impl CodeNode {
         self
     }
 
+    /// Builder pattern: mark as test code.
+    pub fn as_test(mut self) -> Self {
+        self.is_test = true;
+        self
+    }
+
     /// Builder pattern: add references.
     pub fn with_references(mut self, refs: Vec<String>) -> Self {
         self.references = refs;
         self
     }
+
+    /// Builder pattern: set generic parameters.
+    pub fn with_generics(mut self, generics: Vec<String>) -> Self {
+        self.generics = generics;
+        self
+    }
+
+    /// Builder pattern: set attributes or annotations.
+    pub fn with_attributes(mut self, attributes: Vec<String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Builder pattern: set complexity estimate.
+    pub fn with_complexity(mut self, complexity: u32) -> Self {
+        self.complexity = complexity;
+        self
+    }
 }
 
 impl PartialEq for CodeNode {
@@ -2,19 +2,26 @@
 //!
 //! This module wraps Tree-sitter and provides a clean API for parsing
 //! source files into CodeNodes. Language detection is automatic based
-//! on file extension.
+//! on file extension, falling back to the shebang or first line for
+//! files without one.
 
+use crate::complexity::annotate_complexity;
+use crate::config::{is_config_file, parse_config};
 use crate::error::{ParseError, Result};
+use crate::hierarchy::assign_parents;
 use crate::languages::{get_parser, LanguageParser};
 use crate::node::CodeNode;
+use crate::spans::annotate_spans;
 use std::fs;
 use std::path::Path;
+use tree_sitter::{InputEdit, Point, Tree, TreeCursor};
 
 /// Parses a source file and extracts all code nodes.
 ///
 /// This is the main entry point for parsing. It handles:
 /// - Reading the file from disk
-/// - Detecting the language from the extension
+/// - Detecting the language from the extension (or the content, for
+///   extensionless scripts)
 /// - Parsing with Tree-sitter
 /// - Extracting meaningful code entities
 ///
@@ -28,6 +35,36 @@ use std::path::Path;
 /// println!("Found {} nodes", nodes.len());
 /// ```
 pub fn parse_file(path: &Path) -> Result<Vec<CodeNode>> {
+    parse_file_checked(path).map(|parsed| parsed.nodes)
+}
+
+/// Nodes extracted from one file, with the lines of any syntax errors.
+///
+/// Tree-sitter recovers from syntax errors by wrapping the broken region
+/// in an `ERROR` node (or inserting a `MISSING` one) and parsing on, and
+/// extractors descend into those regions like any other. So a file with
+/// errors still yields every symbol outside the broken code, but the
+/// symbols inside it may be missing or cut short.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFile {
+    pub nodes: Vec<CodeNode>,
+    /// First line (1-indexed) of each `ERROR` or `MISSING` node, in order.
+    pub syntax_errors: Vec<u32>,
+}
+
+impl ParsedFile {
+    /// True if the file had syntax errors, so `nodes` may be incomplete.
+    pub fn is_partial(&self) -> bool {
+        !self.syntax_errors.is_empty()
+    }
+}
+
+/// Like `parse_file`, but also reports where the file has syntax errors.
+///
+/// A file with errors is not a failure: its recoverable nodes come back
+/// with `ParsedFile::is_partial` set, so the graph stays useful while the
+/// file is being edited.
+pub fn parse_file_checked(path: &Path) -> Result<ParsedFile> {
     // Read the source file
     let source = fs::read_to_string(path).map_err(|e| ParseError::io(path, e))?;
 
@@ -38,19 +75,69 @@ pub fn parse_file(path: &Path) -> Result<Vec<CodeNode>> {
             .map(|n| n == "__init__.py")
             .unwrap_or(false)
         {
-            return Ok(vec![]); // Return empty nodes, not an error
+            return Ok(ParsedFile::default()); // Return empty nodes, not an error
         }
         return Err(ParseError::EmptyFile(path.to_path_buf()));
     }
 
-    // Get the appropriate parser for this file type
-    let parser =
-        detect_language(path).ok_or_else(|| ParseError::UnsupportedLanguage(path.to_path_buf()))?;
-
     // Use the file path as a string for node IDs
     let file_path = path.to_string_lossy().to_string();
 
-    parse_source(&source, &file_path, parser.as_ref())
+    // Single-file components embed their code in <script> blocks
+    if is_component_file(path) {
+        return parse_component(&source, &file_path);
+    }
+
+    // Config files get a structural pass instead of a grammar
+    if is_config_file(path) {
+        return Ok(ParsedFile {
+            nodes: parse_config(&source, &file_path),
+            syntax_errors: Vec::new(),
+        });
+    }
+
+    // Get the appropriate parser for this file type
+    let parser = detect_language(path)
+        .or_else(|| detect_language_from_content(&source))
+        .ok_or_else(|| ParseError::UnsupportedLanguage(path.to_path_buf()))?;
+
+    let (nodes, tree) = parse_source_with_tree(&source, &file_path, parser.as_ref())?;
+    Ok(ParsedFile {
+        nodes,
+        syntax_errors: syntax_error_lines(&tree),
+    })
+}
+
+/// Lines (1-indexed) where `tree` has `ERROR` or `MISSING` nodes.
+///
+/// Only subtrees that contain errors are visited, so this is cheap for the
+/// common case of a clean file.
+pub fn syntax_error_lines(tree: &Tree) -> Vec<u32> {
+    let mut lines = Vec::new();
+    let mut cursor = tree.walk();
+    if !cursor.node().has_error() {
+        return lines;
+    }
+
+    loop {
+        let node = cursor.node();
+        let descend = if node.is_error() || node.is_missing() {
+            lines.push(node.start_position().row as u32 + 1);
+            false
+        } else {
+            node.has_error()
+        };
+
+        if descend && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                lines.dedup();
+                return lines;
+            }
+        }
+    }
 }
 
 /// Parses source code directly (useful for testing or in-memory content).
@@ -62,6 +149,111 @@ pub fn parse_source(
     file_path: &str,
     lang_parser: &dyn LanguageParser,
 ) -> Result<Vec<CodeNode>> {
+    parse_source_with_tree(source, file_path, lang_parser).map(|(nodes, _)| nodes)
+}
+
+/// Like `parse_source`, but also returns the syntax tree.
+///
+/// Keep the tree around to feed later edits to `parse_source_incremental`.
+pub fn parse_source_with_tree(
+    source: &str,
+    file_path: &str,
+    lang_parser: &dyn LanguageParser,
+) -> Result<(Vec<CodeNode>, Tree)> {
+    let tree = parse_tree(source, lang_parser, None)?;
+
+    // Extract nodes using the language-specific extractor
+    let mut nodes = lang_parser.extract_nodes(&tree, source, file_path);
+    annotate_complexity(&mut nodes, &tree);
+    annotate_spans(&mut nodes, &tree, source);
+    assign_parents(&mut nodes);
+
+    Ok((nodes, tree))
+}
+
+/// Reparses edited source, reusing the unchanged parts of `old_tree`.
+///
+/// `edits` describe how the text that produced `old_tree` turned into
+/// `source`, in the order they were applied. Tree-sitter only re-lexes the
+/// edited ranges, which makes single-keystroke updates in editors cheap.
+/// The result is identical to a fresh `parse_source_with_tree`.
+pub fn parse_source_incremental(
+    old_tree: &Tree,
+    edits: &[InputEdit],
+    source: &str,
+    file_path: &str,
+    lang_parser: &dyn LanguageParser,
+) -> Result<(Vec<CodeNode>, Tree)> {
+    let mut edited = old_tree.clone();
+    for edit in edits {
+        edited.edit(edit);
+    }
+
+    let tree = parse_tree(source, lang_parser, Some(&edited))?;
+    let mut nodes = lang_parser.extract_nodes(&tree, source, file_path);
+    annotate_complexity(&mut nodes, &tree);
+    annotate_spans(&mut nodes, &tree, source);
+    assign_parents(&mut nodes);
+
+    Ok((nodes, tree))
+}
+
+/// Computes a single edit that turns `old` into `new`.
+///
+/// The edit spans everything between the longest common prefix and the
+/// longest common suffix, which is exact for a typical save after typing
+/// in one place. Returns None if the texts are identical.
+pub fn edit_between(old: &str, new: &str) -> Option<InputEdit> {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+
+    let prefix = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old_bytes.len() && prefix == new_bytes.len() {
+        return None;
+    }
+
+    let max_suffix = old_bytes.len().min(new_bytes.len()) - prefix;
+    let suffix = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_end = old_bytes.len() - suffix;
+    let new_end = new_bytes.len() - suffix;
+
+    Some(InputEdit {
+        start_byte: prefix,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point_at(old_bytes, prefix),
+        old_end_position: point_at(old_bytes, old_end),
+        new_end_position: point_at(new_bytes, new_end),
+    })
+}
+
+/// Row and byte column of a byte offset.
+fn point_at(text: &[u8], offset: usize) -> Point {
+    let before = &text[..offset];
+    let row = before.iter().filter(|&&b| b == b'\n').count();
+    let column = match before.iter().rposition(|&b| b == b'\n') {
+        Some(newline) => offset - newline - 1,
+        None => offset,
+    };
+    Point { row, column }
+}
+
+/// Runs Tree-sitter over `source`, optionally reusing an edited tree.
+fn parse_tree(
+    source: &str,
+    lang_parser: &dyn LanguageParser,
+    old_tree: Option<&Tree>,
+) -> Result<Tree> {
     // Create and configure Tree-sitter parser
     let mut parser = tree_sitter::Parser::new();
     parser
@@ -69,14 +261,230 @@ pub fn parse_source(
         .map_err(|e| ParseError::ParserError(format!("Failed to set language: {}", e)))?;
 
     // Parse the source
-    let tree = parser
-        .parse(source, None)
-        .ok_or_else(|| ParseError::ParserError("Tree-sitter returned no tree".into()))?;
+    parser
+        .parse(source, old_tree)
+        .ok_or_else(|| ParseError::ParserError("Tree-sitter returned no tree".into()))
+}
 
-    // Extract nodes using the language-specific extractor
-    let nodes = lang_parser.extract_nodes(&tree, source, file_path);
+/// Renders the named syntax tree of `source`, one node per line.
+///
+/// Each line shows the field name (if any), node kind and its
+/// `line:column` range, indented by depth. This is what a language parser
+/// sees, so it is the first thing to look at when extraction misses a
+/// symbol. `max_depth` stops descending below that depth (root is 0).
+pub fn dump_tree(
+    source: &str,
+    lang_parser: &dyn LanguageParser,
+    max_depth: Option<usize>,
+) -> Result<String> {
+    let tree = parse_tree(source, lang_parser, None)?;
+    let mut out = String::new();
+    write_tree(&mut tree.walk(), 0, max_depth, (0, 0), &mut out);
+    Ok(out)
+}
 
-    Ok(nodes)
+/// Renders the syntax trees of the `<script>` blocks of a Vue or Svelte
+/// component, as `dump_tree` does for a whole file.
+///
+/// Each block gets a `script` header line with its `lang`, and positions
+/// point into the component file. Blocks in a language without a parser
+/// are listed but not parsed.
+pub fn dump_component_tree(source: &str, max_depth: Option<usize>) -> Result<String> {
+    let mut out = String::new();
+
+    for block in extract_script_blocks(source) {
+        let lang = block.lang.unwrap_or("js");
+        out.push_str(&format!(
+            "script lang={} [{}:{}]\n",
+            lang,
+            block.line_offset + 1,
+            block.column_offset
+        ));
+        let Some(parser) = get_parser(lang) else {
+            out.push_str("  (no parser)\n");
+            continue;
+        };
+
+        let tree = parse_tree(block.content, parser.as_ref(), None)?;
+        let offset = (block.line_offset as usize, block.column_offset as usize);
+        write_tree(
+            &mut tree.walk(),
+            1,
+            max_depth.map(|max| max + 1),
+            offset,
+            &mut out,
+        );
+    }
+
+    Ok(out)
+}
+
+/// Writes the node under `cursor` and its named descendants.
+///
+/// `offset` is the (line, column) where the parsed text starts in its
+/// file; the column only shifts positions on the first line.
+fn write_tree(
+    cursor: &mut TreeCursor,
+    depth: usize,
+    max_depth: Option<usize>,
+    offset: (usize, usize),
+    out: &mut String,
+) {
+    let node = cursor.node();
+    let position = |point: Point| {
+        let column = if point.row == 0 {
+            point.column + offset.1
+        } else {
+            point.column
+        };
+        format!("{}:{}", point.row + 1 + offset.0, column)
+    };
+
+    out.push_str(&"  ".repeat(depth));
+    if let Some(field) = cursor.field_name() {
+        out.push_str(field);
+        out.push_str(": ");
+    }
+    out.push_str(&format!(
+        "{} [{} - {}]\n",
+        node.kind(),
+        position(node.start_position()),
+        position(node.end_position())
+    ));
+
+    if matches!(max_depth, Some(max) if depth >= max) {
+        return;
+    }
+
+    if cursor.goto_first_child() {
+        loop {
+            if cursor.node().is_named() {
+                write_tree(cursor, depth + 1, max_depth, offset, out);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        cursor.goto_parent();
+    }
+}
+
+/// A `<script>` block found inside a single-file component.
+struct ScriptBlock<'a> {
+    /// The code between the opening and closing tags.
+    content: &'a str,
+    /// Value of the `lang` attribute, if any.
+    lang: Option<&'a str>,
+    /// Lines before the block starts in the enclosing file.
+    line_offset: u32,
+    /// Column at which the block starts on its first line.
+    column_offset: u32,
+    /// Byte position of the block in the enclosing file.
+    byte_offset: u32,
+}
+
+/// Returns true for Vue and Svelte single-file components.
+///
+/// `parse_file` parses only their `<script>` blocks, so callers that work
+/// on whole-file syntax trees should skip these.
+pub fn is_component_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "vue" | "svelte"))
+        .unwrap_or(false)
+}
+
+/// Parses the `<script>` blocks of a Vue or Svelte component.
+///
+/// Templates and styles are ignored. Each block is parsed with the parser
+/// for its `lang` attribute (JavaScript when absent), and the resulting
+/// positions are shifted so they point into the component file.
+fn parse_component(source: &str, file_path: &str) -> Result<ParsedFile> {
+    let mut parsed = ParsedFile::default();
+
+    for block in extract_script_blocks(source) {
+        let parser = match get_parser(block.lang.unwrap_or("js")) {
+            Some(parser) => parser,
+            None => continue, // e.g. lang="coffee"
+        };
+
+        let (nodes, tree) = parse_source_with_tree(block.content, file_path, parser.as_ref())?;
+        parsed.syntax_errors.extend(
+            syntax_error_lines(&tree)
+                .into_iter()
+                .map(|line| line + block.line_offset),
+        );
+        for mut node in nodes {
+            if node.line_start == 1 {
+                node.column += block.column_offset;
+            }
+            if node.line_end == 1 {
+                node.end_column += block.column_offset;
+            }
+            for span in [&mut node.name_span, &mut node.body_span]
+                .into_iter()
+                .flatten()
+            {
+                span.shift(block.line_offset, block.column_offset);
+            }
+            node.line_start += block.line_offset;
+            node.line_end += block.line_offset;
+            node.byte_start += block.byte_offset;
+            node.byte_end += block.byte_offset;
+            parsed.nodes.push(node);
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Finds every `<script ...>...</script>` block in a component.
+fn extract_script_blocks(source: &str) -> Vec<ScriptBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("<script") {
+        let tag_start = pos + found;
+        let after_name = tag_start + "<script".len();
+
+        // Skip things like <scripts> that merely share the prefix
+        if !source[after_name..].starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            pos = after_name;
+            continue;
+        }
+
+        let Some(tag_len) = source[after_name..].find('>') else {
+            break;
+        };
+        let attributes = &source[after_name..after_name + tag_len];
+        let content_start = after_name + tag_len + 1;
+
+        let Some(content_len) = source[content_start..].find("</script>") else {
+            break;
+        };
+        let before = &source[..content_start];
+
+        blocks.push(ScriptBlock {
+            content: &source[content_start..content_start + content_len],
+            lang: attribute_value(attributes, "lang"),
+            line_offset: before.matches('\n').count() as u32,
+            column_offset: (content_start - before.rfind('\n').map(|i| i + 1).unwrap_or(0)) as u32,
+            byte_offset: content_start as u32,
+        });
+
+        pos = content_start + content_len + "</script>".len();
+    }
+
+    blocks
+}
+
+/// Reads a quoted attribute value such as `lang="ts"` from a tag.
+fn attribute_value<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=", name))? + name.len() + 1;
+    let rest = &attributes[start..];
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &rest[1..];
+    rest.find(quote).map(|end| &rest[..end])
 }
 
 /// Detects the programming language from a file path.
@@ -87,11 +495,97 @@ pub fn detect_language(path: &Path) -> Option<Box<dyn LanguageParser>> {
     get_parser(extension)
 }
 
+/// Detects the programming language from the source text itself.
+///
+/// Used for files whose extension is missing or unknown. Recognizes
+/// shebangs (`#!/usr/bin/env python3`, `#!/bin/bash`, ...) and a leading
+/// `package` clause (Go, or Java when it ends in `;`). Bash and Ruby are
+/// detected but only parse if a parser is registered for `sh`/`rb`.
+pub fn detect_language_from_content(source: &str) -> Option<Box<dyn LanguageParser>> {
+    get_parser(content_extension(source)?)
+}
+
+/// Maps recognizable source text to the extension of its language.
+fn content_extension(source: &str) -> Option<&'static str> {
+    let source = source.trim_start_matches('\u{feff}');
+
+    if let Some(shebang) = source.strip_prefix("#!") {
+        let line = shebang.lines().next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let mut program = words.next()?.rsplit('/').next()?;
+        if program == "env" {
+            // `env -S python3 -u` and friends: skip flags to the program
+            program = words.find(|w| !w.starts_with('-'))?;
+        }
+        // python3.11 -> python, ruby2.7 -> ruby
+        let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+        return match program {
+            "python" | "pypy" => Some("py"),
+            "node" | "nodejs" | "deno" | "bun" => Some("js"),
+            "ts-node" | "tsx" => Some("ts"),
+            "bash" | "sh" | "zsh" | "dash" | "ksh" => Some("sh"),
+            "ruby" => Some("rb"),
+            "perl" => Some("pl"),
+            "Rscript" => Some("r"),
+            "groovy" => Some("groovy"),
+            "nim" => Some("nims"),
+            _ => None,
+        };
+    }
+
+    let first = source
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("//") && !l.starts_with("/*"))?;
+    if let Some(name) = first.strip_prefix("package ") {
+        return Some(if name.trim_end().ends_with(';') {
+            "java"
+        } else {
+            "go"
+        });
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::node::NodeKind;
 
+    #[test]
+    fn test_detect_language_from_content() {
+        assert_eq!(content_extension("#!/usr/bin/env python3\n"), Some("py"));
+        assert_eq!(content_extension("#!/usr/bin/python3.11 -u\n"), Some("py"));
+        assert_eq!(content_extension("#!/bin/bash\nset -e\n"), Some("sh"));
+        assert_eq!(content_extension("#!/usr/bin/env ruby\n"), Some("rb"));
+        assert_eq!(
+            content_extension("#!/usr/bin/env -S node --no-warnings\n"),
+            Some("js")
+        );
+        assert_eq!(content_extension("// main\npackage main\n"), Some("go"));
+        assert_eq!(content_extension("package com.example;\n"), Some("java"));
+        assert_eq!(content_extension("hello world\n"), None);
+    }
+
+    #[test]
+    fn test_parse_file_routes_shebang_script_to_python() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deploy");
+        fs::write(
+            &path,
+            "#!/usr/bin/env python3\n\ndef main():\n    print('deploying')\n",
+        )
+        .unwrap();
+
+        assert!(detect_language(&path).is_none());
+        let nodes = parse_file(&path).unwrap();
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "main" && n.kind == NodeKind::Function));
+    }
+
     #[test]
     fn test_detect_language() {
         assert!(detect_language(Path::new("foo.rs")).is_some());
@@ -146,4 +640,220 @@ mod tests {
             .iter()
             .any(|n| n.name == "UserService" && n.kind == NodeKind::Class));
     }
+
+    #[test]
+    fn test_typescript_export_nodes() {
+        let source = r#"
+            function login() {}
+            function logout() {}
+            class Session {}
+
+            export { login, logout as signOut };
+            export default Session;
+        "#;
+
+        let parser = get_parser("ts").unwrap();
+        let nodes = parse_source(source, "auth.ts", parser.as_ref()).unwrap();
+
+        let exports: Vec<&CodeNode> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Export)
+            .collect();
+        let export = |name: &str| exports.iter().find(|n| n.name == name).unwrap();
+
+        assert_eq!(exports.len(), 3);
+        assert_eq!(export("login").references, vec!["login"]);
+        assert_eq!(export("signOut").references, vec!["logout"]);
+        assert_eq!(export("default").references, vec!["Session"]);
+    }
+
+    #[test]
+    fn test_rust_generics() {
+        let source = r#"
+            pub fn merge<'a, T, U: Clone + Send>(a: &'a T, b: U) -> U {
+                b.clone()
+            }
+
+            pub struct Pair<K, V> {
+                key: K,
+                value: V,
+            }
+        "#;
+
+        let parser = get_parser("rs").unwrap();
+        let nodes = parse_source(source, "test.rs", parser.as_ref()).unwrap();
+
+        let merge = nodes.iter().find(|n| n.name == "merge").unwrap();
+        let pair = nodes.iter().find(|n| n.name == "Pair").unwrap();
+
+        assert_eq!(merge.generics, vec!["'a", "T", "U: Clone + Send"]);
+        assert_eq!(pair.generics, vec!["K", "V"]);
+    }
+
+    #[test]
+    fn test_typescript_generics() {
+        let source = r#"
+            export function pick<T, K extends keyof T>(obj: T, key: K): T[K] {
+                return obj[key];
+            }
+
+            export interface Repository<T> {
+                find(id: string): T;
+            }
+        "#;
+
+        let parser = get_parser("ts").unwrap();
+        let nodes = parse_source(source, "test.ts", parser.as_ref()).unwrap();
+
+        let pick = nodes.iter().find(|n| n.name == "pick").unwrap();
+        let repo = nodes.iter().find(|n| n.name == "Repository").unwrap();
+
+        assert_eq!(pick.generics, vec!["T", "K extends keyof T"]);
+        assert_eq!(repo.generics, vec!["T"]);
+    }
+
+    #[test]
+    fn test_parse_vue_component() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Greeting.vue");
+        std::fs::write(
+            &path,
+            r#"<template>
+  <p>{{ greet(name) }}</p>
+</template>
+
+<script lang="ts">
+export function greet(name: string): string {
+  return `Hello, ${name}!`;
+}
+</script>
+
+<style>
+p { color: red; }
+</style>
+"#,
+        )
+        .unwrap();
+
+        let nodes = parse_file(&path).unwrap();
+
+        let greet = nodes
+            .iter()
+            .find(|n| n.name == "greet")
+            .expect("script function should be extracted");
+        assert_eq!(greet.kind, NodeKind::Function);
+        assert_eq!(greet.line_start, 6);
+        assert_eq!(greet.line_end, 8);
+    }
+
+    #[test]
+    fn test_incremental_reparse_matches_fresh_parse() {
+        let before = r#"
+fn load(id: u32) -> User {
+    fetch(id)
+}
+
+fn save(user: &User) {
+    store(user);
+}
+"#;
+        let after = r#"
+fn load(id: u32) -> User {
+    fetch(id)
+}
+
+fn validate(user: &User) -> bool {
+    check(user)
+}
+
+fn save(user: &User) {
+    store(user);
+}
+"#;
+
+        let parser = get_parser("rs").unwrap();
+        let (_, old_tree) = parse_source_with_tree(before, "test.rs", parser.as_ref()).unwrap();
+
+        let edit = edit_between(before, after).unwrap();
+        let (incremental, _) =
+            parse_source_incremental(&old_tree, &[edit], after, "test.rs", parser.as_ref())
+                .unwrap();
+        let fresh = parse_source(after, "test.rs", parser.as_ref()).unwrap();
+
+        let summary = |nodes: &[CodeNode]| {
+            nodes
+                .iter()
+                .map(|n| {
+                    (
+                        n.id.clone(),
+                        n.line_start,
+                        n.line_end,
+                        n.byte_start,
+                        n.byte_end,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&incremental), summary(&fresh));
+        assert!(incremental.iter().any(|n| n.name == "validate"));
+    }
+
+    #[test]
+    fn test_dump_tree_rust() {
+        let source = "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n";
+        let parser = get_parser("rs").unwrap();
+
+        let dump = dump_tree(source, parser.as_ref(), None).unwrap();
+        assert!(dump.starts_with("source_file [1:0 - 4:0]"));
+        assert!(dump.contains("  function_item [1:0 - 3:1]"));
+        assert!(dump.contains("name: identifier [1:3 - 1:6]"));
+
+        let shallow = dump_tree(source, parser.as_ref(), Some(1)).unwrap();
+        assert!(shallow.contains("function_item"));
+        assert!(!shallow.contains("identifier"));
+    }
+
+    #[test]
+    fn test_dump_component_tree() {
+        let source =
+            "<template><p/></template>\n<script lang=\"ts\">\nfunction save() {}\n</script>\n";
+
+        let dump = dump_component_tree(source, None).unwrap();
+        assert!(dump.starts_with("script lang=ts [2:18]\n"), "{}", dump);
+        // Positions point into the component, not the block
+        assert!(
+            dump.contains("    function_declaration [3:0 - 3:18]"),
+            "{}",
+            dump
+        );
+        assert!(!dump.contains("template"), "{}", dump);
+
+        let shallow = dump_component_tree(source, Some(1)).unwrap();
+        assert!(shallow.contains("function_declaration"));
+        assert!(!shallow.contains("identifier"));
+    }
+
+    #[test]
+    fn test_broken_function_keeps_good_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("billing.py");
+        fs::write(
+            &path,
+            "def broken(:\n    return 1\n\ndef good(amount):\n    return amount + 1\n",
+        )
+        .unwrap();
+
+        let parsed = parse_file_checked(&path).unwrap();
+        assert!(parsed.is_partial());
+        assert_eq!(parsed.syntax_errors[0], 1);
+        assert!(parsed
+            .nodes
+            .iter()
+            .any(|n| n.name == "good" && n.kind == NodeKind::Function && n.line_start == 4));
+
+        fs::write(&path, "def good(amount):\n    return amount + 1\n").unwrap();
+        let parsed = parse_file_checked(&path).unwrap();
+        assert!(!parsed.is_partial());
+        assert_eq!(parsed.nodes.len(), 1);
+    }
 }
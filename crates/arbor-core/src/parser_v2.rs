@@ -7,6 +7,7 @@
 //! The parser is designed for incremental updates - calling it on the same file
 //! will update existing nodes rather than creating duplicates.
 
+use crate::complexity::estimate_complexity;
 use crate::error::{ParseError, Result};
 use crate::node::{CodeNode, NodeKind};
 use std::collections::HashMap;
@@ -327,6 +328,10 @@ impl ArborParser {
                     symbol = symbol.with_signature(sig);
                 }
 
+                if matches!(kind, NodeKind::Function | NodeKind::Method) {
+                    symbol = symbol.with_complexity(estimate_complexity(&node));
+                }
+
                 symbols.push(symbol);
             }
         }
@@ -0,0 +1,169 @@
+//! Exact source ranges for extracted nodes.
+//!
+//! Extractors record a definition's lines and the column its name starts
+//! at. Selecting a symbol in an editor needs more: where the definition
+//! ends on its last line, where the name ends, and where the body begins
+//! (everything before it is the header). This pass fills those in from the
+//! syntax tree, the same way for every language.
+
+use crate::node::{CodeNode, Span};
+use tree_sitter::{Node, Tree};
+
+/// Fills in `end_column`, `name_span` and `body_span` for every node with
+/// a byte range.
+pub(crate) fn annotate_spans(nodes: &mut [CodeNode], tree: &Tree, source: &str) {
+    let root = tree.root_node();
+    let lines = LineIndex::new(source);
+
+    for node in nodes.iter_mut() {
+        let (start, end) = (node.byte_start as usize, node.byte_end as usize);
+        if end <= start || end > source.len() {
+            continue;
+        }
+
+        node.end_column = lines.column(end);
+
+        let syntax = root
+            .descendant_for_byte_range(start, end)
+            .filter(|s| s.start_byte() == start && s.end_byte() == end);
+
+        node.name_span = syntax
+            .and_then(|s| s.child_by_field_name("name"))
+            .filter(|name| source.get(name.byte_range()) == Some(node.name.as_str()))
+            .map(|name| span_of(&name))
+            .or_else(|| find_name(node, source, &lines));
+
+        node.body_span = syntax
+            .and_then(|s| s.child_by_field_name("body"))
+            .map(|body| span_of(&body));
+    }
+}
+
+/// Converts a syntax node's range to a `Span`.
+fn span_of(node: &Node) -> Span {
+    let (start, end) = (node.start_position(), node.end_position());
+    Span {
+        line_start: start.row as u32 + 1,
+        column_start: start.column as u32,
+        line_end: end.row as u32 + 1,
+        column_end: end.column as u32,
+    }
+}
+
+/// Finds the name inside the definition's text at the recorded `column`,
+/// for grammars whose name isn't a `name` field.
+fn find_name(node: &CodeNode, source: &str, lines: &LineIndex) -> Option<Span> {
+    if node.name.is_empty() {
+        return None;
+    }
+    let start = node.byte_start as usize;
+    let text = source.get(start..node.byte_end as usize)?;
+
+    text.match_indices(node.name.as_str())
+        .map(|(offset, _)| start + offset)
+        .find(|&offset| lines.column(offset) == node.column)
+        .map(|offset| {
+            let end = offset + node.name.len();
+            Span {
+                line_start: lines.line(offset),
+                column_start: lines.column(offset),
+                line_end: lines.line(end),
+                column_end: lines.column(end),
+            }
+        })
+}
+
+/// Byte offsets of line starts, for turning offsets into positions.
+struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: &str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { starts }
+    }
+
+    /// 0-indexed row of the line containing `offset`.
+    fn row(&self, offset: usize) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// 1-indexed line of `offset`.
+    fn line(&self, offset: usize) -> u32 {
+        self.row(offset) as u32 + 1
+    }
+
+    /// Byte column of `offset` within its line.
+    fn column(&self, offset: usize) -> u32 {
+        (offset - self.starts[self.row(offset)]) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::languages::get_parser;
+    use crate::node::Span;
+    use crate::parser::parse_source;
+
+    #[test]
+    fn test_spans_of_multiline_function() {
+        let source = "\
+/// Moves money between accounts.
+pub fn transfer(
+    from: &mut Account,
+    amount: u64,
+) -> Result<(), Error> {
+    from.debit(amount)?;
+    Ok(())
+}
+";
+        let parser = get_parser("rs").unwrap();
+        let nodes = parse_source(source, "bank.rs", parser.as_ref()).unwrap();
+        let transfer = nodes.iter().find(|n| n.name == "transfer").unwrap();
+
+        // Existing fields are unchanged
+        assert_eq!((transfer.line_start, transfer.line_end), (2, 8));
+        assert_eq!(transfer.column, 7);
+
+        // The closing brace is the last character, on line 8
+        assert_eq!(transfer.end_column, 1);
+        assert_eq!(
+            transfer.name_span,
+            Some(Span {
+                line_start: 2,
+                column_start: 7,
+                line_end: 2,
+                column_end: 15,
+            })
+        );
+        // The body starts at the `{` after the return type
+        assert_eq!(
+            transfer.body_span,
+            Some(Span {
+                line_start: 5,
+                column_start: 23,
+                line_end: 8,
+                column_end: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_spans_of_python_function() {
+        let source = "def greet(name):\n    return f\"hi {name}\"\n";
+        let parser = get_parser("py").unwrap();
+        let nodes = parse_source(source, "greet.py", parser.as_ref()).unwrap();
+        let greet = nodes.iter().find(|n| n.name == "greet").unwrap();
+
+        let name = greet.name_span.unwrap();
+        assert_eq!(
+            (name.line_start, name.column_start, name.column_end),
+            (1, 4, 9)
+        );
+        assert_eq!(greet.end_column, 23);
+        assert_eq!(greet.body_span.unwrap().line_start, 2);
+    }
+}
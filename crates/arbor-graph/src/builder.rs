@@ -6,11 +6,57 @@
 use crate::edge::{Edge, EdgeKind};
 use crate::graph::{ArborGraph, NodeId};
 use crate::symbol_table::SymbolTable;
-use arbor_core::CodeNode;
+use arbor_core::{CodeNode, NodeKind};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::warn;
 
+/// How many extracted references `resolve_edges` turned into edges.
+///
+/// Unresolved names are mostly calls into external libraries and the
+/// standard library, but a familiar in-repo name among the top ones
+/// points at a resolution miss.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolutionStats {
+    /// References extracted across all nodes.
+    pub references: usize,
+    /// References that resolved to a node in the graph.
+    pub resolved: usize,
+    /// Unresolved reference names and how often each occurred.
+    pub unresolved: HashMap<String, usize>,
+}
+
+impl ResolutionStats {
+    /// Number of references that did not resolve.
+    pub fn unresolved_count(&self) -> usize {
+        self.references - self.resolved
+    }
+
+    /// Share of references that resolved, in `0.0..=1.0` (1.0 when there
+    /// were none).
+    pub fn resolution_rate(&self) -> f64 {
+        if self.references == 0 {
+            1.0
+        } else {
+            self.resolved as f64 / self.references as f64
+        }
+    }
+
+    /// The `limit` most frequent unresolved names, most frequent first
+    /// (ties by name).
+    pub fn top_unresolved(&self, limit: usize) -> Vec<(&str, usize)> {
+        let mut names: Vec<(&str, usize)> = self
+            .unresolved
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+        names.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        names.truncate(limit);
+        names
+    }
+}
+
 /// Builds an ArborGraph from parsed code nodes.
 ///
 /// The builder handles the two-pass process:
@@ -22,6 +68,8 @@ pub struct GraphBuilder {
     symbol_table: SymbolTable,
     /// Legacy map for simple name resolution (within same file)
     name_to_id: HashMap<String, String>,
+    /// Outcome of the last `resolve_edges`.
+    resolution: ResolutionStats,
 }
 
 impl Default for GraphBuilder {
@@ -37,6 +85,7 @@ impl GraphBuilder {
             graph: ArborGraph::new(),
             symbol_table: SymbolTable::new(),
             name_to_id: HashMap::new(),
+            resolution: ResolutionStats::default(),
         }
     }
 
@@ -44,21 +93,40 @@ impl GraphBuilder {
     ///
     /// Call this for each parsed file, then call `resolve_edges`
     /// when all files are added.
+    ///
+    /// Nodes whose id is already in the graph (a C prototype followed by
+    /// its definition, overloads that share a qualified name) are merged
+    /// into the existing node rather than added twice; see `merge_duplicate`.
     pub fn add_nodes(&mut self, nodes: Vec<CodeNode>) {
         for node in nodes {
+            if let Some(existing) = self.graph.get_index(&node.id) {
+                merge_duplicate(&mut self.graph.graph[existing], node);
+                continue;
+            }
+
             let id_str = node.id.clone();
             let name = node.name.clone();
             let qualified = node.qualified_name.clone();
             let file = PathBuf::from(&node.file);
+            let kind = node.kind;
 
             let node_idx = self.graph.add_node(node);
 
-            // Populate Symbol Table
-            if !qualified.is_empty() {
-                self.symbol_table
-                    .insert(qualified.clone(), node_idx, file.clone());
+            // Export nodes share their name with the symbol they expose,
+            // so they must not shadow it during resolution
+            if kind == NodeKind::Export {
+                continue;
             }
 
+            // Populate Symbol Table. Every definition gets an entry, so
+            // same-named symbols in different files stay distinguishable.
+            let fqn = if qualified.is_empty() {
+                name.clone()
+            } else {
+                qualified.clone()
+            };
+            self.symbol_table.insert(fqn, node_idx, file);
+
             self.name_to_id.insert(name.clone(), id_str.clone());
             self.name_to_id.insert(qualified, id_str);
         }
@@ -67,8 +135,10 @@ impl GraphBuilder {
     /// Resolves references into actual graph edges.
     ///
     /// This is the second pass after all nodes are added. It looks up
-    /// reference names and creates edges where targets exist.
+    /// reference names and creates edges where targets exist. What did
+    /// and didn't resolve is kept in `resolution_stats`.
     pub fn resolve_edges(&mut self) {
+        let mut stats = ResolutionStats::default();
         // Collect all the edge additions first to avoid borrow issues
         let mut edges_to_add = Vec::new();
 
@@ -77,47 +147,85 @@ impl GraphBuilder {
 
         for from_idx in node_indices {
             // Get references and file by cloning to release borrow on graph
-            let (references, from_file) = {
+            let (references, from_file, edge_kind) = {
                 let node = self.graph.get(from_idx).unwrap();
-                (node.references.clone(), PathBuf::from(&node.file))
+                if let Some(parent_idx) = node
+                    .parent_id
+                    .as_deref()
+                    .and_then(|id| self.graph.get_index(id))
+                {
+                    edges_to_add.push((parent_idx, from_idx, EdgeKind::Contains));
+                }
+                let edge_kind = if node.kind == NodeKind::Export {
+                    EdgeKind::Exports
+                } else {
+                    EdgeKind::Calls
+                };
+                (
+                    node.references.clone(),
+                    PathBuf::from(&node.file),
+                    edge_kind,
+                )
             };
 
             for reference in references {
-                // 1. Try exact FQN match
-                if let Some(to_idx) = self.symbol_table.resolve(&reference) {
-                    if from_idx != to_idx {
-                        edges_to_add.push((from_idx, to_idx, reference.clone()));
-                    }
-                    continue;
-                }
-
-                // 2. Try context-aware resolution (suffix match with locality)
+                stats.references += 1;
+                // Exact FQN, then suffix match; duplicates are broken by
+                // same-file, then same-directory locality
                 if let Some(to_idx) = self
                     .symbol_table
                     .resolve_with_context(&reference, &from_file)
                 {
+                    stats.resolved += 1;
                     if from_idx != to_idx {
-                        edges_to_add.push((from_idx, to_idx, reference.clone()));
+                        edges_to_add.push((from_idx, to_idx, edge_kind));
                     }
                     continue;
                 }
 
-                // 3. Unresolved - warn
+                // Unresolved - warn
                 warn!(
                     "Unresolved reference '{}' in {}",
                     reference,
                     from_file.display()
                 );
+                *stats.unresolved.entry(reference).or_insert(0) += 1;
             }
         }
+        self.resolution = stats;
 
         // Now add the edges
-        for (from_id, to_id, _ref_name) in edges_to_add {
-            self.graph
-                .add_edge(from_id, to_id, Edge::new(EdgeKind::Calls));
+        for (from_id, to_id, kind) in edges_to_add {
+            self.graph.add_edge(from_id, to_id, Edge::new(kind));
         }
     }
 
+    /// Adds an edge that parsing can't see, such as a route mapped to its
+    /// handler in an OpenAPI spec or a binding in a DI container config.
+    ///
+    /// `from` and `to` are qualified names, resolved like references: an
+    /// exact FQN first, then a unique suffix (`get_user` finds
+    /// `api.handlers.get_user`). Call this after `add_nodes`. Returns false
+    /// and adds nothing if either name is unknown or ambiguous.
+    pub fn add_edge_explicit(&mut self, from: &str, to: &str, kind: EdgeKind) -> bool {
+        let resolve = |fqn: &str| {
+            self.symbol_table
+                .resolve_with_context(fqn, std::path::Path::new(""))
+        };
+        let (Some(from_idx), Some(to_idx)) = (resolve(from), resolve(to)) else {
+            warn!("Unresolved explicit edge '{}' -> '{}'", from, to);
+            return false;
+        };
+
+        self.graph.add_edge(from_idx, to_idx, Edge::new(kind));
+        true
+    }
+
+    /// Resolved and unresolved references from the last `resolve_edges`.
+    pub fn resolution_stats(&self) -> &ResolutionStats {
+        &self.resolution
+    }
+
     /// Finishes building and returns the graph.
     pub fn build(mut self) -> ArborGraph {
         self.resolve_edges();
@@ -130,6 +238,31 @@ impl GraphBuilder {
     }
 }
 
+/// Merges a duplicate node into the one already in the graph.
+///
+/// References are unioned. The node spanning more bytes wins everything
+/// else, since a definition with a body says more than a bare declaration.
+/// On a tie the existing node is kept.
+pub(crate) fn merge_duplicate(existing: &mut CodeNode, incoming: CodeNode) {
+    let mut references = existing.references.clone();
+    for reference in &incoming.references {
+        if !references.contains(reference) {
+            references.push(reference.clone());
+        }
+    }
+
+    let span = |node: &CodeNode| node.byte_end.saturating_sub(node.byte_start);
+    if span(&incoming) > span(existing) {
+        let docstring = existing.docstring.take();
+        *existing = incoming;
+        if existing.docstring.is_none() {
+            existing.docstring = docstring;
+        }
+    }
+
+    existing.references = references;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,6 +296,31 @@ mod tests {
         assert_eq!(graph.edge_count(), 1);
     }
 
+    #[test]
+    fn test_export_nodes_link_with_exports_edges() {
+        let mut builder = GraphBuilder::new();
+
+        let login = CodeNode::new("login", "login", NodeKind::Function, "auth.ts");
+        let export = CodeNode::new("login", "login", NodeKind::Export, "auth.ts")
+            .with_references(vec!["login".to_string()]);
+        let caller = CodeNode::new("main", "main", NodeKind::Function, "auth.ts")
+            .with_references(vec!["login".to_string()]);
+
+        builder.add_nodes(vec![login, export, caller]);
+        let graph = builder.build();
+
+        let kinds: Vec<EdgeKind> = graph.edges().map(|e| e.kind).collect();
+        assert_eq!(kinds.len(), 2);
+        assert!(kinds.contains(&EdgeKind::Exports));
+        assert!(kinds.contains(&EdgeKind::Calls));
+
+        // The call resolves to the function, not the export node
+        let main = graph.get_index(&graph.find_by_name("main")[0].id).unwrap();
+        let callees = graph.get_callees(main);
+        assert_eq!(callees.len(), 1);
+        assert_eq!(callees[0].kind, NodeKind::Function);
+    }
+
     #[test]
     fn test_cross_file_resolution() {
         let mut builder = GraphBuilder::new();
@@ -187,4 +345,148 @@ mod tests {
             "Should resolve cross-file edge via FQN"
         );
     }
+
+    #[test]
+    fn test_duplicate_ids_merge_into_definition() {
+        let mut builder = GraphBuilder::new();
+
+        // int add(int a, int b);
+        let prototype = CodeNode::new("add", "add", NodeKind::Function, "math.c")
+            .with_lines(3, 3)
+            .with_bytes(40, 62)
+            .with_references(vec!["log_call".to_string()]);
+        // int add(int a, int b) { return checked(a + b); }
+        let definition = CodeNode::new("add", "add", NodeKind::Function, "math.c")
+            .with_lines(10, 13)
+            .with_bytes(120, 190)
+            .with_references(vec!["checked".to_string()]);
+        let checked = CodeNode::new("checked", "checked", NodeKind::Function, "math.c");
+        let log_call = CodeNode::new("log_call", "log_call", NodeKind::Function, "math.c");
+
+        builder.add_nodes(vec![prototype, definition, checked, log_call]);
+        let graph = builder.build();
+
+        assert_eq!(graph.node_count(), 3);
+        let add = graph.find_by_name("add");
+        assert_eq!(add.len(), 1);
+        assert_eq!((add[0].line_start, add[0].line_end), (10, 13));
+        assert_eq!(add[0].references, vec!["log_call", "checked"]);
+
+        // Both references resolve from the single merged node
+        let idx = graph.get_index(&add[0].id).unwrap();
+        assert_eq!(graph.get_callees(idx).len(), 2);
+    }
+
+    #[test]
+    fn test_contains_edges_from_parent_ids() {
+        let source = r#"
+class Cart {
+    add(item) { this.items.push(item); }
+    total() { return 0; }
+}
+"#;
+        let parser = arbor_core::languages::get_parser("ts").unwrap();
+        let nodes = arbor_core::parse_source(source, "cart.ts", parser.as_ref()).unwrap();
+
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(nodes);
+        let graph = builder.build();
+
+        let cart = graph.find_by_name("Cart")[0].id.clone();
+        let members: Vec<&str> = graph
+            .export_edges()
+            .into_iter()
+            .filter(|e| e.kind == EdgeKind::Contains && e.source == cart)
+            .map(|e| graph.get_by_id(&e.target).unwrap().name.as_str())
+            .collect();
+
+        assert_eq!(members.len(), 2);
+        assert!(members.contains(&"add"));
+        assert!(members.contains(&"total"));
+    }
+
+    #[test]
+    fn test_same_name_resolves_to_same_directory_definition() {
+        let mut builder = GraphBuilder::new();
+
+        let local = CodeNode::new("helper", "helper", NodeKind::Function, "src/b/util.rs");
+        let remote = CodeNode::new("helper", "helper", NodeKind::Function, "src/a/util.rs");
+        let caller = CodeNode::new("main", "main", NodeKind::Function, "src/b/main.rs")
+            .with_references(vec!["helper".to_string()]);
+        let (local_id, caller_id) = (local.id.clone(), caller.id.clone());
+
+        // Same FQN twice: a table keyed by FQN alone would keep only one
+        builder.add_nodes(vec![local]);
+        builder.add_nodes(vec![remote]);
+        builder.add_nodes(vec![caller]);
+
+        let graph = builder.build();
+        let callees: Vec<String> = graph
+            .edges_iter()
+            .filter(|e| e.source == caller_id)
+            .map(|e| e.target)
+            .collect();
+
+        assert_eq!(callees, vec![local_id]);
+    }
+
+    #[test]
+    fn test_explicit_route_edge_shows_in_impact() {
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new(
+                "get_user",
+                "api.handlers.get_user",
+                NodeKind::Function,
+                "api/handlers.py",
+            ),
+            CodeNode::new(
+                "GET /users/{id}",
+                "GET /users/{id}",
+                NodeKind::Constant,
+                "openapi.yaml",
+            ),
+        ]);
+
+        assert!(builder.add_edge_explicit("GET /users/{id}", "get_user", EdgeKind::References));
+        assert!(!builder.add_edge_explicit("GET /users/{id}", "delete_user", EdgeKind::References));
+
+        let graph = builder.build();
+        let handler = graph
+            .get_index(&graph.find_by_name("get_user")[0].id)
+            .unwrap();
+        let analysis = graph.analyze_impact(handler, 3);
+
+        assert_eq!(analysis.upstream.len(), 1);
+        assert_eq!(analysis.upstream[0].node_info.name, "GET /users/{id}");
+        assert_eq!(analysis.upstream[0].entry_edge, EdgeKind::References);
+    }
+
+    #[test]
+    fn test_resolution_stats_report_external_calls() {
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("main", "main", NodeKind::Function, "src/main.rs").with_references(vec![
+                "helper".to_string(),
+                "serde_json::to_string".to_string(),
+                "println".to_string(),
+            ]),
+            CodeNode::new("helper", "helper", NodeKind::Function, "src/main.rs")
+                .with_references(vec!["println".to_string()]),
+        ]);
+        builder.resolve_edges();
+
+        let stats = builder.resolution_stats();
+        assert_eq!(stats.references, 4);
+        assert_eq!(stats.resolved, 1);
+        assert_eq!(stats.unresolved_count(), 3);
+        assert!((stats.resolution_rate() - 0.25).abs() < 1e-9);
+        // The intra-repo call resolved; the external ones are listed
+        assert!(!stats.unresolved.contains_key("helper"));
+        assert_eq!(
+            stats.top_unresolved(10),
+            [("println", 2), ("serde_json::to_string", 1)]
+        );
+        assert_eq!(builder.build_without_resolve().edge_count(), 1);
+    }
 }
@@ -0,0 +1,124 @@
+//! Connected components.
+//!
+//! A monorepo of independent services indexes into islands that no edge
+//! joins. Numbering the weakly connected components (edges taken in
+//! either direction) lets a query say which island a symbol lives on and
+//! keep its analysis there.
+
+use crate::graph::{ArborGraph, NodeId};
+use arbor_core::CodeNode;
+use petgraph::unionfind::UnionFind;
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+impl ArborGraph {
+    /// Returns the weakly connected component of `id`, or None if the
+    /// node isn't in the graph.
+    ///
+    /// Components are numbered from 0 in order of their first node, so
+    /// numbers stay the same until the graph changes.
+    pub fn component_of(&self, id: NodeId) -> Option<usize> {
+        self.get(id)?;
+        self.component_labels().get(id.index()).copied()
+    }
+
+    /// Returns the nodes of component `component`, in index order.
+    pub fn nodes_in_component(&self, component: usize) -> Vec<&CodeNode> {
+        let labels = self.component_labels();
+        self.node_indexes()
+            .filter(|id| labels[id.index()] == component)
+            .filter_map(|id| self.get(id))
+            .collect()
+    }
+
+    /// Returns the number of weakly connected components.
+    pub fn component_count(&self) -> usize {
+        self.component_labels()
+            .iter()
+            .max()
+            .map_or(0, |&max| max + 1)
+    }
+
+    /// Component number of every node, indexed by node index. Computed
+    /// once per graph generation and shared until the graph changes.
+    fn component_labels(&self) -> Arc<Vec<usize>> {
+        let generation = self.generation();
+        let mut cache = self
+            .component_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((computed_at, labels)) = cache.as_ref() {
+            if *computed_at == generation {
+                return Arc::clone(labels);
+            }
+        }
+
+        let labels = Arc::new(self.compute_component_labels());
+        *cache = Some((generation, Arc::clone(&labels)));
+        labels
+    }
+
+    fn compute_component_labels(&self) -> Vec<usize> {
+        let mut sets = UnionFind::<usize>::new(self.graph.node_count());
+        for edge_ref in self.graph.edge_references() {
+            sets.union(edge_ref.source().index(), edge_ref.target().index());
+        }
+
+        let mut numbers: HashMap<usize, usize> = HashMap::new();
+        self.node_indexes()
+            .map(|id| {
+                let next = numbers.len();
+                *numbers.entry(sets.find(id.index())).or_insert(next)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builder::GraphBuilder;
+    use crate::edge::{Edge, EdgeKind};
+    use crate::graph::ArborGraph;
+    use arbor_core::{CodeNode, NodeKind};
+
+    #[test]
+    fn test_components_follow_graph_changes() {
+        let function = |name: &str, file: &str, calls: &[&str]| {
+            CodeNode::new(name, name, NodeKind::Function, file)
+                .with_references(calls.iter().map(|c| c.to_string()).collect())
+        };
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            // billing service
+            function("charge", "billing/api.rs", &["validate_card"]),
+            function("validate_card", "billing/card.rs", &[]),
+            // search service, never calls into billing
+            function("query", "search/api.rs", &["rank"]),
+            function("rank", "search/rank.rs", &[]),
+        ]);
+        let mut graph = builder.build();
+        let index = |graph: &ArborGraph, name: &str| {
+            graph.get_index(&graph.find_by_name(name)[0].id).unwrap()
+        };
+
+        assert_eq!(graph.component_count(), 2);
+        let billing = graph.component_of(index(&graph, "charge")).unwrap();
+        let search = graph.component_of(index(&graph, "rank")).unwrap();
+        assert_eq!((billing, search), (0, 1));
+        let mut names: Vec<&str> = graph
+            .nodes_in_component(search)
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["query", "rank"]);
+
+        // Joining the services invalidates the cached labels
+        let (query, charge) = (index(&graph, "query"), index(&graph, "charge"));
+        graph.add_edge(query, charge, Edge::new(EdgeKind::Calls));
+        assert_eq!(graph.component_count(), 1);
+        assert_eq!(graph.component_of(index(&graph, "rank")), Some(0));
+        assert_eq!(graph.nodes_in_component(0).len(), 4);
+    }
+}
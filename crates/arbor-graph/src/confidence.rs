@@ -25,6 +25,32 @@ impl std::fmt::Display for ConfidenceLevel {
     }
 }
 
+/// Thresholds `ConfidenceExplanation` grades an analysis against.
+///
+/// The defaults suit a mid-sized codebase; a small repo may want lower
+/// blast-radius cutoffs so that 10 affected nodes already count as large.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfidenceConfig {
+    /// Affected nodes above which a connected node's blast radius is
+    /// large (Medium confidence).
+    pub large_blast_radius: usize,
+    /// Affected nodes above which it is very large (Low confidence).
+    pub very_large_blast_radius: usize,
+    /// Downstream dependencies above which an entry point is only
+    /// Medium confidence.
+    pub max_entry_dependencies: usize,
+}
+
+impl Default for ConfidenceConfig {
+    fn default() -> Self {
+        Self {
+            large_blast_radius: 20,
+            very_large_blast_radius: 50,
+            max_entry_dependencies: 5,
+        }
+    }
+}
+
 /// Reasons explaining the confidence level
 #[derive(Debug, Clone)]
 pub struct ConfidenceExplanation {
@@ -34,8 +60,13 @@ pub struct ConfidenceExplanation {
 }
 
 impl ConfidenceExplanation {
-    /// Compute confidence from an impact analysis
+    /// Compute confidence from an impact analysis, with default thresholds
     pub fn from_analysis(analysis: &ImpactAnalysis) -> Self {
+        Self::from_analysis_with(analysis, &ConfidenceConfig::default())
+    }
+
+    /// Compute confidence from an impact analysis against `config`
+    pub fn from_analysis_with(analysis: &ImpactAnalysis, config: &ConfidenceConfig) -> Self {
         let mut reasons = Vec::new();
         let mut suggestions = Vec::new();
 
@@ -54,7 +85,7 @@ impl ConfidenceExplanation {
             // Entry point
             reasons.push("Node is an entry point (no internal callers)".to_string());
             reasons.push(format!("Has {} downstream dependencies", downstream_count));
-            if downstream_count > 5 {
+            if downstream_count > config.max_entry_dependencies {
                 suggestions.push("Consider impact on downstream dependencies".to_string());
                 ConfidenceLevel::Medium
             } else {
@@ -72,16 +103,17 @@ impl ConfidenceExplanation {
                 upstream_count, downstream_count
             ));
 
-            if total > 20 {
-                reasons.push("Large blast radius detected".to_string());
-                suggestions
-                    .push("Consider breaking this change into smaller refactors".to_string());
-                ConfidenceLevel::Medium
-            } else if total > 50 {
+            // Check the larger cutoff first, or it could never match
+            if total > config.very_large_blast_radius {
                 reasons.push("Very large blast radius".to_string());
                 suggestions
                     .push("This change affects a significant portion of the codebase".to_string());
                 ConfidenceLevel::Low
+            } else if total > config.large_blast_radius {
+                reasons.push("Large blast radius detected".to_string());
+                suggestions
+                    .push("Consider breaking this change into smaller refactors".to_string());
+                ConfidenceLevel::Medium
             } else {
                 reasons.push("Well-connected with manageable impact".to_string());
                 ConfidenceLevel::High
@@ -185,4 +217,66 @@ mod tests {
         assert_eq!(NodeRole::Isolated.to_string(), "Isolated");
         assert_eq!(NodeRole::Adapter.to_string(), "Adapter");
     }
+
+    /// A connected node: one direct caller, one callee, and `extra`
+    /// transitive callers on top.
+    fn connected_analysis(extra: usize) -> ImpactAnalysis {
+        use crate::{AffectedNode, EdgeKind, ImpactDirection, ImpactSeverity, NodeInfo};
+        use arbor_core::{CodeNode, NodeKind};
+
+        let affected = |name: &str, hops: usize, direction| AffectedNode {
+            node_id: Default::default(),
+            node_info: NodeInfo::from(&CodeNode::new(name, name, NodeKind::Function, "a.rs")),
+            severity: ImpactSeverity::from_hops(hops),
+            hop_distance: hops,
+            entry_edge: EdgeKind::Calls,
+            direction,
+        };
+        let mut upstream = vec![affected("caller", 1, ImpactDirection::Upstream)];
+        upstream.extend(
+            (0..extra).map(|i| affected(&format!("far_{}", i), 2, ImpactDirection::Upstream)),
+        );
+        let downstream = vec![affected("callee", 1, ImpactDirection::Downstream)];
+
+        ImpactAnalysis {
+            target: NodeInfo::from(&CodeNode::new("t", "t", NodeKind::Function, "a.rs")),
+            total_affected: upstream.len() + downstream.len(),
+            upstream,
+            downstream,
+            max_depth: 5,
+            query_time_ms: 0,
+            timed_out: false,
+        }
+    }
+
+    #[test]
+    fn test_blast_radius_branches_in_order() {
+        let level = |extra| ConfidenceExplanation::from_analysis(&connected_analysis(extra)).level;
+
+        assert_eq!(level(0), ConfidenceLevel::High);
+        // 22 affected: large
+        assert_eq!(level(20), ConfidenceLevel::Medium);
+        // 62 affected: very large, previously shadowed by the > 20 branch
+        let very_large = ConfidenceExplanation::from_analysis(&connected_analysis(60));
+        assert_eq!(very_large.level, ConfidenceLevel::Low);
+        assert!(very_large
+            .reasons
+            .contains(&"Very large blast radius".to_string()));
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let small_repo = ConfidenceConfig {
+            large_blast_radius: 3,
+            very_large_blast_radius: 8,
+            ..Default::default()
+        };
+        let level = |extra| {
+            ConfidenceExplanation::from_analysis_with(&connected_analysis(extra), &small_repo).level
+        };
+
+        assert_eq!(level(0), ConfidenceLevel::High);
+        assert_eq!(level(3), ConfidenceLevel::Medium);
+        assert_eq!(level(8), ConfidenceLevel::Low);
+    }
 }
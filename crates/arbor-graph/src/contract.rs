@@ -0,0 +1,165 @@
+//! Graph contraction transforms.
+//!
+//! Import nodes make up a large share of most graphs but carry little
+//! meaning on their own; what matters is which symbols end up depending
+//! on which. `without_imports` drops them and keeps those dependencies.
+
+use crate::edge::{Edge, EdgeKind};
+use crate::graph::{ArborGraph, NodeId};
+use crate::symbol_table::{is_suffix_match, SymbolTable};
+use arbor_core::NodeKind;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+impl ArborGraph {
+    /// Returns a copy of the graph with all `Import` nodes removed.
+    ///
+    /// Edges between other nodes are kept as they are. Each import is
+    /// resolved like a reference from its file, trying the whole path and
+    /// then shorter suffixes (`crate::db::query`, `db::query`, `query`).
+    /// Symbols in the importing file that use the imported name, or that
+    /// had an edge to the import node, get a direct `Imports` edge to the
+    /// definition. Imports of code outside the graph just disappear.
+    ///
+    /// Centrality isn't carried over since node indexes change; recompute
+    /// it on the result if needed.
+    pub fn without_imports(&self) -> ArborGraph {
+        let mut contracted = ArborGraph::new();
+        let mut mapping: HashMap<NodeId, NodeId> = HashMap::new();
+        let mut symbols = SymbolTable::new();
+
+        for index in self.graph.node_indices() {
+            let node = &self.graph[index];
+            if node.kind == NodeKind::Import {
+                continue;
+            }
+            let new_index = contracted.add_node(node.clone());
+            mapping.insert(index, new_index);
+            if node.kind != NodeKind::Export {
+                let fqn = if node.qualified_name.is_empty() {
+                    &node.name
+                } else {
+                    &node.qualified_name
+                };
+                symbols.insert(fqn.clone(), new_index, PathBuf::from(&node.file));
+            }
+        }
+
+        let mut seen: HashSet<(NodeId, NodeId, EdgeKind)> = HashSet::new();
+        for edge in self.graph.edge_references() {
+            if let (Some(&from), Some(&to)) =
+                (mapping.get(&edge.source()), mapping.get(&edge.target()))
+            {
+                seen.insert((from, to, edge.weight().kind));
+                contracted.add_edge(from, to, edge.weight().clone());
+            }
+        }
+
+        for index in self.graph.node_indices() {
+            let import = &self.graph[index];
+            if import.kind != NodeKind::Import {
+                continue;
+            }
+            let Some(target) = import_suffixes(&import.name)
+                .find_map(|name| symbols.resolve_with_context(name, Path::new(&import.file)))
+            else {
+                continue;
+            };
+            let imported = import_suffixes(&import.name).last().unwrap_or(&import.name);
+
+            let users = self.file_nodes(&import.file).into_iter().filter(|&user| {
+                self.graph[user]
+                    .references
+                    .iter()
+                    .any(|r| r == imported || r == &import.name || is_suffix_match(r, imported))
+            });
+            let linked = self
+                .graph
+                .edges_directed(index, Direction::Incoming)
+                .map(|e| e.source());
+
+            for from in users.chain(linked).filter_map(|i| mapping.get(&i).copied()) {
+                if from != target && seen.insert((from, target, EdgeKind::Imports)) {
+                    contracted.add_edge(from, target, Edge::new(EdgeKind::Imports));
+                }
+            }
+        }
+
+        contracted
+    }
+}
+
+/// The import path and each shorter suffix after a `::`, `.` or `/`,
+/// longest first.
+fn import_suffixes(path: &str) -> impl Iterator<Item = &str> {
+    let suffixes = path
+        .char_indices()
+        .filter(|(_, c)| matches!(c, ':' | '.' | '/'))
+        .map(move |(i, _)| &path[i + 1..])
+        .filter(|suffix| !suffix.is_empty() && !suffix.starts_with(':'));
+    std::iter::once(path).chain(suffixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::GraphBuilder;
+    use arbor_core::CodeNode;
+
+    fn outgoing(graph: &ArborGraph, name: &str, kind: EdgeKind) -> Vec<String> {
+        let index = graph.get_index(&graph.find_by_name(name)[0].id).unwrap();
+        let mut names: Vec<String> = graph
+            .graph
+            .edges_directed(index, Direction::Outgoing)
+            .filter(|e| e.weight().kind == kind)
+            .map(|e| graph.graph[e.target()].name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_without_imports_links_users_to_definitions() {
+        let mut handle = CodeNode::new("handle", "handle", NodeKind::Function, "api.rs");
+        handle.references = vec!["query".to_string(), "log".to_string()];
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            handle,
+            CodeNode::new(
+                "crate::db::query",
+                "crate::db::query",
+                NodeKind::Import,
+                "api.rs",
+            ),
+            CodeNode::new("std::fmt", "std::fmt", NodeKind::Import, "api.rs"),
+            CodeNode::new("query", "query", NodeKind::Function, "db.rs"),
+            CodeNode::new("log", "log", NodeKind::Function, "log.rs"),
+        ]);
+        let graph = builder.build();
+
+        let contracted = graph.without_imports();
+
+        assert_eq!(contracted.node_count(), 3);
+        assert!(contracted.nodes().all(|n| n.kind != NodeKind::Import));
+        // The import resolves through the symbol table to db.rs; std::fmt
+        // isn't in the graph and leaves nothing behind
+        assert_eq!(
+            outgoing(&contracted, "handle", EdgeKind::Imports),
+            ["query"]
+        );
+        assert_eq!(
+            outgoing(&contracted, "handle", EdgeKind::Calls),
+            outgoing(&graph, "handle", EdgeKind::Calls)
+        );
+    }
+
+    #[test]
+    fn test_import_suffixes() {
+        let suffixes: Vec<&str> = import_suffixes("crate::db::query").collect();
+        assert_eq!(suffixes, ["crate::db::query", "db::query", "query"]);
+        let suffixes: Vec<&str> = import_suffixes("os.path").collect();
+        assert_eq!(suffixes, ["os.path", "path"]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Fan-in / fan-out coupling metrics.
+//!
+//! Fan-in counts the distinct callers of a node, fan-out the distinct
+//! callees. A node with very high fan-in is a "god function" that too much
+//! depends on; one with very high fan-out is a brittle orchestrator that
+//! breaks whenever any of its many dependencies changes.
+
+use crate::edge::EdgeKind;
+use crate::graph::{ArborGraph, NodeId};
+use crate::query::NodeInfo;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::HashSet;
+
+/// Fan-in and fan-out of one node.
+#[derive(Debug, Clone)]
+pub struct FanEntry {
+    /// Node information.
+    pub node: NodeInfo,
+    /// Number of distinct callers.
+    pub fan_in: usize,
+    /// Number of distinct callees.
+    pub fan_out: usize,
+}
+
+/// Fan-in/fan-out for every node, with the thresholds used for flagging.
+#[derive(Debug, Clone)]
+pub struct FanReport {
+    /// Nodes with at least one call edge, most coupled first.
+    pub entries: Vec<FanEntry>,
+    /// Fan-in above which a node is a god function.
+    pub max_fan_in: usize,
+    /// Fan-out above which a node is a brittle orchestrator.
+    pub max_fan_out: usize,
+}
+
+impl FanReport {
+    /// Nodes whose fan-in exceeds `max_fan_in`.
+    pub fn god_functions(&self) -> Vec<&FanEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.fan_in > self.max_fan_in)
+            .collect()
+    }
+
+    /// Nodes whose fan-out exceeds `max_fan_out`.
+    pub fn orchestrators(&self) -> Vec<&FanEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.fan_out > self.max_fan_out)
+            .collect()
+    }
+}
+
+impl ArborGraph {
+    /// Number of distinct nodes that call `id`.
+    pub fn fan_in(&self, id: NodeId) -> usize {
+        self.call_neighbors(id, Direction::Incoming)
+    }
+
+    /// Number of distinct nodes that `id` calls.
+    pub fn fan_out(&self, id: NodeId) -> usize {
+        self.call_neighbors(id, Direction::Outgoing)
+    }
+
+    /// Computes fan-in and fan-out for every node.
+    ///
+    /// Nodes without call edges are left out. Entries are ordered by
+    /// fan-in plus fan-out, highest first, then by name.
+    pub fn fan_in_fan_out(&self, max_fan_in: usize, max_fan_out: usize) -> FanReport {
+        let mut entries: Vec<FanEntry> = self
+            .node_indexes()
+            .filter_map(|id| {
+                let fan_in = self.fan_in(id);
+                let fan_out = self.fan_out(id);
+                if fan_in == 0 && fan_out == 0 {
+                    return None;
+                }
+
+                let mut node = NodeInfo::from(self.get(id)?);
+                node.centrality = self.centrality_or_default(id);
+                Some(FanEntry {
+                    node,
+                    fan_in,
+                    fan_out,
+                })
+            })
+            .collect();
+
+        entries.sort_by(|a, b| {
+            (b.fan_in + b.fan_out)
+                .cmp(&(a.fan_in + a.fan_out))
+                .then_with(|| a.node.qualified_name.cmp(&b.node.qualified_name))
+        });
+
+        FanReport {
+            entries,
+            max_fan_in,
+            max_fan_out,
+        }
+    }
+
+    /// Counts distinct neighbors over `Calls` edges in one direction.
+    fn call_neighbors(&self, id: NodeId, direction: Direction) -> usize {
+        self.graph
+            .edges_directed(id, direction)
+            .filter(|e| e.weight().kind == EdgeKind::Calls)
+            .map(|e| match direction {
+                Direction::Incoming => e.source(),
+                Direction::Outgoing => e.target(),
+            })
+            .filter(|&n| n != id)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use arbor_core::{CodeNode, NodeKind};
+
+    fn make_node(name: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "test.rs")
+    }
+
+    #[test]
+    fn test_flags_hub_and_orchestrator() {
+        let mut graph = ArborGraph::new();
+        let log = graph.add_node(make_node("log"));
+        let run_all = graph.add_node(make_node("run_all"));
+        let steps: Vec<NodeId> = (0..4)
+            .map(|i| graph.add_node(make_node(&format!("step_{}", i))))
+            .collect();
+
+        // Every step calls log (hub); run_all calls every step (orchestrator)
+        for &step in &steps {
+            graph.add_edge(step, log, Edge::new(EdgeKind::Calls));
+            graph.add_edge(run_all, step, Edge::new(EdgeKind::Calls));
+        }
+        // Duplicate edges don't inflate the counts
+        graph.add_edge(steps[0], log, Edge::new(EdgeKind::Calls));
+        // Non-call edges are ignored
+        graph.add_edge(run_all, log, Edge::new(EdgeKind::Imports));
+
+        assert_eq!(graph.fan_in(log), 4);
+        assert_eq!(graph.fan_out(run_all), 4);
+
+        let report = graph.fan_in_fan_out(3, 3);
+
+        let gods: Vec<&str> = report
+            .god_functions()
+            .iter()
+            .map(|e| e.node.name.as_str())
+            .collect();
+        let orchestrators: Vec<&str> = report
+            .orchestrators()
+            .iter()
+            .map(|e| e.node.name.as_str())
+            .collect();
+        assert_eq!(gods, vec!["log"]);
+        assert_eq!(orchestrators, vec!["run_all"]);
+        assert_eq!(report.entries.len(), 6);
+    }
+}
@@ -0,0 +1,107 @@
+//! One-call node summaries.
+//!
+//! Bundles the metadata that the CLI, MCP server and GUI otherwise
+//! assemble piecemeal: degrees, caller/callee counts, centrality, the
+//! inferred role and the confidence explanation.
+
+use crate::confidence::{ConfidenceExplanation, NodeRole};
+use crate::graph::{ArborGraph, NodeId};
+use crate::query::NodeInfo;
+use petgraph::Direction;
+
+/// Impact depth used to infer role and confidence.
+const DESCRIBE_DEPTH: usize = 5;
+
+/// Everything Arbor knows about a single node.
+#[derive(Debug, Clone)]
+pub struct NodeDescription {
+    /// Node information, with centrality filled in.
+    pub node: NodeInfo,
+    /// Number of incoming edges of any kind.
+    pub in_degree: usize,
+    /// Number of outgoing edges of any kind.
+    pub out_degree: usize,
+    /// Number of direct callers (`Calls` edges in).
+    pub caller_count: usize,
+    /// Number of direct callees (`Calls` edges out).
+    pub callee_count: usize,
+    /// Structural role inferred from impact analysis.
+    pub role: NodeRole,
+    /// How much to trust the impact analysis for this node.
+    pub confidence: ConfidenceExplanation,
+}
+
+impl NodeDescription {
+    /// Centrality score of the node.
+    pub fn centrality(&self) -> f64 {
+        self.node.centrality
+    }
+}
+
+impl ArborGraph {
+    /// Describes a node. Returns None if the node doesn't exist.
+    ///
+    /// Role and confidence come from a (cached) impact analysis
+    /// five hops deep.
+    pub fn describe(&self, id: NodeId) -> Option<NodeDescription> {
+        let mut node = NodeInfo::from(self.get(id)?);
+        node.centrality = self.centrality_or_default(id);
+
+        let analysis = self.analyze_impact_cached(id, DESCRIBE_DEPTH);
+
+        Some(NodeDescription {
+            node,
+            in_degree: self.graph.edges_directed(id, Direction::Incoming).count(),
+            out_degree: self.graph.edges_directed(id, Direction::Outgoing).count(),
+            caller_count: self.get_callers(id).len(),
+            callee_count: self.get_callees(id).len(),
+            role: NodeRole::from_analysis(&analysis),
+            confidence: ConfidenceExplanation::from_analysis(&analysis),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::confidence::ConfidenceLevel;
+    use crate::edge::{Edge, EdgeKind};
+    use arbor_core::{CodeNode, NodeKind};
+    use std::collections::HashMap;
+
+    fn make_node(name: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "test.rs")
+    }
+
+    #[test]
+    fn test_describe_aggregates_fields() {
+        let mut graph = ArborGraph::new();
+        let handler = graph.add_node(make_node("handler"));
+        let cli = graph.add_node(make_node("cli"));
+        let validate = graph.add_node(make_node("validate"));
+        let parse = graph.add_node(make_node("parse"));
+        let config = graph.add_node(make_node("config"));
+        graph.add_edge(handler, validate, Edge::new(EdgeKind::Calls));
+        graph.add_edge(cli, validate, Edge::new(EdgeKind::Calls));
+        graph.add_edge(validate, parse, Edge::new(EdgeKind::Calls));
+        graph.add_edge(validate, config, Edge::new(EdgeKind::Imports));
+        graph.set_centrality(HashMap::from([(validate, 0.5)]));
+
+        let desc = graph.describe(validate).unwrap();
+
+        assert_eq!(desc.node.name, "validate");
+        assert_eq!(desc.centrality(), 0.5);
+        assert_eq!(desc.in_degree, 2);
+        assert_eq!(desc.out_degree, 2);
+        assert_eq!(desc.caller_count, 2);
+        assert_eq!(desc.callee_count, 1);
+        assert_eq!(desc.role, NodeRole::CoreLogic);
+        assert_eq!(desc.confidence.level, ConfidenceLevel::High);
+    }
+
+    #[test]
+    fn test_describe_unknown_node() {
+        let graph = ArborGraph::new();
+        assert!(graph.describe(NodeId::new(3)).is_none());
+    }
+}
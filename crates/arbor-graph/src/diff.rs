@@ -0,0 +1,305 @@
+//! Structural diff between two graphs.
+//!
+//! Nodes are matched by ID and edges by (source ID, target ID, kind), so
+//! a diff survives re-indexing: two graphs of the same tree compare equal
+//! even though their internal node indexes differ. IDs hash the file path
+//! as indexed, so graphs indexed from different spellings of the root are
+//! brought to root-relative paths first with `strip_root`.
+
+use crate::edge::{EdgeKind, GraphEdge};
+use crate::graph::ArborGraph;
+use crate::query::NodeInfo;
+use arbor_core::CodeNode;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// What changed between an older and a newer graph.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphDiff {
+    /// Nodes only in the newer graph.
+    pub added: Vec<NodeInfo>,
+    /// Nodes only in the older graph.
+    pub removed: Vec<NodeInfo>,
+    /// Nodes in both whose kind, signature or body size changed.
+    pub modified: Vec<NodeInfo>,
+    /// Edges only in the newer graph.
+    pub added_edges: Vec<GraphEdge>,
+    /// Edges only in the older graph.
+    pub removed_edges: Vec<GraphEdge>,
+}
+
+impl GraphDiff {
+    /// Returns true if the graphs are structurally identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+/// How one symbol's blast radius changed between an older and a newer graph.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImpactDelta {
+    /// The symbol as given.
+    pub symbol: String,
+    /// Affected nodes in the older graph, or `None` if the symbol wasn't
+    /// found there.
+    pub before: Option<usize>,
+    /// Affected nodes in the newer graph, or `None` if the symbol wasn't
+    /// found there.
+    pub after: Option<usize>,
+    /// Nodes affected only in the newer graph.
+    pub added: Vec<NodeInfo>,
+    /// Nodes affected only in the older graph.
+    pub removed: Vec<NodeInfo>,
+    /// Change in the number of affected nodes; negative when the blast
+    /// radius shrank.
+    pub delta: isize,
+}
+
+impl ArborGraph {
+    /// Compares the blast radius of `symbol` in this graph against a newer
+    /// one, to check whether a refactor reduced coupling.
+    ///
+    /// `symbol` is a node ID or name (first match) and is looked up in each
+    /// graph separately. Impact is followed to any depth, upstream and
+    /// downstream; affected nodes are matched by ID.
+    pub fn diff_impact(&self, other: &ArborGraph, symbol: &str) -> ImpactDelta {
+        let before = affected_by_id(self, symbol);
+        let after = affected_by_id(other, symbol);
+        let count = |affected: &Option<HashMap<String, NodeInfo>>| {
+            affected.as_ref().map(HashMap::len).unwrap_or(0) as isize
+        };
+
+        let mut delta = ImpactDelta {
+            symbol: symbol.to_string(),
+            before: before.as_ref().map(HashMap::len),
+            after: after.as_ref().map(HashMap::len),
+            delta: count(&after) - count(&before),
+            ..Default::default()
+        };
+
+        let (before, after) = (before.unwrap_or_default(), after.unwrap_or_default());
+        delta.added = after
+            .iter()
+            .filter(|(id, _)| !before.contains_key(*id))
+            .map(|(_, info)| info.clone())
+            .collect();
+        delta.removed = before
+            .into_iter()
+            .filter(|(id, _)| !after.contains_key(id))
+            .map(|(_, info)| info)
+            .collect();
+        delta.added.sort_by(|a, b| a.id.cmp(&b.id));
+        delta.removed.sort_by(|a, b| a.id.cmp(&b.id));
+
+        delta
+    }
+
+    /// Rewrites the paths of files under `root` relative to it, with node
+    /// IDs recomputed to match.
+    ///
+    /// `./src/a.rs` under `.` and `/srv/repo/src/a.rs` under `/srv/repo`
+    /// both become `src/a.rs`, so the two graphs diff by content rather
+    /// than by how their roots were spelled. Returns the number of nodes
+    /// rewritten.
+    pub fn strip_root(&mut self, root: &Path) -> usize {
+        let relative = |file: &str| {
+            let relative = Path::new(file).strip_prefix(root).ok()?;
+            Some(relative.to_string_lossy().replace('\\', "/"))
+        };
+
+        let new_ids: HashMap<String, String> = self
+            .nodes()
+            .filter_map(|node| {
+                let file = relative(&node.file)?;
+                let id = CodeNode::compute_id(&file, &node.qualified_name, node.kind);
+                Some((node.id.clone(), id))
+            })
+            .collect();
+
+        self.annotate(|node| {
+            if let Some(file) = relative(&node.file) {
+                node.id = new_ids[&node.id].clone();
+                node.file = file;
+            }
+            if let Some(parent) = node.parent_id.as_ref().and_then(|p| new_ids.get(p)) {
+                node.parent_id = Some(parent.clone());
+            }
+        });
+
+        new_ids.len()
+    }
+
+    /// Compares this graph against a newer one.
+    ///
+    /// Line numbers alone don't count as a modification, since editing
+    /// one function shifts every node below it.
+    pub fn diff(&self, newer: &ArborGraph) -> GraphDiff {
+        let mut diff = GraphDiff::default();
+
+        for node in newer.nodes() {
+            match self.get_by_id(&node.id) {
+                None => diff.added.push(NodeInfo::from(node)),
+                Some(old) if node_changed(old, node) => diff.modified.push(NodeInfo::from(node)),
+                Some(_) => {}
+            }
+        }
+        for node in self.nodes() {
+            if newer.get_by_id(&node.id).is_none() {
+                diff.removed.push(NodeInfo::from(node));
+            }
+        }
+
+        let old_edges = edge_keys(self);
+        let new_edges = edge_keys(newer);
+        diff.added_edges = newer
+            .edges_iter()
+            .filter(|e| !old_edges.contains(&(e.source.clone(), e.target.clone(), e.kind)))
+            .collect();
+        diff.removed_edges = self
+            .edges_iter()
+            .filter(|e| !new_edges.contains(&(e.source.clone(), e.target.clone(), e.kind)))
+            .collect();
+
+        diff.added.sort_by(|a, b| a.id.cmp(&b.id));
+        diff.removed.sort_by(|a, b| a.id.cmp(&b.id));
+        diff.modified.sort_by(|a, b| a.id.cmp(&b.id));
+
+        diff
+    }
+}
+
+/// Returns true if a node changed in a way that matters to dependents.
+fn node_changed(old: &CodeNode, new: &CodeNode) -> bool {
+    let span = |n: &CodeNode| n.byte_end.saturating_sub(n.byte_start);
+    old.kind != new.kind || old.signature != new.signature || span(old) != span(new)
+}
+
+/// Runs an unbounded impact analysis of `symbol`, keyed by affected node
+/// ID. Returns `None` if the symbol isn't in the graph.
+fn affected_by_id(graph: &ArborGraph, symbol: &str) -> Option<HashMap<String, NodeInfo>> {
+    let target = graph.get_index(symbol).or_else(|| {
+        graph
+            .find_by_name(symbol)
+            .first()
+            .and_then(|n| graph.get_index(&n.id))
+    })?;
+
+    let analysis = graph.analyze_impact(target, 0);
+    Some(
+        analysis
+            .upstream
+            .into_iter()
+            .chain(analysis.downstream)
+            .map(|affected| (affected.node_info.id.clone(), affected.node_info))
+            .collect(),
+    )
+}
+
+/// Collects edges as (source ID, target ID, kind) for set comparison.
+fn edge_keys(graph: &ArborGraph) -> HashSet<(String, String, EdgeKind)> {
+    graph
+        .edges_iter()
+        .map(|e| (e.source, e.target, e.kind))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use arbor_core::NodeKind;
+
+    fn make_node(name: &str, signature: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "lib.rs").with_signature(signature)
+    }
+
+    #[test]
+    fn test_diff_reports_node_and_edge_changes() {
+        let mut old = ArborGraph::new();
+        let a = old.add_node(make_node("a", "fn a()"));
+        let b = old.add_node(make_node("b", "fn b()"));
+        old.add_node(make_node("gone", "fn gone()"));
+        old.add_edge(a, b, Edge::new(EdgeKind::Calls));
+
+        let mut new = ArborGraph::new();
+        let a = new.add_node(make_node("a", "fn a()"));
+        new.add_node(make_node("b", "fn b(x: i32)"));
+        let c = new.add_node(make_node("c", "fn c()"));
+        new.add_edge(a, c, Edge::new(EdgeKind::Calls));
+
+        let diff = old.diff(&new);
+
+        let names = |nodes: &[NodeInfo]| nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&diff.added), vec!["c"]);
+        assert_eq!(names(&diff.removed), vec!["gone"]);
+        assert_eq!(names(&diff.modified), vec!["b"]);
+        assert_eq!(diff.added_edges.len(), 1);
+        assert_eq!(diff.removed_edges.len(), 1);
+
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_impact_shrinks_when_call_removed() {
+        // Before: api → service → db, and cli → service
+        let build = |with_cli_call: bool| {
+            let mut graph = ArborGraph::new();
+            let api = graph.add_node(make_node("api", "fn api()"));
+            let cli = graph.add_node(make_node("cli", "fn cli()"));
+            let service = graph.add_node(make_node("service", "fn service()"));
+            let db = graph.add_node(make_node("db", "fn db()"));
+            graph.add_edge(api, service, Edge::new(EdgeKind::Calls));
+            graph.add_edge(service, db, Edge::new(EdgeKind::Calls));
+            if with_cli_call {
+                graph.add_edge(cli, service, Edge::new(EdgeKind::Calls));
+            }
+            graph
+        };
+        let (old, new) = (build(true), build(false));
+
+        let delta = old.diff_impact(&new, "service");
+        assert_eq!(delta.before, Some(3));
+        assert_eq!(delta.after, Some(2));
+        assert_eq!(delta.delta, -1);
+        assert!(delta.added.is_empty());
+        assert_eq!(delta.removed.len(), 1);
+        assert_eq!(delta.removed[0].name, "cli");
+
+        let missing = old.diff_impact(&new, "nope");
+        assert_eq!(
+            (missing.before, missing.after, missing.delta),
+            (None, None, 0)
+        );
+    }
+
+    #[test]
+    fn test_strip_root_matches_differently_spelled_roots() {
+        let build = |root: &str| {
+            let file = format!("{}/src/auth.rs", root);
+            let mut graph = ArborGraph::new();
+            let login = graph.add_node(CodeNode::new("login", "login", NodeKind::Function, &file));
+            let check = graph.add_node(CodeNode::new("check", "check", NodeKind::Function, &file));
+            graph.add_edge(login, check, Edge::new(EdgeKind::Calls));
+            graph
+        };
+        let (mut server, mut local) = (build("/srv/repo"), build("."));
+        assert_eq!(server.diff(&local).added.len(), 2);
+
+        assert_eq!(server.strip_root(Path::new("/srv/repo")), 2);
+        assert_eq!(local.strip_root(Path::new(".")), 2);
+
+        assert!(server.diff(&local).is_empty());
+        let login = local.find_by_name("login")[0];
+        assert_eq!(login.file, "src/auth.rs");
+        assert_eq!(
+            login.id,
+            CodeNode::compute_id("src/auth.rs", "login", NodeKind::Function)
+        );
+        assert!(local.get_by_id(&login.id).is_some());
+    }
+}
@@ -38,6 +38,11 @@ pub enum EdgeKind {
     /// Data dependency: variable use depends on definition.
     /// Used for DFA (Data Flow Analysis) edges.
     DataDependency,
+
+    /// Export statement exposes a symbol (`export { a }`, `export default b`).
+    /// Kept last: bincode stores variants by index, so new kinds go at the
+    /// end to keep cached graphs readable.
+    Exports,
 }
 
 impl std::fmt::Display for EdgeKind {
@@ -52,11 +57,36 @@ impl std::fmt::Display for EdgeKind {
             Self::Contains => "contains",
             Self::FlowsTo => "flows_to",
             Self::DataDependency => "data_dependency",
+            Self::Exports => "exports",
         };
         write!(f, "{}", s)
     }
 }
 
+impl std::str::FromStr for EdgeKind {
+    type Err = String;
+
+    /// Parses the `Display` name; `inherits` is accepted for `extends`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "calls" => Ok(Self::Calls),
+            "imports" => Ok(Self::Imports),
+            "extends" | "inherits" => Ok(Self::Extends),
+            "implements" => Ok(Self::Implements),
+            "uses_type" => Ok(Self::UsesType),
+            "references" => Ok(Self::References),
+            "contains" => Ok(Self::Contains),
+            "flows_to" => Ok(Self::FlowsTo),
+            "data_dependency" => Ok(Self::DataDependency),
+            "exports" => Ok(Self::Exports),
+            other => Err(format!(
+                "unknown edge kind '{}' (expected calls, imports, extends/inherits, implements, uses_type, references, contains, exports, flows_to or data_dependency)",
+                other
+            )),
+        }
+    }
+}
+
 /// An edge in the code graph with location info.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
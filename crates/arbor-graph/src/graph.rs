@@ -4,12 +4,14 @@
 //! It's the central data structure that everything else works with.
 
 use crate::edge::{Edge, EdgeKind, GraphEdge};
-use crate::search_index::SearchIndex;
+use crate::impact::ImpactCache;
+use crate::search_index::{match_spans, SearchIndex, SearchMatch};
 use arbor_core::CodeNode;
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef; // For edge_references
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Unique identifier for a node in the graph.
 pub type NodeId = NodeIndex;
@@ -35,9 +37,26 @@ pub struct ArborGraph {
     /// Centrality scores for ranking.
     centrality: HashMap<NodeId, f64>,
 
+    /// Whether `set_centrality` has run, so a missing score can be told
+    /// apart from a genuine 0.0. Restored by `from_snapshot`.
+    #[serde(skip)]
+    centrality_computed: bool,
+
     /// Search index for fast substring queries.
     #[serde(skip)]
     search_index: SearchIndex,
+
+    /// Bumped on every mutation so derived caches know they are stale.
+    #[serde(skip)]
+    generation: u64,
+
+    /// LRU cache for `analyze_impact_cached`.
+    #[serde(skip)]
+    pub(crate) impact_cache: Mutex<ImpactCache>,
+
+    /// Component labels and the generation they were computed at.
+    #[serde(skip)]
+    pub(crate) component_cache: Mutex<Option<(u64, Arc<Vec<usize>>)>>,
 }
 
 impl Default for ArborGraph {
@@ -55,7 +74,11 @@ impl ArborGraph {
             name_index: HashMap::new(),
             file_index: HashMap::new(),
             centrality: HashMap::new(),
+            centrality_computed: false,
             search_index: SearchIndex::new(),
+            generation: 0,
+            impact_cache: Mutex::default(),
+            component_cache: Mutex::default(),
         }
     }
 
@@ -68,6 +91,7 @@ impl ArborGraph {
         let file = node.file.clone();
 
         let index = self.graph.add_node(node);
+        self.generation += 1;
 
         // Update indexes
         self.id_index.insert(id, index);
@@ -81,6 +105,7 @@ impl ArborGraph {
     /// Adds an edge between two nodes.
     pub fn add_edge(&mut self, from: NodeId, to: NodeId, edge: Edge) {
         self.graph.add_edge(from, to, edge);
+        self.generation += 1;
     }
 
     /// Gets a node by its string ID.
@@ -107,6 +132,21 @@ impl ArborGraph {
             .unwrap_or_default()
     }
 
+    /// Finds all nodes with a given name, ignoring case.
+    ///
+    /// Exact-case matches come first, so taking the first result prefers
+    /// `UserService` over `userService` when looking up `UserService`.
+    pub fn find_by_name_ci(&self, name: &str) -> Vec<&CodeNode> {
+        let mut matches: Vec<&CodeNode> = self
+            .search_index
+            .exact(name)
+            .iter()
+            .filter_map(|idx| self.graph.node_weight(*idx))
+            .collect();
+        matches.sort_by_key(|node| node.name != name);
+        matches
+    }
+
     /// Finds all nodes in a file.
     pub fn find_by_file(&self, file: &str) -> Vec<&CodeNode> {
         self.file_index
@@ -120,6 +160,23 @@ impl ArborGraph {
             .unwrap_or_default()
     }
 
+    /// Finds the innermost node in `file` whose line range contains `line`.
+    ///
+    /// For nested definitions (a method inside a class) the node with the
+    /// smallest range wins, so a cursor inside a method resolves to the
+    /// method rather than its class.
+    pub fn node_at(&self, file: &str, line: u32) -> Option<&CodeNode> {
+        self.find_by_file(file)
+            .into_iter()
+            .filter(|node| node.line_start <= line && line <= node.line_end)
+            .min_by_key(|node| {
+                (
+                    node.line_end - node.line_start,
+                    node.byte_end.saturating_sub(node.byte_start),
+                )
+            })
+    }
+
     /// Searches for nodes whose name contains the query.
     ///
     /// Uses the search index for fast O(k) lookups where k is the number of matches,
@@ -132,6 +189,26 @@ impl ArborGraph {
             .collect()
     }
 
+    /// Like `search`, but also reports where the query matched in each
+    /// node's name and signature, so a UI can highlight it.
+    ///
+    /// Spans cover every occurrence, ignoring case; a signature may have
+    /// none when only the name matched.
+    pub fn search_with_spans(&self, query: &str) -> Vec<SearchMatch<'_>> {
+        self.search(query)
+            .into_iter()
+            .map(|node| SearchMatch {
+                node,
+                name_spans: match_spans(&node.name, query),
+                signature_spans: node
+                    .signature
+                    .as_deref()
+                    .map(|signature| match_spans(signature, query))
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
     /// Gets nodes that call the given node.
     pub fn get_callers(&self, index: NodeId) -> Vec<&CodeNode> {
         self.graph
@@ -165,6 +242,25 @@ impl ArborGraph {
             .collect()
     }
 
+    /// Gets the members (methods, fields, variants) of a class, struct,
+    /// enum or interface, in source order.
+    pub fn members(&self, index: NodeId) -> Vec<&CodeNode> {
+        let mut members: Vec<&CodeNode> = self
+            .graph
+            .edges_directed(index, petgraph::Direction::Outgoing)
+            .filter(|edge| edge.weight().kind == EdgeKind::Contains)
+            .filter_map(|edge| self.graph.node_weight(edge.target()))
+            .collect();
+
+        members.sort_by(|a, b| {
+            a.line_start
+                .cmp(&b.line_start)
+                .then_with(|| a.column.cmp(&b.column))
+        });
+        members.dedup_by(|a, b| a.id == b.id);
+        members
+    }
+
     /// Gets all nodes that depend on the given node (directly or transitively).
     pub fn get_dependents(&self, index: NodeId, max_depth: usize) -> Vec<(NodeId, usize)> {
         let mut result = Vec::new();
@@ -195,34 +291,184 @@ impl ArborGraph {
         result
     }
 
+    /// Runs a post-processing pass over every node.
+    ///
+    /// This is the way to enrich nodes after building (test detection,
+    /// decorator-based tagging, normalization). If the pass changes a
+    /// node's ID, name or file, the lookup indexes are updated to match.
+    pub fn annotate<F: FnMut(&mut CodeNode)>(&mut self, mut f: F) {
+        let indexes: Vec<NodeId> = self.graph.node_indices().collect();
+
+        for index in indexes {
+            let node = &mut self.graph[index];
+            let old_id = node.id.clone();
+            let old_name = node.name.clone();
+            let old_file = node.file.clone();
+
+            f(node);
+
+            let node = &self.graph[index];
+            if node.id != old_id {
+                self.id_index.remove(&old_id);
+                self.id_index.insert(node.id.clone(), index);
+            }
+            if node.name != old_name {
+                if let Some(list) = self.name_index.get_mut(&old_name) {
+                    list.retain(|&idx| idx != index);
+                }
+                self.name_index
+                    .entry(node.name.clone())
+                    .or_default()
+                    .push(index);
+                self.search_index.remove(&old_name, index);
+                self.search_index.insert(&node.name, index);
+            }
+            if node.file != old_file {
+                if let Some(list) = self.file_index.get_mut(&old_file) {
+                    list.retain(|&idx| idx != index);
+                }
+                self.file_index
+                    .entry(node.file.clone())
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        self.generation += 1;
+    }
+
+    /// Moves all nodes of `from` to `to` without touching their edges.
+    ///
+    /// Used when a file is renamed with unchanged content: node IDs are
+    /// recomputed for the new path (and `parent_id`s follow them), but the
+    /// graph indexes stay the same, so callers and callees stay linked.
+    /// Returns the number of nodes moved.
+    pub fn rename_file(&mut self, from: &str, to: &str) -> usize {
+        let Some(indexes) = self.file_index.remove(from) else {
+            return 0;
+        };
+        self.generation += 1;
+
+        let mut new_ids = HashMap::new();
+        for &index in &indexes {
+            let node = &mut self.graph[index];
+            let new_id = CodeNode::compute_id(to, &node.qualified_name, node.kind);
+            let old_id = std::mem::replace(&mut node.id, new_id.clone());
+            node.file = to.to_string();
+
+            self.id_index.remove(&old_id);
+            self.id_index.insert(new_id.clone(), index);
+            new_ids.insert(old_id, new_id);
+        }
+        for &index in &indexes {
+            let node = &mut self.graph[index];
+            if let Some(new_parent) = node.parent_id.as_ref().and_then(|p| new_ids.get(p)) {
+                node.parent_id = Some(new_parent.clone());
+            }
+        }
+
+        let moved = indexes.len();
+        self.file_index
+            .entry(to.to_string())
+            .or_default()
+            .extend(indexes);
+        moved
+    }
+
     /// Removes all nodes from a file. Used for incremental updates.
     pub fn remove_file(&mut self, file: &str) {
-        if let Some(indexes) = self.file_index.remove(file) {
+        if let Some(mut indexes) = self.file_index.remove(file) {
+            self.generation += 1;
+            // Highest index first: petgraph moves the last node into the
+            // freed slot, and that node is then never one still to remove.
+            indexes.sort_unstable_by(|a, b| b.cmp(a));
             for index in indexes {
-                if let Some(node) = self.graph.node_weight(index) {
-                    // Remove from name index
-                    let name = node.name.clone();
-                    if let Some(name_list) = self.name_index.get_mut(&name) {
-                        name_list.retain(|&idx| idx != index);
-                    }
-                    // Remove from id index
-                    self.id_index.remove(&node.id);
-                    // Remove from search index
-                    self.search_index.remove(&name, index);
-                }
-                self.graph.remove_node(index);
+                self.remove_node(index);
+            }
+        }
+    }
+
+    /// Node indexes of a file, empty if it has none.
+    pub(crate) fn file_nodes(&self, file: &str) -> Vec<NodeId> {
+        self.file_index.get(file).cloned().unwrap_or_default()
+    }
+
+    /// Removes one node and keeps every index pointing at the right slot.
+    ///
+    /// `DiGraph::remove_node` swaps the last node into the removed index,
+    /// so that node's entries are moved along with it.
+    pub(crate) fn remove_node(&mut self, index: NodeId) {
+        let Some(node) = self.graph.node_weight(index) else {
+            return;
+        };
+        let name = node.name.clone();
+        let file = node.file.clone();
+        self.id_index.remove(&node.id);
+        if let Some(list) = self.name_index.get_mut(&name) {
+            list.retain(|&idx| idx != index);
+        }
+        if let Some(list) = self.file_index.get_mut(&file) {
+            list.retain(|&idx| idx != index);
+        }
+        self.search_index.remove(&name, index);
+        self.centrality.remove(&index);
+
+        let last = NodeIndex::new(self.graph.node_count() - 1);
+        self.graph.remove_node(index);
+        if last == index {
+            return;
+        }
+
+        let moved = &self.graph[index];
+        self.id_index.insert(moved.id.clone(), index);
+        for list in [
+            self.name_index.get_mut(&moved.name),
+            self.file_index.get_mut(&moved.file),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            for idx in list.iter_mut().filter(|idx| **idx == last) {
+                *idx = index;
             }
         }
+        self.search_index.remove(&moved.name, last);
+        self.search_index.insert(&moved.name, index);
+        if let Some(score) = self.centrality.remove(&last) {
+            self.centrality.insert(index, score);
+        }
+    }
+
+    /// Gets the centrality score for a node, or `None` if scores were
+    /// never computed for this graph.
+    pub fn centrality(&self, index: NodeId) -> Option<f64> {
+        self.centrality_computed
+            .then(|| self.centrality.get(&index).copied().unwrap_or(0.0))
     }
 
-    /// Gets the centrality score for a node.
-    pub fn centrality(&self, index: NodeId) -> f64 {
-        self.centrality.get(&index).copied().unwrap_or(0.0)
+    /// Gets the centrality score for a node, 0.0 if not computed.
+    pub fn centrality_or_default(&self, index: NodeId) -> f64 {
+        self.centrality(index).unwrap_or(0.0)
+    }
+
+    /// Whether centrality scores have been computed and set.
+    pub fn centrality_computed(&self) -> bool {
+        self.centrality_computed
     }
 
     /// Sets centrality scores (called after computation).
     pub fn set_centrality(&mut self, scores: HashMap<NodeId, f64>) {
         self.centrality = scores;
+        self.centrality_computed = true;
+        self.generation += 1;
+    }
+
+    /// Returns the mutation counter.
+    ///
+    /// Any change to nodes, edges or centrality increments it, so two equal
+    /// values mean the graph has not changed in between.
+    pub fn generation(&self) -> u64 {
+        self.generation
     }
 
     /// Returns the number of nodes.
@@ -246,30 +492,37 @@ impl ArborGraph {
     }
 
     /// Returns all edges with source and target IDs for export.
+    ///
+    /// Collects `edges_iter`; prefer that on large graphs.
     pub fn export_edges(&self) -> Vec<GraphEdge> {
-        self.graph
-            .edge_references()
-            .map(|edge_ref| {
-                let source = self
-                    .graph
-                    .node_weight(edge_ref.source())
-                    .unwrap()
-                    .id
-                    .clone();
-                let target = self
-                    .graph
-                    .node_weight(edge_ref.target())
-                    .unwrap()
-                    .id
-                    .clone();
-                let weight = edge_ref.weight(); // &Edge
-                GraphEdge {
-                    source,
-                    target,
-                    kind: weight.kind,
-                }
-            })
-            .collect()
+        self.edges_iter().collect()
+    }
+
+    /// Iterates over edges with source and target IDs, one at a time.
+    ///
+    /// Exporters stream from this so the full edge list is never held in
+    /// memory at once.
+    pub fn edges_iter(&self) -> impl Iterator<Item = GraphEdge> + '_ {
+        self.graph.edge_references().map(|edge_ref| {
+            let source = self
+                .graph
+                .node_weight(edge_ref.source())
+                .unwrap()
+                .id
+                .clone();
+            let target = self
+                .graph
+                .node_weight(edge_ref.target())
+                .unwrap()
+                .id
+                .clone();
+            let weight = edge_ref.weight(); // &Edge
+            GraphEdge {
+                source,
+                target,
+                kind: weight.kind,
+            }
+        })
     }
 
     /// Iterates over all node indexes.
@@ -300,6 +553,17 @@ impl ArborGraph {
     pub fn get_index(&self, id: &str) -> Option<NodeId> {
         self.id_index.get(id).copied()
     }
+
+    /// Rebuilds the state that isn't serialized: the search index, and
+    /// the centrality flag (a snapshot with scores had them computed).
+    pub(crate) fn restore_skipped_state(&mut self) {
+        let mut search_index = SearchIndex::new();
+        for index in self.graph.node_indices() {
+            search_index.insert(&self.graph[index].name, index);
+        }
+        self.search_index = search_index;
+        self.centrality_computed = !self.centrality.is_empty();
+    }
 }
 
 /// Graph statistics for the info endpoint.
@@ -320,3 +584,180 @@ impl ArborGraph {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_core::NodeKind;
+
+    #[test]
+    fn test_annotate_marks_tests() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "parse",
+            "parse",
+            NodeKind::Function,
+            "lib.rs",
+        ));
+        graph.add_node(CodeNode::new(
+            "test_parse",
+            "test_parse",
+            NodeKind::Function,
+            "lib.rs",
+        ));
+        let before = graph.generation();
+
+        graph.annotate(|node| node.is_test = node.name.starts_with("test_"));
+
+        assert!(graph.find_by_name("test_parse")[0].is_test);
+        assert!(!graph.find_by_name("parse")[0].is_test);
+        assert!(graph.generation() > before);
+    }
+
+    #[test]
+    fn test_annotate_keeps_indexes_in_sync() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "Parse",
+            "Parse",
+            NodeKind::Function,
+            "lib.rs",
+        ));
+
+        graph.annotate(|node| node.name = node.name.to_lowercase());
+
+        assert!(graph.find_by_name("Parse").is_empty());
+        assert_eq!(graph.find_by_name("parse").len(), 1);
+        assert_eq!(graph.search("parse").len(), 1);
+    }
+
+    #[test]
+    fn test_node_at_prefers_innermost() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(
+            CodeNode::new("UserService", "UserService", NodeKind::Class, "user.ts")
+                .with_lines(1, 20),
+        );
+        graph.add_node(
+            CodeNode::new("save", "UserService.save", NodeKind::Method, "user.ts").with_lines(5, 9),
+        );
+
+        assert_eq!(graph.node_at("user.ts", 7).unwrap().name, "save");
+        assert_eq!(graph.node_at("user.ts", 2).unwrap().name, "UserService");
+        assert!(graph.node_at("user.ts", 21).is_none());
+        assert!(graph.node_at("other.ts", 7).is_none());
+    }
+
+    #[test]
+    fn test_members_in_source_order() {
+        let mut graph = ArborGraph::new();
+        let class = graph
+            .add_node(CodeNode::new("Cart", "Cart", NodeKind::Class, "cart.ts").with_lines(1, 10));
+        let total = graph.add_node(
+            CodeNode::new("total", "Cart.total", NodeKind::Method, "cart.ts").with_lines(6, 8),
+        );
+        let add = graph.add_node(
+            CodeNode::new("add", "Cart.add", NodeKind::Method, "cart.ts").with_lines(2, 4),
+        );
+        let helper = graph.add_node(CodeNode::new(
+            "helper",
+            "helper",
+            NodeKind::Function,
+            "cart.ts",
+        ));
+        graph.add_edge(class, total, Edge::new(EdgeKind::Contains));
+        graph.add_edge(class, add, Edge::new(EdgeKind::Contains));
+        graph.add_edge(class, helper, Edge::new(EdgeKind::Calls));
+
+        let names: Vec<&str> = graph
+            .members(class)
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["add", "total"]);
+    }
+
+    #[test]
+    fn test_edges_iter_matches_export_edges() {
+        let mut graph = ArborGraph::new();
+        let a = graph.add_node(CodeNode::new("a", "a", NodeKind::Function, "lib.rs"));
+        let b = graph.add_node(CodeNode::new("b", "b", NodeKind::Function, "lib.rs"));
+        let c = graph.add_node(CodeNode::new("c", "c", NodeKind::Function, "lib.rs"));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+        graph.add_edge(b, c, Edge::new(EdgeKind::UsesType));
+        graph.add_edge(c, a, Edge::new(EdgeKind::Imports));
+
+        let key = |e: GraphEdge| (e.source, e.target, e.kind);
+        let streamed: Vec<_> = graph.edges_iter().map(key).collect();
+        let collected: Vec<_> = graph.export_edges().into_iter().map(key).collect();
+
+        assert_eq!(streamed.len(), 3);
+        assert_eq!(streamed, collected);
+    }
+
+    #[test]
+    fn test_centrality_not_computed_on_fresh_graph() {
+        let mut graph = ArborGraph::new();
+        let id = graph.add_node(CodeNode::new("a", "a", NodeKind::Function, "a.rs"));
+
+        assert!(!graph.centrality_computed());
+        assert_eq!(graph.centrality(id), None);
+        assert_eq!(graph.centrality_or_default(id), 0.0);
+
+        graph.set_centrality(HashMap::from([(id, 0.25)]));
+        assert!(graph.centrality_computed());
+        assert_eq!(graph.centrality(id), Some(0.25));
+    }
+
+    #[test]
+    fn test_find_by_name_ci_prefers_exact_case() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "userService",
+            "userService",
+            NodeKind::Variable,
+            "app.ts",
+        ));
+        graph.add_node(CodeNode::new(
+            "UserService",
+            "UserService",
+            NodeKind::Class,
+            "user.ts",
+        ));
+
+        assert!(graph.find_by_name("userservice").is_empty());
+        assert_eq!(graph.find_by_name_ci("userservice").len(), 2);
+
+        let matches = graph.find_by_name_ci("UserService");
+        assert_eq!(matches[0].kind, NodeKind::Class);
+        assert_eq!(
+            graph.find_by_name_ci("userService")[0].kind,
+            NodeKind::Variable
+        );
+    }
+
+    #[test]
+    fn test_search_reports_match_spans() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(
+            CodeNode::new(
+                "load_UserProfile",
+                "load_UserProfile",
+                NodeKind::Function,
+                "a.rs",
+            )
+            .with_signature("fn load_UserProfile(user: &User) -> Profile"),
+        );
+        graph.add_node(CodeNode::new("save", "save", NodeKind::Function, "a.rs"));
+
+        let matches = graph.search_with_spans("user");
+        assert_eq!(matches.len(), 1);
+        let hit = &matches[0];
+        assert_eq!(hit.name_spans, vec![(5, 9)]);
+        assert_eq!(&hit.node.name[5..9], "User");
+        assert_eq!(hit.signature_spans, vec![(8, 12), (20, 24), (27, 31)]);
+
+        // The plain accessor returns the same nodes
+        assert_eq!(graph.search("user").len(), 1);
+    }
+}
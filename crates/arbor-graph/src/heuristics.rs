@@ -5,7 +5,10 @@
 //! - Framework-specific patterns (Flutter widgets, etc.)
 //! - Possible runtime dependencies
 
+use crate::graph::ArborGraph;
+use crate::impact::ImpactAnalysis;
 use arbor_core::{CodeNode, NodeKind};
+use serde::Serialize;
 
 /// Types of uncertain edges
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -80,6 +83,30 @@ impl HeuristicsMatcher {
                 || name_lower.ends_with("listener"))
     }
 
+    /// Check if a node looks like a test function
+    ///
+    /// Matches `test_*`/`testFoo`/`TestFoo` names, and any function in a
+    /// test file (`*_test.*`, `*.test.*`, `*.spec.*`, `test_*.*`, `tests/`).
+    pub fn is_test(node: &CodeNode) -> bool {
+        if !matches!(node.kind, NodeKind::Function | NodeKind::Method) {
+            return false;
+        }
+
+        let name_lower = node.name.to_lowercase();
+        if name_lower.starts_with("test") {
+            return true;
+        }
+
+        let path = node.file.replace('\\', "/").to_lowercase();
+        let file_name = path.rsplit('/').next().unwrap_or(&path);
+        path.starts_with("tests/")
+            || path.contains("/tests/")
+            || file_name.starts_with("test_")
+            || file_name.contains("_test.")
+            || file_name.contains(".test.")
+            || file_name.contains(".spec.")
+    }
+
     /// Check if a node looks like a callback parameter
     pub fn is_callback_style(node: &CodeNode) -> bool {
         let name_lower = node.name.to_lowercase();
@@ -143,7 +170,7 @@ impl HeuristicsMatcher {
 }
 
 /// Warnings about analysis limitations
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AnalysisWarning {
     pub message: String,
     pub suggestion: String,
@@ -198,6 +225,30 @@ pub fn detect_analysis_limitations(nodes: &[&CodeNode]) -> Vec<AnalysisWarning>
     warnings
 }
 
+impl ArborGraph {
+    /// Checks the target and affected nodes of an impact analysis for
+    /// patterns static analysis can't see through (callbacks, event
+    /// handlers, widget trees), so the blast radius can be read with
+    /// the right amount of suspicion.
+    pub fn impact_limitations(&self, analysis: &ImpactAnalysis) -> Vec<AnalysisWarning> {
+        let mut nodes: Vec<&CodeNode> = Vec::new();
+        if let Some(target) = self
+            .get_index(&analysis.target.id)
+            .and_then(|id| self.get(id))
+        {
+            nodes.push(target);
+        }
+        nodes.extend(
+            analysis
+                .all_affected()
+                .into_iter()
+                .filter_map(|affected| self.get(affected.node_id)),
+        );
+
+        detect_analysis_limitations(&nodes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -235,4 +286,54 @@ mod tests {
         let non_handler = CodeNode::new("calculate", "calculate", NodeKind::Function, "math.ts");
         assert!(!HeuristicsMatcher::is_event_handler(&non_handler));
     }
+
+    #[test]
+    fn test_test_detection() {
+        let by_name = CodeNode::new("test_login", "test_login", NodeKind::Function, "auth.rs");
+        assert!(HeuristicsMatcher::is_test(&by_name));
+
+        let by_file = CodeNode::new(
+            "it_renders",
+            "it_renders",
+            NodeKind::Function,
+            "src/app.spec.ts",
+        );
+        assert!(HeuristicsMatcher::is_test(&by_file));
+
+        let go_test = CodeNode::new(
+            "TestParse",
+            "TestParse",
+            NodeKind::Function,
+            "parse_test.go",
+        );
+        assert!(HeuristicsMatcher::is_test(&go_test));
+
+        let plain = CodeNode::new("login", "login", NodeKind::Function, "src/auth.rs");
+        assert!(!HeuristicsMatcher::is_test(&plain));
+    }
+
+    #[test]
+    fn test_impact_limitations_flags_event_handlers() {
+        use crate::edge::{Edge, EdgeKind};
+
+        let mut graph = ArborGraph::new();
+        let dispatch = graph.add_node(CodeNode::new(
+            "dispatch",
+            "dispatch",
+            NodeKind::Function,
+            "events.ts",
+        ));
+        for name in ["onClick", "onHover", "handleSubmit", "resizeHandler"] {
+            let handler =
+                graph.add_node(CodeNode::new(name, name, NodeKind::Function, "events.ts"));
+            graph.add_edge(dispatch, handler, Edge::new(EdgeKind::Calls));
+        }
+
+        let analysis = graph.analyze_impact(dispatch, 3);
+        let warnings = graph.impact_limitations(&analysis);
+
+        assert!(warnings
+            .iter()
+            .any(|w| w.message == "Found 4 event handlers"));
+    }
 }
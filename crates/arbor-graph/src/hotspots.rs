@@ -0,0 +1,177 @@
+//! Hotspots: code that is both central and frequently changed.
+//!
+//! Centrality says how much depends on a node; churn says how often it is
+//! touched. Either alone is a weak signal (a stable core library is fine,
+//! so is a busy leaf script), but the product points at the code where a
+//! change is both likely and far-reaching, which is where tests and
+//! refactors pay off most.
+//!
+//! Churn comes from version control as changed line ranges per file. This
+//! module only matches those ranges against node spans; collecting them
+//! is left to the caller (see `arbor_watcher::git_churn`).
+
+use crate::graph::ArborGraph;
+use crate::layering::relative_path;
+use crate::query::NodeInfo;
+use arbor_core::NodeKind;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Lines one commit changed in a file, in the file's current numbering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChurnHunk {
+    /// Index of the commit within the history that was read, so
+    /// distinct commits can be counted.
+    pub commit: u32,
+    /// First line of the hunk (1-indexed).
+    pub line_start: u32,
+    /// Last line of the hunk (inclusive).
+    pub line_end: u32,
+    /// Lines added plus lines removed.
+    pub lines_changed: u32,
+}
+
+/// Changed hunks keyed by file path relative to the indexed root, with `/`
+/// separators.
+pub type FileChurn = HashMap<String, Vec<ChurnHunk>>;
+
+/// One ranked node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hotspot {
+    pub node: NodeInfo,
+    /// Lines changed inside the node's span.
+    pub lines_changed: u32,
+    /// Number of commits that touched the node.
+    pub commits: u32,
+    /// Combined risk in `0.0..=1.0`: centrality times churn, each relative
+    /// to the highest in the graph. Centrality alone when no churn is
+    /// given.
+    pub score: f64,
+}
+
+impl ArborGraph {
+    /// Ranks functions, methods and constructors by risk, highest first.
+    ///
+    /// With `churn`, only nodes that some hunk touches are ranked, and a
+    /// node needs both dependents and changes to score well. Without it
+    /// (outside version control, say) every node is ranked by centrality.
+    /// File paths are made relative to `root` before looking them up.
+    pub fn hotspots(&self, churn: Option<&FileChurn>, root: &Path) -> Vec<Hotspot> {
+        let mut hotspots: Vec<Hotspot> = self
+            .node_indexes()
+            .filter_map(|id| {
+                let node = self.get(id)?;
+                if !matches!(
+                    node.kind,
+                    NodeKind::Function | NodeKind::Method | NodeKind::Constructor
+                ) {
+                    return None;
+                }
+
+                let (mut lines_changed, mut commits) = (0, 0);
+                if let Some(churn) = churn {
+                    let hunks = churn.get(&relative_path(&node.file, root))?;
+                    let touching: Vec<&ChurnHunk> = hunks
+                        .iter()
+                        .filter(|h| h.line_start <= node.line_end && h.line_end >= node.line_start)
+                        .collect();
+                    if touching.is_empty() {
+                        return None;
+                    }
+                    lines_changed = touching.iter().map(|h| h.lines_changed).sum();
+                    commits = touching
+                        .iter()
+                        .map(|h| h.commit)
+                        .collect::<HashSet<_>>()
+                        .len() as u32;
+                }
+
+                let mut info = NodeInfo::from(node);
+                info.centrality = self.centrality_or_default(id);
+                Some(Hotspot {
+                    node: info,
+                    lines_changed,
+                    commits,
+                    score: 0.0,
+                })
+            })
+            .collect();
+
+        let max_centrality = hotspots
+            .iter()
+            .map(|h| h.node.centrality)
+            .fold(0.0, f64::max);
+        let max_churn = hotspots.iter().map(|h| h.lines_changed).max().unwrap_or(0);
+        for hotspot in &mut hotspots {
+            let centrality = ratio(hotspot.node.centrality, max_centrality);
+            hotspot.score = match churn {
+                Some(_) => centrality * ratio(hotspot.lines_changed as f64, max_churn as f64),
+                None => centrality,
+            };
+        }
+
+        hotspots.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.lines_changed.cmp(&a.lines_changed))
+                .then_with(|| a.node.qualified_name.cmp(&b.node.qualified_name))
+        });
+        hotspots
+    }
+}
+
+fn ratio(value: f64, max: f64) -> f64 {
+    if max > 0.0 {
+        value / max
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::{Edge, EdgeKind};
+    use crate::ranking::compute_centrality;
+    use arbor_core::CodeNode;
+
+    #[test]
+    fn test_hotspots_need_centrality_and_churn() {
+        let mut graph = ArborGraph::new();
+        let node = |name: &str, line: u32| {
+            CodeNode::new(name, name, NodeKind::Function, "./src/lib.rs").with_lines(line, line + 4)
+        };
+        let core = graph.add_node(node("core", 1));
+        let busy_leaf = graph.add_node(node("busy_leaf", 10));
+        let quiet = graph.add_node(node("quiet", 20));
+        for caller in [busy_leaf, quiet] {
+            graph.add_edge(caller, core, Edge::new(EdgeKind::Calls));
+        }
+        let scores = compute_centrality(&graph, 20, 0.85);
+        graph.set_centrality(scores.into_map());
+
+        let hunk = |commit, line_start, lines_changed| ChurnHunk {
+            commit,
+            line_start,
+            line_end: line_start,
+            lines_changed,
+        };
+        let churn: FileChurn = HashMap::from([(
+            "src/lib.rs".to_string(),
+            vec![hunk(0, 2, 3), hunk(1, 3, 2), hunk(0, 11, 8)],
+        )]);
+
+        let ranked = graph.hotspots(Some(&churn), Path::new("."));
+        let names: Vec<&str> = ranked.iter().map(|h| h.node.name.as_str()).collect();
+        // `quiet` never changed, so it isn't a hotspot
+        assert_eq!(names, ["core", "busy_leaf"]);
+        assert_eq!((ranked[0].lines_changed, ranked[0].commits), (5, 2));
+        assert!((ranked[0].score - 5.0 / 8.0).abs() < 1e-9);
+
+        // Without churn, centrality alone decides and every function counts
+        let ranked = graph.hotspots(None, Path::new("."));
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].node.name, "core");
+    }
+}
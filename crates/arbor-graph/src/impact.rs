@@ -4,9 +4,11 @@
 //! affected by a change to a target node. It answers the question:
 //! "What breaks if I change this?"
 
+use crate::confidence::{ConfidenceConfig, ConfidenceExplanation, NodeRole};
 use crate::edge::EdgeKind;
 use crate::graph::{ArborGraph, NodeId};
 use crate::query::NodeInfo;
+use arbor_core::Visibility;
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
 use serde::{Deserialize, Serialize};
@@ -55,6 +57,22 @@ impl std::fmt::Display for ImpactSeverity {
     }
 }
 
+impl std::str::FromStr for ImpactSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "direct" => Ok(ImpactSeverity::Direct),
+            "transitive" => Ok(ImpactSeverity::Transitive),
+            "distant" => Ok(ImpactSeverity::Distant),
+            other => Err(format!(
+                "unknown severity '{}' (expected direct, transitive or distant)",
+                other
+            )),
+        }
+    }
+}
+
 /// Direction of impact from the target node.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ImpactDirection {
@@ -94,7 +112,7 @@ pub struct AffectedNode {
 }
 
 /// Complete impact analysis result.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImpactAnalysis {
     /// The target node being analyzed.
     pub target: NodeInfo,
@@ -108,6 +126,10 @@ pub struct ImpactAnalysis {
     pub max_depth: usize,
     /// Time taken in milliseconds.
     pub query_time_ms: u64,
+    /// The deadline passed before the traversal finished, so `upstream`
+    /// and `downstream` hold only what was found until then.
+    #[serde(default)]
+    pub timed_out: bool,
 }
 
 impl ImpactAnalysis {
@@ -127,6 +149,26 @@ impl ImpactAnalysis {
         all
     }
 
+    /// Drops affected nodes less severe than `min` and recounts the total.
+    ///
+    /// `Direct` keeps only immediate breaks; `Transitive` also keeps 2-3
+    /// hop nodes; `Distant` keeps everything.
+    pub fn retain_min_severity(&mut self, min: ImpactSeverity) {
+        self.upstream.retain(|n| n.severity <= min);
+        self.downstream.retain(|n| n.severity <= min);
+        self.total_affected = self.upstream.len() + self.downstream.len();
+    }
+
+    /// Drops affected nodes that aren't `pub`, leaving only externally
+    /// observable breakage, and recounts the total.
+    pub fn retain_public(&mut self) {
+        self.upstream
+            .retain(|n| n.node_info.visibility == Visibility::Public);
+        self.downstream
+            .retain(|n| n.node_info.visibility == Visibility::Public);
+        self.total_affected = self.upstream.len() + self.downstream.len();
+    }
+
     /// Returns only direct (1-hop) affected nodes.
     pub fn direct_only(&self) -> Vec<&AffectedNode> {
         self.all_affected()
@@ -158,9 +200,289 @@ impl ImpactAnalysis {
             self.total_affected, direct, transitive, distant
         )
     }
+
+    /// Renders the analysis as a Markdown "Architectural Brief".
+    ///
+    /// A property table for the target is followed by one table each for
+    /// upstream and downstream nodes. This is the one place impact results
+    /// are formatted as Markdown, shared by the MCP tools and
+    /// `arbor refactor --output markdown`.
+    pub fn to_markdown(&self, opts: &MarkdownOptions) -> String {
+        if opts.compact {
+            return self.to_compact_markdown(opts);
+        }
+
+        let mut md = String::new();
+        let target = &self.target;
+        let direct = |nodes: &[AffectedNode]| nodes.iter().filter(|n| n.hop_distance == 1).count();
+
+        md.push_str(&format!("# Architectural Brief: `{}`\n\n", target.name));
+        md.push_str("| Property | Value |\n");
+        md.push_str("|----------|-------|\n");
+        md.push_str(&format!("| **Type** | {} |\n", target.kind));
+        md.push_str(&format!("| **File** | `{}` |\n", target.file));
+        if let Some(doc) = target.doc_summary() {
+            md.push_str(&format!("| **Doc** | {} |\n", doc.replace('|', "\\|")));
+        }
+        md.push_str(&format!(
+            "| **Impact Level** | {:.2} |\n",
+            target.centrality
+        ));
+        md.push_str(&format!(
+            "| **Role** | {} |\n",
+            NodeRole::from_analysis(self)
+        ));
+
+        let confidence = ConfidenceExplanation::from_analysis_with(self, &opts.confidence);
+        if opts.include_confidence {
+            md.push_str(&format!("| **Confidence** | {} |\n", confidence.level));
+        }
+        md.push_str(&format!(
+            "| **Callers / Callees** | {} / {} |\n",
+            direct(&self.upstream),
+            direct(&self.downstream)
+        ));
+        if target.complexity > 0 {
+            md.push_str(&format!("| **Complexity** | {} |\n", target.complexity));
+        }
+        if let Some(sig) = &target.signature {
+            md.push_str(&format!("| **Signature** | `{}` |\n", sig));
+        }
+        md.push_str(&format!(
+            "| **Blast Radius** | {} nodes |\n",
+            self.total_affected
+        ));
+
+        if opts.include_confidence && !confidence.reasons.is_empty() {
+            md.push_str("\n**Confidence reasons:**\n\n");
+            for reason in &confidence.reasons {
+                md.push_str(&format!("- {}\n", reason));
+            }
+        }
+
+        md.push_str("\n## Upstream (Dependents)\n\n");
+        if self.upstream.is_empty() {
+            md.push_str("*None - Potential entry point or dead code.*\n");
+        } else {
+            push_affected_table(&mut md, &self.upstream, opts);
+        }
+
+        md.push_str("\n## Downstream (Dependencies)\n\n");
+        if self.downstream.is_empty() {
+            md.push_str("*None - This is a leaf node.*\n");
+        } else {
+            push_affected_table(&mut md, &self.downstream, opts);
+        }
+
+        md
+    }
+
+    /// Compact form of `to_markdown` (see `MarkdownOptions::compact`).
+    fn to_compact_markdown(&self, opts: &MarkdownOptions) -> String {
+        let target = &self.target;
+        let mut md = format!(
+            "**Target:** `{}` ({}, `{}`)\n",
+            target.name, target.kind, target.file
+        );
+        if let Some(doc) = target.doc_summary() {
+            md.push_str(&format!("**Doc:** {}\n", doc));
+        }
+        md.push_str(&format!("**Role:** {}\n", NodeRole::from_analysis(self)));
+        if opts.include_confidence {
+            md.push_str(&format!(
+                "**Confidence:** {}\n",
+                ConfidenceExplanation::from_analysis_with(self, &opts.confidence).level
+            ));
+        }
+
+        let breaking: Vec<&AffectedNode> = self
+            .upstream
+            .iter()
+            .filter(|n| n.severity == ImpactSeverity::Direct)
+            .collect();
+        md.push_str("\n**Will break:**\n");
+        if breaking.is_empty() {
+            md.push_str("- none\n");
+        }
+        for node in &breaking {
+            md.push_str(&format!(
+                "- `{}` ({}, `{}`)",
+                node.node_info.name, node.node_info.kind, node.node_info.file
+            ));
+            if opts.include_reasons {
+                md.push_str(&format!(" via `{}`", node.entry_edge));
+            }
+            md.push('\n');
+        }
+
+        md.push_str(&format!(
+            "\n**Transitive:** {} more upstream, {} downstream\n",
+            self.upstream.len() - breaking.len(),
+            self.downstream.len()
+        ));
+        md
+    }
+}
+
+/// What `ImpactAnalysis::to_markdown` includes beyond the core tables.
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownOptions {
+    /// Add a column explaining which edge pulled each node in.
+    pub include_reasons: bool,
+    /// Add the confidence level and the reasons behind it.
+    pub include_confidence: bool,
+    /// Terse summary for LLM prompts: target, role, confidence, the
+    /// direct dependents that will break and a transitive count. Drops the
+    /// property table and the per-direction tables.
+    pub compact: bool,
+    /// Thresholds for the confidence level.
+    pub confidence: ConfidenceConfig,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            include_reasons: true,
+            include_confidence: true,
+            compact: false,
+            confidence: ConfidenceConfig::default(),
+        }
+    }
+}
+
+/// Appends a table of affected nodes, nearest first.
+fn push_affected_table(md: &mut String, nodes: &[AffectedNode], opts: &MarkdownOptions) {
+    if opts.include_reasons {
+        md.push_str("| Symbol | Type | Severity | Hops | Impact | File | Reason |\n");
+        md.push_str("|--------|------|----------|------|--------|------|--------|\n");
+    } else {
+        md.push_str("| Symbol | Type | Severity | Hops | Impact | File |\n");
+        md.push_str("|--------|------|----------|------|--------|------|\n");
+    }
+
+    for node in nodes {
+        let info = &node.node_info;
+        md.push_str(&format!(
+            "| `{}` | {} | {} | {} | {:.2} | `{}` |",
+            info.name, info.kind, node.severity, node.hop_distance, info.centrality, info.file
+        ));
+        if opts.include_reasons {
+            md.push_str(&format!(" {} via `{}` |", node.direction, node.entry_edge));
+        }
+        md.push('\n');
+    }
+}
+
+/// Default number of analyses kept by the impact cache.
+const DEFAULT_IMPACT_CACHE_CAPACITY: usize = 128;
+
+/// LRU cache of impact analyses keyed by `(target, max_depth)`.
+///
+/// The cache remembers the graph generation its entries were computed at.
+/// When the graph has been mutated since, every entry is dropped on the
+/// next access instead of being served stale.
+#[derive(Debug)]
+pub(crate) struct ImpactCache {
+    capacity: usize,
+    generation: u64,
+    entries: HashMap<(NodeId, usize), ImpactAnalysis>,
+    /// Keys from least to most recently used.
+    order: VecDeque<(NodeId, usize)>,
+}
+
+impl Default for ImpactCache {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_IMPACT_CACHE_CAPACITY,
+            generation: 0,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl ImpactCache {
+    /// Drops all entries if they were computed against an older graph.
+    fn sync_generation(&mut self, generation: u64) {
+        if self.generation != generation {
+            self.entries.clear();
+            self.order.clear();
+            self.generation = generation;
+        }
+    }
+
+    fn get(&mut self, key: (NodeId, usize), generation: u64) -> Option<ImpactAnalysis> {
+        self.sync_generation(generation);
+        let hit = self.entries.get(&key)?.clone();
+        self.touch(key);
+        Some(hit)
+    }
+
+    fn insert(&mut self, key: (NodeId, usize), generation: u64, analysis: ImpactAnalysis) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.sync_generation(generation);
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.entries.insert(key, analysis);
+        self.touch(key);
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            match self.order.pop_front() {
+                Some(evicted) => {
+                    self.entries.remove(&evicted);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Marks a key as most recently used.
+    fn touch(&mut self, key: (NodeId, usize)) {
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key);
+    }
 }
 
 impl ArborGraph {
+    /// Like `analyze_impact`, but serves repeated queries from an LRU cache.
+    ///
+    /// Useful when agents retry the same question. Any mutation of the graph
+    /// invalidates the cache. A cached result keeps the `query_time_ms` of the
+    /// run that produced it.
+    pub fn analyze_impact_cached(&self, target: NodeId, max_depth: usize) -> ImpactAnalysis {
+        let key = (target, max_depth);
+        let generation = self.generation();
+
+        if let Some(hit) = self.lock_impact_cache().get(key, generation) {
+            return hit;
+        }
+
+        let analysis = self.analyze_impact(target, max_depth);
+        self.lock_impact_cache()
+            .insert(key, generation, analysis.clone());
+        analysis
+    }
+
+    /// Sets how many analyses `analyze_impact_cached` keeps (0 disables caching).
+    pub fn set_impact_cache_capacity(&mut self, capacity: usize) {
+        self.lock_impact_cache().set_capacity(capacity);
+    }
+
+    fn lock_impact_cache(&self) -> std::sync::MutexGuard<'_, ImpactCache> {
+        self.impact_cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Analyzes the impact of changing a node.
     ///
     /// Performs bidirectional BFS from the target:
@@ -174,10 +496,25 @@ impl ArborGraph {
     /// # Returns
     /// Complete impact analysis with affected nodes sorted by severity.
     pub fn analyze_impact(&self, target: NodeId, max_depth: usize) -> ImpactAnalysis {
+        self.analyze_impact_until(target, max_depth, None)
+    }
+
+    /// Like `analyze_impact`, but stops traversing once `deadline` passes
+    /// and returns what was found so far with `timed_out` set.
+    pub fn analyze_impact_until(
+        &self,
+        target: NodeId,
+        max_depth: usize,
+        deadline: Option<Instant>,
+    ) -> ImpactAnalysis {
         let start = Instant::now();
 
         let target_node = match self.get(target) {
-            Some(node) => NodeInfo::from(node),
+            Some(node) => {
+                let mut info = NodeInfo::from(node);
+                info.centrality = self.centrality_or_default(target);
+                info
+            }
             None => {
                 return ImpactAnalysis {
                     target: NodeInfo {
@@ -190,12 +527,16 @@ impl ArborGraph {
                         line_end: 0,
                         signature: None,
                         centrality: 0.0,
+                        complexity: 0,
+                        visibility: Default::default(),
+                        docstring: None,
                     },
                     upstream: Vec::new(),
                     downstream: Vec::new(),
                     total_affected: 0,
                     max_depth,
                     query_time_ms: 0,
+                    timed_out: false,
                 };
             }
         };
@@ -206,8 +547,10 @@ impl ArborGraph {
             max_depth
         };
 
-        let upstream = self.bfs_impact(target, Direction::Incoming, effective_depth);
-        let downstream = self.bfs_impact(target, Direction::Outgoing, effective_depth);
+        let (upstream, upstream_timed_out) =
+            self.bfs_impact(target, Direction::Incoming, effective_depth, deadline);
+        let (downstream, downstream_timed_out) =
+            self.bfs_impact(target, Direction::Outgoing, effective_depth, deadline);
 
         let total = upstream.len() + downstream.len();
         let elapsed = start.elapsed().as_millis() as u64;
@@ -219,17 +562,22 @@ impl ArborGraph {
             total_affected: total,
             max_depth,
             query_time_ms: elapsed,
+            timed_out: upstream_timed_out || downstream_timed_out,
         }
     }
 
     /// BFS traversal in one direction from target.
+    ///
+    /// Also returns whether `deadline` cut the traversal short.
     fn bfs_impact(
         &self,
         target: NodeId,
         direction: Direction,
         max_depth: usize,
-    ) -> Vec<AffectedNode> {
+        deadline: Option<Instant>,
+    ) -> (Vec<AffectedNode>, bool) {
         let mut result = Vec::new();
+        let mut timed_out = false;
         let mut visited: HashSet<NodeId> = HashSet::new();
         let mut queue: VecDeque<(NodeId, usize, EdgeKind)> = VecDeque::new();
 
@@ -238,8 +586,12 @@ impl ArborGraph {
 
         visited.insert(target);
 
-        // Seed queue with immediate neighbors
+        // Seed queue with immediate neighbors. A container doesn't depend
+        // on its members, so Contains edges are not followed.
         for edge_ref in self.graph.edges_directed(target, direction) {
+            if edge_ref.weight().kind == EdgeKind::Contains {
+                continue;
+            }
             let neighbor = match direction {
                 Direction::Incoming => edge_ref.source(),
                 Direction::Outgoing => edge_ref.target(),
@@ -253,6 +605,10 @@ impl ArborGraph {
         }
 
         while let Some((current, depth, entry_edge)) = queue.pop_front() {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                timed_out = true;
+                break;
+            }
             if depth > max_depth || visited.contains(&current) {
                 continue;
             }
@@ -261,7 +617,7 @@ impl ArborGraph {
 
             if let Some(node) = self.get(current) {
                 let mut node_info = NodeInfo::from(node);
-                node_info.centrality = self.centrality(current);
+                node_info.centrality = self.centrality_or_default(current);
 
                 let impact_direction = match direction {
                     Direction::Incoming => ImpactDirection::Upstream,
@@ -281,6 +637,9 @@ impl ArborGraph {
             // Continue BFS if not at max depth
             if depth < max_depth {
                 for edge_ref in self.graph.edges_directed(current, direction) {
+                    if edge_ref.weight().kind == EdgeKind::Contains {
+                        continue;
+                    }
                     let neighbor = match direction {
                         Direction::Incoming => edge_ref.source(),
                         Direction::Outgoing => edge_ref.target(),
@@ -307,7 +666,7 @@ impl ArborGraph {
                 .then_with(|| a.node_info.id.cmp(&b.node_info.id))
         });
 
-        result
+        (result, timed_out)
     }
 }
 
@@ -375,6 +734,32 @@ mod tests {
         assert_eq!(result.downstream[0].hop_distance, 1);
     }
 
+    #[test]
+    fn test_contains_edges_are_not_impact() {
+        // Cart contains add and total; checkout calls add
+        let mut graph = ArborGraph::new();
+        let cart = graph.add_node(make_node("cart"));
+        let add = graph.add_node(make_node("add"));
+        let total = graph.add_node(make_node("total"));
+        let checkout = graph.add_node(make_node("checkout"));
+
+        graph.add_edge(cart, add, Edge::new(EdgeKind::Contains));
+        graph.add_edge(cart, total, Edge::new(EdgeKind::Contains));
+        graph.add_edge(checkout, add, Edge::new(EdgeKind::Calls));
+
+        let result = graph.analyze_impact(add, 5);
+        let upstream: Vec<_> = result
+            .upstream
+            .iter()
+            .map(|n| n.node_info.name.as_str())
+            .collect();
+        assert_eq!(upstream, vec!["checkout"]);
+
+        let result = graph.analyze_impact(cart, 5);
+        assert!(result.downstream.is_empty());
+        assert_eq!(result.total_affected, 0);
+    }
+
     #[test]
     fn test_diamond_pattern() {
         //     A
@@ -506,4 +891,164 @@ mod tests {
         assert!(names.contains(&"m_caller"));
         assert!(names.contains(&"z_caller"));
     }
+
+    #[test]
+    fn test_cached_impact_reused_and_invalidated() {
+        // A → B
+        let mut graph = ArborGraph::new();
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+
+        let first = graph.analyze_impact_cached(b, 5);
+        let second = graph.analyze_impact_cached(b, 5);
+        assert_eq!(first.total_affected, 1);
+        assert_eq!(second.total_affected, first.total_affected);
+        assert_eq!(second.query_time_ms, first.query_time_ms);
+        assert_eq!(graph.lock_impact_cache().entries.len(), 1);
+
+        // C → B: the mutation must not be hidden by the cache
+        let c = graph.add_node(make_node("c"));
+        graph.add_edge(c, b, Edge::new(EdgeKind::Calls));
+
+        let after = graph.analyze_impact_cached(b, 5);
+        assert_eq!(after.total_affected, 2);
+    }
+
+    #[test]
+    fn test_to_markdown_sections() {
+        // caller → target → callee
+        let mut graph = ArborGraph::new();
+        let caller = graph.add_node(make_node("caller"));
+        let target = graph.add_node(make_node("target"));
+        let callee = graph.add_node(make_node("callee"));
+        graph.add_edge(caller, target, Edge::new(EdgeKind::Calls));
+        graph.add_edge(target, callee, Edge::new(EdgeKind::Calls));
+
+        let analysis = graph.analyze_impact(target, 5);
+        let md = analysis.to_markdown(&MarkdownOptions::default());
+
+        assert!(md.contains("# Architectural Brief: `target`"));
+        assert!(md.contains("## Upstream (Dependents)"));
+        assert!(md.contains("## Downstream (Dependencies)"));
+        assert!(md.contains("`caller`"));
+        assert!(md.contains("`callee`"));
+        assert!(md.contains("| Reason |"));
+        assert!(md.contains("**Confidence**"));
+
+        let bare = analysis.to_markdown(&MarkdownOptions {
+            include_reasons: false,
+            include_confidence: false,
+            compact: false,
+            confidence: Default::default(),
+        });
+        assert!(!bare.contains("| Reason |"));
+        assert!(!bare.contains("**Confidence**"));
+    }
+
+    #[test]
+    fn test_compact_markdown_omits_decorative_sections() {
+        // outer → caller → target → callee
+        let mut graph = ArborGraph::new();
+        let outer = graph.add_node(make_node("outer"));
+        let caller = graph.add_node(make_node("caller"));
+        let target = graph.add_node(make_node("target"));
+        let callee = graph.add_node(make_node("callee"));
+        graph.add_edge(outer, caller, Edge::new(EdgeKind::Calls));
+        graph.add_edge(caller, target, Edge::new(EdgeKind::Calls));
+        graph.add_edge(target, callee, Edge::new(EdgeKind::Calls));
+
+        let analysis = graph.analyze_impact(target, 5);
+        let md = analysis.to_markdown(&MarkdownOptions {
+            compact: true,
+            ..MarkdownOptions::default()
+        });
+
+        assert!(md.contains("**Target:** `target`"));
+        assert!(md.contains("**Role:**"));
+        assert!(md.contains("**Confidence:**"));
+        assert!(md.contains("- `caller` (function, `test.rs`) via `calls`"));
+        assert!(!md.contains("`outer`"));
+        assert!(md.contains("**Transitive:** 1 more upstream, 1 downstream"));
+
+        assert!(!md.contains("# Architectural Brief"));
+        assert!(!md.contains("| Property | Value |"));
+        assert!(!md.contains("## Upstream"));
+        assert!(md.len() < analysis.to_markdown(&MarkdownOptions::default()).len());
+    }
+
+    #[test]
+    fn test_retain_min_severity_direct() {
+        // a → b → c → target
+        let mut graph = ArborGraph::new();
+        let target = graph.add_node(make_node("target"));
+        let b = graph.add_node(make_node("b"));
+        let c = graph.add_node(make_node("c"));
+        let a = graph.add_node(make_node("a"));
+        graph.add_edge(c, target, Edge::new(EdgeKind::Calls));
+        graph.add_edge(b, c, Edge::new(EdgeKind::Calls));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+
+        let mut analysis = graph.analyze_impact(target, 5);
+        assert_eq!(analysis.total_affected, 3);
+
+        analysis.retain_min_severity("direct".parse().unwrap());
+
+        let names: Vec<&str> = analysis
+            .upstream
+            .iter()
+            .map(|n| n.node_info.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["c"]);
+        assert_eq!(analysis.total_affected, 1);
+        assert_eq!(
+            analysis.summary(),
+            "Blast Radius: 1 nodes (direct: 1, transitive: 0, distant: 0)"
+        );
+    }
+
+    #[test]
+    fn test_retain_public_drops_private_callers() {
+        let mut graph = ArborGraph::new();
+        let target = graph.add_node(make_node("target"));
+        let exported = graph.add_node(make_node("exported").with_visibility(Visibility::Public));
+        let helper = graph.add_node(make_node("helper"));
+        graph.add_edge(exported, target, Edge::new(EdgeKind::Calls));
+        graph.add_edge(helper, target, Edge::new(EdgeKind::Calls));
+
+        let mut analysis = graph.analyze_impact(target, 5);
+        assert_eq!(analysis.total_affected, 2);
+
+        analysis.retain_public();
+
+        let names: Vec<&str> = analysis
+            .upstream
+            .iter()
+            .map(|n| n.node_info.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["exported"]);
+        assert_eq!(analysis.total_affected, 1);
+    }
+
+    #[test]
+    fn test_deadline_returns_partial_result() {
+        // A long call chain: every node calls the next one
+        let mut graph = ArborGraph::new();
+        let ids: Vec<NodeId> = (0..2000)
+            .map(|i| graph.add_node(make_node(&format!("f{}", i))))
+            .collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(pair[0], pair[1], Edge::new(EdgeKind::Calls));
+        }
+
+        let full = graph.analyze_impact_until(ids[0], 0, None);
+        assert!(!full.timed_out);
+        assert_eq!(full.total_affected, 1999);
+
+        // A deadline that has already passed stops the traversal at once
+        let partial = graph.analyze_impact_until(ids[0], 0, Some(Instant::now()));
+        assert!(partial.timed_out);
+        assert!(partial.total_affected < full.total_affected);
+        assert_eq!(partial.target.name, "f0");
+    }
 }
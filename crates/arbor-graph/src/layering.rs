@@ -0,0 +1,452 @@
+//! Architecture rules over file layers.
+//!
+//! Teams layer code by directory ("ui must not call db directly"). A rules
+//! file states which layers may not depend on which, as file globs:
+//!
+//! ```text
+//! # ui goes through services
+//! forbid: src/ui/** -> src/db/**
+//! allow: src/ui/admin/** -> src/db/**
+//! # nor through anything else
+//! forbid: src/ui/** ->> src/db/**
+//! ```
+//!
+//! An edge violates the rules when its source and target files match a
+//! `forbid` rule and no `allow` rule. A `->>` rule also forbids reaching
+//! the target layer through other nodes; each node in its source layer is
+//! reported once per target-layer node it reaches, with the shortest path. `*` and `?` match within one path
+//! segment and `**` matches any number of segments. Paths are matched
+//! relative to the indexed root, with `/` separators.
+
+use crate::edge::EdgeKind;
+use crate::graph::{ArborGraph, NodeId};
+use crate::query::NodeInfo;
+use petgraph::graph::EdgeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+/// Whether a rule permits or prohibits the dependencies it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    Forbid,
+}
+
+/// One `action: from -> to` line of a rules file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerRule {
+    pub action: RuleAction,
+    /// Glob for files the dependency starts in.
+    pub from: String,
+    /// Glob for files the dependency points into.
+    pub to: String,
+    /// Written `->>`: paths through other nodes count too.
+    #[serde(default)]
+    pub transitive: bool,
+}
+
+impl LayerRule {
+    /// Returns true if an edge from `from_file` to `to_file` matches.
+    pub fn matches(&self, from_file: &str, to_file: &str) -> bool {
+        glob_matches(&self.from, from_file) && glob_matches(&self.to, to_file)
+    }
+}
+
+impl std::fmt::Display for LayerRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self.action {
+            RuleAction::Allow => "allow",
+            RuleAction::Forbid => "forbid",
+        };
+        let arrow = if self.transitive { "->>" } else { "->" };
+        write!(f, "{}: {} {} {}", action, self.from, arrow, self.to)
+    }
+}
+
+impl std::str::FromStr for LayerRule {
+    type Err = String;
+
+    /// Parses `forbid: a/** -> b/**` or `allow: a/** -> b/**`, with `->>`
+    /// for a transitive rule.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (action, globs) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected 'forbid: FROM -> TO', got '{}'", s.trim()))?;
+        let action = match action.trim().to_lowercase().as_str() {
+            "forbid" | "deny" => RuleAction::Forbid,
+            "allow" => RuleAction::Allow,
+            other => {
+                return Err(format!(
+                    "unknown rule action '{}' (expected forbid or allow)",
+                    other
+                ))
+            }
+        };
+        let transitive = globs.contains("->>");
+        let (from, to) = globs
+            .split_once(if transitive { "->>" } else { "->" })
+            .ok_or_else(|| format!("expected 'FROM -> TO', got '{}'", globs.trim()))?;
+        let (from, to) = (from.trim(), to.trim());
+        if from.is_empty() || to.is_empty() {
+            return Err(format!("empty glob in '{}'", s.trim()));
+        }
+
+        Ok(Self {
+            action,
+            from: from.to_string(),
+            to: to.to_string(),
+            transitive,
+        })
+    }
+}
+
+/// Parses a rules file: one rule per line, `#` comments and blank lines
+/// ignored. Errors name the offending line.
+pub fn parse_layer_rules(text: &str) -> Result<Vec<LayerRule>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| {
+            line.parse()
+                .map_err(|e: String| format!("line {}: {}", number, e))
+        })
+        .collect()
+}
+
+/// A dependency that breaks a `forbid` rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerViolation {
+    /// The rule that was broken.
+    pub rule: LayerRule,
+    pub from: NodeInfo,
+    pub to: NodeInfo,
+    pub kind: EdgeKind,
+    /// File of the offending call site.
+    pub file: String,
+    /// Line of the call site where the edge records one, otherwise the
+    /// first line of the dependent symbol.
+    pub line: u32,
+    /// Nodes between `from` and `to` when a `->>` rule was broken through
+    /// them. `kind`, `file` and `line` then describe the first hop.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub via: Vec<NodeInfo>,
+}
+
+impl ArborGraph {
+    /// Returns every edge that breaks `rules`, ordered by file and line.
+    ///
+    /// File paths are made relative to `root` before matching. `Contains`
+    /// edges are structural, not dependencies, and are never checked.
+    pub fn layer_violations(&self, rules: &[LayerRule], root: &Path) -> Vec<LayerViolation> {
+        let mut violations = Vec::new();
+
+        for edge_ref in self.graph.edge_references() {
+            let edge = edge_ref.weight();
+            if edge.kind == EdgeKind::Contains {
+                continue;
+            }
+            let (from, to) = (
+                &self.graph[edge_ref.source()],
+                &self.graph[edge_ref.target()],
+            );
+            let (from_file, to_file) = (
+                relative_path(&from.file, root),
+                relative_path(&to.file, root),
+            );
+
+            let matching = |action: RuleAction| {
+                rules
+                    .iter()
+                    .find(|r| r.action == action && r.matches(&from_file, &to_file))
+            };
+            let Some(rule) = matching(RuleAction::Forbid) else {
+                continue;
+            };
+            if matching(RuleAction::Allow).is_some() {
+                continue;
+            }
+
+            violations.push(LayerViolation {
+                rule: rule.clone(),
+                from: NodeInfo::from(from),
+                to: NodeInfo::from(to),
+                kind: edge.kind,
+                file: edge.file.clone().unwrap_or_else(|| from.file.clone()),
+                line: edge.line.unwrap_or(from.line_start),
+                via: Vec::new(),
+            });
+        }
+
+        for rule in rules
+            .iter()
+            .filter(|r| r.action == RuleAction::Forbid && r.transitive)
+        {
+            let found = self.transitive_violations(rule, rules, root);
+            violations.extend(found);
+        }
+
+        violations.sort_by(|a, b| (&a.file, a.line, &a.to.id).cmp(&(&b.file, b.line, &b.to.id)));
+        violations
+    }
+
+    /// Breaks of a `->>` rule through other nodes.
+    ///
+    /// One BFS per node in a `from` file, over dependency edges, finds the
+    /// `to`-file nodes it reaches through at least one other node. Each one
+    /// no `allow` rule covers is reported with the shortest path. The
+    /// search doesn't continue past `to`-file nodes, so only the first on
+    /// each path counts; direct edges are left to `layer_violations`.
+    fn transitive_violations(
+        &self,
+        rule: &LayerRule,
+        rules: &[LayerRule],
+        root: &Path,
+    ) -> Vec<LayerViolation> {
+        let files: Vec<String> = self
+            .graph
+            .node_indices()
+            .map(|id| relative_path(&self.graph[id].file, root))
+            .collect();
+        let file_of = |id: NodeId| files[id.index()].as_str();
+        let is_target = |id: NodeId| glob_matches(&rule.to, file_of(id));
+        let allowed = |from: NodeId, to: NodeId| {
+            rules
+                .iter()
+                .any(|r| r.action == RuleAction::Allow && r.matches(file_of(from), file_of(to)))
+        };
+        if !self.graph.node_indices().any(is_target) {
+            return Vec::new();
+        }
+
+        let mut violations = Vec::new();
+        for source in self.graph.node_indices() {
+            if !glob_matches(&rule.from, file_of(source)) {
+                continue;
+            }
+
+            // Each reached node maps to the edge it was first reached by
+            let mut parents: HashMap<NodeId, EdgeIndex> = HashMap::new();
+            let mut queue = VecDeque::from([source]);
+            while let Some(current) = queue.pop_front() {
+                for edge_ref in self.graph.edges_directed(current, Direction::Outgoing) {
+                    let next = edge_ref.target();
+                    if edge_ref.weight().kind == EdgeKind::Contains
+                        || next == source
+                        || parents.contains_key(&next)
+                    {
+                        continue;
+                    }
+                    parents.insert(next, edge_ref.id());
+                    if !is_target(next) {
+                        queue.push_back(next);
+                    } else if current != source && !allowed(source, next) {
+                        violations.push(self.path_violation(rule, source, next, &parents));
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    /// Builds the violation for `target`, following `parents` back to
+    /// `source`. The first hop's edge gives the kind and location.
+    fn path_violation(
+        &self,
+        rule: &LayerRule,
+        source: NodeId,
+        target: NodeId,
+        parents: &HashMap<NodeId, EdgeIndex>,
+    ) -> LayerViolation {
+        let mut hops = Vec::new();
+        let mut current = target;
+        while current != source {
+            let edge = parents[&current];
+            hops.push(edge);
+            current = self
+                .graph
+                .edge_endpoints(edge)
+                .map(|(from, _)| from)
+                .unwrap();
+        }
+        hops.reverse();
+
+        let from = &self.graph[source];
+        let first = &self.graph[hops[0]];
+        LayerViolation {
+            rule: rule.clone(),
+            from: NodeInfo::from(from),
+            to: NodeInfo::from(&self.graph[target]),
+            kind: first.kind,
+            file: first.file.clone().unwrap_or_else(|| from.file.clone()),
+            line: first.line.unwrap_or(from.line_start),
+            via: hops[1..]
+                .iter()
+                .filter_map(|&edge| self.graph.edge_endpoints(edge))
+                .map(|(node, _)| NodeInfo::from(&self.graph[node]))
+                .collect(),
+        }
+    }
+}
+
+/// Strips `root` and any leading `./` from `file`, using `/` separators.
+pub(crate) fn relative_path(file: &str, root: &Path) -> String {
+    let path = Path::new(file);
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    relative.trim_start_matches("./").to_string()
+}
+
+/// Matches a `/`-separated path against a glob where `**` spans segments.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_start_matches("./").split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                match_segment(segment.as_bytes(), name.as_bytes())
+                    && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Matches one path segment against `*` and `?` wildcards.
+fn match_segment(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_segment(rest, &name[skip..])),
+        Some((b'?', rest)) => !name.is_empty() && match_segment(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_segment(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use arbor_core::{CodeNode, NodeKind};
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(glob_matches("ui/**", "ui/page.ts"));
+        assert!(glob_matches("ui/**", "ui/admin/users.ts"));
+        assert!(!glob_matches("ui/**", "src/ui/page.ts"));
+        assert!(glob_matches("**/db/*.ts", "src/db/query.ts"));
+        assert!(!glob_matches("**/db/*.ts", "src/db/sql/raw.ts"));
+        assert!(glob_matches("src/*/mod.rs", "src/net/mod.rs"));
+        assert!(glob_matches("src/?b/**", "src/db/x.rs"));
+    }
+
+    #[test]
+    fn test_layering_violation_reported() {
+        let rules = parse_layer_rules(
+            "# ui goes through services\n\
+             forbid: ui/** -> db/**\n\
+             allow: ui/admin/** -> db/**   # admin tools may\n",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].to_string(), "forbid: ui/** -> db/**");
+
+        let mut graph = ArborGraph::new();
+        let node = |name: &str, file: &str, line: u32| {
+            CodeNode::new(name, name, NodeKind::Function, file).with_lines(line, line + 5)
+        };
+        let page = graph.add_node(node("render_page", "./app/ui/page.ts", 10));
+        let admin = graph.add_node(node("purge", "./app/ui/admin/tools.ts", 3));
+        let service = graph.add_node(node("load_user", "./app/services/user.ts", 1));
+        let query = graph.add_node(node("db_query", "./app/db/query.ts", 1));
+        graph.add_edge(page, service, Edge::new(EdgeKind::Calls));
+        graph.add_edge(service, query, Edge::new(EdgeKind::Calls));
+        graph.add_edge(admin, query, Edge::new(EdgeKind::Calls));
+        // The shortcut: ui calls db directly
+        graph.add_edge(
+            page,
+            query,
+            Edge::with_location(EdgeKind::Calls, "./app/ui/page.ts", 14),
+        );
+
+        let violations = graph.layer_violations(&rules, Path::new("./app"));
+        assert_eq!(violations.len(), 1);
+        let violation = &violations[0];
+        assert_eq!(violation.from.name, "render_page");
+        assert_eq!(violation.to.name, "db_query");
+        assert_eq!(
+            (violation.file.as_str(), violation.line),
+            ("./app/ui/page.ts", 14)
+        );
+        assert_eq!(violation.rule.action, RuleAction::Forbid);
+
+        assert!(parse_layer_rules("forbid ui -> db")
+            .unwrap_err()
+            .starts_with("line 1:"));
+    }
+
+    #[test]
+    fn test_transitive_rule_follows_paths() {
+        let rules = parse_layer_rules(
+            "forbid: ui/** ->> db/**\n\
+             allow: ui/admin/** -> db/**\n",
+        )
+        .unwrap();
+        assert!(rules[0].transitive && !rules[1].transitive);
+        assert_eq!(rules[0].to_string(), "forbid: ui/** ->> db/**");
+
+        let mut graph = ArborGraph::new();
+        let node = |name: &str, file: &str| CodeNode::new(name, name, NodeKind::Function, file);
+        let page = graph.add_node(node("render_page", "ui/page.ts"));
+        let admin = graph.add_node(node("purge", "ui/admin/tools.ts"));
+        let service = graph.add_node(node("load_user", "services/user.ts"));
+        let query = graph.add_node(node("db_query", "db/query.ts"));
+        let widget = graph.add_node(node("Widget", "ui/widget.ts"));
+        let schema = graph.add_node(node("Schema", "db/schema.ts"));
+        graph.add_edge(
+            page,
+            service,
+            Edge::with_location(EdgeKind::Calls, "ui/page.ts", 7),
+        );
+        graph.add_edge(admin, service, Edge::new(EdgeKind::Calls));
+        graph.add_edge(service, query, Edge::new(EdgeKind::Calls));
+        // Structure, not a dependency: never a path into db
+        graph.add_edge(widget, schema, Edge::new(EdgeKind::Contains));
+
+        let violations = graph.layer_violations(&rules, Path::new(""));
+        assert_eq!(violations.len(), 1);
+        let violation = &violations[0];
+        assert_eq!(violation.from.name, "render_page");
+        assert_eq!(violation.to.name, "db_query");
+        assert_eq!(violation.kind, EdgeKind::Calls);
+        let via: Vec<_> = violation.via.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(via, vec!["load_user"]);
+        assert_eq!((violation.file.as_str(), violation.line), ("ui/page.ts", 7));
+
+        // Every db node reached counts, each with its own path; nothing
+        // past the first db node on a path is
+        let helper = graph.add_node(node("format_row", "services/format.ts"));
+        let pool = graph.add_node(node("Pool", "db/pool.ts"));
+        graph.add_edge(widget, helper, Edge::new(EdgeKind::UsesType));
+        graph.add_edge(helper, schema, Edge::new(EdgeKind::UsesType));
+        graph.add_edge(schema, pool, Edge::new(EdgeKind::UsesType));
+
+        let violations = graph.layer_violations(&rules, Path::new(""));
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.from.name.as_str(), v.to.name.as_str(), v.kind))
+            .collect();
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&("render_page", "db_query", EdgeKind::Calls)));
+        assert!(found.contains(&("Widget", "Schema", EdgeKind::UsesType)));
+    }
+}
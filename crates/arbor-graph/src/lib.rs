@@ -28,32 +28,53 @@
 //! ```
 
 mod builder;
+mod components;
 mod confidence;
+mod contract;
+mod coupling;
+mod describe;
+mod diff;
 mod edge;
 mod graph;
 mod heuristics;
+mod hotspots;
 mod impact;
+mod layering;
+mod matrix;
+mod neighbors;
 mod query;
 mod ranking;
+mod reachability;
+mod relationship;
 mod search_index;
 mod slice;
+mod trace;
+mod update;
 
 pub mod store;
 pub mod symbol_table;
 
-pub use search_index::SearchIndex;
+pub use search_index::{match_spans, SearchIndex, SearchMatch};
 
-pub use builder::GraphBuilder;
-pub use confidence::{ConfidenceExplanation, ConfidenceLevel, NodeRole};
+pub use builder::{GraphBuilder, ResolutionStats};
+pub use confidence::{ConfidenceConfig, ConfidenceExplanation, ConfidenceLevel, NodeRole};
+pub use coupling::{FanEntry, FanReport};
+pub use describe::NodeDescription;
+pub use diff::{GraphDiff, ImpactDelta};
 pub use edge::{Edge, EdgeKind, GraphEdge};
 pub use graph::{ArborGraph, NodeId};
 pub use heuristics::{
     detect_analysis_limitations, AnalysisWarning, HeuristicsMatcher, UncertainEdge,
     UncertainEdgeKind,
 };
-pub use impact::{AffectedNode, ImpactAnalysis, ImpactDirection, ImpactSeverity};
-pub use query::{DependentInfo, ImpactResult, NodeInfo, QueryResult};
+pub use hotspots::{ChurnHunk, FileChurn, Hotspot};
+pub use impact::{AffectedNode, ImpactAnalysis, ImpactDirection, ImpactSeverity, MarkdownOptions};
+pub use layering::{parse_layer_rules, LayerRule, LayerViolation, RuleAction};
+pub use neighbors::{Neighbor, NeighborPage};
+pub use query::{DependentInfo, ImpactResult, NodeInfo, QueryResult, DOC_SUMMARY_CHARS};
 pub use ranking::{compute_centrality, CentralityScores};
+pub use relationship::format_relationship;
 pub use slice::{ContextNode, ContextSlice, TruncationReason};
-pub use store::{GraphStore, StoreError};
+pub use store::{migrate, GraphStore, StoreError};
 pub use symbol_table::SymbolTable;
+pub use trace::PathStep;
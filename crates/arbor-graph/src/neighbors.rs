@@ -0,0 +1,148 @@
+//! Paged neighbor listing for click-to-expand views.
+//!
+//! A visualizer that can't load a whole graph starts from one node and
+//! expands outward on click. Hub nodes can have hundreds of edges, so the
+//! neighbors come back in pages with a cursor for the next one.
+
+use crate::edge::EdgeKind;
+use crate::graph::{ArborGraph, NodeId};
+use crate::impact::ImpactDirection;
+use crate::query::NodeInfo;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+
+/// A node one edge away from the expanded node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighbor {
+    /// The neighbor, with its centrality.
+    pub node: NodeInfo,
+    /// Kind of the connecting edge.
+    pub edge_kind: EdgeKind,
+    /// Upstream if the edge points at the expanded node (e.g. a caller),
+    /// downstream if it points away from it.
+    pub direction: ImpactDirection,
+}
+
+/// One page of a node's neighbors.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeighborPage {
+    pub neighbors: Vec<Neighbor>,
+    /// Number of neighbor edges across all pages.
+    pub total: usize,
+    /// Cursor for the next page, or `None` on the last page.
+    pub next_cursor: Option<usize>,
+}
+
+impl ArborGraph {
+    /// Returns up to `limit` neighbors of `id`, starting at `cursor`.
+    ///
+    /// Every edge touching the node counts once, so a node that both calls
+    /// and is called by `id` appears twice. Neighbors are ordered
+    /// downstream first, then by ID, which keeps pages stable while the
+    /// graph is unchanged. Pass 0 as the first cursor. A `limit` of 0
+    /// returns every neighbor from `cursor` on, so the page is the last.
+    pub fn neighbors_paged(&self, id: NodeId, cursor: usize, limit: usize) -> NeighborPage {
+        let limit = if limit == 0 { usize::MAX } else { limit };
+        let mut edges: Vec<(NodeId, EdgeKind, ImpactDirection)> = self
+            .graph
+            .edges_directed(id, Direction::Outgoing)
+            .map(|e| (e.target(), e.weight().kind, ImpactDirection::Downstream))
+            .chain(
+                self.graph
+                    .edges_directed(id, Direction::Incoming)
+                    .map(|e| (e.source(), e.weight().kind, ImpactDirection::Upstream)),
+            )
+            .collect();
+        edges.sort_by(|a, b| {
+            (a.2 == ImpactDirection::Upstream)
+                .cmp(&(b.2 == ImpactDirection::Upstream))
+                .then_with(|| self.graph[a.0].id.cmp(&self.graph[b.0].id))
+                .then_with(|| a.1.to_string().cmp(&b.1.to_string()))
+        });
+
+        let total = edges.len();
+        let end = cursor.saturating_add(limit).min(total);
+        let neighbors = edges
+            .get(cursor..end)
+            .unwrap_or_default()
+            .iter()
+            .map(|&(index, edge_kind, direction)| {
+                let mut node = NodeInfo::from(&self.graph[index]);
+                node.centrality = self.centrality_or_default(index);
+                Neighbor {
+                    node,
+                    edge_kind,
+                    direction,
+                }
+            })
+            .collect();
+
+        NeighborPage {
+            neighbors,
+            total,
+            next_cursor: (end < total).then_some(end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use arbor_core::{CodeNode, NodeKind};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_pages_through_hub_neighbors() {
+        let mut graph = ArborGraph::new();
+        let hub = graph.add_node(CodeNode::new("log", "log", NodeKind::Function, "log.rs"));
+        for i in 0..250 {
+            let name = format!("caller_{}", i);
+            let caller = graph.add_node(CodeNode::new(&name, &name, NodeKind::Function, "app.rs"));
+            graph.add_edge(caller, hub, Edge::new(EdgeKind::Calls));
+        }
+
+        let mut seen = HashSet::new();
+        let mut pages = Vec::new();
+        let mut cursor = Some(0);
+        while let Some(start) = cursor {
+            let page = graph.neighbors_paged(hub, start, 100);
+            assert_eq!(page.total, 250);
+            pages.push(page.neighbors.len());
+            for neighbor in &page.neighbors {
+                assert_eq!(neighbor.edge_kind, EdgeKind::Calls);
+                assert_eq!(neighbor.direction, ImpactDirection::Upstream);
+                seen.insert(neighbor.node.id.clone());
+            }
+            cursor = page.next_cursor;
+        }
+
+        assert_eq!(pages, vec![100, 100, 50]);
+        assert_eq!(seen.len(), 250);
+
+        // Past the end is empty rather than a panic
+        let past = graph.neighbors_paged(hub, 500, 100);
+        assert!(past.neighbors.is_empty());
+        assert_eq!(past.next_cursor, None);
+    }
+
+    #[test]
+    fn test_zero_limit_returns_the_rest() {
+        let mut graph = ArborGraph::new();
+        let hub = graph.add_node(CodeNode::new("log", "log", NodeKind::Function, "log.rs"));
+        for i in 0..5 {
+            let name = format!("caller_{}", i);
+            let caller = graph.add_node(CodeNode::new(&name, &name, NodeKind::Function, "app.rs"));
+            graph.add_edge(caller, hub, Edge::new(EdgeKind::Calls));
+        }
+
+        let page = graph.neighbors_paged(hub, 0, 0);
+        assert_eq!(page.neighbors.len(), 5);
+        assert_eq!(page.next_cursor, None);
+
+        let page = graph.neighbors_paged(hub, 2, 0);
+        assert_eq!(page.neighbors.len(), 3);
+        assert_eq!(page.next_cursor, None);
+    }
+}
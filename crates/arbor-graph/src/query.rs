@@ -3,7 +3,7 @@
 //! These structs represent the results of various graph queries.
 //! They're designed to be easily serializable for the protocol.
 
-use arbor_core::CodeNode;
+use arbor_core::{CodeNode, Visibility};
 use serde::{Deserialize, Serialize};
 
 /// Result of an impact analysis query.
@@ -47,6 +47,37 @@ pub struct NodeInfo {
     pub line_end: u32,
     pub signature: Option<String>,
     pub centrality: f64,
+    #[serde(default)]
+    pub complexity: u32,
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// Docstring or leading comment, as parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docstring: Option<String>,
+}
+
+/// Longest docstring summary, in characters, that context output includes.
+pub const DOC_SUMMARY_CHARS: usize = 200;
+
+impl NodeInfo {
+    /// Returns the first paragraph of the docstring on one line, cut to
+    /// [`DOC_SUMMARY_CHARS`] characters.
+    ///
+    /// Context output uses this rather than the full docstring so a long
+    /// one can't crowd the graph out of a token budget.
+    pub fn doc_summary(&self) -> Option<String> {
+        let doc = self.docstring.as_deref()?.trim();
+        let paragraph = doc.split("\n\n").next().unwrap_or(doc);
+        let line = paragraph.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            return None;
+        }
+        if line.chars().count() <= DOC_SUMMARY_CHARS {
+            return Some(line);
+        }
+        let cut: String = line.chars().take(DOC_SUMMARY_CHARS - 1).collect();
+        Some(format!("{}…", cut.trim_end()))
+    }
 }
 
 impl From<&CodeNode> for NodeInfo {
@@ -61,6 +92,9 @@ impl From<&CodeNode> for NodeInfo {
             line_end: node.line_end,
             signature: node.signature.clone(),
             centrality: 0.0, // Will be filled in by the graph
+            complexity: node.complexity,
+            visibility: node.visibility,
+            docstring: node.docstring.clone(),
         }
     }
 }
@@ -5,7 +5,7 @@
 //! others rank higher.
 
 use crate::graph::{ArborGraph, NodeId};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Stores centrality scores after computation.
 #[derive(Debug, Default)]
@@ -94,6 +94,54 @@ pub fn compute_centrality(graph: &ArborGraph, iterations: usize, damping: f64) -
     CentralityScores { scores }
 }
 
+impl ArborGraph {
+    /// Returns the nodes in the k-core of the graph.
+    ///
+    /// The k-core is the largest subgraph where every node has at least
+    /// `k` distinct neighbors, ignoring edge direction. It is found by
+    /// repeatedly peeling off nodes with fewer than `k` neighbors. Where
+    /// centrality finds individually important nodes, the k-core finds
+    /// clusters of nodes that are tightly coupled to each other.
+    pub fn k_core(&self, k: usize) -> Vec<NodeId> {
+        let mut neighbors: HashMap<NodeId, HashSet<NodeId>> = self
+            .graph
+            .node_indices()
+            .map(|idx| {
+                let set = self
+                    .graph
+                    .neighbors_undirected(idx)
+                    .filter(|&n| n != idx)
+                    .collect();
+                (idx, set)
+            })
+            .collect();
+
+        let mut to_remove: Vec<NodeId> = neighbors
+            .iter()
+            .filter(|(_, set)| set.len() < k)
+            .map(|(&idx, _)| idx)
+            .collect();
+
+        while let Some(idx) = to_remove.pop() {
+            let Some(removed) = neighbors.remove(&idx) else {
+                continue;
+            };
+            for neighbor in removed {
+                if let Some(set) = neighbors.get_mut(&neighbor) {
+                    // Only queue once: when the degree first drops below k
+                    if set.remove(&idx) && set.len() + 1 == k {
+                        to_remove.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        let mut core: Vec<NodeId> = neighbors.into_keys().collect();
+        core.sort();
+        core
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,4 +191,27 @@ mod tests {
         let popular_score = scores.get(popular_idx);
         assert!(popular_score > 0.5, "Popular node should rank high");
     }
+
+    #[test]
+    fn test_k_core_keeps_dense_triangle() {
+        let mut graph = ArborGraph::new();
+        let mut add =
+            |name: &str| graph.add_node(CodeNode::new(name, name, NodeKind::Function, "test.rs"));
+        let a = add("a");
+        let b = add("b");
+        let c = add("c");
+        let leaf1 = add("leaf1");
+        let leaf2 = add("leaf2");
+
+        // Triangle a-b-c, with leaves hanging off a and c
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+        graph.add_edge(b, c, Edge::new(EdgeKind::Calls));
+        graph.add_edge(c, a, Edge::new(EdgeKind::Calls));
+        graph.add_edge(a, leaf1, Edge::new(EdgeKind::Calls));
+        graph.add_edge(leaf2, c, Edge::new(EdgeKind::Calls));
+
+        assert_eq!(graph.k_core(2), vec![a, b, c]);
+        assert_eq!(graph.k_core(1).len(), 5);
+        assert!(graph.k_core(3).is_empty());
+    }
 }
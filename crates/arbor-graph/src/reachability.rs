@@ -0,0 +1,531 @@
+//! Reachability queries.
+//!
+//! Given the roots that are known to run (mains, test entry points,
+//! enabled feature flags), everything they can reach is live and the
+//! rest is a candidate for dead code. The same forward walk also tells
+//! which dependencies two nodes share, and a two-ended walk answers
+//! whether one node can reach another at all.
+
+use crate::edge::EdgeKind;
+use crate::graph::{ArborGraph, NodeId};
+use crate::heuristics::HeuristicsMatcher;
+use crate::query::NodeInfo;
+use arbor_core::{CodeNode, NodeKind, Visibility};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+impl ArborGraph {
+    /// Returns every node reachable from any of `roots`, roots included.
+    ///
+    /// Only call and inheritance edges (`Calls`, `Extends`, `Implements`)
+    /// are followed, since those are the ones that make code run. Unknown
+    /// roots are ignored.
+    pub fn reachable_from(&self, roots: &[NodeId]) -> HashSet<NodeId> {
+        let mut reachable: HashSet<NodeId> = HashSet::new();
+        let mut queue: VecDeque<NodeId> = VecDeque::new();
+
+        for &root in roots {
+            if self.get(root).is_some() && reachable.insert(root) {
+                queue.push_back(root);
+            }
+        }
+
+        while let Some(current) = queue.pop_front() {
+            for edge_ref in self.graph.edges_directed(current, Direction::Outgoing) {
+                let follows = matches!(
+                    edge_ref.weight().kind,
+                    EdgeKind::Calls | EdgeKind::Extends | EdgeKind::Implements
+                );
+                if follows && reachable.insert(edge_ref.target()) {
+                    queue.push_back(edge_ref.target());
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Returns the tests that transitively call `id`, sorted by file and line.
+    ///
+    /// Walks incoming `Calls` edges from the target and keeps every caller
+    /// that looks like a test (see [`HeuristicsMatcher::is_test`]). The
+    /// target itself is included if it is a test.
+    pub fn impacted_tests(&self, id: NodeId) -> Vec<&CodeNode> {
+        let mut seen: HashSet<NodeId> = HashSet::new();
+        let mut queue: VecDeque<NodeId> = VecDeque::new();
+        if self.get(id).is_some() {
+            seen.insert(id);
+            queue.push_back(id);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            for edge_ref in self.graph.edges_directed(current, Direction::Incoming) {
+                if edge_ref.weight().kind == EdgeKind::Calls && seen.insert(edge_ref.source()) {
+                    queue.push_back(edge_ref.source());
+                }
+            }
+        }
+
+        let mut tests: Vec<&CodeNode> = seen
+            .into_iter()
+            .filter_map(|idx| self.get(idx))
+            .filter(|node| HeuristicsMatcher::is_test(node))
+            .collect();
+        tests.sort_by(|a, b| (&a.file, a.line_start).cmp(&(&b.file, b.line_start)));
+        tests
+    }
+
+    /// Returns the nearest public nodes upstream of `id`.
+    ///
+    /// Walks incoming edges (other than `Contains`) breadth-first and stops
+    /// at each public or exported node, so a public caller hides the ones
+    /// behind it: these are the entry points where breaking a private node
+    /// becomes visible to outside code. `id` itself is never included.
+    /// Ordered by distance, then file and line.
+    pub fn closest_public_ancestors(&self, id: NodeId) -> Vec<NodeInfo> {
+        let mut seen: HashSet<NodeId> = HashSet::new();
+        let mut queue: VecDeque<(NodeId, usize)> = VecDeque::new();
+        let mut found: Vec<(usize, NodeInfo)> = Vec::new();
+        if self.get(id).is_some() {
+            seen.insert(id);
+            queue.push_back((id, 0));
+        }
+
+        while let Some((current, depth)) = queue.pop_front() {
+            for edge_ref in self.graph.edges_directed(current, Direction::Incoming) {
+                let caller = edge_ref.source();
+                if edge_ref.weight().kind == EdgeKind::Contains || !seen.insert(caller) {
+                    continue;
+                }
+                let node = &self.graph[caller];
+                if node.visibility == Visibility::Public || node.is_exported {
+                    let mut info = NodeInfo::from(node);
+                    info.centrality = self.centrality_or_default(caller);
+                    found.push((depth + 1, info));
+                } else {
+                    queue.push_back((caller, depth + 1));
+                }
+            }
+        }
+
+        found.sort_by(|(da, a), (db, b)| {
+            da.cmp(db)
+                .then_with(|| a.file.cmp(&b.file))
+                .then(a.line_start.cmp(&b.line_start))
+        });
+        found.into_iter().map(|(_, info)| info).collect()
+    }
+
+    /// Returns the downstream dependencies shared by `a` and `b`.
+    ///
+    /// Intersects everything each node reaches through outgoing edges
+    /// other than `Contains` within `max_depth` hops (0 means unlimited). Neither `a` nor `b`
+    /// is included. Results are ordered by file, then line.
+    pub fn common_dependencies(&self, a: NodeId, b: NodeId, max_depth: usize) -> Vec<NodeInfo> {
+        let from_a = self.descendants(a, max_depth);
+        let from_b = self.descendants(b, max_depth);
+
+        let mut common: Vec<NodeInfo> = from_a
+            .intersection(&from_b)
+            .filter(|&&id| id != a && id != b)
+            .filter_map(|&id| {
+                let mut info = NodeInfo::from(self.get(id)?);
+                info.centrality = self.centrality_or_default(id);
+                Some(info)
+            })
+            .collect();
+
+        common.sort_by(|x, y| {
+            x.file
+                .cmp(&y.file)
+                .then(x.line_start.cmp(&y.line_start))
+                .then_with(|| x.id.cmp(&y.id))
+        });
+        common
+    }
+
+    /// Returns indexed files that contribute nothing to the graph.
+    ///
+    /// A file is an orphan when every one of its nodes is either an import
+    /// or has no edges at all, not counting `Contains` edges to its own
+    /// members: nothing it defines is used, and it uses nothing. These are
+    /// candidates for dead modules. Sorted by path.
+    pub fn orphan_files(&self) -> Vec<String> {
+        let mut files: BTreeMap<&str, bool> = BTreeMap::new();
+
+        for id in self.graph.node_indices() {
+            let node = &self.graph[id];
+            let contributes = node.kind != NodeKind::Import
+                && [Direction::Outgoing, Direction::Incoming]
+                    .into_iter()
+                    .flat_map(|direction| self.graph.edges_directed(id, direction))
+                    .any(|e| e.weight().kind != EdgeKind::Contains);
+            *files.entry(node.file.as_str()).or_insert(false) |= contributes;
+        }
+
+        files
+            .into_iter()
+            .filter(|(_, contributes)| !contributes)
+            .map(|(file, _)| file.to_string())
+            .collect()
+    }
+
+    /// Returns true if `to` is reachable from `from` over edges of `kinds`
+    /// (all kinds if empty) in at most `max_depth` hops (0 means unlimited).
+    ///
+    /// A yes/no answer for policy checks such as "may `ui` reach `db`?",
+    /// cheaper than [`find_path`](ArborGraph::find_path) on large graphs:
+    /// it searches forward from `from` and backward from `to` at once,
+    /// always expanding the frontier with fewer edges to follow, and stops
+    /// as soon as the two meet or the two searches together have gone
+    /// `max_depth` hops. A node always reaches itself.
+    pub fn path_exists(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        kinds: &[EdgeKind],
+        max_depth: usize,
+    ) -> bool {
+        self.bidirectional_search(from, to, kinds, max_depth).0
+    }
+
+    /// Runs the search behind [`path_exists`](ArborGraph::path_exists),
+    /// also returning how many nodes it visited.
+    fn bidirectional_search(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        kinds: &[EdgeKind],
+        max_depth: usize,
+    ) -> (bool, usize) {
+        if self.get(from).is_none() || self.get(to).is_none() {
+            return (false, 0);
+        }
+        if from == to {
+            return (true, 1);
+        }
+
+        let follows = |kind: EdgeKind| kinds.is_empty() || kinds.contains(&kind);
+        let mut forward_seen: HashSet<NodeId> = HashSet::from([from]);
+        let mut backward_seen: HashSet<NodeId> = HashSet::from([to]);
+        let mut forward = vec![from];
+        let mut backward = vec![to];
+        // Hops covered by both searches; a meeting found while expanding
+        // adds one more, so the loop stops once that would exceed the bound
+        let mut depth = 0;
+
+        while !forward.is_empty() && !backward.is_empty() && (max_depth == 0 || depth < max_depth) {
+            depth += 1;
+            let fan = |frontier: &[NodeId], direction: Direction| -> usize {
+                frontier
+                    .iter()
+                    .map(|&n| self.graph.edges_directed(n, direction).count())
+                    .sum()
+            };
+            let expand_forward =
+                fan(&forward, Direction::Outgoing) <= fan(&backward, Direction::Incoming);
+            let (frontier, seen, other, direction) = if expand_forward {
+                (
+                    &mut forward,
+                    &mut forward_seen,
+                    &backward_seen,
+                    Direction::Outgoing,
+                )
+            } else {
+                (
+                    &mut backward,
+                    &mut backward_seen,
+                    &forward_seen,
+                    Direction::Incoming,
+                )
+            };
+
+            let mut next = Vec::new();
+            for &current in frontier.iter() {
+                for edge_ref in self.graph.edges_directed(current, direction) {
+                    if !follows(edge_ref.weight().kind) {
+                        continue;
+                    }
+                    let neighbor = match direction {
+                        Direction::Outgoing => edge_ref.target(),
+                        Direction::Incoming => edge_ref.source(),
+                    };
+                    if other.contains(&neighbor) {
+                        return (true, forward_seen.len() + backward_seen.len());
+                    }
+                    if seen.insert(neighbor) {
+                        next.push(neighbor);
+                    }
+                }
+            }
+            *frontier = next;
+        }
+
+        (false, forward_seen.len() + backward_seen.len())
+    }
+
+    /// Collects every node reachable from `start` over outgoing edges,
+    /// skipping `Contains`: a class doesn't depend on its own methods.
+    fn descendants(&self, start: NodeId, max_depth: usize) -> HashSet<NodeId> {
+        let max_depth = if max_depth == 0 {
+            usize::MAX
+        } else {
+            max_depth
+        };
+
+        let mut seen: HashSet<NodeId> = HashSet::new();
+        let mut queue: VecDeque<(NodeId, usize)> = VecDeque::new();
+        if self.get(start).is_some() {
+            queue.push_back((start, 0));
+        }
+
+        while let Some((current, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+            for edge_ref in self.graph.edges_directed(current, Direction::Outgoing) {
+                let neighbor = edge_ref.target();
+                if edge_ref.weight().kind == EdgeKind::Contains {
+                    continue;
+                }
+                if neighbor != start && seen.insert(neighbor) {
+                    queue.push_back((neighbor, depth + 1));
+                }
+            }
+        }
+
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+
+    fn make_node(name: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "test.rs")
+    }
+
+    #[test]
+    fn test_reachable_from_multiple_roots() {
+        let mut graph = ArborGraph::new();
+        let main = graph.add_node(make_node("main"));
+        let run = graph.add_node(make_node("run"));
+        let test_main = graph.add_node(make_node("test_main"));
+        let fixture = graph.add_node(make_node("fixture"));
+        graph.add_edge(main, run, Edge::new(EdgeKind::Calls));
+        graph.add_edge(test_main, fixture, Edge::new(EdgeKind::Calls));
+
+        let both = graph.reachable_from(&[main, test_main]);
+        assert_eq!(both.len(), 4);
+
+        // fixture is only reachable from the excluded test root
+        let live = graph.reachable_from(&[main]);
+        assert!(live.contains(&main));
+        assert!(live.contains(&run));
+        assert!(!live.contains(&test_main));
+        assert!(!live.contains(&fixture));
+    }
+
+    #[test]
+    fn test_reachable_ignores_non_call_edges() {
+        let mut graph = ArborGraph::new();
+        let main = graph.add_node(make_node("main"));
+        let config = graph.add_node(make_node("config"));
+        graph.add_edge(main, config, Edge::new(EdgeKind::Imports));
+
+        let live = graph.reachable_from(&[main]);
+        assert!(!live.contains(&config));
+    }
+
+    #[test]
+    fn test_common_dependencies_shared_helper() {
+        let mut graph = ArborGraph::new();
+        let create = graph.add_node(make_node("create_user"));
+        let delete = graph.add_node(make_node("delete_user"));
+        let db = graph.add_node(make_node("db_execute"));
+        let hash = graph.add_node(make_node("hash_password"));
+        let audit = graph.add_node(make_node("audit_log"));
+        graph.add_edge(create, db, Edge::new(EdgeKind::Calls));
+        graph.add_edge(create, hash, Edge::new(EdgeKind::Calls));
+        graph.add_edge(delete, db, Edge::new(EdgeKind::Calls));
+        graph.add_edge(delete, audit, Edge::new(EdgeKind::Calls));
+
+        let common = graph.common_dependencies(create, delete, 5);
+
+        let names: Vec<&str> = common.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["db_execute"]);
+    }
+
+    #[test]
+    fn test_orphan_files() {
+        let mut graph = ArborGraph::new();
+        // imports.rs only imports things
+        graph.add_node(CodeNode::new(
+            "serde",
+            "serde",
+            NodeKind::Import,
+            "imports.rs",
+        ));
+        // main.rs calls a function defined in util.rs
+        let main = graph.add_node(CodeNode::new("main", "main", NodeKind::Function, "main.rs"));
+        let helper = graph.add_node(CodeNode::new(
+            "helper",
+            "helper",
+            NodeKind::Function,
+            "util.rs",
+        ));
+        graph.add_edge(main, helper, Edge::new(EdgeKind::Calls));
+
+        assert_eq!(graph.orphan_files(), vec!["imports.rs".to_string()]);
+    }
+
+    #[test]
+    fn test_contains_edges_are_not_dependencies() {
+        let mut graph = ArborGraph::new();
+        // model.rs only declares a class with a method
+        let class = graph.add_node(CodeNode::new("User", "User", NodeKind::Class, "model.rs"));
+        let method = graph.add_node(CodeNode::new(
+            "name",
+            "User.name",
+            NodeKind::Method,
+            "model.rs",
+        ));
+        graph.add_edge(class, method, Edge::new(EdgeKind::Contains));
+        assert_eq!(graph.orphan_files(), vec!["model.rs".to_string()]);
+
+        // Two functions that each call a method of User share only that
+        let a = graph.add_node(CodeNode::new("a", "a", NodeKind::Function, "a.rs"));
+        let b = graph.add_node(CodeNode::new("b", "b", NodeKind::Function, "b.rs"));
+        let other = graph.add_node(CodeNode::new(
+            "email",
+            "User.email",
+            NodeKind::Method,
+            "model.rs",
+        ));
+        graph.add_edge(class, other, Edge::new(EdgeKind::Contains));
+        graph.add_edge(a, class, Edge::new(EdgeKind::UsesType));
+        graph.add_edge(b, class, Edge::new(EdgeKind::UsesType));
+        let names: Vec<String> = graph
+            .common_dependencies(a, b, 0)
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, ["User"]);
+        assert!(graph.orphan_files().is_empty());
+    }
+
+    #[test]
+    fn test_impacted_tests_follows_callers() {
+        let mut graph = ArborGraph::new();
+        let target = graph.add_node(make_node("parse_header"));
+        let helper = graph.add_node(make_node("read_request"));
+        let covering = graph.add_node(make_node("test_read_request"));
+        let unrelated = graph.add_node(make_node("test_unrelated"));
+        let other = graph.add_node(make_node("format_date"));
+        graph.add_edge(helper, target, Edge::new(EdgeKind::Calls));
+        graph.add_edge(covering, helper, Edge::new(EdgeKind::Calls));
+        graph.add_edge(unrelated, other, Edge::new(EdgeKind::Calls));
+
+        let names: Vec<&str> = graph
+            .impacted_tests(target)
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["test_read_request"]);
+    }
+
+    #[test]
+    fn test_closest_public_ancestor_across_private_hops() {
+        let mut graph = ArborGraph::new();
+        let public = |name: &str| make_node(name).with_visibility(Visibility::Public);
+        let helper = graph.add_node(make_node("normalize_email"));
+        let private_a = graph.add_node(make_node("parse_form"));
+        let private_b = graph.add_node(make_node("read_request"));
+        let api = graph.add_node(public("handle_signup"));
+        let outer = graph.add_node(public("router"));
+        let other = graph.add_node(public("unrelated_api"));
+        let elsewhere = graph.add_node(make_node("elsewhere"));
+        graph.add_edge(private_a, helper, Edge::new(EdgeKind::Calls));
+        graph.add_edge(private_b, private_a, Edge::new(EdgeKind::Calls));
+        graph.add_edge(api, private_b, Edge::new(EdgeKind::Calls));
+        // Behind the nearest public node, so not reported
+        graph.add_edge(outer, api, Edge::new(EdgeKind::Calls));
+        graph.add_edge(other, elsewhere, Edge::new(EdgeKind::Calls));
+
+        let names: Vec<String> = graph
+            .closest_public_ancestors(helper)
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, vec!["handle_signup"]);
+
+        // A public node's callers are found the same way
+        let names: Vec<String> = graph
+            .closest_public_ancestors(api)
+            .into_iter()
+            .map(|n| n.name)
+            .collect();
+        assert_eq!(names, vec!["router"]);
+    }
+
+    #[test]
+    fn test_path_exists_agrees_with_find_path() {
+        let mut graph = ArborGraph::new();
+        let ui = graph.add_node(make_node("render_page"));
+        let service = graph.add_node(make_node("load_user"));
+        let db = graph.add_node(make_node("db_query"));
+        let config = graph.add_node(make_node("read_config"));
+        let island = graph.add_node(make_node("unused"));
+        graph.add_edge(ui, service, Edge::new(EdgeKind::Calls));
+        graph.add_edge(service, db, Edge::new(EdgeKind::Calls));
+        graph.add_edge(service, config, Edge::new(EdgeKind::Imports));
+        graph.add_edge(db, service, Edge::new(EdgeKind::Calls));
+
+        let nodes = [ui, service, db, config, island];
+        for &from in &nodes {
+            for &to in &nodes {
+                assert_eq!(
+                    graph.path_exists(from, to, &[], 0),
+                    graph.find_path(from, to).is_some(),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+
+        // Filtering by kind drops the import hop
+        assert!(graph.path_exists(ui, config, &[], 0));
+        assert!(!graph.path_exists(ui, config, &[EdgeKind::Calls], 0));
+        assert!(graph.path_exists(ui, db, &[EdgeKind::Calls], 0));
+
+        // ui -> service -> db is two hops
+        assert!(!graph.path_exists(ui, db, &[], 1));
+        assert!(graph.path_exists(ui, db, &[], 2));
+        assert!(graph.path_exists(ui, service, &[], 1));
+    }
+
+    #[test]
+    fn test_path_exists_short_circuits() {
+        // `from` fans out to many helpers; `to` has a single caller
+        let mut graph = ArborGraph::new();
+        let from = graph.add_node(make_node("main"));
+        let to = graph.add_node(make_node("target"));
+        for i in 0..1000 {
+            let helper = graph.add_node(make_node(&format!("helper_{}", i)));
+            graph.add_edge(from, helper, Edge::new(EdgeKind::Calls));
+        }
+        let via = graph.add_node(make_node("via"));
+        graph.add_edge(from, via, Edge::new(EdgeKind::Calls));
+        graph.add_edge(via, to, Edge::new(EdgeKind::Calls));
+
+        let (found, visited) = graph.bidirectional_search(from, to, &[], 0);
+        assert!(found);
+        assert!(graph.find_path(from, to).is_some());
+        // Expanding the small end meets `from` without walking the fan-out
+        assert!(visited < 10, "visited {} nodes", visited);
+    }
+}
@@ -0,0 +1,235 @@
+//! Relationship explanations.
+//!
+//! Answers "why does A depend on B?" with the chain of edges that links
+//! them, e.g. "A calls helper C, which uses type B". Among the shortest
+//! chains we prefer the one made of the most trustworthy edge kinds, since
+//! a direct call says more than a loose textual reference.
+
+use crate::edge::EdgeKind;
+use crate::graph::{ArborGraph, NodeId};
+use crate::query::NodeInfo;
+use crate::trace::PathStep;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::HashMap;
+
+/// How much an edge kind is trusted as evidence of a dependency.
+pub(crate) fn edge_confidence(kind: EdgeKind) -> u32 {
+    match kind {
+        EdgeKind::Calls | EdgeKind::Extends | EdgeKind::Implements | EdgeKind::Exports => 3,
+        EdgeKind::UsesType | EdgeKind::Imports | EdgeKind::Contains => 2,
+        EdgeKind::References | EdgeKind::FlowsTo | EdgeKind::DataDependency => 1,
+    }
+}
+
+/// Describes an edge kind as a verb for prose output.
+fn edge_phrase(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Calls => "calls",
+        EdgeKind::Imports => "imports",
+        EdgeKind::Extends => "extends",
+        EdgeKind::Implements => "implements",
+        EdgeKind::UsesType => "uses type",
+        EdgeKind::References => "references",
+        EdgeKind::Contains => "contains",
+        EdgeKind::Exports => "exports",
+        EdgeKind::FlowsTo => "flows to",
+        EdgeKind::DataDependency => "uses data from",
+    }
+}
+
+impl ArborGraph {
+    /// Explains why `from` depends on `to`.
+    ///
+    /// Follows outgoing edges from `from` and returns the shortest path to
+    /// `to`, breaking ties by total edge confidence (calls and inheritance
+    /// beat imports, which beat plain references). Each step carries the
+    /// kind of edge leading to the next one.
+    ///
+    /// `max_depth` limits the number of hops; 0 means unlimited. Returns
+    /// `None` if either node is unknown or `to` isn't reachable.
+    pub fn explain_relationship(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        max_depth: usize,
+    ) -> Option<Vec<PathStep>> {
+        self.get(from)?;
+        self.get(to)?;
+
+        // Best score and predecessor per visited node
+        let mut best: HashMap<NodeId, (u32, Option<(NodeId, EdgeKind)>)> = HashMap::new();
+        best.insert(from, (0, None));
+        if from == to {
+            return Some(self.relationship_path(to, &best));
+        }
+
+        let mut frontier = vec![from];
+        let mut depth = 0;
+
+        while !frontier.is_empty() && (max_depth == 0 || depth < max_depth) {
+            depth += 1;
+
+            let mut next: HashMap<NodeId, (u32, NodeId, EdgeKind)> = HashMap::new();
+            for &node in &frontier {
+                let score = best[&node].0;
+                for edge in self.graph.edges_directed(node, Direction::Outgoing) {
+                    let target = edge.target();
+                    if best.contains_key(&target) {
+                        continue;
+                    }
+
+                    let kind = edge.weight().kind;
+                    let candidate = (score + edge_confidence(kind), node, kind);
+                    let entry = next.entry(target).or_insert(candidate);
+                    if candidate.0 > entry.0 {
+                        *entry = candidate;
+                    }
+                }
+            }
+
+            for (&node, &(score, prev, kind)) in &next {
+                best.insert(node, (score, Some((prev, kind))));
+            }
+            if next.contains_key(&to) {
+                return Some(self.relationship_path(to, &best));
+            }
+
+            // Sorted so equal-score ties resolve the same way every run
+            frontier = next.into_keys().collect();
+            frontier.sort();
+        }
+
+        None
+    }
+
+    /// Walks predecessor links back from `to` and returns the path in order.
+    fn relationship_path(
+        &self,
+        to: NodeId,
+        best: &HashMap<NodeId, (u32, Option<(NodeId, EdgeKind)>)>,
+    ) -> Vec<PathStep> {
+        let mut path = Vec::new();
+        let mut current = Some((to, None));
+
+        while let Some((node_id, edge)) = current {
+            if let Some(node) = self.get(node_id) {
+                let mut node_info = NodeInfo::from(node);
+                node_info.centrality = self.centrality_or_default(node_id);
+                path.push(PathStep {
+                    node_id,
+                    node_info,
+                    edge,
+                });
+            }
+            current = best
+                .get(&node_id)
+                .and_then(|(_, prev)| *prev)
+                .map(|(prev, kind)| (prev, Some(kind)));
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Formats a path from `explain_relationship` as prose for an LLM.
+///
+/// Gives a one-sentence summary followed by one numbered line per node
+/// with its kind and location.
+pub fn format_relationship(steps: &[PathStep]) -> String {
+    let (Some(first), Some(last)) = (steps.first(), steps.last()) else {
+        return String::new();
+    };
+    if steps.len() == 1 {
+        return format!("`{}` is the node itself.", first.node_info.name);
+    }
+
+    let mut chain = format!("`{}`", first.node_info.name);
+    for (i, pair) in steps.windows(2).enumerate() {
+        let phrase = pair[0].edge.map(edge_phrase).unwrap_or("reaches");
+        if i == 0 {
+            chain.push_str(&format!(" {} `{}`", phrase, pair[1].node_info.name));
+        } else {
+            chain.push_str(&format!(", which {} `{}`", phrase, pair[1].node_info.name));
+        }
+    }
+
+    let mut out = format!(
+        "`{}` depends on `{}` because {}.\n",
+        first.node_info.name, last.node_info.name, chain
+    );
+    for (i, step) in steps.iter().enumerate() {
+        let info = &step.node_info;
+        out.push_str(&format!(
+            "\n{}. `{}` ({}, {}:{})",
+            i + 1,
+            info.qualified_name,
+            info.kind,
+            info.file,
+            info.line_start
+        ));
+        if let Some(kind) = step.edge {
+            out.push_str(&format!(" {}", edge_phrase(kind)));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use arbor_core::{CodeNode, NodeKind};
+
+    fn make_node(name: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "test.rs")
+    }
+
+    #[test]
+    fn test_prefers_confident_three_hop_path() {
+        let mut graph = ArborGraph::new();
+        let handler = graph.add_node(make_node("handler"));
+        let helper = graph.add_node(make_node("helper"));
+        let widget = graph.add_node(make_node("Widget"));
+        let base = graph.add_node(make_node("Base"));
+        let loose_a = graph.add_node(make_node("loose_a"));
+        let loose_b = graph.add_node(make_node("loose_b"));
+
+        // handler calls helper, which uses type Widget, which extends Base
+        graph.add_edge(handler, helper, Edge::new(EdgeKind::Calls));
+        graph.add_edge(helper, widget, Edge::new(EdgeKind::UsesType));
+        graph.add_edge(widget, base, Edge::new(EdgeKind::Extends));
+
+        // An equally short chain of weak references
+        graph.add_edge(handler, loose_a, Edge::new(EdgeKind::References));
+        graph.add_edge(loose_a, loose_b, Edge::new(EdgeKind::References));
+        graph.add_edge(loose_b, base, Edge::new(EdgeKind::References));
+
+        let path = graph.explain_relationship(handler, base, 5).unwrap();
+        let names: Vec<&str> = path.iter().map(|s| s.node_info.name.as_str()).collect();
+        let edges: Vec<Option<EdgeKind>> = path.iter().map(|s| s.edge).collect();
+
+        assert_eq!(names, vec!["handler", "helper", "Widget", "Base"]);
+        assert_eq!(
+            edges,
+            vec![
+                Some(EdgeKind::Calls),
+                Some(EdgeKind::UsesType),
+                Some(EdgeKind::Extends),
+                None
+            ]
+        );
+
+        let text = format_relationship(&path);
+        assert!(text.starts_with(
+            "`handler` depends on `Base` because `handler` calls `helper`, \
+             which uses type `Widget`, which extends `Base`."
+        ));
+
+        // Too shallow, or the wrong direction
+        assert!(graph.explain_relationship(handler, base, 2).is_none());
+        assert!(graph.explain_relationship(base, handler, 0).is_none());
+    }
+}
@@ -4,6 +4,7 @@
 //! search where k is the number of matches, instead of O(n) linear scan.
 
 use crate::graph::NodeId;
+use arbor_core::CodeNode;
 use std::collections::{HashMap, HashSet};
 
 /// Minimum n-gram length for indexing.
@@ -12,6 +13,56 @@ const MIN_NGRAM_LEN: usize = 2;
 /// Maximum n-gram length for indexing.
 const MAX_NGRAM_LEN: usize = 4;
 
+/// A search result with where the query matched, for highlighting.
+#[derive(Debug, Clone)]
+pub struct SearchMatch<'a> {
+    pub node: &'a CodeNode,
+    /// Byte ranges `(start, end)` of each match in `node.name`, end
+    /// exclusive, so `&node.name[start..end]` is the matched text.
+    pub name_spans: Vec<(usize, usize)>,
+    /// Byte ranges of each match in `node.signature`, if it has one.
+    pub signature_spans: Vec<(usize, usize)>,
+}
+
+/// Finds the non-overlapping occurrences of `query` in `text`, ignoring
+/// case, as byte ranges into `text` (end exclusive), left to right.
+pub fn match_spans(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let needle: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let mut spans = Vec::new();
+    let mut start = 0;
+    while let Some(c) = text[start..].chars().next() {
+        match match_len(&text[start..], &needle) {
+            Some(len) => {
+                spans.push((start, start + len));
+                start += len;
+            }
+            None => start += c.len_utf8(),
+        }
+    }
+    spans
+}
+
+/// Length in bytes of the prefix of `text` that lowercases to `needle`.
+fn match_len(text: &str, needle: &[char]) -> Option<usize> {
+    let mut rest = needle;
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            match rest.split_first() {
+                Some((&expected, tail)) if expected == lower => rest = tail,
+                _ => return None,
+            }
+        }
+        if rest.is_empty() {
+            return Some(offset + c.len_utf8());
+        }
+    }
+    None
+}
+
 /// An inverted index for fast substring search.
 ///
 /// Uses n-gram indexing to support substring matching. When a name is added,
@@ -67,6 +118,14 @@ impl SearchIndex {
         }
     }
 
+    /// Returns nodes whose name equals `name`, ignoring case.
+    pub fn exact(&self, name: &str) -> &[NodeId] {
+        self.exact_index
+            .get(&name.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
     /// Searches for nodes whose names contain the query substring.
     ///
     /// Returns matching NodeIds sorted for deterministic output.
@@ -245,4 +304,16 @@ mod tests {
         assert!(results.contains(&node_id(1)));
         assert!(!results.contains(&node_id(2)));
     }
+
+    #[test]
+    fn test_match_spans() {
+        assert_eq!(match_spans("get_user_profile", "user"), vec![(4, 8)]);
+        assert_eq!(match_spans("UserService", "user"), vec![(0, 4)]);
+        assert_eq!(match_spans("abab", "ab"), vec![(0, 2), (2, 4)]);
+        assert_eq!(match_spans("aaa", "aa"), vec![(0, 2)]);
+        assert_eq!(match_spans("hello", "world"), vec![]);
+        assert_eq!(match_spans("hello", ""), vec![]);
+        // Byte offsets, not char offsets
+        assert_eq!(match_spans("größe_Größe", "grö"), vec![(0, 4), (8, 12)]);
+    }
 }
@@ -4,6 +4,7 @@
 //! Given a target node, it collects the minimal set of related nodes that fit
 //! within a token budget.
 
+use crate::edge::EdgeKind;
 use crate::graph::{ArborGraph, NodeId};
 use crate::query::NodeInfo;
 use petgraph::visit::EdgeRef;
@@ -21,6 +22,8 @@ pub enum TruncationReason {
     TokenBudget,
     /// Stopped because max depth was reached.
     MaxDepth,
+    /// Stopped because the deadline passed.
+    Timeout,
 }
 
 impl std::fmt::Display for TruncationReason {
@@ -29,6 +32,7 @@ impl std::fmt::Display for TruncationReason {
             TruncationReason::Complete => write!(f, "complete"),
             TruncationReason::TokenBudget => write!(f, "token_budget"),
             TruncationReason::MaxDepth => write!(f, "max_depth"),
+            TruncationReason::Timeout => write!(f, "timeout"),
         }
     }
 }
@@ -49,8 +53,11 @@ pub struct ContextNode {
 /// Result of a context slicing operation.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ContextSlice {
-    /// The target node being queried.
+    /// The target node being queried (the first root of a multi-root slice).
     pub target: NodeInfo,
+    /// Every root the slice was seeded from, in the order given.
+    #[serde(default)]
+    pub targets: Vec<NodeInfo>,
     /// Nodes included in the context, ordered by relevance.
     pub nodes: Vec<ContextNode>,
     /// Total estimated tokens in this slice.
@@ -96,8 +103,9 @@ const LARGE_FILE_THRESHOLD: usize = 800 * 1024;
 fn estimate_tokens(node: &NodeInfo) -> usize {
     let base = node.name.len() + node.qualified_name.len() + node.file.len();
     let signature_len = node.signature.as_ref().map(|s| s.len()).unwrap_or(0);
+    let doc = node.doc_summary().unwrap_or_default();
     let lines = (node.line_end.saturating_sub(node.line_start) + 1) as usize;
-    let estimated_chars = base + signature_len + (lines * 40);
+    let estimated_chars = base + signature_len + doc.len() + (lines * 40);
 
     // Performance guardrail: use heuristic for very large content
     if estimated_chars > LARGE_FILE_THRESHOLD {
@@ -106,10 +114,11 @@ fn estimate_tokens(node: &NodeInfo) -> usize {
 
     // Build text representation for accurate tokenization
     let text = format!(
-        "{} {} {}{}",
+        "{} {} {} {}{}",
         node.qualified_name,
         node.file,
         node.signature.as_deref().unwrap_or(""),
+        doc,
         " ".repeat(lines * 40) // Approximate code content
     );
 
@@ -139,15 +148,60 @@ impl ArborGraph {
         max_tokens: usize,
         max_depth: usize,
         pinned: &[NodeId],
+    ) -> ContextSlice {
+        self.slice_context_multi(&[target], max_tokens, max_depth, pinned, &[])
+    }
+
+    /// Extracts one token-bounded context slice around several roots.
+    ///
+    /// All roots are seeded at depth 0 of the same BFS, so nodes shared
+    /// between their neighborhoods are included (and counted against
+    /// `max_tokens`) only once. Unknown roots are ignored; `target` of the
+    /// result is the first known root.
+    ///
+    /// When `edge_kinds` is non-empty the BFS only follows edges of those
+    /// kinds, e.g. `[Calls, Extends]` to keep import-only neighbors out.
+    pub fn slice_context_multi(
+        &self,
+        roots: &[NodeId],
+        max_tokens: usize,
+        max_depth: usize,
+        pinned: &[NodeId],
+        edge_kinds: &[EdgeKind],
+    ) -> ContextSlice {
+        self.slice_context_until(roots, max_tokens, max_depth, pinned, edge_kinds, None)
+    }
+
+    /// Like `slice_context_multi`, but stops collecting once `deadline`
+    /// passes and returns the nodes gathered so far with
+    /// `TruncationReason::Timeout`.
+    pub fn slice_context_until(
+        &self,
+        roots: &[NodeId],
+        max_tokens: usize,
+        max_depth: usize,
+        pinned: &[NodeId],
+        edge_kinds: &[EdgeKind],
+        deadline: Option<Instant>,
     ) -> ContextSlice {
         let start = Instant::now();
 
-        let target_node = match self.get(target) {
-            Some(node) => {
+        let mut targets: Vec<NodeInfo> = Vec::new();
+        let mut seeds: Vec<NodeId> = Vec::new();
+        for &root in roots {
+            if seeds.contains(&root) {
+                continue;
+            }
+            if let Some(node) = self.get(root) {
                 let mut info = NodeInfo::from(node);
-                info.centrality = self.centrality(target);
-                info
+                info.centrality = self.centrality_or_default(root);
+                targets.push(info);
+                seeds.push(root);
             }
+        }
+
+        let target_node = match targets.first() {
+            Some(info) => info.clone(),
             None => {
                 return ContextSlice {
                     target: NodeInfo {
@@ -160,7 +214,11 @@ impl ArborGraph {
                         line_end: 0,
                         signature: None,
                         centrality: 0.0,
+                        complexity: 0,
+                        visibility: Default::default(),
+                        docstring: None,
                     },
+                    targets,
                     nodes: Vec::new(),
                     total_tokens: 0,
                     max_tokens,
@@ -181,6 +239,7 @@ impl ArborGraph {
             max_tokens
         };
 
+        let follows = |kind: EdgeKind| edge_kinds.is_empty() || edge_kinds.contains(&kind);
         let pinned_set: HashSet<NodeId> = pinned.iter().copied().collect();
         let mut visited: HashSet<NodeId> = HashSet::new();
         let mut result: Vec<ContextNode> = Vec::new();
@@ -190,10 +249,16 @@ impl ArborGraph {
         // BFS queue: (node_id, depth)
         let mut queue: VecDeque<(NodeId, usize)> = VecDeque::new();
 
-        // Start with target
-        queue.push_back((target, 0));
+        // Start with every root
+        for &seed in &seeds {
+            queue.push_back((seed, 0));
+        }
 
         while let Some((current, depth)) = queue.pop_front() {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                truncation_reason = TruncationReason::Timeout;
+                break;
+            }
             if visited.contains(&current) {
                 continue;
             }
@@ -209,7 +274,7 @@ impl ArborGraph {
 
             if let Some(node) = self.get(current) {
                 let mut node_info = NodeInfo::from(node);
-                node_info.centrality = self.centrality(current);
+                node_info.centrality = self.centrality_or_default(current);
 
                 let token_est = estimate_tokens(&node_info);
 
@@ -236,7 +301,7 @@ impl ArborGraph {
                 // Upstream (incoming)
                 for edge_ref in self.graph.edges_directed(current, Direction::Incoming) {
                     let neighbor = edge_ref.source();
-                    if !visited.contains(&neighbor) {
+                    if follows(edge_ref.weight().kind) && !visited.contains(&neighbor) {
                         queue.push_back((neighbor, depth + 1));
                     }
                 }
@@ -244,7 +309,7 @@ impl ArborGraph {
                 // Downstream (outgoing)
                 for edge_ref in self.graph.edges_directed(current, Direction::Outgoing) {
                     let neighbor = edge_ref.target();
-                    if !visited.contains(&neighbor) {
+                    if follows(edge_ref.weight().kind) && !visited.contains(&neighbor) {
                         queue.push_back((neighbor, depth + 1));
                     }
                 }
@@ -268,6 +333,7 @@ impl ArborGraph {
 
         ContextSlice {
             target: target_node,
+            targets,
             nodes: result,
             total_tokens,
             max_tokens,
@@ -280,7 +346,7 @@ impl ArborGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::edge::{Edge, EdgeKind};
+    use crate::edge::Edge;
     use arbor_core::{CodeNode, NodeKind};
 
     fn make_node(name: &str) -> CodeNode {
@@ -387,4 +453,146 @@ mod tests {
         assert_eq!(result.truncation_reason, TruncationReason::Complete);
         assert_eq!(result.nodes.len(), 2);
     }
+
+    #[test]
+    fn test_multi_root_counts_shared_nodes_once() {
+        // a → shared ← b
+        let mut graph = ArborGraph::new();
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        let shared = graph.add_node(make_node("shared"));
+
+        graph.add_edge(a, shared, Edge::new(EdgeKind::Calls));
+        graph.add_edge(b, shared, Edge::new(EdgeKind::Calls));
+
+        let result = graph.slice_context_multi(&[a, b], 100000, 2, &[], &[]);
+
+        let names: Vec<&str> = result
+            .nodes
+            .iter()
+            .map(|n| n.node_info.name.as_str())
+            .collect();
+        assert_eq!(names.iter().filter(|&&n| n == "shared").count(), 1);
+        assert_eq!(result.nodes.len(), 3);
+
+        let summed: usize = result.nodes.iter().map(|n| n.token_estimate).sum();
+        assert_eq!(result.total_tokens, summed);
+
+        let targets: Vec<&str> = result.targets.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(targets, vec!["a", "b"]);
+        assert_eq!(result.target.name, "a");
+    }
+
+    #[test]
+    fn test_edge_kind_filter_drops_import_neighbors() {
+        // config ←imports— target —calls→ helper
+        let mut graph = ArborGraph::new();
+        let target = graph.add_node(make_node("target"));
+        let helper = graph.add_node(make_node("helper"));
+        let config = graph.add_node(make_node("config"));
+        graph.add_edge(target, helper, Edge::new(EdgeKind::Calls));
+        graph.add_edge(target, config, Edge::new(EdgeKind::Imports));
+
+        let names = |slice: &ContextSlice| -> Vec<String> {
+            slice
+                .nodes
+                .iter()
+                .map(|n| n.node_info.name.clone())
+                .collect()
+        };
+
+        let all = graph.slice_context_multi(&[target], 100000, 2, &[], &[]);
+        assert!(names(&all).contains(&"config".to_string()));
+
+        let calls_only = graph.slice_context_multi(&[target], 100000, 2, &[], &[EdgeKind::Calls]);
+        assert_eq!(names(&calls_only), vec!["target", "helper"]);
+    }
+
+    #[test]
+    fn test_deeper_slices_grow_until_budget() {
+        // n0 → n1 → ... → n5, sliced from n0
+        let mut graph = ArborGraph::new();
+        let ids: Vec<NodeId> = (0..6)
+            .map(|i| graph.add_node(make_node(&format!("n{}", i))))
+            .collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(pair[0], pair[1], Edge::new(EdgeKind::Calls));
+        }
+
+        // Room for exactly four nodes
+        let per_node = graph.slice_context(ids[0], 0, 1, &[]).nodes[0].token_estimate;
+        let budget = per_node * 4;
+
+        let sizes: Vec<usize> = (1..=5)
+            .map(|depth| graph.slice_context(ids[0], budget, depth, &[]).nodes.len())
+            .collect();
+        assert_eq!(sizes, vec![2, 3, 4, 4, 4]);
+
+        assert_eq!(
+            graph
+                .slice_context(ids[0], budget, 2, &[])
+                .truncation_reason,
+            TruncationReason::MaxDepth
+        );
+        assert_eq!(
+            graph
+                .slice_context(ids[0], budget, 5, &[])
+                .truncation_reason,
+            TruncationReason::TokenBudget
+        );
+    }
+
+    #[test]
+    fn test_docstring_carried_into_slice() {
+        let mut graph = ArborGraph::new();
+        let mut documented = make_node("hash_password");
+        documented.docstring = Some(
+            "Hash a password with a per-user salt.\n\n  Uses argon2id with the\n  default cost."
+                .to_string(),
+        );
+        let hash = graph.add_node(documented);
+        let login = graph.add_node(make_node("login"));
+        graph.add_edge(login, hash, Edge::new(EdgeKind::Calls));
+
+        let slice = graph.slice_context(login, 1000, 2, &[]);
+        let node = slice
+            .nodes
+            .iter()
+            .find(|n| n.node_info.name == "hash_password")
+            .unwrap();
+        assert!(node
+            .node_info
+            .docstring
+            .as_deref()
+            .unwrap()
+            .contains("argon2id"));
+        assert_eq!(
+            node.node_info.doc_summary().as_deref(),
+            Some("Hash a password with a per-user salt.")
+        );
+        assert!(slice.target.docstring.is_none());
+
+        // Long docstrings are cut for context output
+        let mut info = node.node_info.clone();
+        info.docstring = Some("word ".repeat(100));
+        let summary = info.doc_summary().unwrap();
+        assert_eq!(summary.chars().count(), crate::query::DOC_SUMMARY_CHARS);
+        assert!(summary.ends_with('…'));
+    }
+
+    #[test]
+    fn test_deadline_truncates_slice() {
+        let mut graph = ArborGraph::new();
+        let ids: Vec<NodeId> = (0..2000)
+            .map(|i| graph.add_node(make_node(&format!("f{}", i))))
+            .collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(pair[0], pair[1], Edge::new(EdgeKind::Calls));
+        }
+
+        let partial = graph.slice_context_until(&ids[..1], 0, 0, &[], &[], Some(Instant::now()));
+        assert_eq!(partial.truncation_reason, TruncationReason::Timeout);
+        assert!(partial.nodes.len() < ids.len());
+        assert_eq!(partial.target.name, "f0");
+    }
 }
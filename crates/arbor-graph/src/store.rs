@@ -1,12 +1,212 @@
 use crate::builder::GraphBuilder;
 use crate::graph::ArborGraph;
-use arbor_core::CodeNode;
+use arbor_core::{CodeNode, Span};
 use sled::{Batch, Db};
 use std::path::Path;
 use thiserror::Error;
 
-/// Current cache format version. Increment when schema changes.
-const CACHE_VERSION: &str = "arbor-1.3";
+/// Current cache format version. Increment when schema changes, and
+/// register a migration from the previous version in `MIGRATIONS` if the
+/// old entries can be upgraded in place.
+const CACHE_VERSION: &str = "arbor-1.10";
+
+/// One step in upgrading a cache written by an older version.
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    /// Rewrites the serialized nodes of one file from `from`'s layout to `to`'s.
+    ///
+    /// Only appends bytes: decoding as `CodeNode` here would fail, since
+    /// the fields of later versions aren't there yet.
+    apply: fn(Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError>,
+    /// Recomputes this step's fields once the chain reaches the current
+    /// layout and the nodes can be decoded.
+    finish: Option<fn(&mut [CodeNode])>,
+    /// Whether this step's fields need the syntax tree. Migrated files then
+    /// lose their mtime, so the next index re-parses them; their nodes are
+    /// still served from the cache until it does.
+    reparse: bool,
+}
+
+/// Known upgrades, oldest first. Caches older than the first `from` are
+/// reset (1.4 -> 1.5 inserted `is_test` mid-record, which bincode can't
+/// patch without the old struct).
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: "arbor-1.5",
+        to: "arbor-1.6",
+        apply: append_complexity,
+        finish: None,
+        reparse: true,
+    },
+    Migration {
+        from: "arbor-1.6",
+        to: "arbor-1.7",
+        apply: append_parent_id,
+        finish: Some(arbor_core::assign_parents),
+        reparse: false,
+    },
+    Migration {
+        from: "arbor-1.7",
+        to: "arbor-1.8",
+        apply: append_attributes,
+        finish: None,
+        reparse: true,
+    },
+    Migration {
+        from: "arbor-1.8",
+        to: "arbor-1.9",
+        apply: append_spans,
+        finish: None,
+        reparse: true,
+    },
+    Migration {
+        from: "arbor-1.9",
+        to: "arbor-1.10",
+        apply: keep_layout,
+        finish: None,
+        reparse: true,
+    },
+];
+
+/// 1.6 appended `complexity: u32`. It needs the syntax tree, so it starts
+/// at 0 and the file is re-parsed on the next index.
+fn append_complexity(nodes: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError> {
+    let default = bincode::serialize(&0u32)?;
+    Ok(nodes
+        .into_iter()
+        .map(|mut bytes| {
+            bytes.extend_from_slice(&default);
+            bytes
+        })
+        .collect())
+}
+
+/// 1.7 appended `parent_id: Option<String>`. It starts unset and is
+/// recomputed from the file's own nodes when the chain finishes.
+fn append_parent_id(nodes: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError> {
+    let default = bincode::serialize(&None::<String>)?;
+    Ok(nodes
+        .into_iter()
+        .map(|mut bytes| {
+            bytes.extend_from_slice(&default);
+            bytes
+        })
+        .collect())
+}
+
+/// 1.8 appended `attributes: Vec<String>`. It needs the syntax tree, so it
+/// starts empty and the file is re-parsed on the next index.
+fn append_attributes(nodes: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError> {
+    let default = bincode::serialize(&Vec::<String>::new())?;
+    Ok(nodes
+        .into_iter()
+        .map(|mut bytes| {
+            bytes.extend_from_slice(&default);
+            bytes
+        })
+        .collect())
+}
+
+/// 1.9 appended `end_column`, `name_span` and `body_span`. They need the
+/// source, so they start unset and the file is re-parsed on the next index.
+fn append_spans(nodes: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError> {
+    let default = bincode::serialize(&(0u32, None::<Span>, None::<Span>))?;
+    Ok(nodes
+        .into_iter()
+        .map(|mut bytes| {
+            bytes.extend_from_slice(&default);
+            bytes
+        })
+        .collect())
+}
+
+/// 1.10 extracts JS/TS export nodes and moved `EdgeKind::Exports` to the
+/// end of the enum. Node layout is unchanged, but older entries lack the
+/// export nodes, so files are re-parsed. Snapshots store edges and are
+/// turned away by their version check instead.
+fn keep_layout(nodes: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, StoreError> {
+    Ok(nodes)
+}
+
+/// Upgrades a cache from `from_version` to `to_version` in place.
+///
+/// Chains the registered migrations file by file, so cached nodes survive.
+/// Fields that are recomputed rather than defaulted (`parent_id`) are
+/// filled once every step has run. If a step's fields need the parser
+/// (`reparse`), the migrated files' mtimes are dropped so the next index
+/// re-parses them instead of keeping the defaults; otherwise unchanged
+/// files stay cached. Fails with `VersionMismatch` if no chain connects the
+/// two versions. Every rewrite and the new version land in one batch.
+pub fn migrate(from_version: &str, to_version: &str, db: &Db) -> Result<(), StoreError> {
+    let mut steps = Vec::new();
+    let mut version = from_version;
+    while version != to_version {
+        let step = MIGRATIONS
+            .iter()
+            .find(|m| m.from == version)
+            .ok_or_else(|| StoreError::VersionMismatch {
+                expected: to_version.to_string(),
+                found: from_version.to_string(),
+            })?;
+        steps.push(step);
+        version = step.to;
+    }
+
+    let reparse = steps.iter().any(|step| step.reparse);
+    let mut batch = Batch::default();
+    for item in db.scan_prefix(b"f:") {
+        let (file_key, index_bytes) = item?;
+        if reparse {
+            let mut mtime_key = b"m:".to_vec();
+            mtime_key.extend_from_slice(&file_key[2..]);
+            batch.remove(mtime_key);
+        }
+        let ids: Vec<String> = bincode::deserialize(&index_bytes)?;
+
+        let mut keys = Vec::with_capacity(ids.len());
+        let mut nodes = Vec::with_capacity(ids.len());
+        for id in ids {
+            let node_key = format!("n:{}", id);
+            if let Some(bytes) = db.get(&node_key)? {
+                keys.push(node_key);
+                nodes.push(bytes.to_vec());
+            }
+        }
+
+        for step in &steps {
+            nodes = (step.apply)(nodes)?;
+        }
+        let finishes: Vec<_> = steps.iter().filter_map(|step| step.finish).collect();
+        if !finishes.is_empty() {
+            let mut decoded = nodes
+                .iter()
+                .map(|bytes| bincode::deserialize::<CodeNode>(bytes))
+                .collect::<Result<Vec<_>, _>>()?;
+            for finish in finishes {
+                finish(&mut decoded);
+            }
+            nodes = decoded
+                .iter()
+                .map(bincode::serialize)
+                .collect::<Result<Vec<_>, _>>()?;
+        }
+        for (key, bytes) in keys.iter().zip(nodes) {
+            batch.insert(key.as_bytes(), bytes);
+        }
+    }
+
+    batch.insert(
+        "meta:version".as_bytes(),
+        bincode::serialize(&to_version.to_string())?,
+    );
+    db.apply_batch(batch)?;
+    db.flush()?;
+    Ok(())
+}
+
+/// Magic bytes at the start of an `.arbg` snapshot.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"ARBG";
 
 #[derive(Error, Debug)]
 pub enum StoreError {
@@ -26,7 +226,9 @@ pub struct GraphStore {
 
 impl GraphStore {
     /// Opens or creates a graph store at the specified path.
-    /// Returns an error if the cache version doesn't match.
+    ///
+    /// A cache written by an older version is migrated when a migration
+    /// path exists; otherwise this returns `VersionMismatch`.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
         let db = sled::open(path)?;
         let store = Self { db };
@@ -35,10 +237,7 @@ impl GraphStore {
         if let Some(version_bytes) = store.db.get("meta:version")? {
             let version: String = bincode::deserialize(&version_bytes)?;
             if version != CACHE_VERSION {
-                return Err(StoreError::VersionMismatch {
-                    expected: CACHE_VERSION.to_string(),
-                    found: version,
-                });
+                migrate(&version, CACHE_VERSION, &store.db)?;
             }
         } else {
             // New cache, set version
@@ -49,7 +248,7 @@ impl GraphStore {
         Ok(store)
     }
 
-    /// Opens a store, clearing it if version mismatches.
+    /// Opens a store, clearing it if the version mismatches and can't be migrated.
     pub fn open_or_reset<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
         match Self::open(path.as_ref()) {
             Ok(store) => Ok(store),
@@ -98,6 +297,15 @@ impl GraphStore {
         }
     }
 
+    /// Gets the syntax error lines stored for a file, empty if it parsed
+    /// cleanly or isn't cached.
+    pub fn get_syntax_errors(&self, file_path: &str) -> Result<Vec<u32>, StoreError> {
+        match self.db.get(format!("e:{}", file_path))? {
+            Some(bytes) => Ok(bincode::deserialize(&bytes)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Updates the nodes and mtime for a specific file.
     ///
     /// This operation is atomic: it removes old nodes associated with the file
@@ -107,9 +315,23 @@ impl GraphStore {
         file_path: &str,
         nodes: &[CodeNode],
         mtime: u64,
+    ) -> Result<(), StoreError> {
+        self.update_file_with_errors(file_path, nodes, mtime, &[])
+    }
+
+    /// Like `update_file`, for a file that parsed with syntax errors: its
+    /// recoverable nodes are cached along with the error lines, so a cache
+    /// hit can still report the file as partial.
+    pub fn update_file_with_errors(
+        &self,
+        file_path: &str,
+        nodes: &[CodeNode],
+        mtime: u64,
+        syntax_errors: &[u32],
     ) -> Result<(), StoreError> {
         let file_key = format!("f:{}", file_path);
         let mtime_key = format!("m:{}", file_path);
+        let errors_key = format!("e:{}", file_path);
         let mut batch = Batch::default();
 
         // 1. Get old nodes for this file
@@ -133,9 +355,14 @@ impl GraphStore {
         let index_bytes = bincode::serialize(&new_ids)?;
         batch.insert(file_key.as_bytes(), index_bytes);
 
-        // 4. Update mtime
+        // 4. Update mtime and syntax errors
         let mtime_bytes = bincode::serialize(&mtime)?;
         batch.insert(mtime_key.as_bytes(), mtime_bytes);
+        if syntax_errors.is_empty() {
+            batch.remove(errors_key.as_bytes());
+        } else {
+            batch.insert(errors_key.as_bytes(), bincode::serialize(syntax_errors)?);
+        }
 
         // 5. Commit batch
         self.db.apply_batch(batch)?;
@@ -159,6 +386,7 @@ impl GraphStore {
 
         batch.remove(file_key.as_bytes());
         batch.remove(mtime_key.as_bytes());
+        batch.remove(format!("e:{}", file_path).as_bytes());
 
         self.db.apply_batch(batch)?;
         self.db.flush()?;
@@ -219,6 +447,39 @@ impl GraphStore {
     }
 }
 
+impl ArborGraph {
+    /// Serializes the whole graph, edges and centrality included, into
+    /// the binary `.arbg` snapshot format.
+    ///
+    /// The snapshot carries the cache version; `from_snapshot` rejects
+    /// snapshots written by a different version.
+    pub fn to_snapshot(&self) -> Result<Vec<u8>, StoreError> {
+        let mut bytes = SNAPSHOT_MAGIC.to_vec();
+        bincode::serialize_into(&mut bytes, CACHE_VERSION)?;
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Restores a graph from an `.arbg` snapshot.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, StoreError> {
+        let mut rest = bytes
+            .strip_prefix(SNAPSHOT_MAGIC.as_slice())
+            .ok_or_else(|| StoreError::Corrupted("not an .arbg snapshot".to_string()))?;
+
+        let version: String = bincode::deserialize_from(&mut rest)?;
+        if version != CACHE_VERSION {
+            return Err(StoreError::VersionMismatch {
+                expected: CACHE_VERSION.to_string(),
+                found: version,
+            });
+        }
+
+        let mut graph: ArborGraph = bincode::deserialize_from(&mut rest)?;
+        graph.restore_skipped_state();
+        Ok(graph)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -291,6 +552,29 @@ mod tests {
         assert!(store.get_file_nodes("test.rs").unwrap().is_none());
     }
 
+    #[test]
+    fn test_syntax_errors_stored_with_file() {
+        let dir = tempdir().unwrap();
+        let store = GraphStore::open(dir.path()).unwrap();
+
+        let node = CodeNode::new("foo", "foo", NodeKind::Function, "test.rs");
+        store
+            .update_file_with_errors("test.rs", &[node.clone()], 1000, &[3, 7])
+            .unwrap();
+        assert_eq!(store.get_syntax_errors("test.rs").unwrap(), [3, 7]);
+        assert_eq!(store.get_file_nodes("test.rs").unwrap().unwrap().len(), 1);
+
+        // A clean parse clears them
+        store.update_file("test.rs", &[node.clone()], 2000).unwrap();
+        assert!(store.get_syntax_errors("test.rs").unwrap().is_empty());
+
+        store
+            .update_file_with_errors("test.rs", &[node], 3000, &[5])
+            .unwrap();
+        store.remove_file("test.rs").unwrap();
+        assert!(store.get_syntax_errors("test.rs").unwrap().is_empty());
+    }
+
     #[test]
     fn test_list_cached_files() {
         let dir = tempdir().unwrap();
@@ -307,4 +591,180 @@ mod tests {
         assert!(files.contains(&"a.rs".to_string()));
         assert!(files.contains(&"b.rs".to_string()));
     }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        use crate::edge::{Edge, EdgeKind};
+        use std::collections::HashMap;
+
+        let mut graph = ArborGraph::new();
+        let a = graph.add_node(CodeNode::new("foo", "foo", NodeKind::Function, "test.rs"));
+        let b = graph.add_node(CodeNode::new("bar", "bar", NodeKind::Function, "test.rs"));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+        graph.set_centrality(HashMap::from([(b, 0.5)]));
+
+        let bytes = graph.to_snapshot().unwrap();
+        let restored = ArborGraph::from_snapshot(&bytes).unwrap();
+
+        assert_eq!(restored.node_count(), 2);
+        assert_eq!(restored.edge_count(), 1);
+        let bar = restored.get_index(&graph.get(b).unwrap().id).unwrap();
+        assert_eq!(restored.centrality(bar), Some(0.5));
+        assert_eq!(restored.search("bar").len(), 1);
+
+        assert!(matches!(
+            ArborGraph::from_snapshot(b"not a snapshot"),
+            Err(StoreError::Corrupted(_))
+        ));
+    }
+
+    #[test]
+    fn test_migrate_from_previous_schema_keeps_cache() {
+        let dir = tempdir().unwrap();
+
+        let class = CodeNode::new("Store", "Store", NodeKind::Class, "store.py").with_bytes(0, 100);
+        let method =
+            CodeNode::new("save", "Store.save", NodeKind::Method, "store.py").with_bytes(20, 80);
+        let (class_id, method_id) = (class.id.clone(), method.id.clone());
+
+        // Write the nodes as arbor-1.6 did: no trailing parent_id,
+        // attributes or spans
+        let empty_attributes = bincode::serialize(&Vec::<String>::new()).unwrap();
+        let empty_spans = bincode::serialize(&(0u32, None::<Span>, None::<Span>)).unwrap();
+        {
+            let db = sled::open(dir.path()).unwrap();
+            db.insert(
+                "meta:version",
+                bincode::serialize(&"arbor-1.6".to_string()).unwrap(),
+            )
+            .unwrap();
+            for node in [&class, &method] {
+                let mut bytes = bincode::serialize(node).unwrap();
+                assert!(bytes.ends_with(&empty_spans));
+                bytes.truncate(bytes.len() - empty_spans.len());
+                assert!(bytes.ends_with(&empty_attributes));
+                bytes.truncate(bytes.len() - empty_attributes.len());
+                assert_eq!(bytes.pop(), Some(0)); // None
+                db.insert(format!("n:{}", node.id), bytes).unwrap();
+            }
+            db.insert(
+                "f:store.py",
+                bincode::serialize(&vec![class_id.clone(), method_id.clone()]).unwrap(),
+            )
+            .unwrap();
+            db.insert("m:store.py", bincode::serialize(&1000u64).unwrap())
+                .unwrap();
+            db.flush().unwrap();
+        }
+
+        let store = GraphStore::open(dir.path()).unwrap();
+
+        // Nodes are still served, but attributes need the parser, so the
+        // mtime is dropped and the next index re-parses the file
+        assert_eq!(store.get_mtime("store.py").unwrap(), None);
+        let nodes = store.get_file_nodes("store.py").unwrap().unwrap();
+        assert_eq!(nodes.len(), 2);
+        let save = nodes.iter().find(|n| n.id == method_id).unwrap();
+        assert_eq!(save.parent_id.as_deref(), Some(class_id.as_str()));
+
+        // The version is current, so reopening doesn't migrate again
+        drop(store);
+        assert!(GraphStore::open(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn test_migrate_reparses_files_missing_complexity() {
+        let dir = tempdir().unwrap();
+        let node = CodeNode::new("load", "load", NodeKind::Function, "io.rs");
+
+        // Write the node as arbor-1.5 did: nothing from complexity on
+        let current = bincode::serialize(&node).unwrap();
+        let trailing = bincode::serialize(&(
+            0u32,
+            None::<String>,
+            Vec::<String>::new(),
+            (0u32, None::<Span>, None::<Span>),
+        ))
+        .unwrap();
+        assert!(current.ends_with(&trailing));
+        {
+            let db = sled::open(dir.path()).unwrap();
+            db.insert(
+                "meta:version",
+                bincode::serialize(&"arbor-1.5".to_string()).unwrap(),
+            )
+            .unwrap();
+            db.insert(
+                format!("n:{}", node.id),
+                &current[..current.len() - trailing.len()],
+            )
+            .unwrap();
+            db.insert(
+                "f:io.rs",
+                bincode::serialize(&vec![node.id.clone()]).unwrap(),
+            )
+            .unwrap();
+            db.insert("m:io.rs", bincode::serialize(&1000u64).unwrap())
+                .unwrap();
+            db.flush().unwrap();
+        }
+
+        let store = GraphStore::open(dir.path()).unwrap();
+        // Nodes still load, but the mtime is gone so the next index
+        // re-parses the file and computes complexity
+        assert_eq!(store.get_file_nodes("io.rs").unwrap().unwrap().len(), 1);
+        assert_eq!(store.get_mtime("io.rs").unwrap(), None);
+    }
+
+    #[test]
+    fn test_migrate_appends_unset_spans() {
+        let dir = tempdir().unwrap();
+
+        let node = CodeNode::new("load", "load", NodeKind::Function, "io.rs")
+            .with_column(4)
+            .with_attributes(vec!["#[inline]".to_string()]);
+
+        // Write the node as arbor-1.8 did: no trailing spans
+        let empty_spans = bincode::serialize(&(0u32, None::<Span>, None::<Span>)).unwrap();
+        {
+            let db = sled::open(dir.path()).unwrap();
+            db.insert(
+                "meta:version",
+                bincode::serialize(&"arbor-1.8".to_string()).unwrap(),
+            )
+            .unwrap();
+            let mut bytes = bincode::serialize(&node).unwrap();
+            assert!(bytes.ends_with(&empty_spans));
+            bytes.truncate(bytes.len() - empty_spans.len());
+            db.insert(format!("n:{}", node.id), bytes).unwrap();
+            db.insert(
+                "f:io.rs",
+                bincode::serialize(&vec![node.id.clone()]).unwrap(),
+            )
+            .unwrap();
+            db.insert("m:io.rs", bincode::serialize(&1000u64).unwrap())
+                .unwrap();
+            db.flush().unwrap();
+        }
+
+        let store = GraphStore::open(dir.path()).unwrap();
+        // Spans need the source, so the file is re-parsed on the next index
+        assert_eq!(store.get_mtime("io.rs").unwrap(), None);
+        let nodes = store.get_file_nodes("io.rs").unwrap().unwrap();
+        assert_eq!(nodes.len(), 1);
+        // Earlier fields survive; the spans start unset
+        assert_eq!(nodes[0].column, 4);
+        assert_eq!(nodes[0].attributes, ["#[inline]"]);
+        assert_eq!(nodes[0].end_column, 0);
+        assert!(nodes[0].name_span.is_none() && nodes[0].body_span.is_none());
+    }
+
+    #[test]
+    fn test_migrate_without_path_is_version_mismatch() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        assert!(matches!(
+            migrate("arbor-1.3", CACHE_VERSION, &db),
+            Err(StoreError::VersionMismatch { .. })
+        ));
+    }
 }
@@ -6,10 +6,17 @@ use std::path::PathBuf;
 ///
 /// Maps Fully Qualified Names (FQNs) to Node IDs.
 /// Example FQN: "arbor::graph::SymbolTable" -> NodeId(42)
+///
+/// An FQN may have several definitions (overloads with distinct IDs, or
+/// the same module path declared in different files); all of them are
+/// kept and `resolve_with_context` picks between them by locality.
 #[derive(Debug, Default, Clone)]
 pub struct SymbolTable {
-    /// Map of FQN to NodeId
-    by_fqn: HashMap<String, NodeId>,
+    /// Map of FQN to every node defining it, in insertion order
+    by_fqn: HashMap<String, Vec<NodeId>>,
+
+    /// Map of Node ID to its defining file
+    file_of: HashMap<NodeId, PathBuf>,
 
     /// Map of File Path to list of exported symbols (FQNs)
     /// Used to resolve wildcard imports or find all symbols in a file.
@@ -28,13 +35,28 @@ impl SymbolTable {
     /// * `id` - The Node ID in the graph
     /// * `file` - The file path defining this symbol
     pub fn insert(&mut self, fqn: String, id: NodeId, file: PathBuf) {
-        self.by_fqn.insert(fqn.clone(), id);
+        let ids = self.by_fqn.entry(fqn.clone()).or_default();
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+        self.file_of.insert(id, file.clone());
         self.exports_by_file.entry(file).or_default().push(fqn);
     }
 
     /// Resolves a Fully Qualified Name to a Node ID.
+    ///
+    /// Returns None if the FQN is unknown or has several definitions;
+    /// use `resolve_with_context` to disambiguate those.
     pub fn resolve(&self, fqn: &str) -> Option<NodeId> {
-        self.by_fqn.get(fqn).copied()
+        match self.by_fqn.get(fqn)?.as_slice() {
+            [id] => Some(*id),
+            _ => None,
+        }
+    }
+
+    /// Returns every node defining `fqn`.
+    pub fn definitions(&self, fqn: &str) -> &[NodeId] {
+        self.by_fqn.get(fqn).map(Vec::as_slice).unwrap_or(&[])
     }
 
     /// Returns all symbols exported by a file.
@@ -45,6 +67,7 @@ impl SymbolTable {
     /// Clears the symbol table.
     pub fn clear(&mut self) {
         self.by_fqn.clear();
+        self.file_of.clear();
         self.exports_by_file.clear();
     }
 
@@ -53,65 +76,74 @@ impl SymbolTable {
     /// Resolution order:
     /// 1. Exact FQN match
     /// 2. Suffix match (e.g., "helper" matches "pkg.Utils.helper")
-    ///    - Only matches if unambiguous OR in same directory as `context_file`
+    ///
+    /// At each step a single candidate wins outright. Several candidates
+    /// resolve only if exactly one is in `context_file` itself, or failing
+    /// that, exactly one is in the same directory.
     ///
     /// Returns None if:
     /// - No match found
-    /// - Multiple matches exist and none are in the same directory (ambiguous)
+    /// - Multiple matches exist and locality doesn't single one out (ambiguous)
     pub fn resolve_with_context(
         &self,
         name: &str,
         context_file: &std::path::Path,
     ) -> Option<NodeId> {
-        // 1. Try exact match first
-        if let Some(id) = self.by_fqn.get(name) {
-            return Some(*id);
+        // 1. Try exact match first; an ambiguous exact match is not
+        //    second-guessed by a suffix match
+        if let Some(ids) = self.by_fqn.get(name) {
+            return self.pick_by_locality(ids, context_file);
         }
 
         // 2. Suffix match
-        let context_dir = context_file.parent();
-        let mut candidates: Vec<(&String, NodeId, bool)> = Vec::new();
-
-        for (fqn, &id) in &self.by_fqn {
-            // Check if FQN ends with the name (with separator)
-            if fqn.ends_with(name) {
-                // Ensure it's a proper suffix (preceded by separator or start)
-                let prefix_len = fqn.len() - name.len();
-                if prefix_len == 0
-                    || fqn.chars().nth(prefix_len - 1) == Some('.')
-                    || fqn.chars().nth(prefix_len - 1) == Some(':')
-                {
-                    // Check if in same directory
-                    let same_dir = self
-                        .exports_by_file
-                        .iter()
-                        .find(|(_, exports)| exports.contains(fqn))
-                        .map(|(file, _)| file.parent() == context_dir)
-                        .unwrap_or(false);
-
-                    candidates.push((fqn, id, same_dir));
-                }
+        let mut candidates: Vec<NodeId> = Vec::new();
+        for (fqn, ids) in &self.by_fqn {
+            if is_suffix_match(fqn, name) {
+                candidates.extend(ids);
             }
         }
 
-        match candidates.len() {
-            0 => None,
-            1 => Some(candidates[0].1),
-            _ => {
-                // Multiple candidates: only resolve if exactly one is in same directory
-                let same_dir_candidates: Vec<_> =
-                    candidates.iter().filter(|(_, _, same)| *same).collect();
-                if same_dir_candidates.len() == 1 {
-                    Some(same_dir_candidates[0].1)
-                } else {
-                    // Ambiguous: don't auto-link
-                    None
-                }
-            }
+        self.pick_by_locality(&candidates, context_file)
+    }
+
+    /// Picks the one candidate that is unique, or unique in the context
+    /// file, or unique in the context directory.
+    fn pick_by_locality(
+        &self,
+        candidates: &[NodeId],
+        context_file: &std::path::Path,
+    ) -> Option<NodeId> {
+        if let [only] = candidates {
+            return Some(*only);
         }
+
+        let unique = |pred: &dyn Fn(&std::path::Path) -> bool| {
+            let mut matching = candidates.iter().filter(|id| {
+                self.file_of
+                    .get(id)
+                    .map(|file| pred(file.as_path()))
+                    .unwrap_or(false)
+            });
+            match (matching.next(), matching.next()) {
+                (Some(id), None) => Some(*id),
+                _ => None,
+            }
+        };
+
+        let context_dir = context_file.parent();
+        unique(&|file| file == context_file)
+            .or_else(|| unique(&|file| file.parent() == context_dir))
     }
 }
 
+/// Whether `name` is a proper suffix of `fqn`, preceded by a separator
+/// (`get_user` of `api.handlers.get_user`, but not of `forget_user`).
+pub(crate) fn is_suffix_match(fqn: &str, name: &str) -> bool {
+    fqn.len() > name.len()
+        && fqn.ends_with(name)
+        && matches!(fqn.as_bytes()[fqn.len() - name.len() - 1], b'.' | b':')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +240,26 @@ mod tests {
         let result = table.resolve_with_context("helper", &PathBuf::from("src/b/caller.rs"));
         assert_eq!(result, Some(id2));
     }
+
+    #[test]
+    fn test_duplicate_fqns_are_kept_and_disambiguated() {
+        let mut table = SymbolTable::new();
+        let id1 = NodeId::new(1);
+        let id2 = NodeId::new(2);
+
+        // Same FQN declared in two files
+        table.insert("config".to_string(), id1, PathBuf::from("src/a/config.rs"));
+        table.insert("config".to_string(), id2, PathBuf::from("src/b/config.rs"));
+
+        assert_eq!(table.definitions("config"), &[id1, id2]);
+        assert_eq!(table.resolve("config"), None);
+        assert_eq!(
+            table.resolve_with_context("config", &PathBuf::from("src/b/main.rs")),
+            Some(id2)
+        );
+        assert_eq!(
+            table.resolve_with_context("config", &PathBuf::from("src/c/main.rs")),
+            None
+        );
+    }
 }
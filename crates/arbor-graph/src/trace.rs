@@ -0,0 +1,165 @@
+//! Entry-point tracing.
+//!
+//! Walks the graph backwards from a node until it reaches something
+//! that nothing else calls into. It answers the question:
+//! "How does execution get here?"
+
+use crate::edge::{Edge, EdgeKind};
+use crate::graph::{ArborGraph, NodeId};
+use crate::query::NodeInfo;
+use petgraph::graph::EdgeReference;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// One hop on the path from an entry point to a target node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathStep {
+    /// The node's graph index.
+    pub node_id: NodeId,
+    /// Full node information.
+    pub node_info: NodeInfo,
+    /// Kind of the edge leading from this node to the next step.
+    /// `None` for the last step (the target itself).
+    pub edge: Option<EdgeKind>,
+}
+
+impl ArborGraph {
+    /// Finds the shortest path from an entry point down to `id`.
+    ///
+    /// Does a reverse BFS over incoming edges and stops at the first node
+    /// that is an entry point: one named `main` or one with no incoming
+    /// edges at all. `Contains` edges are structure, not execution, so they
+    /// are neither followed nor counted. The returned steps run from the
+    /// entry point to `id`.
+    ///
+    /// Returns `None` if `id` is unknown or every caller chain loops back
+    /// on itself without reaching an entry point.
+    pub fn path_to_nearest_entrypoint(&self, id: NodeId) -> Option<Vec<PathStep>> {
+        self.get(id)?;
+
+        // Maps each visited node to the node it leads to and the edge kind.
+        let mut next_hop: HashMap<NodeId, Option<(NodeId, EdgeKind)>> = HashMap::new();
+        let mut queue: VecDeque<NodeId> = VecDeque::new();
+
+        next_hop.insert(id, None);
+        queue.push_back(id);
+
+        while let Some(current) = queue.pop_front() {
+            if self.is_entrypoint(current) {
+                return Some(self.collect_path(current, &next_hop));
+            }
+
+            for edge_ref in self.incoming_flow(current) {
+                let caller = edge_ref.source();
+                if !next_hop.contains_key(&caller) {
+                    next_hop.insert(caller, Some((current, edge_ref.weight().kind)));
+                    queue.push_back(caller);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns true if the node is named `main` or has no incoming edges
+    /// besides its container's.
+    fn is_entrypoint(&self, id: NodeId) -> bool {
+        let is_main = self.get(id).map(|n| n.name == "main").unwrap_or(false);
+        is_main || self.incoming_flow(id).next().is_none()
+    }
+
+    /// Incoming edges other than `Contains`.
+    fn incoming_flow(&self, id: NodeId) -> impl Iterator<Item = EdgeReference<'_, Edge>> {
+        self.graph
+            .edges_directed(id, Direction::Incoming)
+            .filter(|e| e.weight().kind != EdgeKind::Contains)
+    }
+
+    /// Follows `next_hop` links from an entry point back to the target.
+    fn collect_path(
+        &self,
+        entry: NodeId,
+        next_hop: &HashMap<NodeId, Option<(NodeId, EdgeKind)>>,
+    ) -> Vec<PathStep> {
+        let mut path = Vec::new();
+        let mut current = Some(entry);
+
+        while let Some(node_id) = current {
+            let hop = next_hop.get(&node_id).copied().flatten();
+            if let Some(node) = self.get(node_id) {
+                let mut node_info = NodeInfo::from(node);
+                node_info.centrality = self.centrality_or_default(node_id);
+                path.push(PathStep {
+                    node_id,
+                    node_info,
+                    edge: hop.map(|(_, kind)| kind),
+                });
+            }
+            current = hop.map(|(next, _)| next);
+        }
+
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_core::{CodeNode, NodeKind};
+
+    fn make_node(name: &str) -> CodeNode {
+        CodeNode::new(name, name, NodeKind::Function, "test.rs")
+    }
+
+    #[test]
+    fn test_path_from_main() {
+        let mut graph = ArborGraph::new();
+        let main = graph.add_node(make_node("main"));
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        graph.add_edge(main, a, Edge::new(EdgeKind::Calls));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+
+        let path = graph.path_to_nearest_entrypoint(b).unwrap();
+
+        let names: Vec<&str> = path.iter().map(|s| s.node_info.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "a", "b"]);
+        assert_eq!(path[0].edge, Some(EdgeKind::Calls));
+        assert_eq!(path[2].edge, None);
+    }
+
+    #[test]
+    fn test_container_is_not_a_caller() {
+        let mut graph = ArborGraph::new();
+        let class = graph.add_node(CodeNode::new(
+            "Server",
+            "Server",
+            NodeKind::Class,
+            "test.rs",
+        ));
+        let handler = graph.add_node(make_node("handle"));
+        let helper = graph.add_node(make_node("parse"));
+        graph.add_edge(class, handler, Edge::new(EdgeKind::Contains));
+        graph.add_edge(class, helper, Edge::new(EdgeKind::Contains));
+        graph.add_edge(handler, helper, Edge::new(EdgeKind::Calls));
+
+        let path = graph.path_to_nearest_entrypoint(helper).unwrap();
+
+        // handle is only contained, so nothing calls it: it's the entry
+        let names: Vec<&str> = path.iter().map(|s| s.node_info.name.as_str()).collect();
+        assert_eq!(names, vec!["handle", "parse"]);
+    }
+
+    #[test]
+    fn test_cycle_without_entrypoint() {
+        let mut graph = ArborGraph::new();
+        let a = graph.add_node(make_node("a"));
+        let b = graph.add_node(make_node("b"));
+        graph.add_edge(a, b, Edge::new(EdgeKind::Calls));
+        graph.add_edge(b, a, Edge::new(EdgeKind::Calls));
+
+        assert!(graph.path_to_nearest_entrypoint(b).is_none());
+    }
+}
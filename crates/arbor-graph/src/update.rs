@@ -0,0 +1,270 @@
+//! Splicing changed files into a built graph.
+//!
+//! A full build resolves every reference again. When a few files changed,
+//! only edges of their nodes, and edges of other nodes whose references
+//! name a symbol those files define (before or after the change), can
+//! resolve differently. Everything else is left as it is, including
+//! edges added with `add_edge_explicit` and centrality scores.
+
+use crate::builder::merge_duplicate;
+use crate::edge::{Edge, EdgeKind};
+use crate::graph::{ArborGraph, NodeId};
+use crate::symbol_table::{is_suffix_match, SymbolTable};
+use arbor_core::{CodeNode, NodeKind};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+impl ArborGraph {
+    /// Replaces the nodes of changed files and re-resolves the edges that
+    /// can depend on them.
+    ///
+    /// `changed` maps a file to its new nodes, or to None if it was
+    /// deleted. Edges the references can't reproduce (explicit ones) that
+    /// touch a changed file are carried over to the new node with the same
+    /// ID, if there is one. New nodes have no centrality score until it is
+    /// computed again.
+    pub fn update_files<I>(&mut self, changed: I)
+    where
+        I: IntoIterator<Item = (String, Option<Vec<CodeNode>>)>,
+    {
+        let changed: HashMap<String, Option<Vec<CodeNode>>> = changed.into_iter().collect();
+        if changed.is_empty() {
+            return;
+        }
+
+        let old: Vec<NodeId> = changed
+            .keys()
+            .flat_map(|file| self.file_nodes(file))
+            .collect();
+
+        // Every name a reference could resolve through, before and after
+        let mut affected = HashSet::new();
+        for &index in &old {
+            insert_suffixes(&mut affected, &self.graph[index]);
+        }
+        for node in changed.values().flatten().flatten() {
+            insert_suffixes(&mut affected, node);
+        }
+
+        // Explicit edges of the old nodes, by node ID
+        let old_set: HashSet<NodeId> = old.iter().copied().collect();
+        let mut explicit = Vec::new();
+        for &index in &old {
+            let outgoing = self.graph.edges_directed(index, Direction::Outgoing);
+            // Edges from another old node were already seen as outgoing
+            let incoming = self
+                .graph
+                .edges_directed(index, Direction::Incoming)
+                .filter(|e| !old_set.contains(&e.source()));
+            for edge in outgoing.chain(incoming) {
+                if !self.is_derived(edge.source(), edge.target(), edge.weight().kind) {
+                    explicit.push((
+                        self.graph[edge.source()].id.clone(),
+                        self.graph[edge.target()].id.clone(),
+                        edge.weight().clone(),
+                    ));
+                }
+            }
+        }
+
+        for file in changed.keys() {
+            self.remove_file(file);
+        }
+
+        // Untouched nodes with a reference that may now resolve elsewhere.
+        // Their edges to what those references named are dropped and
+        // resolved again below.
+        let mut dependents = Vec::new();
+        for index in self.graph.node_indices() {
+            let references: Vec<String> = self.graph[index]
+                .references
+                .iter()
+                .filter(|r| affected.contains(r.as_str()))
+                .cloned()
+                .collect();
+            if !references.is_empty() {
+                dependents.push((index, references));
+            }
+        }
+        for (index, references) in &dependents {
+            let mut stale: Vec<_> = self
+                .graph
+                .edges_directed(*index, Direction::Outgoing)
+                .filter(|e| matches!(e.weight().kind, EdgeKind::Calls | EdgeKind::Exports))
+                .filter(|e| {
+                    let fqn = fqn_of(&self.graph[e.target()]);
+                    references
+                        .iter()
+                        .any(|r| fqn == r || is_suffix_match(fqn, r))
+                })
+                .map(|e| e.id())
+                .collect();
+            // petgraph also swaps the last edge into a removed slot
+            stale.sort_unstable_by(|a, b| b.cmp(a));
+            for edge in stale {
+                self.graph.remove_edge(edge);
+            }
+        }
+
+        let mut added = Vec::new();
+        for node in changed.into_values().flatten().flatten() {
+            match self.get_index(&node.id) {
+                Some(existing) => merge_duplicate(&mut self.graph[existing], node),
+                None => added.push(self.add_node(node)),
+            }
+        }
+
+        let mut symbols = SymbolTable::new();
+        for index in self.graph.node_indices() {
+            let node = &self.graph[index];
+            if node.kind != NodeKind::Export {
+                symbols.insert(fqn_of(node).to_string(), index, PathBuf::from(&node.file));
+            }
+        }
+
+        let mut edges = Vec::new();
+        for &index in &added {
+            let node = &self.graph[index];
+            if let Some(parent) = node.parent_id.as_deref().and_then(|id| self.get_index(id)) {
+                edges.push((parent, index, EdgeKind::Contains));
+            }
+            self.resolve_into(&symbols, index, &node.references, &mut edges);
+        }
+        for (index, references) in &dependents {
+            self.resolve_into(&symbols, *index, references, &mut edges);
+        }
+        for (from, to, kind) in edges {
+            self.add_edge(from, to, Edge::new(kind));
+        }
+
+        for (from, to, edge) in explicit {
+            if let (Some(from), Some(to)) = (self.get_index(&from), self.get_index(&to)) {
+                self.add_edge(from, to, edge);
+            }
+        }
+    }
+
+    /// Whether resolving references would produce this edge, as
+    /// `GraphBuilder::resolve_edges` does.
+    fn is_derived(&self, from: NodeId, to: NodeId, kind: EdgeKind) -> bool {
+        let (source, target) = (&self.graph[from], &self.graph[to]);
+        match kind {
+            EdgeKind::Contains => target.parent_id.as_deref() == Some(source.id.as_str()),
+            EdgeKind::Calls | EdgeKind::Exports => {
+                let fqn = fqn_of(target);
+                source
+                    .references
+                    .iter()
+                    .any(|r| fqn == r || is_suffix_match(fqn, r))
+            }
+            _ => false,
+        }
+    }
+
+    fn resolve_into(
+        &self,
+        symbols: &SymbolTable,
+        from: NodeId,
+        references: &[String],
+        edges: &mut Vec<(NodeId, NodeId, EdgeKind)>,
+    ) {
+        let node = &self.graph[from];
+        let kind = if node.kind == NodeKind::Export {
+            EdgeKind::Exports
+        } else {
+            EdgeKind::Calls
+        };
+        for reference in references {
+            if let Some(to) = symbols.resolve_with_context(reference, Path::new(&node.file)) {
+                if to != from {
+                    edges.push((from, to, kind));
+                }
+            }
+        }
+    }
+}
+
+/// The key a node is resolved by.
+fn fqn_of(node: &CodeNode) -> &str {
+    if node.qualified_name.is_empty() {
+        &node.name
+    } else {
+        &node.qualified_name
+    }
+}
+
+/// Adds the node's FQN and each of its separator-led suffixes, i.e. every
+/// reference that resolves to it by exact or suffix match.
+fn insert_suffixes(names: &mut HashSet<String>, node: &CodeNode) {
+    if node.kind == NodeKind::Export {
+        return;
+    }
+    let fqn = fqn_of(node);
+    names.insert(fqn.to_string());
+    for (i, c) in fqn.char_indices() {
+        if matches!(c, '.' | ':') && i + 1 < fqn.len() {
+            names.insert(fqn[i + 1..].to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::GraphBuilder;
+
+    fn node(name: &str, file: &str, references: &[&str]) -> CodeNode {
+        let mut node = CodeNode::new(name, name, NodeKind::Function, file);
+        node.references = references.iter().map(|r| r.to_string()).collect();
+        node
+    }
+
+    fn callees(graph: &ArborGraph, name: &str) -> Vec<String> {
+        let index = graph.get_index(&graph.find_by_name(name)[0].id).unwrap();
+        let mut names: Vec<String> = graph
+            .get_callees(index)
+            .iter()
+            .map(|n| n.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_update_files_re_resolves_touching_edges() {
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![node("a", "a.rs", &[])]);
+        builder.add_nodes(vec![node("b", "b.rs", &["a", "c"])]);
+        builder.add_nodes(vec![node("d", "d.rs", &[]), node("e", "d.rs", &[])]);
+        assert!(builder.add_edge_explicit("d", "e", EdgeKind::Calls));
+        assert!(builder.add_edge_explicit("d", "a", EdgeKind::Calls));
+        let mut graph = builder.build();
+        let scores = graph.node_indexes().map(|i| (i, 1.0)).collect();
+        graph.set_centrality(scores);
+        assert_eq!(callees(&graph, "b"), ["a"]);
+
+        // a.rs now also defines c, which b.rs calls
+        graph.update_files([(
+            "a.rs".to_string(),
+            Some(vec![node("a", "a.rs", &[]), node("c", "a.rs", &[])]),
+        )]);
+        assert_eq!(callees(&graph, "b"), ["a", "c"]);
+        assert_eq!(graph.node_count(), 5);
+
+        // Explicit edges survive, both untouched and re-attached by ID
+        assert_eq!(callees(&graph, "d"), ["a", "e"]);
+
+        // Untouched nodes keep their score; indexes stay consistent
+        let b = graph.get_index(&graph.find_by_name("b")[0].id).unwrap();
+        assert_eq!(graph.centrality(b), Some(1.0));
+        assert_eq!(graph.find_by_file("d.rs").len(), 2);
+
+        graph.update_files([("a.rs".to_string(), None)]);
+        assert!(callees(&graph, "b").is_empty());
+        assert_eq!(callees(&graph, "d"), ["e"]);
+        assert!(graph.find_by_file("a.rs").is_empty());
+        assert_eq!(graph.search("e").len(), 1);
+    }
+}
@@ -1,7 +1,9 @@
 // Full file skipped by rustfmt manually via block-level attributes to avoid unstable inner attributes
 //! Main application state and UI logic
 
-use arbor_graph::ArborGraph;
+use crate::export::{self, Scene, Viewport};
+use crate::navigation::{Selection, Step};
+use arbor_graph::{ArborGraph, NodeId};
 use arbor_watcher::{index_directory, IndexOptions};
 use eframe::egui;
 use std::path::PathBuf;
@@ -18,6 +20,7 @@ struct AnalysisResult {
     downstream: Vec<String>,
     total_affected: usize,
     confidence: String,
+    scene: Scene,
 }
 
 /// Main application state
@@ -35,6 +38,12 @@ pub struct ArborApp {
     /// Current analysis result
     result: Option<AnalysisResult>,
 
+    /// Node shown in the result, for keyboard navigation
+    selection: Option<Selection>,
+
+    /// Focus the symbol input on the next frame (`/`)
+    focus_search: bool,
+
     /// Status message
     status: String,
 
@@ -55,6 +64,15 @@ pub struct ArborApp {
 
     /// Show file path (spoiler mode - click to reveal)
     show_file_path: bool,
+
+    /// Pan/zoom of the graph panel, shared with image export
+    viewport: Viewport,
+
+    /// Refit the viewport to the scene on the next frame
+    fit_pending: bool,
+
+    /// Last drawn size of the graph panel (the export image size)
+    graph_size: egui::Vec2,
 }
 
 #[rustfmt::skip]
@@ -65,6 +83,8 @@ impl ArborApp {
             symbol_input: String::new(),
             graph: None,
             result: None,
+            selection: None,
+            focus_search: false,
             status: "Ready. Enter a symbol name to analyze.".to_string(),
             loading: false,
             dark_mode: true,
@@ -72,6 +92,9 @@ impl ArborApp {
             show_call_tree: true,
             show_dependencies: true,
             show_file_path: false, // Hidden by default (spoiler mode)
+            viewport: Viewport::default(),
+            fit_pending: false,
+            graph_size: egui::vec2(600.0, 260.0),
         }
     }
 
@@ -96,91 +119,190 @@ impl ArborApp {
             }
         }
 
-        // Analyze the symbol
-        if let Some(graph) = &self.graph {
-            let target = self.symbol_input.trim();
-
-            // Find the node
-            let node_idx = graph.get_index(target).or_else(|| {
+        // Find the node
+        let target = self.symbol_input.trim().to_string();
+        let node_idx = self.graph.as_ref().and_then(|graph| {
+            graph.get_index(&target).or_else(|| {
                 graph
-                    .find_by_name(target)
+                    .find_by_name(&target)
                     .first()
                     .and_then(|n| graph.get_index(&n.id))
-            });
-
-            match node_idx {
-                Some(idx) => {
-                    let node = graph.get(idx).unwrap();
-                    let analysis = graph.analyze_impact(idx, 5);
-
-                    let has_upstream = !analysis.upstream.is_empty();
-                    let has_downstream = !analysis.downstream.is_empty();
-
-                    let role = match (has_upstream, has_downstream) {
-                        (false, false) => "Isolated",
-                        (false, true) => "Entry Point",
-                        (true, false) => "Utility",
-                        (true, true) => "Core Logic",
-                    };
-
-                    let direct: Vec<_> = analysis
-                        .all_affected()
-                        .into_iter()
-                        .filter(|n| n.severity == arbor_graph::ImpactSeverity::Direct)
-                        .map(|n| format!("{} ({})", n.node_info.name, n.node_info.kind))
-                        .collect();
-
-                    let indirect: Vec<_> = analysis
-                        .all_affected()
-                        .into_iter()
-                        .filter(|n| n.severity != arbor_graph::ImpactSeverity::Direct)
-                        .map(|n| format!("{} ({} hops)", n.node_info.name, n.hop_distance))
-                        .collect();
-
-                    let downstream: Vec<_> = analysis
-                        .downstream
-                        .iter()
-                        .take(10)
-                        .map(|n| format!("{} ({})", n.node_info.name, n.entry_edge))
-                        .collect();
-
-                    self.result = Some(AnalysisResult {
-                        target_name: node.name.clone(),
-                        target_file: node.file.clone(),
-                        role: role.to_string(),
-                        direct_callers: direct,
-                        indirect_callers: indirect,
-                        downstream,
-                        total_affected: analysis.total_affected,
-                        confidence: if analysis.total_affected == 0 {
-                            "Low (no edges found)".to_string()
-                        } else {
-                            "High".to_string()
-                        },
-                    });
-
-                    self.status = format!("Analyzed '{}'", target);
+            })
+        });
 
-                    // Add to search history
-                    let query = target.to_string();
-                    if !self.search_history.contains(&query) {
-                        self.search_history.insert(0, query);
-                        if self.search_history.len() > 10 {
-                            self.search_history.pop();
-                        }
+        match node_idx {
+            Some(idx) => {
+                self.selection = self
+                    .graph
+                    .as_ref()
+                    .and_then(|graph| graph.get(idx))
+                    .map(|node| Selection::new(&node.id));
+                self.show_impact(idx);
+                self.status = format!("Analyzed '{}'", target);
+
+                // Add to search history
+                if !self.search_history.contains(&target) {
+                    self.search_history.insert(0, target);
+                    if self.search_history.len() > 10 {
+                        self.search_history.pop();
                     }
                 }
-                None => {
-                    self.result = None;
-                    self.status = format!(
-                        "Symbol '{}' not found. Try: arbor status --files to see indexed files.",
-                        target
-                    );
-                }
+            }
+            None => {
+                self.result = None;
+                self.selection = None;
+                self.status = format!(
+                    "Symbol '{}' not found. Try: arbor status --files to see indexed files.",
+                    target
+                );
             }
         }
     }
 
+    /// Analyzes the node at `idx` and shows it in the results and graph panel.
+    fn show_impact(&mut self, idx: NodeId) {
+        let Some(graph) = &self.graph else { return };
+        let Some(node) = graph.get(idx) else { return };
+        let analysis = graph.analyze_impact_cached(idx, 5);
+        let description = graph.describe(idx).unwrap();
+
+        let direct: Vec<_> = analysis
+            .all_affected()
+            .into_iter()
+            .filter(|n| n.severity == arbor_graph::ImpactSeverity::Direct)
+            .map(|n| format!("{} ({})", n.node_info.name, n.node_info.kind))
+            .collect();
+
+        let indirect: Vec<_> = analysis
+            .all_affected()
+            .into_iter()
+            .filter(|n| n.severity != arbor_graph::ImpactSeverity::Direct)
+            .map(|n| format!("{} ({} hops)", n.node_info.name, n.hop_distance))
+            .collect();
+
+        let downstream: Vec<_> = analysis
+            .downstream
+            .iter()
+            .take(10)
+            .map(|n| format!("{} ({})", n.node_info.name, n.entry_edge))
+            .collect();
+
+        let scene = Scene::from_impact(&node.name, &direct, &indirect, &downstream);
+
+        self.result = Some(AnalysisResult {
+            target_name: node.name.clone(),
+            target_file: node.file.clone(),
+            role: description.role.to_string(),
+            direct_callers: direct,
+            indirect_callers: indirect,
+            downstream,
+            total_affected: analysis.total_affected,
+            confidence: description.confidence.level.to_string(),
+            scene,
+        });
+        self.fit_pending = true;
+    }
+
+    /// Moves the selection along a call edge and analyzes the node it lands on.
+    fn navigate(&mut self, step: Step) {
+        let (Some(graph), Some(selection)) = (&self.graph, &self.selection) else { return };
+        let Some(next) = selection.step(graph, step) else { return };
+        let Some(idx) = graph.get_index(&next.node) else { return };
+
+        if let Some(node) = graph.get(idx) {
+            self.symbol_input = node.name.clone();
+            self.status = format!("Selected '{}'", node.name);
+        }
+        self.selection = Some(next);
+        self.show_impact(idx);
+    }
+
+    /// Centers the graph panel on the selected node, keeping the zoom.
+    fn center_selected(&mut self) {
+        if let Some(target) = self.result.as_ref().and_then(|r| r.scene.nodes.first()) {
+            self.viewport.center_on(target.pos, self.graph_size);
+        }
+    }
+
+    /// Draws the impact graph; drag to pan, scroll to zoom.
+    fn graph_panel(&mut self, ui: &mut egui::Ui) {
+        let Some(result) = &self.result else { return };
+
+        let size = egui::vec2(ui.available_width(), 260.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::drag());
+        let rect = response.rect;
+        self.graph_size = rect.size();
+
+        if self.fit_pending {
+            if let Some(bounds) = result.scene.bounds() {
+                self.viewport = Viewport::fit(bounds, rect.size());
+            }
+            self.fit_pending = false;
+        }
+        if response.dragged() {
+            self.viewport.offset += response.drag_delta();
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                self.viewport.zoom_around((pointer - rect.min).to_pos2(), (scroll * 0.002).exp());
+            }
+        }
+
+        painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+        let viewport = self.viewport;
+        let to_screen = |pos| rect.min + viewport.world_to_screen(pos).to_vec2();
+        let visible = export::visible_nodes(&result.scene, &viewport, rect.size());
+
+        for &(from, to) in &result.scene.edges {
+            if visible.contains(&from) || visible.contains(&to) {
+                painter.line_segment(
+                    [to_screen(result.scene.nodes[from].pos), to_screen(result.scene.nodes[to].pos)],
+                    egui::Stroke::new(1.5, egui::Color32::GRAY),
+                );
+            }
+        }
+        let radius = export::NODE_RADIUS * viewport.zoom;
+        let font = egui::FontId::proportional(12.0 * viewport.zoom.clamp(0.5, 2.0));
+        for &i in &visible {
+            let node = &result.scene.nodes[i];
+            let center = to_screen(node.pos);
+            painter.circle_filled(center, radius, node.color);
+            painter.text(
+                center + egui::vec2(0.0, radius + 2.0),
+                egui::Align2::CENTER_TOP,
+                &node.label,
+                font.clone(),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+
+    /// Writes the graph panel as it is currently shown to the working directory.
+    fn export_image(&mut self, png: bool) {
+        let Some(result) = &self.result else { return };
+
+        let svg = export::to_svg(&result.scene, &self.viewport, self.graph_size, self.dark_mode);
+        let stem: String = result
+            .target_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .collect();
+        let path = self
+            .cwd
+            .join(format!("arbor-{}.{}", stem, if png { "png" } else { "svg" }));
+
+        let outcome = if png {
+            export::write_png(&svg, &path)
+        } else {
+            std::fs::write(&path, svg).map_err(|e| e.to_string())
+        };
+        self.status = match outcome {
+            Ok(()) => format!("Exported {}", path.display()),
+            Err(e) => format!("Export failed: {}", e),
+        };
+    }
+
     fn copy_as_markdown(&self) -> String {
         if let Some(r) = &self.result {
             let mut md = format!("## Impact Analysis: {}\n\n", r.target_name);
@@ -223,6 +345,31 @@ impl eframe::App for ArborApp {
             ctx.set_visuals(egui::Visuals::light());
         }
 
+        // Keyboard shortcuts, unless a text field is being typed in
+        if !ctx.wants_keyboard_input() {
+            let (focus_search, center, step) = ctx.input(|i| {
+                let step = [
+                    (egui::Key::ArrowLeft, Step::Caller),
+                    (egui::Key::ArrowRight, Step::Callee),
+                    (egui::Key::ArrowDown, Step::Next),
+                    (egui::Key::ArrowUp, Step::Previous),
+                ]
+                .into_iter()
+                .find(|(key, _)| i.key_pressed(*key))
+                .map(|(_, step)| step);
+                (i.key_pressed(egui::Key::Slash), i.key_pressed(egui::Key::F), step)
+            });
+            if focus_search {
+                self.focus_search = true;
+            }
+            if center {
+                self.center_selected();
+            }
+            if let Some(step) = step {
+                self.navigate(step);
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // Header
             ui.horizontal(|ui| {
@@ -242,6 +389,10 @@ impl eframe::App for ArborApp {
             ui.horizontal(|ui| {
                 ui.label("Symbol:");
                 let response = ui.text_edit_singleline(&mut self.symbol_input);
+                if self.focus_search {
+                    response.request_focus();
+                    self.focus_search = false;
+                }
                 if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                     self.analyze();
                 }
@@ -280,6 +431,8 @@ impl eframe::App for ArborApp {
             
             let mut toggle_file_path = false;
             let mut toggle_hide_path = false;
+            let mut export_png = false;
+            let mut export_svg = false;
 
             if let Some((target_name, target_file, role, confidence, direct_callers, indirect_callers, downstream, total_affected)) = result_data {
                 egui::ScrollArea::vertical().show(ui, |ui| {
@@ -364,6 +517,21 @@ impl eframe::App for ArborApp {
 
                     ui.add_space(10.0);
 
+                    // Impact graph, exportable as shown
+                    ui.label(egui::RichText::new("Graph:").strong());
+                    ui.label(egui::RichText::new("Drag to pan, scroll to zoom. ←/→ callers/callees, ↑/↓ siblings, F to center, / to search.").small().weak());
+                    self.graph_panel(ui);
+                    ui.horizontal(|ui| {
+                        if ui.button("🖼 Export image (PNG)").clicked() {
+                            export_png = true;
+                        }
+                        if ui.button("🖼 Export image (SVG)").clicked() {
+                            export_svg = true;
+                        }
+                    });
+
+                    ui.add_space(10.0);
+
                     // Copy button
                     if ui.button("📋 Copy as Markdown").clicked() {
                         if let Ok(mut clipboard) = arboard::Clipboard::new() {
@@ -383,6 +551,12 @@ impl eframe::App for ArborApp {
                 if toggle_hide_path {
                     self.show_file_path = false;
                 }
+                if export_png {
+                    self.export_image(true);
+                }
+                if export_svg {
+                    self.export_image(false);
+                }
             } else {
                 ui.centered_and_justified(|ui| {
                     ui.label("Enter a function or class name above to analyze its impact.");
@@ -0,0 +1,343 @@
+//! Impact graph scene, viewport transform and image export
+//!
+//! The graph panel and the "Export image" buttons share one scene and one
+//! viewport, so an exported PNG or SVG shows exactly what is on screen:
+//! same zoom, same pan, and only the nodes inside the visible area.
+
+use eframe::egui::{Color32, Pos2, Rect, Vec2};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Node radius in world units.
+pub const NODE_RADIUS: f32 = 10.0;
+
+/// Horizontal gap between columns in world units.
+const COLUMN_GAP: f32 = 180.0;
+
+/// Vertical gap between rows in world units.
+const ROW_GAP: f32 = 40.0;
+
+/// A node placed in world space.
+pub struct SceneNode {
+    pub label: String,
+    pub pos: Pos2,
+    pub color: Color32,
+}
+
+/// The impact graph: target in the middle, callers to the left,
+/// dependencies to the right.
+#[derive(Default)]
+pub struct Scene {
+    pub nodes: Vec<SceneNode>,
+    /// Edges as (from, to) indexes into `nodes`, pointing in call direction.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Scene {
+    /// Lays out an analysis as columns: indirect callers, direct callers,
+    /// target, dependencies. Indirect callers are drawn without edges,
+    /// since the analysis doesn't keep the hop path that reached them.
+    pub fn from_impact(
+        target: &str,
+        direct: &[String],
+        indirect: &[String],
+        downstream: &[String],
+    ) -> Self {
+        let mut scene = Scene::default();
+        scene.nodes.push(SceneNode {
+            label: target.to_string(),
+            pos: Pos2::ZERO,
+            color: Color32::from_rgb(0x4a, 0x90, 0xd9),
+        });
+
+        let direct_ids =
+            scene.push_column(direct, -COLUMN_GAP, Color32::from_rgb(0xe7, 0x4c, 0x3c));
+        for &id in &direct_ids {
+            scene.edges.push((id, 0));
+        }
+
+        scene.push_column(
+            indirect,
+            -2.0 * COLUMN_GAP,
+            Color32::from_rgb(0xf3, 0x9c, 0x12),
+        );
+
+        let downstream_ids =
+            scene.push_column(downstream, COLUMN_GAP, Color32::from_rgb(0x95, 0xa5, 0xa6));
+        for &id in &downstream_ids {
+            scene.edges.push((0, id));
+        }
+
+        scene
+    }
+
+    /// Adds a vertically centered column of nodes at `x`, returning their indexes.
+    fn push_column(&mut self, labels: &[String], x: f32, color: Color32) -> Vec<usize> {
+        let top = -(labels.len().saturating_sub(1) as f32) * ROW_GAP / 2.0;
+        labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                self.nodes.push(SceneNode {
+                    label: label.clone(),
+                    pos: Pos2::new(x, top + i as f32 * ROW_GAP),
+                    color,
+                });
+                self.nodes.len() - 1
+            })
+            .collect()
+    }
+
+    /// World-space bounding box of all nodes, including their radius.
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut nodes = self.nodes.iter();
+        let first = nodes.next()?;
+        let mut rect = Rect::from_center_size(first.pos, Vec2::splat(NODE_RADIUS * 2.0));
+        for node in nodes {
+            rect = rect.union(Rect::from_center_size(
+                node.pos,
+                Vec2::splat(NODE_RADIUS * 2.0),
+            ));
+        }
+        Some(rect)
+    }
+}
+
+/// Pan and zoom of the graph panel.
+///
+/// Screen coordinates are relative to the panel's top-left corner, so the
+/// same viewport renders identically on screen and in an exported image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub offset: Vec2,
+    pub zoom: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Viewport {
+    /// Centers `bounds` in a panel of `size`, zooming out if needed.
+    pub fn fit(bounds: Rect, size: Vec2) -> Self {
+        let padding = 40.0;
+        let zoom = ((size.x - padding) / bounds.width())
+            .min((size.y - padding) / bounds.height())
+            .clamp(0.1, 1.5);
+        Self {
+            offset: size / 2.0 - bounds.center().to_vec2() * zoom,
+            zoom,
+        }
+    }
+
+    pub fn world_to_screen(&self, pos: Pos2) -> Pos2 {
+        Pos2::new(
+            pos.x * self.zoom + self.offset.x,
+            pos.y * self.zoom + self.offset.y,
+        )
+    }
+
+    /// Maps a world-space rectangle to panel coordinates.
+    pub fn world_rect_to_screen(&self, rect: Rect) -> Rect {
+        Rect::from_min_max(
+            self.world_to_screen(rect.min),
+            self.world_to_screen(rect.max),
+        )
+    }
+
+    /// Pans so the world point `pos` sits in the middle of a panel of
+    /// `size`, keeping the zoom.
+    pub fn center_on(&mut self, pos: Pos2, size: Vec2) {
+        self.offset = size / 2.0 - pos.to_vec2() * self.zoom;
+    }
+
+    /// Zooms by `factor`, keeping the world point under `anchor` fixed.
+    pub fn zoom_around(&mut self, anchor: Pos2, factor: f32) {
+        let zoom = (self.zoom * factor).clamp(0.1, 5.0);
+        let world = (anchor.to_vec2() - self.offset) / self.zoom;
+        self.offset = anchor.to_vec2() - world * zoom;
+        self.zoom = zoom;
+    }
+}
+
+/// Indexes of nodes whose circle intersects a panel of `size`.
+pub fn visible_nodes(scene: &Scene, viewport: &Viewport, size: Vec2) -> Vec<usize> {
+    let screen = Rect::from_min_size(Pos2::ZERO, size);
+    scene
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| {
+            let center = viewport.world_to_screen(node.pos);
+            screen.expand(NODE_RADIUS * viewport.zoom).contains(center)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Renders the visible part of the scene as an SVG document of `size`.
+pub fn to_svg(scene: &Scene, viewport: &Viewport, size: Vec2, dark: bool) -> String {
+    let (background, text) = if dark {
+        ("#1b1b1b", "#e0e0e0")
+    } else {
+        ("#f8f8f8", "#202020")
+    };
+    let hex = |c: Color32| format!("#{:02x}{:02x}{:02x}", c.r(), c.g(), c.b());
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+
+    let visible = visible_nodes(scene, viewport, size);
+    let radius = NODE_RADIUS * viewport.zoom;
+    let font_size = 12.0 * viewport.zoom.clamp(0.5, 2.0);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = size.x,
+        h = size.y
+    );
+    let _ = writeln!(
+        svg,
+        r#"  <rect width="100%" height="100%" fill="{}"/>"#,
+        background
+    );
+    for &(from, to) in &scene.edges {
+        if !visible.contains(&from) && !visible.contains(&to) {
+            continue;
+        }
+        let a = viewport.world_to_screen(scene.nodes[from].pos);
+        let b = viewport.world_to_screen(scene.nodes[to].pos);
+        let _ = writeln!(
+            svg,
+            r##"  <line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#888888" stroke-width="1.5"/>"##,
+            a.x, a.y, b.x, b.y
+        );
+    }
+    for &i in &visible {
+        let node = &scene.nodes[i];
+        let center = viewport.world_to_screen(node.pos);
+        let _ = writeln!(
+            svg,
+            r#"  <circle cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{}"/>"#,
+            center.x,
+            center.y,
+            radius,
+            hex(node.color)
+        );
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{:.1}" y="{:.1}" font-family="sans-serif" font-size="{:.1}" text-anchor="middle" fill="{}">{}</text>"#,
+            center.x,
+            center.y + radius + font_size,
+            font_size,
+            text,
+            escape(&node.label)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Rasterizes an SVG document and writes it to `path` as PNG.
+pub fn write_png(svg: &str, path: &Path) -> Result<(), String> {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = resvg::usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+
+    let size = tree.size().to_int_size();
+    let mut pixmap =
+        resvg::tiny_skia::Pixmap::new(size.width(), size.height()).ok_or("image has zero size")?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::identity(),
+        &mut pixmap.as_mut(),
+    );
+    pixmap.save_png(path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_scene() -> Scene {
+        Scene::from_impact(
+            "target",
+            &["caller".to_string()],
+            &[],
+            &["helper".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_indirect_callers_have_no_edges() {
+        let scene = Scene::from_impact(
+            "target",
+            &["a".to_string(), "b".to_string()],
+            &["c".to_string()],
+            &[],
+        );
+        // Which direct caller reaches `c` isn't known, so none is drawn
+        assert_eq!(scene.nodes.len(), 4);
+        assert_eq!(scene.edges, vec![(1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn test_world_to_screen_bounds() {
+        let scene = sample_scene();
+        let bounds = scene.bounds().unwrap();
+        assert_eq!(
+            bounds.min,
+            Pos2::new(-COLUMN_GAP - NODE_RADIUS, -NODE_RADIUS)
+        );
+        assert_eq!(bounds.max, Pos2::new(COLUMN_GAP + NODE_RADIUS, NODE_RADIUS));
+
+        let viewport = Viewport {
+            offset: Vec2::new(400.0, 300.0),
+            zoom: 2.0,
+        };
+        let screen = viewport.world_rect_to_screen(bounds);
+        assert_eq!(screen.min, Pos2::new(400.0 - 380.0, 300.0 - 20.0));
+        assert_eq!(screen.max, Pos2::new(400.0 + 380.0, 300.0 + 20.0));
+    }
+
+    #[test]
+    fn test_fit_centers_scene() {
+        let scene = sample_scene();
+        let bounds = scene.bounds().unwrap();
+        let size = Vec2::new(800.0, 600.0);
+
+        let viewport = Viewport::fit(bounds, size);
+        let screen = viewport.world_rect_to_screen(bounds);
+
+        assert!((screen.center() - Pos2::new(400.0, 300.0)).length() < 0.01);
+        assert!(Rect::from_min_size(Pos2::ZERO, size).contains_rect(screen));
+    }
+
+    #[test]
+    fn test_export_skips_offscreen_nodes() {
+        let scene = sample_scene();
+        // Zoomed in on the target: caller and helper fall outside the panel
+        let viewport = Viewport {
+            offset: Vec2::new(100.0, 100.0),
+            zoom: 1.0,
+        };
+        let size = Vec2::new(200.0, 200.0);
+
+        assert_eq!(visible_nodes(&scene, &viewport, size), vec![0]);
+
+        let svg = to_svg(&scene, &viewport, size, true);
+        assert_eq!(svg.matches("<circle").count(), 1);
+        assert!(svg.contains(">target</text>"));
+        assert!(!svg.contains(">caller</text>"));
+    }
+}
@@ -3,6 +3,8 @@
 //! A minimal, focused GUI for answering: "What breaks if I change this?"
 
 mod app;
+mod export;
+mod navigation;
 
 use eframe::egui;
 
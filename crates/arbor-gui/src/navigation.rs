@@ -0,0 +1,132 @@
+//! Keyboard navigation along call edges
+//!
+//! Left and right move the selection to the callers and callees of the
+//! selected node; up and down step through the nodes the last move chose
+//! from, so `→ ↓ ↓` visits the current node's first three callees.
+
+use arbor_graph::ArborGraph;
+
+/// One keyboard step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// First caller of the selected node.
+    Caller,
+    /// First callee of the selected node.
+    Callee,
+    /// Next node in the list the selection was picked from.
+    Next,
+    /// Previous node in that list.
+    Previous,
+}
+
+/// The selected node and the neighbor list it was picked from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selection {
+    /// Id of the selected node.
+    pub node: String,
+    /// Ids of the callers or callees the node was picked from, sorted by
+    /// name. Empty when the node was selected by searching.
+    siblings: Vec<String>,
+    /// Position of `node` in `siblings`.
+    index: usize,
+}
+
+impl Selection {
+    /// Selects `node` on its own, as a search does.
+    pub fn new(node: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Applies `step`, returning the new selection, or None when there is
+    /// nowhere to go (no callers, no callees, no other siblings).
+    pub fn step(&self, graph: &ArborGraph, step: Step) -> Option<Selection> {
+        match step {
+            Step::Caller | Step::Callee => {
+                let index = graph.get_index(&self.node)?;
+                let mut neighbors = if step == Step::Caller {
+                    graph.get_callers(index)
+                } else {
+                    graph.get_callees(index)
+                };
+                neighbors.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+                neighbors.dedup_by(|a, b| a.id == b.id);
+
+                let siblings: Vec<String> = neighbors.iter().map(|n| n.id.clone()).collect();
+                Some(Selection {
+                    node: siblings.first()?.clone(),
+                    siblings,
+                    index: 0,
+                })
+            }
+            Step::Next | Step::Previous => {
+                let count = self.siblings.len();
+                if count < 2 {
+                    return None;
+                }
+                let index = if step == Step::Next {
+                    (self.index + 1) % count
+                } else {
+                    (self.index + count - 1) % count
+                };
+                Some(Selection {
+                    node: self.siblings[index].clone(),
+                    siblings: self.siblings.clone(),
+                    index,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_core::{CodeNode, NodeKind};
+    use arbor_graph::GraphBuilder;
+
+    #[test]
+    fn test_steps_follow_call_edges() {
+        let function = |name: &str, calls: &[&str]| {
+            CodeNode::new(name, name, NodeKind::Function, "src/lib.rs")
+                .with_references(calls.iter().map(|c| c.to_string()).collect())
+        };
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            function("main", &["parse", "check", "emit"]),
+            function("parse", &[]),
+            function("check", &["parse"]),
+            function("emit", &[]),
+        ]);
+        let graph = builder.build();
+        let id = |name: &str| graph.find_by_name(name)[0].id.clone();
+        let main = Selection::new(id("main"));
+
+        // Next callee goes to the callees in name order, wrapping around
+        let callee = main.step(&graph, Step::Callee).unwrap();
+        assert_eq!(callee.node, id("check"));
+        let next = callee.step(&graph, Step::Next).unwrap();
+        assert_eq!(next.node, id("emit"));
+        let next = next.step(&graph, Step::Next).unwrap();
+        assert_eq!(next.node, id("parse"));
+        assert_eq!(next.step(&graph, Step::Next).unwrap().node, id("check"));
+        assert_eq!(
+            callee.step(&graph, Step::Previous).unwrap().node,
+            id("parse")
+        );
+
+        // `parse` is called by `check` and `main`
+        let caller = next.step(&graph, Step::Caller).unwrap();
+        assert_eq!(caller.node, id("check"));
+        assert_eq!(caller.step(&graph, Step::Next).unwrap().node, id("main"));
+
+        // Nowhere to go: no callers of main, no callees of emit, no siblings
+        assert!(main.step(&graph, Step::Caller).is_none());
+        assert!(Selection::new(id("emit"))
+            .step(&graph, Step::Callee)
+            .is_none());
+        assert!(main.step(&graph, Step::Next).is_none());
+    }
+}
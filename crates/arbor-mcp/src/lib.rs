@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::io::{self, BufRead, Write};
 
+use arbor_graph::MarkdownOptions;
 use arbor_server::{SharedGraph, SyncServerHandle};
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -85,19 +86,7 @@ impl McpServer {
                 continue;
             }
 
-            // Parse request
-            let req: JsonRpcRequest = match serde_json::from_str(&line) {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("Failed to parse input: {}", e);
-                    continue;
-                }
-            };
-
-            // Handle method
-            if let Some(response) = self.handle_request(req).await {
-                // Serialize and write
-                let json = serde_json::to_string(&response)?;
+            if let Some(json) = self.handle_line(&line).await? {
                 writeln!(stdout, "{}", json)?;
                 stdout.flush()?;
             }
@@ -105,6 +94,75 @@ impl McpServer {
         Ok(())
     }
 
+    /// Handles one line of input: a single request or a batch.
+    ///
+    /// A batch (JSON array) is answered with an array of responses in
+    /// request order. Notifications get no response, so a batch of only
+    /// notifications produces no output.
+    async fn handle_line(&self, line: &str) -> Result<Option<String>> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Failed to parse input: {}", e);
+                return Ok(None);
+            }
+        };
+
+        let batch = match value {
+            Value::Array(batch) => batch,
+            single => {
+                let response = match serde_json::from_value(single) {
+                    Ok(req) => self.handle_request(req).await,
+                    Err(e) => {
+                        eprintln!("Failed to parse input: {}", e);
+                        None
+                    }
+                };
+                return Ok(match response {
+                    Some(response) => Some(serde_json::to_string(&response)?),
+                    None => None,
+                });
+            }
+        };
+
+        // JSON-RPC answers an empty batch with one error, not an array
+        if batch.is_empty() {
+            let response = JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32600,
+                    message: "Invalid request: empty batch".to_string(),
+                    data: None,
+                }),
+                id: None,
+            };
+            return Ok(Some(serde_json::to_string(&response)?));
+        }
+
+        let mut responses = Vec::new();
+        for item in batch {
+            match serde_json::from_value::<JsonRpcRequest>(item) {
+                Ok(req) => responses.extend(self.handle_request(req).await),
+                Err(e) => responses.push(JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: -32600,
+                        message: format!("Invalid request: {}", e),
+                        data: None,
+                    }),
+                    id: None,
+                }),
+            }
+        }
+
+        if responses.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::to_string(&responses)?))
+    }
+
     async fn handle_request(&self, req: JsonRpcRequest) -> Option<JsonRpcResponse> {
         let id = req.id.clone();
 
@@ -176,7 +234,11 @@ impl McpServer {
                         "type": "object",
                         "properties": {
                             "node_id": { "type": "string", "description": "ID or name of the node to analyze" },
-                            "max_depth": { "type": "integer", "description": "Maximum hop distance (default: 5, 0 = unlimited)", "default": 5 }
+                            "max_depth": { "type": "integer", "description": "Maximum hop distance (default: 5, 0 = unlimited)", "default": 5 },
+                            "min_severity": { "type": "string", "enum": ["direct", "transitive", "distant"], "description": "Only return affected nodes at least this severe (default: all)" },
+                            "public_only": { "type": "boolean", "description": "Only return public affected nodes, i.e. externally observable breakage (default: false)", "default": false },
+                            "large_blast_radius": { "type": "integer", "description": "Affected nodes above which confidence drops to Medium (default: 20)", "default": 20 },
+                            "very_large_blast_radius": { "type": "integer", "description": "Affected nodes above which confidence drops to Low (default: 50)", "default": 50 }
                         },
                         "required": ["node_id"]
                     }
@@ -192,6 +254,31 @@ impl McpServer {
                         },
                         "required": ["start_node", "end_node"]
                     }
+                },
+                {
+                    "name": "explain_relationship",
+                    "description": "Explains why one node depends on another, e.g. 'A calls helper C, which uses type B'. Picks the shortest, most confident chain of edges.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "from": { "type": "string", "description": "Name or ID of the dependent node" },
+                            "to": { "type": "string", "description": "Name or ID of the node it may depend on" },
+                            "max_depth": { "type": "integer", "description": "Maximum hop distance (default: 6, 0 = unlimited)", "default": 6 }
+                        },
+                        "required": ["from", "to"]
+                    }
+                },
+                {
+                    "name": "node_at",
+                    "description": "Returns the innermost symbol defined at a file and line, e.g. the method under an editor cursor.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "file": { "type": "string", "description": "File path as indexed" },
+                            "line": { "type": "integer", "description": "1-based line number" }
+                        },
+                        "required": ["file", "line"]
+                    }
                 }
             ]
         }))
@@ -240,6 +327,36 @@ impl McpServer {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(5) as usize;
 
+                let min_severity = match arguments.get("min_severity").and_then(|v| v.as_str()) {
+                    Some(s) => match s.parse::<arbor_graph::ImpactSeverity>() {
+                        Ok(severity) => Some(severity),
+                        Err(message) => {
+                            return Err(JsonRpcError {
+                                code: -32602,
+                                message,
+                                data: None,
+                            })
+                        }
+                    },
+                    None => None,
+                };
+
+                let public_only = arguments
+                    .get("public_only")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                let mut confidence_config = arbor_graph::ConfidenceConfig::default();
+                if let Some(n) = arguments.get("large_blast_radius").and_then(|v| v.as_u64()) {
+                    confidence_config.large_blast_radius = n as usize;
+                }
+                if let Some(n) = arguments
+                    .get("very_large_blast_radius")
+                    .and_then(|v| v.as_u64())
+                {
+                    confidence_config.very_large_blast_radius = n as usize;
+                }
+
                 // Trigger Spotlight
                 self.trigger_spotlight(node_id).await;
 
@@ -255,12 +372,21 @@ impl McpServer {
 
                 match node_index {
                     Some(idx) => {
-                        let analysis = graph.analyze_impact(idx, max_depth);
+                        let mut analysis = graph.analyze_impact_cached(idx, max_depth);
+                        if let Some(min) = min_severity {
+                            analysis.retain_min_severity(min);
+                        }
+                        if public_only {
+                            analysis.retain_public();
+                        }
 
                         // Compute confidence and role
-                        let confidence =
-                            arbor_graph::ConfidenceExplanation::from_analysis(&analysis);
+                        let confidence = arbor_graph::ConfidenceExplanation::from_analysis_with(
+                            &analysis,
+                            &confidence_config,
+                        );
                         let role = arbor_graph::NodeRole::from_analysis(&analysis);
+                        let warnings = graph.impact_limitations(&analysis);
 
                         // Build structured response
                         let upstream: Vec<Value> = analysis
@@ -310,6 +436,7 @@ impl McpServer {
                                         "reasons": confidence.reasons
                                     },
                                     "role": role.to_string(),
+                                    "warnings": warnings,
                                     "upstream": upstream,
                                     "downstream": downstream,
                                     "total_affected": analysis.total_affected,
@@ -321,6 +448,9 @@ impl McpServer {
                                         analysis.downstream.len()
                                     )
                                 })).unwrap_or_default()
+                            }, {
+                                "type": "text",
+                                "text": analysis.to_markdown(&MarkdownOptions::default())
                             }]
                         }))
                     }
@@ -381,6 +511,70 @@ impl McpServer {
                     }),
                 }
             }
+            "explain_relationship" => {
+                let from = arguments.get("from").and_then(|v| v.as_str()).unwrap_or("");
+                let to = arguments.get("to").and_then(|v| v.as_str()).unwrap_or("");
+                let max_depth = arguments
+                    .get("max_depth")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(6) as usize;
+
+                let graph = self.graph.read().await;
+
+                let from_idx = graph.get_index(from).or_else(|| {
+                    graph
+                        .find_by_name(from)
+                        .first()
+                        .and_then(|n| graph.get_index(&n.id))
+                });
+                let to_idx = graph.get_index(to).or_else(|| {
+                    graph
+                        .find_by_name(to)
+                        .first()
+                        .and_then(|n| graph.get_index(&n.id))
+                });
+
+                match (from_idx, to_idx) {
+                    (Some(u), Some(v)) => {
+                        let text = match graph.explain_relationship(u, v, max_depth) {
+                            Some(path) => arbor_graph::format_relationship(&path),
+                            None => format!(
+                                "No dependency from `{}` to `{}` within {} hops. \
+                                 Try swapping the nodes or raising max_depth.",
+                                from, to, max_depth
+                            ),
+                        };
+                        Ok(json!({
+                            "content": [{ "type": "text", "text": text }]
+                        }))
+                    }
+                    _ => Err(JsonRpcError {
+                        code: -32602,
+                        message: "Could not resolve from or to node.".to_string(),
+                        data: None,
+                    }),
+                }
+            }
+            "node_at" => {
+                let file = arguments.get("file").and_then(|v| v.as_str()).unwrap_or("");
+                let line = arguments.get("line").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+
+                let graph = self.graph.read().await;
+
+                let text = match graph.node_at(file, line) {
+                    Some(node) => {
+                        let mut info = arbor_graph::NodeInfo::from(node);
+                        if let Some(idx) = graph.get_index(&node.id) {
+                            info.centrality = graph.centrality_or_default(idx);
+                        }
+                        serde_json::to_string_pretty(&info).unwrap_or_default()
+                    }
+                    None => format!("No symbol found at {}:{}", file, line),
+                };
+                Ok(json!({
+                    "content": [{ "type": "text", "text": text }]
+                }))
+            }
             _ => Err(JsonRpcError {
                 code: -32601,
                 message: format!("Tool not found: {}", name),
@@ -415,59 +609,90 @@ impl McpServer {
             }
         };
 
-        // 2. Extract Data
-        let node = graph.get(node_idx).unwrap();
-        let callers = graph.get_callers(node_idx);
-        let callees = graph.get_callees(node_idx);
-        let centrality = graph.centrality(node_idx);
-
-        // 3. Format Output (The "Architectural Brief" with Markdown Tables)
-        let mut brief = String::new();
-
-        brief.push_str(&format!("# Architectural Brief: `{}`\n\n", node.name));
-        brief.push_str(&format!("| Property | Value |\n"));
-        brief.push_str(&format!("|----------|-------|\n"));
-        brief.push_str(&format!("| **Type** | {} |\n", node.kind));
-        brief.push_str(&format!("| **File** | `{}` |\n", node.file));
-        brief.push_str(&format!("| **Impact Level** | {:.2} |\n", centrality));
-        if let Some(sig) = &node.signature {
-            brief.push_str(&format!("| **Signature** | `{}` |\n", sig));
-        }
-
-        // Dependencies Table
-        brief.push_str("\n## Dependencies (Callees)\n\n");
-        if callees.is_empty() {
-            brief.push_str("*None - This is a leaf node.*\n");
+        // 2. Format the "Architectural Brief" from a one-hop impact analysis
+        let brief = graph
+            .analyze_impact_cached(node_idx, 1)
+            .to_markdown(&MarkdownOptions {
+                include_reasons: false,
+                include_confidence: true,
+                compact: false,
+                confidence: Default::default(),
+            });
+
+        // The brief shows a centrality column; say so when it's all zeros
+        if graph.centrality_computed() {
+            brief
         } else {
-            brief.push_str("| Symbol | Type | Impact | File |\n");
-            brief.push_str("|--------|------|--------|------|\n");
-            for callee in callees {
-                let callee_idx = graph.get_index(&callee.id);
-                let impact = callee_idx.map(|idx| graph.centrality(idx)).unwrap_or(0.0);
-                brief.push_str(&format!(
-                    "| `{}` | {} | {:.2} | `{}` |\n",
-                    callee.name, callee.kind, impact, callee.file
-                ));
-            }
+            format!(
+                "> Centrality not computed; impact scores below are placeholders.\n\n{}",
+                brief
+            )
         }
+    }
+}
 
-        // Usage Table
-        brief.push_str("\n## Usage (Callers)\n\n");
-        if callers.is_empty() {
-            brief.push_str("*None - Potential entry point or dead code.*\n");
-        } else {
-            brief.push_str("| Symbol | Type | Impact | File |\n");
-            brief.push_str("|--------|------|--------|------|\n");
-            for caller in callers {
-                let caller_idx = graph.get_index(&caller.id);
-                let impact = caller_idx.map(|idx| graph.centrality(idx)).unwrap_or(0.0);
-                brief.push_str(&format!(
-                    "| `{}` | {} | {:.2} | `{}` |\n",
-                    caller.name, caller.kind, impact, caller.file
-                ));
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_graph::ArborGraph;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    #[tokio::test]
+    async fn test_empty_batch_is_invalid_request() {
+        let server = McpServer::new(Arc::new(RwLock::new(ArborGraph::new())));
+
+        let json = server.handle_line("[]").await.unwrap().unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(response["error"]["code"], -32600);
+        assert!(response["id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_context_flags_missing_centrality() {
+        use arbor_core::{CodeNode, NodeKind};
+
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "login",
+            "login",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+        let server = McpServer::new(Arc::new(RwLock::new(graph)));
+
+        let context = server.generate_context("login").await;
+        assert!(
+            context.starts_with("> Centrality not computed"),
+            "{}",
+            context
+        );
+
+        server
+            .graph
+            .write()
+            .await
+            .set_centrality(Default::default());
+        let context = server.generate_context("login").await;
+        assert!(!context.contains("Centrality not computed"), "{}", context);
+    }
 
-        brief
+    #[tokio::test]
+    async fn test_batch_omits_notifications() {
+        let server = McpServer::new(Arc::new(RwLock::new(ArborGraph::new())));
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "tools/list"},
+            {"jsonrpc": "2.0", "method": "notifications/initialized"},
+            {"jsonrpc": "2.0", "id": 2, "method": "resources/list"}
+        ]"#;
+
+        let json = server.handle_line(batch).await.unwrap().unwrap();
+        let responses: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert!(responses[0]["result"]["tools"].is_array());
+        assert_eq!(responses[1]["id"], 2);
     }
 }
@@ -0,0 +1,102 @@
+//! Minimal client for pulling a graph off a running server.
+//!
+//! Used by `arbor diff --against-server` to compare the working tree
+//! against what a server currently has indexed.
+
+use arbor_graph::ArborGraph;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// Fetches a server's graph via the `graph.export` method.
+///
+/// `url` is the server's WebSocket endpoint, e.g. `ws://127.0.0.1:7432`.
+pub async fn fetch_graph(
+    url: &str,
+    token: Option<&str>,
+) -> Result<ArborGraph, Box<dyn std::error::Error + Send + Sync>> {
+    let (mut ws, _) = connect_async(url).await?;
+
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "graph.export",
+        "params": { "token": token }
+    });
+    ws.send(Message::Text(request.to_string())).await?;
+
+    let reply = loop {
+        match ws.next().await {
+            Some(Ok(Message::Text(text))) => break text,
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err("Server closed the connection without replying".into()),
+        }
+    };
+    ws.close(None).await.ok();
+
+    let response: Value = serde_json::from_str(&reply)?;
+    if let Some(error) = response.get("error") {
+        return Err(format!(
+            "Server error {}: {}",
+            error["code"],
+            error["message"].as_str().unwrap_or("unknown")
+        )
+        .into());
+    }
+
+    let snapshot = response["result"]["snapshot"]
+        .as_str()
+        .ok_or("Response has no snapshot")?;
+    let bytes = BASE64.decode(snapshot.as_bytes())?;
+
+    Ok(ArborGraph::from_snapshot(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArborServer, ServerConfig};
+    use arbor_core::{CodeNode, NodeKind};
+
+    #[tokio::test]
+    async fn test_fetch_graph_from_running_server() {
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "validate",
+            "validate",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = ArborServer::new(graph, ServerConfig::default());
+        tokio::spawn(async move { server.run_on(listener).await.ok() });
+
+        let fetched = fetch_graph(&url, None).await.unwrap();
+        assert_eq!(fetched.node_count(), 1);
+        assert_eq!(fetched.find_by_name("validate").len(), 1);
+
+        // A local tree with one more function shows up as an addition
+        let mut local = ArborGraph::new();
+        local.add_node(CodeNode::new(
+            "validate",
+            "validate",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+        local.add_node(CodeNode::new(
+            "login",
+            "login",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+        let diff = fetched.diff(&local);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].name, "login");
+        assert!(diff.removed.is_empty());
+    }
+}
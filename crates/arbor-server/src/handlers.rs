@@ -3,9 +3,12 @@
 //! Each handler implements one method from the Arbor Protocol.
 
 use crate::protocol::{
-    ContextParams, DiscoverParams, ImpactParams, NodeGetParams, Response, SearchParams,
+    ContextParams, DiscoverParams, GraphImportParams, ImpactParams, NodeExpandParams,
+    NodeGetParams, Response, SearchParams,
 };
 use arbor_graph::{ArborGraph, NodeInfo};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use serde::Serialize;
 use serde_json::Value;
 use std::sync::Arc;
@@ -16,6 +19,16 @@ use tracing::debug;
 /// Shared state between handlers.
 pub type SharedGraph = Arc<RwLock<ArborGraph>>;
 
+/// Turns a `limit` param into a count, with 0 meaning unlimited (as with
+/// the CLI's `--limit`).
+fn limit_or_all(limit: usize) -> usize {
+    if limit == 0 {
+        usize::MAX
+    } else {
+        limit
+    }
+}
+
 /// Handles the graph.info method.
 pub async fn handle_info(graph: SharedGraph, id: Option<Value>) -> Response {
     let g = graph.read().await;
@@ -57,7 +70,7 @@ pub async fn handle_discover(
         .search(&params.query)
         .into_iter()
         .map(|node| {
-            let centrality = g.centrality(g.get_index(&node.id).unwrap_or_default());
+            let centrality = g.centrality_or_default(g.get_index(&node.id).unwrap_or_default());
             let mut info = NodeInfo::from(node);
             info.centrality = centrality;
             info
@@ -72,7 +85,7 @@ pub async fn handle_discover(
     });
 
     // Limit results
-    matches.truncate(params.limit);
+    matches.truncate(limit_or_all(params.limit));
 
     #[derive(Serialize)]
     struct DiscoverResult {
@@ -154,7 +167,7 @@ pub async fn handle_context(
         .search(&params.task)
         .into_iter()
         .map(|node| {
-            let centrality = g.centrality(g.get_index(&node.id).unwrap_or_default());
+            let centrality = g.centrality_or_default(g.get_index(&node.id).unwrap_or_default());
             let mut info = NodeInfo::from(node);
             info.centrality = centrality;
             info
@@ -179,7 +192,15 @@ pub async fn handle_context(
             break;
         }
         total_tokens += estimated_tokens;
-        selected.push(node);
+
+        let mut value = serde_json::to_value(&node).unwrap_or(Value::Null);
+        if params.include_source {
+            value["source"] = read_snippet(&node.file, node.line_start, node.line_end)
+                .await
+                .map(Value::String)
+                .unwrap_or(Value::Null);
+        }
+        selected.push(value);
     }
 
     Response::success(
@@ -218,7 +239,7 @@ pub async fn handle_search(
         .collect();
 
     let total = matches.len();
-    matches.truncate(params.limit);
+    matches.truncate(limit_or_all(params.limit));
 
     Response::success(
         id,
@@ -274,3 +295,158 @@ pub async fn handle_node_get(
         None => Response::error(id, -32001, format!("Node not found: {}", params.id)),
     }
 }
+
+/// Handles the node.expand method: one page of a node's neighbors.
+pub async fn handle_node_expand(
+    graph: SharedGraph,
+    id: Option<Value>,
+    params: NodeExpandParams,
+) -> Response {
+    let g = graph.read().await;
+
+    let Some(idx) = g.get_index(&params.id) else {
+        return Response::error(id, -32001, format!("Node not found: {}", params.id));
+    };
+    let page = g.neighbors_paged(idx, params.cursor, params.limit);
+
+    let neighbors: Vec<_> = page
+        .neighbors
+        .iter()
+        .map(|n| {
+            serde_json::json!({
+                "node": n.node,
+                "edgeKind": n.edge_kind.to_string(),
+                "direction": n.direction.to_string(),
+            })
+        })
+        .collect();
+
+    Response::success(
+        id,
+        serde_json::json!({
+            "neighbors": neighbors,
+            "total": page.total,
+            "nextCursor": page.next_cursor,
+        }),
+    )
+}
+
+/// Handles the node.source method.
+///
+/// Only routed when the server exposes source; see `ServerConfig::expose_source`.
+pub async fn handle_node_source(
+    graph: SharedGraph,
+    id: Option<Value>,
+    params: NodeGetParams,
+) -> Response {
+    let g = graph.read().await;
+
+    let node = match g.get_by_id(&params.id) {
+        Some(node) => node,
+        None => return Response::error(id, -32001, format!("Node not found: {}", params.id)),
+    };
+
+    match read_snippet(&node.file, node.line_start, node.line_end).await {
+        Some(source) => Response::success(
+            id,
+            serde_json::json!({
+                "id": node.id,
+                "file": node.file,
+                "lineStart": node.line_start,
+                "lineEnd": node.line_end,
+                "source": source
+            }),
+        ),
+        None => Response::internal_error(id, format!("Could not read {}", node.file)),
+    }
+}
+
+/// Handles the graph.export method.
+///
+/// Returns the whole graph as a base64 `.arbg` snapshot, unless it is
+/// larger than `max_bytes`.
+pub async fn handle_graph_export(
+    graph: SharedGraph,
+    id: Option<Value>,
+    max_bytes: usize,
+) -> Response {
+    let g = graph.read().await;
+
+    let bytes = match g.to_snapshot() {
+        Ok(bytes) => bytes,
+        Err(e) => return Response::internal_error(id, e.to_string()),
+    };
+    if bytes.len() > max_bytes {
+        return Response::payload_too_large(
+            id,
+            format!(
+                "Snapshot is {} bytes, over the {} byte limit",
+                bytes.len(),
+                max_bytes
+            ),
+        );
+    }
+
+    Response::success(
+        id,
+        serde_json::json!({
+            "snapshot": BASE64.encode(&bytes),
+            "bytes": bytes.len(),
+            "nodeCount": g.node_count(),
+            "edgeCount": g.edge_count()
+        }),
+    )
+}
+
+/// Handles the graph.import method.
+///
+/// Replaces the served graph with the one in the snapshot.
+pub async fn handle_graph_import(
+    graph: SharedGraph,
+    id: Option<Value>,
+    params: GraphImportParams,
+    max_bytes: usize,
+) -> Response {
+    // Reject before decoding; base64 encodes 3 bytes in 4 characters
+    let decoded_len = params.snapshot.len() / 4 * 3;
+    if decoded_len > max_bytes {
+        return Response::payload_too_large(
+            id,
+            format!(
+                "Snapshot is about {} bytes, over the {} byte limit",
+                decoded_len, max_bytes
+            ),
+        );
+    }
+
+    let bytes = match BASE64.decode(params.snapshot.as_bytes()) {
+        Ok(bytes) => bytes,
+        Err(e) => return Response::invalid_params(id, format!("Invalid base64: {}", e)),
+    };
+    let imported = match ArborGraph::from_snapshot(&bytes) {
+        Ok(imported) => imported,
+        Err(e) => return Response::invalid_params(id, format!("Invalid snapshot: {}", e)),
+    };
+
+    let (node_count, edge_count) = (imported.node_count(), imported.edge_count());
+    *graph.write().await = imported;
+
+    Response::success(
+        id,
+        serde_json::json!({
+            "nodeCount": node_count,
+            "edgeCount": edge_count
+        }),
+    )
+}
+
+/// Reads lines `start..=end` (1-indexed) of a file.
+async fn read_snippet(file: &str, start: u32, end: u32) -> Option<String> {
+    let text = tokio::fs::read_to_string(file).await.ok()?;
+    let lines: Vec<&str> = text
+        .lines()
+        .skip(start.saturating_sub(1) as usize)
+        .take(end.saturating_sub(start) as usize + 1)
+        .collect();
+    Some(lines.join("\n"))
+}
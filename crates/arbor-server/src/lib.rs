@@ -8,6 +8,7 @@
 //! - JSON-RPC 2.0 messages
 //! - Real-time graph updates via subscriptions
 //! - File watching with debounced re-indexing
+//! - Periodic re-indexing as a fallback for missed file events
 
 use arbor_graph::ArborGraph;
 use std::sync::Arc;
@@ -25,14 +26,23 @@ pub enum ServerMessage {
     IndexerStatus,
 }
 
+mod client;
 mod handlers;
+mod metrics;
 mod protocol;
+mod reindex;
 mod server;
+mod snapshot;
 pub mod sync_server;
+mod tls;
 
+pub use client::fetch_graph;
+pub use metrics::Metrics;
 pub use protocol::{Request, Response, RpcError};
 pub use server::{ArborServer, ServerConfig};
+pub use snapshot::write_snapshot;
 pub use sync_server::{
     BroadcastMessage, FocusNodePayload, GraphUpdatePayload, IndexerStatusPayload, SyncServer,
     SyncServerConfig, SyncServerHandle,
 };
+pub use tls::TlsConfig;
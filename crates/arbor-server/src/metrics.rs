@@ -0,0 +1,280 @@
+//! Prometheus metrics for the server.
+//!
+//! A deliberately small registry: a handful of atomics plus one
+//! latency histogram per JSON-RPC method, rendered in the Prometheus
+//! text exposition format on demand. Served over plain HTTP when the
+//! server is started with a metrics address.
+
+use crate::SharedGraph;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, error, info};
+
+/// Upper bounds (in seconds) of the latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+/// Latency histogram for a single method.
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    /// Cumulative count per bucket in `LATENCY_BUCKETS`.
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// Server metrics registry.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    queries_total: AtomicU64,
+    active_connections: AtomicI64,
+    last_reindex_ms: AtomicU64,
+    latencies: Mutex<BTreeMap<String, Histogram>>,
+}
+
+impl Metrics {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a handled JSON-RPC request.
+    pub fn record_query(&self, method: &str, elapsed: Duration) {
+        self.queries_total.fetch_add(1, Ordering::Relaxed);
+        let mut latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        latencies
+            .entry(method.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Marks a client connection as opened.
+    pub fn connection_opened(&self) {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Marks a client connection as closed.
+    pub fn connection_closed(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Records how long the most recent (re)index took.
+    pub fn record_reindex(&self, elapsed: Duration) {
+        self.last_reindex_ms
+            .store(elapsed.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self, node_count: usize, edge_count: usize) -> String {
+        let mut out = String::new();
+
+        write_metric(
+            &mut out,
+            "arbor_queries_total",
+            "counter",
+            "Total JSON-RPC requests handled.",
+            self.queries_total.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut out,
+            "arbor_active_connections",
+            "gauge",
+            "Currently open client connections.",
+            self.active_connections.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut out,
+            "arbor_graph_nodes",
+            "gauge",
+            "Nodes in the code graph.",
+            node_count,
+        );
+        write_metric(
+            &mut out,
+            "arbor_graph_edges",
+            "gauge",
+            "Edges in the code graph.",
+            edge_count,
+        );
+        write_metric(
+            &mut out,
+            "arbor_last_reindex_duration_seconds",
+            "gauge",
+            "Duration of the most recent index run.",
+            self.last_reindex_ms.load(Ordering::Relaxed) as f64 / 1000.0,
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP arbor_query_duration_seconds JSON-RPC request latency by method."
+        );
+        let _ = writeln!(out, "# TYPE arbor_query_duration_seconds histogram");
+        let latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        for (method, histogram) in latencies.iter() {
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "arbor_query_duration_seconds_bucket{{method=\"{}\",le=\"{}\"}} {}",
+                    method, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "arbor_query_duration_seconds_bucket{{method=\"{}\",le=\"+Inf\"}} {}",
+                method, histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "arbor_query_duration_seconds_sum{{method=\"{}\"}} {}",
+                method, histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "arbor_query_duration_seconds_count{{method=\"{}\"}} {}",
+                method, histogram.count
+            );
+        }
+
+        out
+    }
+}
+
+/// Writes a single unlabelled metric with its HELP and TYPE lines.
+fn write_metric(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    value: impl std::fmt::Display,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Serves `GET /metrics` over plain HTTP, forever.
+pub async fn serve_metrics(
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+    graph: SharedGraph,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    info!(
+        "Metrics endpoint on http://{}/metrics",
+        listener.local_addr()?
+    );
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                let metrics = metrics.clone();
+                let graph = graph.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_metrics_request(stream, metrics, graph).await {
+                        debug!("Metrics request from {} failed: {}", addr, e);
+                    }
+                });
+            }
+            Err(e) => {
+                error!("Metrics accept error: {}", e);
+            }
+        }
+    }
+}
+
+/// Answers a single HTTP request and closes the connection.
+async fn handle_metrics_request(
+    mut stream: TcpStream,
+    metrics: Arc<Metrics>,
+    graph: SharedGraph,
+) -> std::io::Result<()> {
+    let mut buf = [0u8; 1024];
+    let n = stream.read(&mut buf).await?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let request_line = request.lines().next().unwrap_or("");
+
+    let response = if request_line.starts_with("GET /metrics ") {
+        let body = {
+            let g = graph.read().await;
+            metrics.render(g.node_count(), g.edge_count())
+        };
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    } else {
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    };
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_graph::ArborGraph;
+    use tokio::sync::RwLock;
+
+    #[test]
+    fn test_render_exposition_format() {
+        let metrics = Metrics::new();
+        metrics.connection_opened();
+        metrics.record_query("search", Duration::from_millis(3));
+        metrics.record_reindex(Duration::from_millis(1500));
+
+        let text = metrics.render(10, 4);
+
+        assert!(text.contains("# TYPE arbor_queries_total counter"));
+        assert!(text.contains("arbor_queries_total 1"));
+        assert!(text.contains("arbor_active_connections 1"));
+        assert!(text.contains("arbor_graph_nodes 10"));
+        assert!(text.contains("arbor_graph_edges 4"));
+        assert!(text.contains("arbor_last_reindex_duration_seconds 1.5"));
+        assert!(text.contains("# TYPE arbor_query_duration_seconds histogram"));
+        assert!(
+            text.contains("arbor_query_duration_seconds_bucket{method=\"search\",le=\"0.001\"} 0")
+        );
+        assert!(
+            text.contains("arbor_query_duration_seconds_bucket{method=\"search\",le=\"0.005\"} 1")
+        );
+        assert!(text.contains("arbor_query_duration_seconds_count{method=\"search\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let graph = Arc::new(RwLock::new(ArborGraph::new()));
+        tokio::spawn(serve_metrics(listener, Arc::new(Metrics::new()), graph));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("text/plain; version=0.0.4"));
+        assert!(response.contains("arbor_queries_total 0"));
+        assert!(response.contains("arbor_graph_nodes 0"));
+    }
+}
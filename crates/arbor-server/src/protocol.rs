@@ -87,6 +87,16 @@ impl Response {
         Self::error(id, -32602, message)
     }
 
+    /// Predefined error: Permission denied (e.g. source access disabled).
+    pub fn permission_denied(id: Option<Value>, message: impl Into<String>) -> Self {
+        Self::error(id, -32002, message)
+    }
+
+    /// Predefined error: Payload too large (snapshots over the size limit).
+    pub fn payload_too_large(id: Option<Value>, message: impl Into<String>) -> Self {
+        Self::error(id, -32003, message)
+    }
+
     /// Predefined error: Internal error.
     pub fn internal_error(id: Option<Value>, message: impl Into<String>) -> Self {
         Self::error(id, -32603, message)
@@ -130,7 +140,7 @@ pub struct ContextParams {
     #[serde(default = "default_max_tokens", rename = "maxTokens")]
     pub max_tokens: usize,
     #[serde(default, rename = "includeSource")]
-    pub _include_source: bool,
+    pub include_source: bool,
 }
 
 /// Params for the search method.
@@ -142,12 +152,43 @@ pub struct SearchParams {
     pub limit: usize,
 }
 
+/// Params for the graph.export method.
+#[derive(Debug, Default, Deserialize)]
+pub struct GraphExportParams {
+    /// Auth token, required when the server has one set.
+    pub token: Option<String>,
+}
+
+/// Params for the graph.import method.
+#[derive(Debug, Deserialize)]
+pub struct GraphImportParams {
+    /// Base64-encoded `.arbg` snapshot.
+    pub snapshot: String,
+    /// Auth token, required when the server has one set.
+    pub token: Option<String>,
+}
+
 /// Params for node.get method.
 #[derive(Debug, Deserialize)]
 pub struct NodeGetParams {
     pub id: String,
 }
 
+/// Params for the node.expand method.
+#[derive(Debug, Deserialize)]
+pub struct NodeExpandParams {
+    pub id: String,
+    /// Where to resume, from the previous page's `nextCursor`.
+    #[serde(default)]
+    pub cursor: usize,
+    #[serde(default = "default_expand_limit")]
+    pub limit: usize,
+}
+
+fn default_expand_limit() -> usize {
+    100
+}
+
 fn default_limit() -> usize {
     10
 }
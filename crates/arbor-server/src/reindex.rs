@@ -0,0 +1,123 @@
+//! Periodic re-indexing.
+//!
+//! File watching misses changes on network mounts, inside containers, and
+//! from tools that replace files behind the watcher's back. A fixed-interval
+//! re-index is the safety net: with a cache it only re-parses what changed.
+
+use crate::handlers::SharedGraph;
+use crate::metrics::Metrics;
+use crate::server::ArborServer;
+use arbor_watcher::{index_directory, IndexOptions};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, warn};
+
+impl ArborServer {
+    /// Re-indexes `root` every `interval`, replacing the served graph.
+    ///
+    /// Set `options.cache_path` so each pass is incremental. Returns None
+    /// (and starts nothing) when `interval` is zero.
+    pub fn spawn_reindex(
+        &self,
+        root: PathBuf,
+        options: IndexOptions,
+        interval: Duration,
+    ) -> Option<JoinHandle<()>> {
+        if interval.is_zero() {
+            return None;
+        }
+
+        Some(tokio::spawn(reindex_loop(
+            self.graph(),
+            self.metrics(),
+            root,
+            options,
+            interval,
+        )))
+    }
+}
+
+async fn reindex_loop(
+    graph: SharedGraph,
+    metrics: Arc<Metrics>,
+    root: PathBuf,
+    options: IndexOptions,
+    interval: Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    // A pass that outlasts the interval delays the next one instead of
+    // letting the missed ticks fire back to back
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick fires immediately; the graph is already fresh then
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        let (root, options) = (root.clone(), options.clone());
+        let result = tokio::task::spawn_blocking(move || index_directory(&root, options)).await;
+
+        match result {
+            Ok(Ok(result)) => {
+                debug!(
+                    "Periodic re-index: {} parsed, {} cached, {} removed",
+                    result.files_indexed, result.cache_hits, result.files_removed
+                );
+                metrics.record_reindex(Duration::from_millis(result.duration_ms));
+                *graph.write().await = result.graph;
+            }
+            Ok(Err(e)) => warn!("Periodic re-index failed: {}", e),
+            Err(e) => warn!("Periodic re-index task panicked: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ServerConfig;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_interval_picks_up_new_files() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("a.rs"), "fn alpha() {}\n").unwrap();
+
+        let options = IndexOptions {
+            cache_path: Some(dir.path().join("cache")),
+            ..Default::default()
+        };
+        let initial = index_directory(&src, options.clone()).unwrap();
+        let server = ArborServer::new(initial.graph, ServerConfig::default());
+        assert!(server
+            .spawn_reindex(src.clone(), options.clone(), Duration::ZERO)
+            .is_none());
+
+        let handle = server
+            .spawn_reindex(src.clone(), options, Duration::from_millis(50))
+            .unwrap();
+
+        // Not seen by any watcher; only the interval can find it
+        fs::write(src.join("b.rs"), "fn beta() {}\n").unwrap();
+
+        let graph = server.graph();
+        let mut found = false;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if !graph.read().await.find_by_name("beta").is_empty() {
+                found = true;
+                break;
+            }
+        }
+        handle.abort();
+
+        assert!(found, "periodic re-index never picked up b.rs");
+        assert_eq!(graph.read().await.find_by_name("alpha").len(), 1);
+    }
+}
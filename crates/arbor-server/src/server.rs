@@ -3,53 +3,184 @@
 //! Handles client connections and routes messages to handlers.
 
 use crate::handlers::{
-    handle_context, handle_discover, handle_impact, handle_info, handle_node_get, handle_search,
+    handle_context, handle_discover, handle_graph_export, handle_graph_import, handle_impact,
+    handle_info, handle_node_expand, handle_node_get, handle_node_source, handle_search,
     SharedGraph,
 };
+use crate::metrics::{serve_metrics, Metrics};
 use crate::protocol::{
-    ContextParams, DiscoverParams, ImpactParams, NodeGetParams, Request, Response, SearchParams,
+    ContextParams, DiscoverParams, GraphExportParams, GraphImportParams, ImpactParams,
+    NodeExpandParams, NodeGetParams, Request, Response, SearchParams,
 };
+use crate::tls::{load_acceptor, TlsConfig};
 use arbor_graph::ArborGraph;
 use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
 use tokio::sync::RwLock;
-use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tokio_tungstenite::accept_hdr_async;
+use tokio_tungstenite::tungstenite::handshake::server::{
+    ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+};
+use tokio_tungstenite::tungstenite::http::{HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, error, info, warn};
 
+/// Error message for source requests on a read-only server.
+const SOURCE_DISABLED: &str = "Source access is disabled on this server";
+
+/// Error message for snapshot requests with a missing or wrong token.
+const UNAUTHORIZED: &str = "A valid auth token is required";
+
+/// Error message for snapshot requests to a public server with no token.
+const SNAPSHOTS_DISABLED: &str =
+    "Snapshots are disabled on this server; start it with an auth token to enable them";
+
 /// Server configuration.
 pub struct ServerConfig {
     /// Address to bind to.
     pub addr: SocketAddr,
+
+    /// Address for the Prometheus `/metrics` endpoint.
+    /// If None, metrics are not served.
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Whether clients may read file contents (`node.source`,
+    /// `context` with `includeSource`). When false only structural graph
+    /// data is served, so a public server can't leak secrets from files.
+    pub expose_source: bool,
+
+    /// Token clients must pass to `graph.export` and `graph.import`.
+    /// If None, snapshots need no token on a loopback address and are
+    /// refused on any other, so a public server never swaps or hands out
+    /// its whole graph to anyone who asks.
+    pub auth_token: Option<String>,
+
+    /// Largest `.arbg` snapshot accepted or returned, in bytes.
+    pub max_snapshot_bytes: usize,
+
+    /// Certificate and key for serving `wss://`.
+    /// If None, connections are plain `ws://`.
+    pub tls: Option<TlsConfig>,
+
+    /// Browser origins allowed to connect (`*` allows any). If None, only
+    /// pages served from localhost are. Clients that send no `Origin`
+    /// header, such as CLI tools and editors, are always allowed.
+    pub cors_origins: Option<Vec<String>>,
+
+    /// Close connections that send nothing for this long.
+    /// If None, idle connections stay open.
+    pub idle_timeout: Option<Duration>,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             addr: "127.0.0.1:7432".parse().unwrap(),
+            metrics_addr: None,
+            expose_source: true,
+            auth_token: None,
+            max_snapshot_bytes: 64 * 1024 * 1024,
+            tls: None,
+            cors_origins: None,
+            idle_timeout: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Checks that `token` grants access to snapshot methods, returning
+    /// the message to deny the request with if not.
+    fn authorize(&self, token: Option<&str>) -> Result<(), &'static str> {
+        match &self.auth_token {
+            Some(expected) if token == Some(expected.as_str()) => Ok(()),
+            Some(_) => Err(UNAUTHORIZED),
+            None if self.addr.ip().is_loopback() => Ok(()),
+            None => Err(SNAPSHOTS_DISABLED),
         }
     }
+
+    /// Returns true if a browser page from `origin` may connect.
+    fn origin_allowed(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/');
+        match &self.cors_origins {
+            Some(allowed) => allowed
+                .iter()
+                .any(|a| a == "*" || a.trim_end_matches('/').eq_ignore_ascii_case(origin)),
+            None => is_localhost_origin(origin),
+        }
+    }
+}
+
+/// Returns true for `http(s)://localhost`, `127.0.0.1` or `[::1]` on any port.
+fn is_localhost_origin(origin: &str) -> bool {
+    let Some((_, rest)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = match rest.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or(""),
+        None => rest.split(':').next().unwrap_or(""),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Applies the CORS origin policy to a WebSocket handshake.
+///
+/// Browsers send `Origin` on WebSocket upgrades but don't enforce CORS on
+/// them, so the server must: a disallowed origin gets `403 Forbidden`, and
+/// an allowed one is echoed back in `Access-Control-Allow-Origin`.
+fn check_origin(
+    request: &HandshakeRequest,
+    mut response: HandshakeResponse,
+    config: &ServerConfig,
+) -> Result<HandshakeResponse, ErrorResponse> {
+    let Some(origin) = request.headers().get("origin") else {
+        return Ok(response);
+    };
+
+    let origin = origin.to_str().unwrap_or_default();
+    if !config.origin_allowed(origin) {
+        warn!("Rejected connection from origin {}", origin);
+        let mut forbidden = ErrorResponse::new(Some(format!("Origin {} is not allowed", origin)));
+        *forbidden.status_mut() = StatusCode::FORBIDDEN;
+        return Err(forbidden);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(origin) {
+        response
+            .headers_mut()
+            .insert("access-control-allow-origin", value);
+    }
+    Ok(response)
 }
 
 /// The Arbor WebSocket server.
 pub struct ArborServer {
-    config: ServerConfig,
+    config: Arc<ServerConfig>,
     graph: SharedGraph,
+    metrics: Arc<Metrics>,
 }
 
 impl ArborServer {
     /// Creates a new server with the given graph.
     pub fn new(graph: ArborGraph, config: ServerConfig) -> Self {
-        Self {
-            config,
-            graph: Arc::new(RwLock::new(graph)),
-        }
+        Self::new_with_shared(Arc::new(RwLock::new(graph)), config)
     }
 
     /// Creates a new server with an existing shared graph handle.
     pub fn new_with_shared(graph: SharedGraph, config: ServerConfig) -> Self {
-        Self { config, graph }
+        Self {
+            config: Arc::new(config),
+            graph,
+            metrics: Arc::new(Metrics::new()),
+        }
     }
 
     /// Returns a handle to the shared graph for updates.
@@ -57,18 +188,69 @@ impl ArborServer {
         self.graph.clone()
     }
 
+    /// Returns a handle to the server's metrics registry.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.metrics.clone()
+    }
+
     /// Runs the server, accepting connections forever.
     pub async fn run(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let listener = TcpListener::bind(&self.config.addr).await?;
-        info!("Arbor server listening on {}", self.config.addr);
+        self.run_on(listener).await
+    }
+
+    /// Like `run`, but accepts on a listener the caller already bound,
+    /// e.g. to port 0 with the address read back from `local_addr`.
+    /// `config.addr` is ignored.
+    pub async fn run_on(
+        &self,
+        listener: TcpListener,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(metrics_addr) = self.config.metrics_addr {
+            let metrics_listener = TcpListener::bind(metrics_addr).await?;
+            let metrics = self.metrics.clone();
+            let graph = self.graph.clone();
+            tokio::spawn(async move {
+                if let Err(e) = serve_metrics(metrics_listener, metrics, graph).await {
+                    error!("Metrics endpoint error: {}", e);
+                }
+            });
+        }
+
+        let acceptor = self.config.tls.as_ref().map(load_acceptor).transpose()?;
+
+        let scheme = if acceptor.is_some() { "wss" } else { "ws" };
+        info!(
+            "Arbor server listening on {}://{}",
+            scheme,
+            listener.local_addr()?
+        );
 
         loop {
             match listener.accept().await {
                 Ok((stream, addr)) => {
                     debug!("New connection from {}", addr);
                     let graph = self.graph.clone();
+                    let metrics = self.metrics.clone();
+                    let config = self.config.clone();
+                    let acceptor = acceptor.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, addr, graph).await {
+                        metrics.connection_opened();
+                        let result = match acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(stream) => {
+                                    handle_connection(stream, addr, graph, metrics.clone(), config)
+                                        .await
+                                }
+                                Err(e) => Err(e.into()),
+                            },
+                            None => {
+                                handle_connection(stream, addr, graph, metrics.clone(), config)
+                                    .await
+                            }
+                        };
+                        metrics.connection_closed();
+                        if let Err(e) = result {
                             error!("Connection error from {}: {}", addr, e);
                         }
                     });
@@ -79,20 +261,64 @@ impl ArborServer {
             }
         }
     }
+
+    /// Runs the server until `shutdown` resolves (e.g. on Ctrl+C).
+    ///
+    /// Returns Ok once `shutdown` fires; connections still open are
+    /// dropped. Errors from the server itself are returned as from `run`.
+    pub async fn run_until(
+        &self,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tokio::select! {
+            result = self.run() => result,
+            () = shutdown => {
+                info!("Shutting down");
+                Ok(())
+            }
+        }
+    }
 }
 
-/// Handles a single WebSocket connection.
-async fn handle_connection(
-    stream: TcpStream,
+/// Handles a single WebSocket connection over plain TCP or TLS.
+async fn handle_connection<S>(
+    stream: S,
     addr: SocketAddr,
     graph: SharedGraph,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ws_stream = accept_async(stream).await?;
+    metrics: Arc<Metrics>,
+    config: Arc<ServerConfig>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let ws_stream = accept_hdr_async(stream, |request: &HandshakeRequest, response| {
+        check_origin(request, response, &config)
+    })
+    .await?;
     info!("WebSocket connection established with {}", addr);
 
     let (mut write, mut read) = ws_stream.split();
 
-    while let Some(msg) = read.next().await {
+    loop {
+        let next = match config.idle_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, read.next()).await {
+                Ok(next) => next,
+                Err(_) => {
+                    info!("Closing idle connection from {}", addr);
+                    let frame = CloseFrame {
+                        code: CloseCode::Normal,
+                        reason: "idle timeout".into(),
+                    };
+                    write.send(Message::Close(Some(frame))).await.ok();
+                    break;
+                }
+            },
+            None => read.next().await,
+        };
+        let Some(msg) = next else {
+            break;
+        };
+
         let msg = match msg {
             Ok(m) => m,
             Err(e) => {
@@ -113,9 +339,9 @@ async fn handle_connection(
 
         if msg.is_text() {
             let text = msg.to_text().unwrap_or("");
-            let response = process_message(text, graph.clone()).await;
-            let json = serde_json::to_string(&response)?;
-            write.send(Message::Text(json)).await?;
+            if let Some(json) = process_message(text, graph.clone(), &metrics, &config).await? {
+                write.send(Message::Text(json)).await?;
+            }
         }
     }
 
@@ -123,12 +349,61 @@ async fn handle_connection(
     Ok(())
 }
 
-/// Processes a JSON-RPC message and returns a response.
-async fn process_message(text: &str, graph: SharedGraph) -> Response {
-    // Parse the request
-    let request: Request = match serde_json::from_str(text) {
+/// Processes a JSON-RPC message and returns the serialized reply.
+///
+/// The message is either a single request or a batch (a JSON array of
+/// requests). A batch gets an array of responses in request order, with
+/// notifications left out; if nothing is left there is no reply at all.
+async fn process_message(
+    text: &str,
+    graph: SharedGraph,
+    metrics: &Metrics,
+    config: &ServerConfig,
+) -> Result<Option<String>, serde_json::Error> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return serde_json::to_string(&Response::parse_error()).map(Some),
+    };
+
+    let batch = match value {
+        Value::Array(batch) => batch,
+        single => {
+            let response = process_value(single, graph, metrics, config).await;
+            return serde_json::to_string(&response).map(Some);
+        }
+    };
+
+    if batch.is_empty() {
+        return serde_json::to_string(&Response::invalid_request(None)).map(Some);
+    }
+
+    let mut responses = Vec::with_capacity(batch.len());
+    for item in batch {
+        // Malformed entries still get an error, even without an id
+        let notification = item.get("id").map_or(true, Value::is_null)
+            && serde_json::from_value::<Request>(item.clone()).is_ok();
+        let response = process_value(item, graph.clone(), metrics, config).await;
+        if !notification {
+            responses.push(response);
+        }
+    }
+
+    if responses.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(&responses).map(Some)
+}
+
+/// Processes a single JSON-RPC request.
+async fn process_value(
+    value: Value,
+    graph: SharedGraph,
+    metrics: &Metrics,
+    config: &ServerConfig,
+) -> Response {
+    let request: Request = match serde_json::from_value(value) {
         Ok(r) => r,
-        Err(_) => return Response::parse_error(),
+        Err(_) => return Response::invalid_request(None),
     };
 
     let id = request.id.clone();
@@ -136,8 +411,10 @@ async fn process_message(text: &str, graph: SharedGraph) -> Response {
 
     debug!("Processing method: {}", method);
 
+    let start = Instant::now();
+
     // Route to handler
-    match method {
+    let response = match method {
         "graph.info" => handle_info(graph, id).await,
 
         "discover" => match serde_json::from_value::<DiscoverParams>(request.params) {
@@ -151,6 +428,9 @@ async fn process_message(text: &str, graph: SharedGraph) -> Response {
         },
 
         "context" => match serde_json::from_value::<ContextParams>(request.params) {
+            Ok(params) if params.include_source && !config.expose_source => {
+                Response::permission_denied(id, SOURCE_DISABLED)
+            }
             Ok(params) => handle_context(graph, id, params).await,
             Err(e) => Response::invalid_params(id, e.to_string()),
         },
@@ -165,6 +445,396 @@ async fn process_message(text: &str, graph: SharedGraph) -> Response {
             Err(e) => Response::invalid_params(id, e.to_string()),
         },
 
+        "node.expand" => match serde_json::from_value::<NodeExpandParams>(request.params) {
+            Ok(params) => handle_node_expand(graph, id, params).await,
+            Err(e) => Response::invalid_params(id, e.to_string()),
+        },
+
+        "node.source" if !config.expose_source => Response::permission_denied(id, SOURCE_DISABLED),
+
+        "node.source" => match serde_json::from_value::<NodeGetParams>(request.params) {
+            Ok(params) => handle_node_source(graph, id, params).await,
+            Err(e) => Response::invalid_params(id, e.to_string()),
+        },
+
+        "graph.export" => {
+            let params =
+                serde_json::from_value::<GraphExportParams>(request.params).unwrap_or_default();
+            match config.authorize(params.token.as_deref()) {
+                Ok(()) => handle_graph_export(graph, id, config.max_snapshot_bytes).await,
+                Err(message) => Response::permission_denied(id, message),
+            }
+        }
+
+        "graph.import" => match serde_json::from_value::<GraphImportParams>(request.params) {
+            Ok(params) => match config.authorize(params.token.as_deref()) {
+                Ok(()) => handle_graph_import(graph, id, params, config.max_snapshot_bytes).await,
+                Err(message) => Response::permission_denied(id, message),
+            },
+            Err(e) => Response::invalid_params(id, e.to_string()),
+        },
+
         _ => Response::method_not_found(id, method),
+    };
+
+    // Unknown methods share one label so clients can't blow up cardinality
+    let label = match &response.error {
+        Some(e) if e.code == -32601 => "unknown",
+        _ => method,
+    };
+    metrics.record_query(label, start.elapsed());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arbor_core::{CodeNode, NodeKind};
+
+    fn shared_graph() -> SharedGraph {
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "validate",
+            "validate",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+        Arc::new(RwLock::new(graph))
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_response_per_request() {
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "graph.info"},
+            {"jsonrpc": "2.0", "id": "two", "method": "search", "params": {"query": "validate"}},
+            {"jsonrpc": "2.0", "id": 3, "method": "no.such.method"}
+        ]"#;
+
+        let json = process_message(
+            batch,
+            shared_graph(),
+            &Metrics::new(),
+            &ServerConfig::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let responses: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert!(responses[0]["result"].is_object());
+        assert_eq!(responses[1]["id"], "two");
+        assert!(responses[1]["result"].is_object());
+        assert_eq!(responses[2]["id"], 3);
+        assert_eq!(responses[2]["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn test_search_limit_zero_returns_all() {
+        let graph = shared_graph();
+        for i in 0..12 {
+            graph.write().await.add_node(CodeNode::new(
+                &format!("validate_{}", i),
+                &format!("validate_{}", i),
+                NodeKind::Function,
+                "auth.rs",
+            ));
+        }
+        let search = |limit: &str| {
+            format!(
+                r#"{{"jsonrpc": "2.0", "id": 1, "method": "search", "params": {{"query": "validate"{}}}}}"#,
+                limit
+            )
+        };
+        let count = |request: String| {
+            let graph = graph.clone();
+            async move {
+                let json =
+                    process_message(&request, graph, &Metrics::new(), &ServerConfig::default())
+                        .await
+                        .unwrap()
+                        .unwrap();
+                let response: Value = serde_json::from_str(&json).unwrap();
+                assert_eq!(response["result"]["total"], 13);
+                response["result"]["nodes"].as_array().unwrap().len()
+            }
+        };
+
+        assert_eq!(count(search("")).await, 10);
+        assert_eq!(count(search(r#", "limit": 3"#)).await, 3);
+        assert_eq!(count(search(r#", "limit": 0"#)).await, 13);
+    }
+
+    #[tokio::test]
+    async fn test_batch_omits_notifications() {
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "graph.info"},
+            {"jsonrpc": "2.0", "method": "graph.info"},
+            42
+        ]"#;
+
+        let json = process_message(
+            batch,
+            shared_graph(),
+            &Metrics::new(),
+            &ServerConfig::default(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let responses: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["error"]["code"], -32600);
+
+        let only_notifications = r#"[{"jsonrpc": "2.0", "method": "graph.info"}]"#;
+        let reply = process_message(
+            only_notifications,
+            shared_graph(),
+            &Metrics::new(),
+            &ServerConfig::default(),
+        )
+        .await
+        .unwrap();
+        assert!(reply.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_source_requests() {
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "node.source", "params": {"id": "x"}},
+            {"jsonrpc": "2.0", "id": 2, "method": "context", "params": {"task": "validate", "includeSource": true}},
+            {"jsonrpc": "2.0", "id": 3, "method": "context", "params": {"task": "validate"}}
+        ]"#;
+
+        let config = ServerConfig {
+            expose_source: false,
+            ..Default::default()
+        };
+        let json = process_message(batch, shared_graph(), &Metrics::new(), &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let responses: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(responses[0]["error"]["code"], -32002);
+        assert_eq!(responses[1]["error"]["code"], -32002);
+        // Structural data is still served
+        assert!(responses[2]["result"]["nodes"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import_round_trips_graph() {
+        let config = ServerConfig {
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let source = shared_graph();
+        let metrics = Metrics::new();
+
+        let denied = r#"{"jsonrpc": "2.0", "id": 1, "method": "graph.export"}"#;
+        let json = process_message(denied, source.clone(), &metrics, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(response["error"]["code"], -32002);
+
+        let export = r#"{"jsonrpc": "2.0", "id": 2, "method": "graph.export", "params": {"token": "secret"}}"#;
+        let json = process_message(export, source.clone(), &metrics, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+        let snapshot = response["result"]["snapshot"].as_str().unwrap();
+
+        let target: SharedGraph = Arc::new(RwLock::new(ArborGraph::new()));
+        let import = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "graph.import",
+            "params": {"snapshot": snapshot, "token": "secret"}
+        })
+        .to_string();
+        let json = process_message(&import, target.clone(), &metrics, &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(response["result"]["nodeCount"], 1);
+
+        let (original, restored) = (source.read().await, target.read().await);
+        assert_eq!(restored.node_count(), original.node_count());
+        assert_eq!(restored.edge_count(), original.edge_count());
+        let node = &original.find_by_name("validate")[0];
+        assert!(restored.get_by_id(&node.id).is_some());
+        assert_eq!(restored.search("validate").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_public_server_without_token_refuses_snapshots() {
+        let config = ServerConfig {
+            addr: "0.0.0.0:7432".parse().unwrap(),
+            ..Default::default()
+        };
+        let batch = r#"[
+            {"jsonrpc": "2.0", "id": 1, "method": "graph.export"},
+            {"jsonrpc": "2.0", "id": 2, "method": "graph.import", "params": {"snapshot": ""}}
+        ]"#;
+
+        let graph = shared_graph();
+        let json = process_message(batch, graph.clone(), &Metrics::new(), &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let responses: Vec<Value> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(responses[0]["error"]["code"], -32002);
+        assert_eq!(responses[1]["error"]["code"], -32002);
+        assert_eq!(graph.read().await.node_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_size_limit() {
+        let config = ServerConfig {
+            max_snapshot_bytes: 16,
+            ..Default::default()
+        };
+        let export = r#"{"jsonrpc": "2.0", "id": 1, "method": "graph.export"}"#;
+
+        let json = process_message(export, shared_graph(), &Metrics::new(), &config)
+            .await
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(response["error"]["code"], -32003);
+    }
+
+    #[tokio::test]
+    async fn test_impact_on_graph_loaded_from_snapshot() {
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("validate", "validate", NodeKind::Function, "auth.rs"),
+            CodeNode::new("login", "login", NodeKind::Function, "session.rs")
+                .with_references(vec!["validate".to_string()]),
+        ]);
+        let bytes = builder.build().to_snapshot().unwrap();
+
+        // What `arbor serve --graph graph.arbg` hands the server
+        let loaded = ArborGraph::from_snapshot(&bytes).unwrap();
+        let validate_id = loaded.find_by_name("validate")[0].id.clone();
+        let graph: SharedGraph = Arc::new(RwLock::new(loaded));
+
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "impact",
+            "params": {"node": validate_id}
+        })
+        .to_string();
+        let json = process_message(&request, graph, &Metrics::new(), &ServerConfig::default())
+            .await
+            .unwrap()
+            .unwrap();
+        let response: Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(response["result"]["target"]["name"], "validate");
+        assert_eq!(response["result"]["totalAffected"], 1);
+        assert_eq!(response["result"]["dependents"][0]["node"]["name"], "login");
+    }
+
+    #[test]
+    fn test_cors_origin_policy() {
+        let handshake = |origin: &str, config: &ServerConfig| {
+            let request = HandshakeRequest::builder()
+                .uri("/")
+                .header("Origin", origin)
+                .body(())
+                .unwrap();
+            check_origin(&request, HandshakeResponse::new(()), config)
+        };
+        let allow_origin = |response: &HandshakeResponse| {
+            response
+                .headers()
+                .get("access-control-allow-origin")
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        let config = ServerConfig {
+            cors_origins: Some(vec!["https://tools.example.com".to_string()]),
+            ..Default::default()
+        };
+        let allowed = handshake("https://tools.example.com", &config).unwrap();
+        assert_eq!(
+            allow_origin(&allowed).as_deref(),
+            Some("https://tools.example.com")
+        );
+        let rejected = handshake("https://evil.example.com", &config).unwrap_err();
+        assert_eq!(rejected.status(), StatusCode::FORBIDDEN);
+        assert!(rejected
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+
+        // Default: localhost pages only
+        let default = ServerConfig::default();
+        assert!(handshake("http://localhost:3000", &default).is_ok());
+        assert!(handshake("http://127.0.0.1:8080", &default).is_ok());
+        assert!(handshake("https://example.com", &default).is_err());
+        assert!(handshake("http://localhost.example.com", &default).is_err());
+
+        // No Origin header: not a browser, so no policy applies
+        let request = HandshakeRequest::builder().uri("/").body(()).unwrap();
+        assert!(check_origin(&request, HandshakeResponse::new(()), &default).is_ok());
+
+        let any = ServerConfig {
+            cors_origins: Some(vec!["*".to_string()]),
+            ..Default::default()
+        };
+        assert!(handshake("https://example.com", &any).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_idle_connection_closed_after_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let config = ServerConfig {
+            idle_timeout: Some(Duration::from_millis(300)),
+            ..Default::default()
+        };
+        let server = ArborServer::new_with_shared(shared_graph(), config);
+        tokio::spawn(async move { server.run_on(listener).await.ok() });
+
+        let connect = || async {
+            let (ws, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+            ws
+        };
+        let mut idle = connect().await;
+        let mut active = connect().await;
+
+        // Twice the timeout passes, but the active client keeps talking
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "graph.info"}"#;
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            active
+                .send(Message::Text(request.to_string()))
+                .await
+                .unwrap();
+            let reply = active.next().await.unwrap().unwrap();
+            assert!(reply.is_text());
+        }
+
+        let closed = tokio::time::timeout(Duration::from_secs(1), idle.next())
+            .await
+            .expect("idle connection was not closed");
+        match closed {
+            Some(Ok(Message::Close(Some(frame)))) => {
+                assert_eq!(frame.code, CloseCode::Normal);
+                assert_eq!(frame.reason, "idle timeout");
+            }
+            other => panic!("expected a close frame, got {:?}", other),
+        }
     }
 }
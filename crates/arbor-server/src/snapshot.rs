@@ -0,0 +1,77 @@
+//! Snapshot on shutdown.
+//!
+//! Indexing a large repository takes a while; loading an `.arbg` snapshot
+//! with `serve --graph` doesn't. Writing one when the server stops cleanly
+//! makes the next start instant and records exactly the graph that was
+//! served.
+
+use crate::handlers::SharedGraph;
+use std::path::Path;
+use tracing::info;
+
+/// Writes `graph` to `path` as an `.arbg` snapshot and returns its size.
+///
+/// The snapshot goes to a temporary file first and is renamed into place,
+/// so a server starting with `--graph` never reads a half-written one.
+pub async fn write_snapshot(
+    graph: &SharedGraph,
+    path: &Path,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = graph.read().await.to_snapshot()?;
+
+    let tmp_path = path.with_extension("arbg.tmp");
+    tokio::fs::write(&tmp_path, &bytes).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+
+    info!(
+        "Wrote snapshot to {} ({} bytes)",
+        path.display(),
+        bytes.len()
+    );
+    Ok(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{ArborServer, ServerConfig};
+    use arbor_core::{CodeNode, NodeKind};
+    use arbor_graph::{ArborGraph, GraphBuilder};
+
+    #[tokio::test]
+    async fn test_snapshot_written_after_shutdown() {
+        let mut builder = GraphBuilder::new();
+        builder.add_nodes(vec![
+            CodeNode::new("main", "main", NodeKind::Function, "main.rs")
+                .with_references(vec!["helper".to_string()]),
+            CodeNode::new("helper", "helper", NodeKind::Function, "main.rs"),
+        ]);
+        let config = ServerConfig {
+            addr: "127.0.0.1:0".parse().unwrap(),
+            ..Default::default()
+        };
+        let server = ArborServer::new(builder.build(), config);
+
+        // Simulated Ctrl+C: shut down as soon as the server is running
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        stop.send(()).unwrap();
+        server
+            .run_until(async {
+                stopped.await.ok();
+            })
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.arbg");
+        let written = write_snapshot(&server.graph(), &path).await.unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), written);
+        assert!(!dir.path().join("graph.arbg.tmp").exists());
+
+        let restored = ArborGraph::from_snapshot(&bytes).unwrap();
+        assert_eq!(restored.node_count(), 2);
+        assert_eq!(restored.edge_count(), 1);
+    }
+}
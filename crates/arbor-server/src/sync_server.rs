@@ -365,10 +365,9 @@ async fn handle_client(
     let (node_count, edge_count, nodes, edges) = {
         let g = graph.read().await;
         let mut nodes: Vec<_> = g.nodes().cloned().collect();
-        let edges_raw = g.export_edges();
+        let mut edges: Vec<_> = g.edges_iter().collect();
         // Sort for deterministic output (run twice = identical)
         nodes.sort_by(|a, b| a.id.cmp(&b.id));
-        let mut edges = edges_raw;
         edges.sort_by(|a, b| (&a.source, &a.target).cmp(&(&b.source, &b.target)));
         (g.node_count(), g.edge_count(), nodes, edges)
     };
@@ -666,7 +665,7 @@ async fn run_background_indexer(
                                 .unwrap()
                                 .as_secs(),
                             nodes: Some(g.nodes().cloned().collect()),
-                            edges: Some(g.export_edges()),
+                            edges: Some(g.edges_iter().collect()),
                         });
 
                         let _ = broadcast_tx.send(update);
@@ -695,7 +694,7 @@ async fn run_background_indexer(
                         .unwrap()
                         .as_secs(),
                     nodes: Some(g.nodes().cloned().collect()),
-                    edges: Some(g.export_edges()),
+                    edges: Some(g.edges_iter().collect()),
                 });
 
                 let _ = broadcast_tx.send(update);
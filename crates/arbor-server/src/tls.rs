@@ -0,0 +1,116 @@
+//! TLS support for serving `wss://`.
+//!
+//! Certificates and keys are read from PEM files once at startup; a bad
+//! file fails `ArborServer::run` instead of every handshake.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+
+/// PEM certificate chain and private key for `wss://`.
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// Certificate chain, leaf first.
+    pub cert: PathBuf,
+    /// Private key (PKCS#8, PKCS#1 or SEC1).
+    pub key: PathBuf,
+}
+
+/// Builds a TLS acceptor from the configured PEM files.
+pub(crate) fn load_acceptor(
+    config: &TlsConfig,
+) -> Result<TlsAcceptor, Box<dyn std::error::Error + Send + Sync>> {
+    let mut cert_reader = BufReader::new(File::open(&config.cert)?);
+    let certs = rustls_pemfile::certs(&mut cert_reader).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(format!("No certificates in {}", config.cert.display()).into());
+    }
+
+    let mut key_reader = BufReader::new(File::open(&config.key)?);
+    let key = rustls_pemfile::private_key(&mut key_reader)?
+        .ok_or_else(|| format!("No private key in {}", config.key.display()))?;
+
+    let server_config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArborServer, ServerConfig};
+    use arbor_core::{CodeNode, NodeKind};
+    use arbor_graph::ArborGraph;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::Value;
+    use tokio::net::TcpStream;
+    use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName};
+    use tokio_rustls::TlsConnector;
+    use tokio_tungstenite::{client_async, tungstenite::Message};
+
+    #[tokio::test]
+    async fn test_tls_handshake_and_rpc() {
+        // Self-signed certificate for localhost
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let tls = TlsConfig {
+            cert: dir.join("cert.pem"),
+            key: dir.join("key.pem"),
+        };
+        std::fs::write(&tls.cert, cert.serialize_pem().unwrap()).unwrap();
+        std::fs::write(&tls.key, cert.serialize_private_key_pem()).unwrap();
+
+        let mut graph = ArborGraph::new();
+        graph.add_node(CodeNode::new(
+            "validate",
+            "validate",
+            NodeKind::Function,
+            "auth.rs",
+        ));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = ArborServer::new(
+            graph,
+            ServerConfig {
+                tls: Some(tls),
+                ..Default::default()
+            },
+        );
+        tokio::spawn(async move { server.run_on(listener).await.ok() });
+
+        // Trust only our certificate
+        let mut roots = rustls::RootCertStore::empty();
+        roots
+            .add(CertificateDer::from(cert.serialize_der().unwrap()))
+            .unwrap();
+        let client_config = rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connector = TlsConnector::from(Arc::new(client_config));
+
+        let tcp = TcpStream::connect(addr).await.unwrap();
+
+        let server_name = ServerName::try_from("localhost").unwrap();
+        let tls_stream = connector.connect(server_name, tcp).await.unwrap();
+        let (mut ws, _) = client_async("wss://localhost/", tls_stream).await.unwrap();
+
+        let request = r#"{"jsonrpc": "2.0", "id": 1, "method": "graph.info"}"#;
+        ws.send(Message::Text(request.to_string())).await.unwrap();
+        let reply = loop {
+            match ws.next().await.unwrap().unwrap() {
+                Message::Text(text) => break text,
+                _ => continue,
+            }
+        };
+
+        let response: Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(response["result"]["nodeCount"], 1);
+    }
+}
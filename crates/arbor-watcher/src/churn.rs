@@ -0,0 +1,231 @@
+//! Churn from git history.
+//!
+//! Reads `git log -p -U0` and turns every hunk into a `ChurnHunk` in the
+//! file's new numbering, for `ArborGraph::hotspots`. Hunks from older
+//! commits are placed by the line numbers of their own commit, so when a
+//! later commit shifts lines above them they drift a little; over tens of
+//! commits that's a fair trade for not replaying history.
+
+use arbor_graph::{ChurnHunk, FileChurn};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Collects changed hunks for files under `root`.
+///
+/// Reads the commits in `since..HEAD` when `since` is given, otherwise the
+/// history leading to `HEAD`, at most `max_commits` either way (0 means no
+/// limit). Paths are relative to `root`. Returns `Ok(None)` when `root`
+/// isn't inside a git work tree or git isn't installed, so callers can fall
+/// back to centrality alone; a bad `since` ref is an error.
+pub fn git_churn(
+    root: &Path,
+    since: Option<&str>,
+    max_commits: usize,
+) -> io::Result<Option<FileChurn>> {
+    let inside = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output();
+    match inside {
+        Ok(output) if output.status.success() => {}
+        _ => return Ok(None),
+    }
+
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotePath=false", "log", "-p", "-U0"])
+        .args(["--no-color", "--no-renames", "--no-prefix", "--relative"])
+        .arg("--format=commit %H");
+    if max_commits > 0 {
+        command.arg(format!("--max-count={}", max_commits));
+    }
+    if let Some(since) = since {
+        command.arg(format!("{}..HEAD", since));
+    }
+    command.args(["--", "."]);
+
+    let output = command.output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::other(format!(
+            "git log failed: {}",
+            stderr.trim()
+        )));
+    }
+
+    Ok(Some(parse_log(&String::from_utf8_lossy(&output.stdout))))
+}
+
+/// Parses `git log -p -U0 --no-prefix --format='commit %H'` output.
+fn parse_log(log: &str) -> FileChurn {
+    let mut churn = FileChurn::new();
+    let mut commit: Option<u32> = None;
+    let mut file: Option<String> = None;
+    // Between `diff --git` and the first hunk, where `+++` names the file
+    // rather than adding a line that starts with `++`
+    let mut in_file_header = false;
+
+    for line in log.lines() {
+        if line.starts_with("commit ") {
+            commit = Some(commit.map_or(0, |c| c + 1));
+            file = None;
+        } else if line.starts_with("diff --git ") {
+            file = None;
+            in_file_header = true;
+        } else if let Some(path) = line.strip_prefix("+++ ").filter(|_| in_file_header) {
+            // Deleted files have no lines left to attribute churn to
+            file = (path != "/dev/null").then(|| path.to_string());
+        } else if let (Some(commit), Some(file), Some(header)) =
+            (commit, file.as_ref(), line.strip_prefix("@@ "))
+        {
+            in_file_header = false;
+            if let Some(hunk) = parse_hunk_header(header, commit) {
+                churn.entry(file.clone()).or_default().push(hunk);
+            }
+        }
+    }
+
+    churn
+}
+
+/// Parses the `-a,b +c,d @@` part of a hunk header.
+fn parse_hunk_header(header: &str, commit: u32) -> Option<ChurnHunk> {
+    let mut ranges = header.split_whitespace();
+    let (_, removed) = parse_range(ranges.next()?.strip_prefix('-')?)?;
+    let (start, added) = parse_range(ranges.next()?.strip_prefix('+')?)?;
+
+    // A pure deletion is placed on the line it happened after
+    let line_start = start.max(1);
+    Some(ChurnHunk {
+        commit,
+        line_start,
+        line_end: line_start + added.saturating_sub(1),
+        lines_changed: removed + added,
+    })
+}
+
+/// Parses `start[,count]`; the count defaults to 1.
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index_directory, IndexOptions};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_log_hunks() {
+        let log = "\
+commit bbb
+diff --git src/lib.rs src/lib.rs
+--- src/lib.rs
++++ src/lib.rs
+@@ -3 +3,2 @@ fn core() {
+-    old();
++    new();
++++ not_a_file();
+@@ -10,2 +10,0 @@
+-gone
+-gone
+diff --git old.rs old.rs
+--- old.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-fn old() {}
+commit aaa
+diff --git src/lib.rs src/lib.rs
+--- /dev/null
++++ src/lib.rs
+@@ -0,0 +1,2 @@
++fn core() {
++}
+";
+        let churn = parse_log(log);
+        assert_eq!(churn.len(), 1);
+        let hunks = &churn["src/lib.rs"];
+        let summary: Vec<(u32, u32, u32, u32)> = hunks
+            .iter()
+            .map(|h| (h.commit, h.line_start, h.line_end, h.lines_changed))
+            .collect();
+        assert_eq!(summary, [(0, 3, 4, 3), (0, 10, 10, 2), (1, 1, 2, 2)]);
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_central_churned_function_ranks_top() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return; // git not installed
+        }
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        // Outside a repository there is no churn, not an error
+        assert!(git_churn(root, None, 0).unwrap().is_none());
+
+        git(root, &["init", "-q"]);
+        let write = |version: u32| {
+            fs::write(
+                root.join("lib.rs"),
+                format!(
+                    "pub fn core() -> u32 {{\n    {}\n}}\n\n\
+                     pub fn a() -> u32 {{ core() }}\n\
+                     pub fn b() -> u32 {{ core() }}\n\
+                     pub fn c() -> u32 {{ core() + a() }}\n\
+                     pub fn helper() -> u32 {{ {} }}\n",
+                    version,
+                    version % 2,
+                ),
+            )
+            .unwrap();
+        };
+        write(0);
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "initial"]);
+        git(root, &["tag", "base"]);
+        for version in 1..=4 {
+            write(version);
+            git(root, &["commit", "-qam", "tweak"]);
+        }
+
+        let churn = git_churn(root, Some("base"), 0).unwrap().unwrap();
+        assert_eq!(churn["lib.rs"].len(), 8);
+
+        let result = index_directory(
+            root,
+            IndexOptions {
+                compute_centrality: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let hotspots = result.graph.hotspots(Some(&churn), root);
+        let names: Vec<&str> = hotspots.iter().map(|h| h.node.name.as_str()).collect();
+        assert_eq!(names, ["core", "helper"]);
+        assert_eq!(hotspots[0].commits, 4);
+
+        // A ref that doesn't exist is reported
+        assert!(git_churn(root, Some("no-such-ref"), 0).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Incremental reparsing for modified files.
+//!
+//! File system events don't say what changed inside a file, so we keep
+//! the last source text and syntax tree per file and derive the edit
+//! ourselves. Tree-sitter then re-lexes only the edited range.
+
+use crate::watcher::FileChange;
+use arbor_core::parser::is_component_file;
+use arbor_core::{
+    detect_language, detect_language_from_content, edit_between, is_config_file, parse_file,
+    parse_source_incremental, parse_source_with_tree, CodeNode, ParseError, Tree,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Reparses files, reusing each file's previous syntax tree.
+#[derive(Default)]
+pub struct IncrementalParser {
+    /// Last parsed source and tree, keyed by path.
+    files: HashMap<PathBuf, (String, Tree)>,
+}
+
+impl IncrementalParser {
+    /// Creates a parser with no remembered files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a file, incrementally if it was parsed before.
+    ///
+    /// The first call for a path does a full parse and remembers the tree.
+    /// Vue and Svelte components and config files are always parsed in
+    /// full.
+    pub fn reparse(&mut self, path: &Path) -> Result<Vec<CodeNode>, ParseError> {
+        if is_component_file(path) || is_config_file(path) {
+            return parse_file(path);
+        }
+
+        let source = fs::read_to_string(path).map_err(|e| ParseError::io(path, e))?;
+        let parser = detect_language(path)
+            .or_else(|| detect_language_from_content(&source))
+            .ok_or_else(|| ParseError::UnsupportedLanguage(path.to_path_buf()))?;
+        let file_path = path.to_string_lossy().to_string();
+
+        let (nodes, tree) = match self.files.get(path) {
+            Some((old_source, old_tree)) => {
+                let edits: Vec<_> = edit_between(old_source, &source).into_iter().collect();
+                debug!(
+                    "Incremental reparse of {} ({} edits)",
+                    path.display(),
+                    edits.len()
+                );
+                parse_source_incremental(old_tree, &edits, &source, &file_path, parser.as_ref())?
+            }
+            None => parse_source_with_tree(&source, &file_path, parser.as_ref())?,
+        };
+
+        self.files.insert(path.to_path_buf(), (source, tree));
+        Ok(nodes)
+    }
+
+    /// Drops the remembered tree for a file.
+    pub fn forget(&mut self, path: &Path) {
+        self.files.remove(path);
+    }
+
+    /// Moves the remembered tree from `from` to `to` if the file's content
+    /// and language are unchanged, returning whether it did.
+    ///
+    /// When this returns true the file's nodes only need re-keying
+    /// (`ArborGraph::rename_file`); otherwise `to` needs a reparse.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> bool {
+        let Some((source, tree)) = self.files.remove(from) else {
+            return false;
+        };
+        let unchanged = from.extension() == to.extension()
+            && fs::read_to_string(to).is_ok_and(|current| current == source);
+        if unchanged {
+            self.files.insert(to.to_path_buf(), (source, tree));
+        }
+        unchanged
+    }
+
+    /// Applies a watcher event.
+    ///
+    /// Created and modified files are reparsed; deleted files are forgotten
+    /// and yield `None`. Renamed files yield `None` when their content is
+    /// unchanged (re-key their nodes instead) and are reparsed otherwise.
+    pub fn handle(&mut self, change: &FileChange) -> Option<Result<Vec<CodeNode>, ParseError>> {
+        match change {
+            FileChange::Created(path) | FileChange::Modified(path) => Some(self.reparse(path)),
+            FileChange::Deleted(path) => {
+                self.forget(path);
+                None
+            }
+            FileChange::Renamed(from, to) => {
+                if self.rename(from, to) {
+                    None
+                } else {
+                    Some(self.reparse(to))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_modified_file_matches_full_parse() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("lib.rs");
+        fs::write(&path, "pub fn a() {}\n\npub fn b() { a(); }\n").unwrap();
+
+        let mut parser = IncrementalParser::new();
+        parser.reparse(&path).unwrap();
+
+        fs::write(
+            &path,
+            "pub fn a() {}\n\npub fn c() {}\n\npub fn b() { a(); }\n",
+        )
+        .unwrap();
+        let incremental = parser
+            .handle(&FileChange::Modified(path.clone()))
+            .unwrap()
+            .unwrap();
+        let fresh = parse_file(&path).unwrap();
+
+        let names = |nodes: &[CodeNode]| {
+            nodes
+                .iter()
+                .map(|n| (n.name.clone(), n.line_start))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&incremental), names(&fresh));
+    }
+
+    #[test]
+    fn test_rename_rekeys_nodes_without_reparse() {
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("old.rs");
+        let new_path = dir.path().join("new.rs");
+        fs::write(&old_path, "pub fn a() {}\n\npub fn b() { a(); }\n").unwrap();
+
+        let mut parser = IncrementalParser::new();
+        let mut builder = arbor_graph::GraphBuilder::new();
+        builder.add_nodes(parser.reparse(&old_path).unwrap());
+        let mut graph = builder.build();
+        let (nodes, edges) = (graph.node_count(), graph.edge_count());
+
+        fs::rename(&old_path, &new_path).unwrap();
+        let change = FileChange::Renamed(old_path.clone(), new_path.clone());
+        assert!(parser.handle(&change).is_none());
+
+        let (from, to) = (old_path.to_string_lossy(), new_path.to_string_lossy());
+        assert_eq!(graph.rename_file(&from, &to), nodes);
+
+        assert_eq!(graph.node_count(), nodes);
+        assert_eq!(graph.edge_count(), edges);
+        assert!(graph.nodes().all(|n| n.file == to));
+        assert!(graph.find_by_file(&from).is_empty());
+        let b = graph.find_by_name("b")[0];
+        assert_eq!(b.id, CodeNode::compute_id(&to, &b.qualified_name, b.kind));
+        assert!(graph.get_by_id(&b.id).is_some());
+
+        // A later edit reparses incrementally under the new path
+        fs::write(&new_path, "pub fn a() {}\n\npub fn b() {}\n").unwrap();
+        let reparsed = parser
+            .handle(&FileChange::Modified(new_path.clone()))
+            .unwrap()
+            .unwrap();
+        assert!(reparsed.iter().all(|n| n.file == to));
+    }
+}
@@ -3,12 +3,13 @@
 //! Walks directories to find and parse source files, building
 //! the initial code graph.
 
-use arbor_core::{parse_file, CodeNode};
-use arbor_graph::{ArborGraph, GraphBuilder, GraphStore};
+use arbor_core::{parse_file, parse_file_checked, CodeNode, ParseError, ParsedFile};
+use arbor_graph::{compute_centrality, ArborGraph, GraphBuilder, GraphStore, ResolutionStats};
 use ignore::WalkBuilder;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
 /// Result of indexing a directory.
@@ -22,6 +23,9 @@ pub struct IndexResult {
     /// Number of files loaded from cache.
     pub cache_hits: usize,
 
+    /// Number of deleted files dropped from the cache.
+    pub files_removed: usize,
+
     /// Number of nodes extracted.
     pub nodes_extracted: usize,
 
@@ -30,6 +34,24 @@ pub struct IndexResult {
 
     /// Files that failed to parse.
     pub errors: Vec<(String, String)>,
+
+    /// Files with syntax errors, and the line of the first one. Their
+    /// recoverable nodes are in the graph but may be incomplete. They are
+    /// cached with their error lines, so cache hits are reported here too.
+    pub partial_files: Vec<(String, u32)>,
+
+    /// Wall-clock time per phase: `walk`, `parse`, `symbol_table`,
+    /// `edge_resolution` and `centrality` (zero unless
+    /// `IndexOptions::compute_centrality` is set). Together they account
+    /// for nearly all of `duration_ms`.
+    pub phase_timings: BTreeMap<&'static str, Duration>,
+
+    /// Parse time per language, summed over files. With several threads
+    /// this can exceed the wall-clock `parse` phase.
+    pub language_timings: BTreeMap<String, Duration>,
+
+    /// References that became edges and the ones that didn't.
+    pub resolution: ResolutionStats,
 }
 
 /// Options for directory indexing.
@@ -41,6 +63,227 @@ pub struct IndexOptions {
     /// Path to cache directory (e.g., `.arbor/cache`).
     /// If None, caching is disabled.
     pub cache_path: Option<PathBuf>,
+
+    /// Skip well-known vendored and generated directories (see
+    /// `VENDORED_DIRS`) even when they are not gitignored.
+    pub skip_vendored: bool,
+
+    /// Number of threads parsing files. If None, uses the machine's
+    /// available parallelism.
+    pub threads: Option<usize>,
+
+    /// Compute centrality scores once the graph is built. Otherwise every
+    /// node is left at zero and callers compute it when they need it.
+    pub compute_centrality: bool,
+
+    /// Also index `.toml`, `.yaml` and `.yml` files, extracting their
+    /// sections and keys (see `arbor_core::config`).
+    pub config_files: bool,
+}
+
+impl IndexOptions {
+    /// Resolves `threads` to an actual thread count (at least 1).
+    pub fn thread_count(&self) -> usize {
+        self.threads
+            .unwrap_or_else(|| {
+                std::thread::available_parallelism()
+                    .map(|n| n.get())
+                    .unwrap_or(1)
+            })
+            .max(1)
+    }
+}
+
+/// Directory names skipped when `IndexOptions::skip_vendored` is set.
+pub const VENDORED_DIRS: &[&str] = &[
+    "node_modules",
+    "vendor",
+    "target",
+    "dist",
+    "build",
+    ".venv",
+    "__pycache__",
+    "Pods",
+];
+
+/// What an index run *would* parse, without parsing anything.
+#[derive(Debug, Default)]
+pub struct DryRunResult {
+    /// Number of files that would be parsed, keyed by language name.
+    pub files_by_language: BTreeMap<String, usize>,
+
+    /// Total number of files that would be parsed.
+    pub total_files: usize,
+}
+
+/// Walks a directory and yields every file Arbor knows how to parse.
+///
+/// Respects .gitignore, hidden-file rules, `options.follow_symlinks`,
+/// `options.skip_vendored` and `options.config_files`, so indexing and dry runs always agree on the
+/// file set.
+fn indexable_files(root: &Path, options: &IndexOptions) -> impl Iterator<Item = PathBuf> {
+    let skip_vendored = options.skip_vendored;
+    let config_files = options.config_files;
+
+    WalkBuilder::new(root)
+        .hidden(true) // Skip hidden files
+        .git_ignore(true) // Respect .gitignore
+        .git_global(true)
+        .git_exclude(true)
+        .follow_links(options.follow_symlinks)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            !(skip_vendored
+                && is_dir
+                && entry.depth() > 0
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map(|name| VENDORED_DIRS.contains(&name))
+                    .unwrap_or(false))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(move |path| !path.is_dir() && is_indexable(path, config_files))
+}
+
+/// Returns true for files in a supported language, and for config files
+/// when `config_files` is set. Extensionless files (`bin/deploy`) are
+/// indexed when their shebang names a supported interpreter.
+fn is_indexable(path: &Path, config_files: bool) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) => {
+            arbor_core::languages::is_supported(ext)
+                || (config_files && arbor_core::is_config_file(path))
+        }
+        None => has_supported_shebang(path),
+    }
+}
+
+/// Reads just the first line of `path` and checks its shebang.
+fn has_supported_shebang(path: &Path) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut first_line = String::new();
+    // Binaries have no newline to stop at, so cap the read
+    if BufReader::new(file.take(256))
+        .read_line(&mut first_line)
+        .is_err()
+    {
+        return false;
+    }
+    first_line.trim_start_matches('\u{feff}').starts_with("#!")
+        && arbor_core::detect_language_from_content(&first_line).is_some()
+}
+
+/// Counts the files per language that `index_directory` would parse.
+///
+/// Uses the same walker and filters as a real index, but never reads
+/// or parses file contents.
+pub fn dry_run_directory(root: &Path, options: IndexOptions) -> DryRunResult {
+    let mut result = DryRunResult::default();
+
+    for path in indexable_files(root, &options) {
+        let language = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(arbor_core::languages::language_name)
+            .unwrap_or("Other");
+
+        *result
+            .files_by_language
+            .entry(language.to_string())
+            .or_insert(0) += 1;
+        result.total_files += 1;
+    }
+
+    result
+}
+
+/// Parse latency for one language, from `bench_parse`.
+#[derive(Debug, Clone)]
+pub struct ParseBench {
+    /// Language name, as in `DryRunResult::files_by_language`.
+    pub language: String,
+    /// Sampled files with their size in bytes and parse time.
+    pub samples: Vec<(PathBuf, u64, Duration)>,
+}
+
+impl ParseBench {
+    /// Mean parse time over the samples.
+    pub fn mean(&self) -> Duration {
+        let total: Duration = self.samples.iter().map(|(_, _, t)| *t).sum();
+        total / self.samples.len().max(1) as u32
+    }
+
+    /// The slowest sampled file.
+    pub fn slowest(&self) -> Option<&(PathBuf, u64, Duration)> {
+        self.samples.iter().max_by_key(|(_, _, t)| *t)
+    }
+}
+
+/// Parses up to `per_language` files of each language under `root`,
+/// timing each parse.
+///
+/// Samples are spread across file sizes (smallest, largest and evenly in
+/// between) so a single giant file doesn't hide a slow grammar, or vice
+/// versa. Parsing is single-threaded so timings don't contend.
+pub fn bench_parse(root: &Path, options: &IndexOptions, per_language: usize) -> Vec<ParseBench> {
+    let mut by_language: BTreeMap<String, Vec<(PathBuf, u64)>> = BTreeMap::new();
+    for path in indexable_files(root, options) {
+        let language = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(arbor_core::languages::language_name)
+            .unwrap_or("Other");
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        by_language
+            .entry(language.to_string())
+            .or_default()
+            .push((path, size));
+    }
+
+    by_language
+        .into_iter()
+        .map(|(language, mut files)| {
+            files.sort_by_key(|(_, size)| *size);
+            let picked: Vec<(PathBuf, u64)> = if files.len() <= per_language {
+                files
+            } else {
+                let last = files.len() - 1;
+                let steps = per_language.saturating_sub(1).max(1);
+                let mut indexes: Vec<usize> = (0..per_language).map(|i| i * last / steps).collect();
+                indexes.dedup();
+                indexes.into_iter().map(|i| files[i].clone()).collect()
+            };
+
+            let paths: Vec<&Path> = picked.iter().map(|(p, _)| p.as_path()).collect();
+            let samples = parse_files(&paths, 1)
+                .into_iter()
+                .zip(&picked)
+                .map(|((_, elapsed), (path, size))| (path.clone(), *size, elapsed))
+                .collect();
+            ParseBench { language, samples }
+        })
+        .collect()
+}
+
+/// Languages whose mean parse time is more than `factor` times the median
+/// of all languages' means. Needs at least three languages to compare.
+pub fn parse_outliers(benches: &[ParseBench], factor: u32) -> Vec<&ParseBench> {
+    if benches.len() < 3 {
+        return Vec::new();
+    }
+    let mut means: Vec<Duration> = benches.iter().map(ParseBench::mean).collect();
+    means.sort();
+    let median = means[means.len() / 2];
+
+    benches
+        .iter()
+        .filter(|bench| bench.mean() > median * factor)
+        .collect()
 }
 
 /// Indexes a directory and returns the code graph.
@@ -62,136 +305,310 @@ pub struct IndexOptions {
 /// ```
 pub fn index_directory(root: &Path, options: IndexOptions) -> Result<IndexResult, std::io::Error> {
     let start = Instant::now();
-    let mut builder = GraphBuilder::new();
-    let mut files_indexed = 0;
-    let mut cache_hits = 0;
-    let mut nodes_extracted = 0;
-    let mut errors = Vec::new();
 
     info!("Starting index of {}", root.display());
 
-    // Open cache if configured
-    let store =
-        options
-            .cache_path
-            .as_ref()
-            .and_then(|path| match GraphStore::open_or_reset(path) {
-                Ok(s) => Some(s),
-                Err(e) => {
-                    warn!("Failed to open cache: {}, proceeding without cache", e);
-                    None
-                }
-            });
+    let store = open_cache(&options);
 
     // Track files we've seen (for detecting deleted files)
     let mut seen_files: HashSet<String> = HashSet::new();
 
-    // Walk the directory, respecting .gitignore
-    let walker = WalkBuilder::new(root)
-        .hidden(true) // Skip hidden files
-        .git_ignore(true) // Respect .gitignore
-        .git_global(true)
-        .git_exclude(true)
-        .follow_links(options.follow_symlinks)
-        .build();
-
-    for entry in walker.filter_map(Result::ok) {
-        let path = entry.path();
+    // Walk the directory, respecting .gitignore, and pull unchanged files
+    // from the cache. Everything else is parsed below.
+    let mut pending: Vec<PendingFile> = Vec::new();
+    for path in indexable_files(root, &options) {
+        let path_str = path.display().to_string();
+        seen_files.insert(path_str.clone());
 
-        // Skip directories
-        if path.is_dir() {
+        let Some(ref store) = store else {
+            pending.push(PendingFile::Parse { path, mtime: None });
             continue;
-        }
-
-        // Check if it's a supported file type
-        let extension = match path.extension().and_then(|e| e.to_str()) {
-            Some(ext) => ext,
-            None => continue,
         };
 
-        if !arbor_core::languages::is_supported(extension) {
-            continue;
+        let current_mtime = file_mtime(&path);
+
+        // Check cached mtime
+        if let Ok(Some(cached_mtime)) = store.get_mtime(&path_str) {
+            if cached_mtime == current_mtime {
+                // File unchanged, load from cache
+                if let Ok(Some(nodes)) = store.get_file_nodes(&path_str) {
+                    debug!("Cache hit: {}", path.display());
+                    let syntax_errors = store.get_syntax_errors(&path_str).unwrap_or_default();
+                    pending.push(PendingFile::Cached {
+                        file: path_str,
+                        nodes,
+                        syntax_errors,
+                    });
+                    continue;
+                }
+            }
         }
 
-        let path_str = path.display().to_string();
-        seen_files.insert(path_str.clone());
+        // Cache miss or stale, parse file
+        debug!("Parsing (cache miss): {}", path.display());
+        pending.push(PendingFile::Parse {
+            path,
+            mtime: Some(current_mtime),
+        });
+    }
 
-        // Check cache
-        if let Some(ref store) = store {
-            // Get file mtime
-            let current_mtime = match std::fs::metadata(path) {
-                Ok(meta) => meta
-                    .modified()
-                    .ok()
-                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-                    .map(|d| d.as_secs())
-                    .unwrap_or(0),
-                Err(_) => 0,
-            };
+    let walk = start.elapsed();
 
-            // Check cached mtime
-            if let Ok(Some(cached_mtime)) = store.get_mtime(&path_str) {
-                if cached_mtime == current_mtime {
-                    // File unchanged, load from cache
-                    if let Ok(Some(cached_nodes)) = store.get_file_nodes(&path_str) {
-                        debug!("Cache hit: {}", path.display());
-                        nodes_extracted += cached_nodes.len();
-                        cache_hits += 1;
-                        builder.add_nodes(cached_nodes);
-                        continue;
+    // Handle deleted files: remove from cache any files that no longer exist
+    let mut files_removed = 0;
+    if let Some(ref store) = store {
+        if let Ok(cached_files) = store.list_cached_files() {
+            for cached_file in cached_files {
+                if !seen_files.contains(&cached_file) {
+                    debug!("Removing deleted file from cache: {}", cached_file);
+                    match store.remove_file(&cached_file) {
+                        Ok(()) => files_removed += 1,
+                        Err(e) => warn!("Failed to remove {} from cache: {}", cached_file, e),
                     }
                 }
             }
+        }
+    }
 
-            // Cache miss or stale, parse file
-            debug!("Parsing (cache miss): {}", path.display());
-            match parse_file(path) {
-                Ok(nodes) => {
-                    nodes_extracted += nodes.len();
-                    files_indexed += 1;
-                    // Update cache
-                    if let Err(e) = store.update_file(&path_str, &nodes, current_mtime) {
-                        warn!("Failed to update cache for {}: {}", path_str, e);
-                    }
-                    builder.add_nodes(nodes);
-                }
-                Err(e) => {
-                    warn!("Failed to parse {}: {}", path.display(), e);
-                    errors.push((path_str, e.to_string()));
-                }
+    let mut result = build_index(pending, store.as_ref(), &options, start);
+    result.phase_timings.insert("walk", walk);
+    result.files_removed = files_removed;
+    Ok(result)
+}
+
+/// Indexes exactly `files`, taking every other file from the cache.
+///
+/// For CI jobs that already know which files changed: the listed files
+/// are parsed fresh and their cache entries updated, while the rest of
+/// the graph is loaded from the cache as-is, with no directory walk and
+/// no mtime checks. Without a cache the graph holds only the listed
+/// files.
+///
+/// Relative paths are taken relative to `root`. Every listed file must
+/// exist under `root` and be indexable (see `indexable_files`); otherwise
+/// nothing is indexed and the `InvalidInput` error names each bad entry.
+pub fn index_file_list(
+    root: &Path,
+    files: &[PathBuf],
+    options: IndexOptions,
+) -> Result<IndexResult, std::io::Error> {
+    let start = Instant::now();
+
+    let mut listed: Vec<PathBuf> = Vec::new();
+    let mut problems: Vec<String> = Vec::new();
+    for file in files {
+        match listed_path(root, file) {
+            Ok(path) if !path.is_file() => {
+                problems.push(format!("{}: no such file", file.display()))
             }
-        } else {
-            // No cache, parse directly
-            debug!("Parsing {}", path.display());
-            match parse_file(path) {
-                Ok(nodes) => {
-                    nodes_extracted += nodes.len();
-                    files_indexed += 1;
-                    builder.add_nodes(nodes);
-                }
-                Err(e) => {
-                    warn!("Failed to parse {}: {}", path.display(), e);
-                    errors.push((path_str, e.to_string()));
+            Ok(path) if !is_indexable(&path, options.config_files) => {
+                problems.push(format!("{}: unsupported file type", file.display()))
+            }
+            Ok(path) => {
+                if !listed.contains(&path) {
+                    listed.push(path);
                 }
             }
+            Err(reason) => problems.push(format!("{}: {}", file.display(), reason)),
         }
     }
+    if !problems.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid file list: {}", problems.join("; ")),
+        ));
+    }
 
-    // Handle deleted files: remove from cache any files that no longer exist
+    info!(
+        "Starting index of {} listed files in {}",
+        listed.len(),
+        root.display()
+    );
+
+    let store = open_cache(&options);
+    let listed_keys: HashSet<String> = listed.iter().map(|p| p.display().to_string()).collect();
+
+    let mut pending: Vec<PendingFile> = Vec::new();
     if let Some(ref store) = store {
-        if let Ok(cached_files) = store.list_cached_files() {
-            for cached_file in cached_files {
-                if !seen_files.contains(&cached_file) {
-                    debug!("Removing deleted file from cache: {}", cached_file);
-                    if let Err(e) = store.remove_file(&cached_file) {
-                        warn!("Failed to remove {} from cache: {}", cached_file, e);
+        for cached_file in store.list_cached_files().unwrap_or_default() {
+            if listed_keys.contains(&cached_file) {
+                continue;
+            }
+            if let Ok(Some(nodes)) = store.get_file_nodes(&cached_file) {
+                let syntax_errors = store.get_syntax_errors(&cached_file).unwrap_or_default();
+                pending.push(PendingFile::Cached {
+                    file: cached_file,
+                    nodes,
+                    syntax_errors,
+                });
+            }
+        }
+    }
+    for path in listed {
+        let mtime = store.as_ref().map(|_| file_mtime(&path));
+        pending.push(PendingFile::Parse { path, mtime });
+    }
+
+    let lookup = start.elapsed();
+    let mut result = build_index(pending, store.as_ref(), &options, start);
+    result.phase_timings.insert("walk", lookup);
+    Ok(result)
+}
+
+/// Resolves a listed file to the path the directory walk would produce
+/// for it, so it shares a cache key with walked files.
+fn listed_path(root: &Path, file: &Path) -> Result<PathBuf, &'static str> {
+    if file.is_absolute() {
+        let file = file.canonicalize().map_err(|_| "no such file")?;
+        let canonical_root = root
+            .canonicalize()
+            .map_err(|_| "cannot resolve the indexed directory")?;
+        let relative = file
+            .strip_prefix(&canonical_root)
+            .map_err(|_| "outside the indexed directory")?;
+        return Ok(root.join(relative));
+    }
+    Ok(root.join(file.strip_prefix(".").unwrap_or(file)))
+}
+
+/// Opens the cache at `options.cache_path`, if any. A cache that can't be
+/// opened is skipped with a warning rather than failing the index.
+fn open_cache(options: &IndexOptions) -> Option<GraphStore> {
+    options
+        .cache_path
+        .as_ref()
+        .and_then(|path| match GraphStore::open_or_reset(path) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                warn!("Failed to open cache: {}, proceeding without cache", e);
+                None
+            }
+        })
+}
+
+/// Returns a file's mtime in seconds since the epoch, or 0 if unknown.
+fn file_mtime(path: &Path) -> u64 {
+    match std::fs::metadata(path) {
+        Ok(meta) => meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        Err(_) => 0,
+    }
+}
+
+/// Parses the pending files and builds the graph from them and the
+/// cached ones. Fills every phase timing except `walk`; `files_removed`
+/// is left at 0 for the caller.
+fn build_index(
+    pending: Vec<PendingFile>,
+    store: Option<&GraphStore>,
+    options: &IndexOptions,
+    start: Instant,
+) -> IndexResult {
+    let mut builder = GraphBuilder::new();
+    let mut files_indexed = 0;
+    let mut cache_hits = 0;
+    let mut nodes_extracted = 0;
+    let mut errors = Vec::new();
+    let mut partial_files = Vec::new();
+    let mut phase_timings: BTreeMap<&'static str, Duration> = BTreeMap::new();
+    let mut language_timings: BTreeMap<String, Duration> = BTreeMap::new();
+
+    let phase = Instant::now();
+    let to_parse: Vec<&Path> = pending
+        .iter()
+        .filter_map(|file| match file {
+            PendingFile::Parse { path, .. } => Some(path.as_path()),
+            PendingFile::Cached { .. } => None,
+        })
+        .collect();
+    let mut parsed = parse_files(&to_parse, options.thread_count()).into_iter();
+    phase_timings.insert("parse", phase.elapsed());
+
+    // Add nodes in walk order so the graph doesn't depend on thread count
+    let mut symbol_table_time = Duration::ZERO;
+    for file in pending {
+        match file {
+            PendingFile::Cached {
+                file,
+                nodes,
+                syntax_errors,
+            } => {
+                nodes_extracted += nodes.len();
+                cache_hits += 1;
+                if let Some(&line) = syntax_errors.first() {
+                    partial_files.push((file, line));
+                }
+                let phase = Instant::now();
+                builder.add_nodes(nodes);
+                symbol_table_time += phase.elapsed();
+            }
+            PendingFile::Parse { path, mtime } => {
+                let path_str = path.display().to_string();
+                let (result, elapsed) = parsed.next().expect("one parse result per pending file");
+                let language = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .and_then(arbor_core::languages::language_name)
+                    .unwrap_or("Other");
+                *language_timings.entry(language.to_string()).or_default() += elapsed;
+
+                match result {
+                    Ok(ParsedFile {
+                        nodes,
+                        syntax_errors,
+                    }) => {
+                        nodes_extracted += nodes.len();
+                        files_indexed += 1;
+                        if let Some(&line) = syntax_errors.first() {
+                            warn!(
+                                "Syntax errors in {} (first at line {}); indexed what was recoverable",
+                                path_str, line
+                            );
+                            partial_files.push((path_str.clone(), line));
+                        }
+                        if let (Some(store), Some(mtime)) = (store, mtime) {
+                            // Update cache, keeping the error lines for cache hits
+                            if let Err(e) = store.update_file_with_errors(
+                                &path_str,
+                                &nodes,
+                                mtime,
+                                &syntax_errors,
+                            ) {
+                                warn!("Failed to update cache for {}: {}", path_str, e);
+                            }
+                        }
+                        let phase = Instant::now();
+                        builder.add_nodes(nodes);
+                        symbol_table_time += phase.elapsed();
+                    }
+                    Err(e) => {
+                        warn!("Failed to parse {}: {}", path.display(), e);
+                        errors.push((path_str, e.to_string()));
                     }
                 }
             }
         }
     }
 
-    let graph = builder.build();
+    phase_timings.insert("symbol_table", symbol_table_time);
+
+    let phase = Instant::now();
+    builder.resolve_edges();
+    let resolution = builder.resolution_stats().clone();
+    let mut graph = builder.build_without_resolve();
+    phase_timings.insert("edge_resolution", phase.elapsed());
+
+    let phase = Instant::now();
+    if options.compute_centrality {
+        let scores = compute_centrality(&graph, 20, 0.85);
+        graph.set_centrality(scores.into_map());
+    }
+    phase_timings.insert("centrality", phase.elapsed());
+
     let duration = start.elapsed();
 
     info!(
@@ -199,13 +616,62 @@ pub fn index_directory(root: &Path, options: IndexOptions) -> Result<IndexResult
         files_indexed, cache_hits, nodes_extracted, duration
     );
 
-    Ok(IndexResult {
+    IndexResult {
         graph,
         files_indexed,
         cache_hits,
+        files_removed: 0,
         nodes_extracted,
         duration_ms: duration.as_millis() as u64,
         errors,
+        partial_files,
+        phase_timings,
+        language_timings,
+        resolution,
+    }
+}
+
+/// A walked file, either served from the cache or waiting to be parsed.
+enum PendingFile {
+    Cached {
+        file: String,
+        nodes: Vec<CodeNode>,
+        syntax_errors: Vec<u32>,
+    },
+    Parse {
+        path: PathBuf,
+        mtime: Option<u64>,
+    },
+}
+
+/// A file's parse result and how long parsing it took.
+type TimedParse = (Result<ParsedFile, ParseError>, Duration);
+
+/// Parses files on up to `threads` threads.
+///
+/// Results come back in the same order as `paths`.
+fn parse_files(paths: &[&Path], threads: usize) -> Vec<TimedParse> {
+    let timed_parse = |path: &&Path| {
+        let start = Instant::now();
+        let result = parse_file_checked(path);
+        (result, start.elapsed())
+    };
+
+    if threads <= 1 || paths.len() <= 1 {
+        return paths.iter().map(timed_parse).collect();
+    }
+
+    let chunk_size = paths.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(timed_parse).collect::<Vec<_>>()))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("parser thread panicked"))
+            .collect()
     })
 }
 
@@ -221,6 +687,108 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_single_thread_matches_default() {
+        let dir = tempdir().unwrap();
+        for i in 0..8 {
+            fs::write(
+                dir.path().join(format!("mod_{}.rs", i)),
+                format!("fn f_{i}() {{ g_{i}(); }}\nfn g_{i}() {{}}\n"),
+            )
+            .unwrap();
+        }
+
+        let default = index_directory(dir.path(), IndexOptions::default()).unwrap();
+        let single = index_directory(
+            dir.path(),
+            IndexOptions {
+                threads: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(single.files_indexed, default.files_indexed);
+        assert_eq!(single.nodes_extracted, default.nodes_extracted);
+        assert_eq!(single.graph.edge_count(), default.graph.edge_count());
+        let ids = |graph: &ArborGraph| graph.nodes().map(|n| n.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&single.graph), ids(&default.graph));
+    }
+
+    #[test]
+    fn test_phase_timings_cover_total() {
+        let dir = tempdir().unwrap();
+        for i in 0..8 {
+            fs::write(
+                dir.path().join(format!("mod_{}.rs", i)),
+                format!("fn f_{i}() {{ g_{i}(); }}\nfn g_{i}() {{}}\n"),
+            )
+            .unwrap();
+        }
+
+        let result = index_directory(
+            dir.path(),
+            IndexOptions {
+                compute_centrality: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for phase in [
+            "walk",
+            "parse",
+            "symbol_table",
+            "edge_resolution",
+            "centrality",
+        ] {
+            assert!(
+                result.phase_timings.contains_key(phase),
+                "missing {}",
+                phase
+            );
+        }
+        assert!(result.language_timings.contains_key("Rust"));
+
+        let sum: Duration = result.phase_timings.values().sum();
+        let sum_ms = sum.as_millis() as u64;
+        assert!(sum_ms <= result.duration_ms);
+        assert!(result.duration_ms - sum_ms <= 50);
+    }
+
+    #[test]
+    fn test_unchanged_tree_is_fully_cached() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        for i in 0..3 {
+            fs::write(
+                src.join(format!("mod_{}.rs", i)),
+                format!("fn f_{i}() {{}}\n"),
+            )
+            .unwrap();
+        }
+        let options = IndexOptions {
+            cache_path: Some(dir.path().join("cache")),
+            ..Default::default()
+        };
+
+        let first = index_directory(&src, options.clone()).unwrap();
+        assert_eq!(first.files_indexed, 3);
+        assert_eq!(first.cache_hits, 0);
+
+        let second = index_directory(&src, options.clone()).unwrap();
+        assert_eq!(second.cache_hits, 3);
+        assert_eq!(second.files_indexed, 0);
+        assert_eq!(second.files_removed, 0);
+        assert_eq!(second.nodes_extracted, first.nodes_extracted);
+
+        fs::remove_file(src.join("mod_0.rs")).unwrap();
+        let third = index_directory(&src, options).unwrap();
+        assert_eq!(third.cache_hits, 2);
+        assert_eq!(third.files_removed, 1);
+    }
+
     #[test]
     fn test_index_empty_directory() {
         let dir = tempdir().unwrap();
@@ -249,6 +817,22 @@ mod tests {
         assert!(result.nodes_extracted > 0);
     }
 
+    #[test]
+    fn test_index_extensionless_script_by_shebang() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("bin")).unwrap();
+        fs::write(
+            dir.path().join("bin/deploy"),
+            "#!/usr/bin/env python3\n\ndef deploy():\n    pass\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("LICENSE"), "MIT License\n").unwrap();
+
+        let result = index_directory(dir.path(), IndexOptions::default()).unwrap();
+        assert_eq!(result.files_indexed, 1);
+        assert_eq!(result.graph.find_by_name("deploy").len(), 1);
+    }
+
     /// Helper to create a directory symlink cross-platform.
     /// Returns None if symlink creation fails (e.g., no privileges on Windows).
     fn create_dir_symlink(original: &std::path::Path, link: &std::path::Path) -> Option<()> {
@@ -306,10 +890,162 @@ mod tests {
         // Index with follow_symlinks enabled
         let options = IndexOptions {
             follow_symlinks: true,
-            cache_path: None,
+            ..Default::default()
         };
         let result = index_directory(dir.path(), options).unwrap();
         assert_eq!(result.files_indexed, 1);
         assert!(result.nodes_extracted > 0);
     }
+
+    #[test]
+    fn test_dry_run_matches_index() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("lib.rs"), "pub fn a() {}").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("util.py"), "def helper():\n    pass\n").unwrap();
+        fs::write(dir.path().join("README.md"), "# not code").unwrap();
+
+        let dry = dry_run_directory(dir.path(), IndexOptions::default());
+        let result = index_directory(dir.path(), IndexOptions::default()).unwrap();
+
+        assert_eq!(dry.total_files, result.files_indexed);
+        assert_eq!(dry.files_by_language.get("Rust"), Some(&2));
+        assert_eq!(dry.files_by_language.get("Python"), Some(&1));
+    }
+
+    #[test]
+    fn test_skip_vendored_ignores_node_modules() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("app.ts"), "export function main() {}").unwrap();
+        let vendored = dir.path().join("node_modules").join("left-pad");
+        fs::create_dir_all(&vendored).unwrap();
+        fs::write(vendored.join("index.js"), "function leftPad() {}").unwrap();
+
+        // No .gitignore, so node_modules is walked by default
+        let all = dry_run_directory(dir.path(), IndexOptions::default());
+        assert_eq!(all.total_files, 2);
+
+        let options = IndexOptions {
+            skip_vendored: true,
+            ..Default::default()
+        };
+        let result = index_directory(dir.path(), options).unwrap();
+        assert_eq!(result.files_indexed, 1);
+        assert!(result.graph.find_by_name("leftPad").is_empty());
+    }
+
+    #[test]
+    fn test_config_files_are_opt_in() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+        fs::write(
+            dir.path().join("deploy.yml"),
+            "database:\n  url: postgres://db\n",
+        )
+        .unwrap();
+
+        let default = index_directory(dir.path(), IndexOptions::default()).unwrap();
+        assert_eq!(default.files_indexed, 1);
+
+        let options = IndexOptions {
+            config_files: true,
+            ..Default::default()
+        };
+        let dry = dry_run_directory(dir.path(), options.clone());
+        assert_eq!(dry.files_by_language.get("YAML"), Some(&1));
+
+        let result = index_directory(dir.path(), options).unwrap();
+        assert_eq!(result.files_indexed, 2);
+        assert_eq!(
+            result.graph.find_by_name("url")[0].qualified_name,
+            "database.url"
+        );
+    }
+
+    #[test]
+    fn test_bench_parse_covers_present_languages() {
+        let dir = tempdir().unwrap();
+        for i in 0..5 {
+            let body = "pub fn f() {}\n".repeat(i + 1);
+            fs::write(dir.path().join(format!("m{}.rs", i)), body).unwrap();
+        }
+        fs::write(dir.path().join("util.py"), "def helper():\n    pass\n").unwrap();
+        fs::write(dir.path().join("app.ts"), "export function main() {}").unwrap();
+        fs::write(dir.path().join("README.md"), "# not code").unwrap();
+
+        let benches = bench_parse(dir.path(), &IndexOptions::default(), 3);
+
+        let languages: Vec<&str> = benches.iter().map(|b| b.language.as_str()).collect();
+        assert_eq!(languages, ["Python", "Rust", "TypeScript"]);
+
+        let rust = &benches[1];
+        assert_eq!(rust.samples.len(), 3);
+        // Smallest and largest Rust files are always sampled
+        let sizes: Vec<u64> = rust.samples.iter().map(|(_, size, _)| *size).collect();
+        assert_eq!(sizes.first(), Some(&14));
+        assert_eq!(sizes.last(), Some(&70));
+        assert!(rust.slowest().is_some());
+    }
+
+    #[test]
+    fn test_file_list_parses_only_listed_files() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("api.rs"), "pub fn handler() {}").unwrap();
+        fs::write(dir.path().join("db.rs"), "pub fn query() {}").unwrap();
+        let options = IndexOptions {
+            cache_path: Some(dir.path().join(".cache")),
+            ..Default::default()
+        };
+        index_directory(dir.path(), options.clone()).unwrap();
+
+        // Both files change, but CI only reports api.rs
+        fs::write(dir.path().join("api.rs"), "pub fn handler_v2() {}").unwrap();
+        fs::write(dir.path().join("db.rs"), "pub fn query_v2() {}").unwrap();
+
+        let listed = vec![PathBuf::from("api.rs")];
+        let result = index_file_list(dir.path(), &listed, options).unwrap();
+        assert_eq!(result.files_indexed, 1);
+        assert_eq!(result.cache_hits, 1);
+        assert_eq!(result.graph.find_by_name("handler_v2").len(), 1);
+        assert!(result.graph.find_by_name("handler").is_empty());
+        // db.rs came from the cache, not from disk
+        assert_eq!(result.graph.find_by_name("query").len(), 1);
+        assert!(result.graph.find_by_name("query_v2").is_empty());
+
+        let bad = vec![PathBuf::from("missing.rs"), PathBuf::from("notes.txt")];
+        fs::write(dir.path().join("notes.txt"), "todo").unwrap();
+        let err = index_file_list(dir.path(), &bad, IndexOptions::default()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("missing.rs: no such file"));
+        assert!(err.to_string().contains("notes.txt: unsupported file type"));
+    }
+
+    #[test]
+    fn test_partial_file_cached_and_still_flagged() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(
+            src.join("billing.py"),
+            "def broken(:\n    return 1\n\ndef good(amount):\n    return amount + 1\n",
+        )
+        .unwrap();
+        fs::write(src.join("clean.py"), "def fine():\n    pass\n").unwrap();
+        let options = IndexOptions {
+            cache_path: Some(dir.path().join("cache")),
+            ..Default::default()
+        };
+
+        let first = index_directory(&src, options.clone()).unwrap();
+        assert!(first.errors.is_empty());
+        assert_eq!(first.partial_files.len(), 1);
+        assert!(first.partial_files[0].0.ends_with("billing.py"));
+        assert!(!first.graph.find_by_name("good").is_empty());
+
+        // Both files come from the cache; the broken one is still flagged
+        let second = index_directory(&src, options).unwrap();
+        assert_eq!((second.cache_hits, second.files_indexed), (2, 0));
+        assert_eq!(second.partial_files, first.partial_files);
+        assert!(!second.graph.find_by_name("good").is_empty());
+    }
 }
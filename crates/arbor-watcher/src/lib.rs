@@ -4,11 +4,23 @@
 //! - Walking directories to find source files
 //! - Watching for changes
 //! - Triggering incremental re-indexing
+//! - Reparsing modified files incrementally
 //!
 //! It respects .gitignore and other ignore patterns.
 
+mod churn;
+mod incremental;
 mod indexer;
+mod live;
+mod root;
 mod watcher;
 
-pub use indexer::{index_directory, IndexOptions, IndexResult};
+pub use churn::git_churn;
+pub use incremental::IncrementalParser;
+pub use indexer::{
+    bench_parse, dry_run_directory, index_directory, index_file_list, parse_outliers, DryRunResult,
+    IndexOptions, IndexResult, ParseBench, VENDORED_DIRS,
+};
+pub use live::{LiveIndex, LiveUpdate};
+pub use root::find_project_root;
 pub use watcher::{FileChange, FileWatcher};
@@ -0,0 +1,251 @@
+//! A graph kept current from watcher events.
+//!
+//! Modified and deleted files are spliced into the graph, reparsing with
+//! `IncrementalParser`, instead of walking and re-indexing the whole tree.
+//! Renamed files with unchanged content keep their nodes and edges, re-keyed
+//! to the new path. New files are left to a full re-index, since only the
+//! walk knows whether they're ignored or vendored.
+
+use crate::incremental::IncrementalParser;
+use crate::watcher::FileChange;
+use arbor_core::CodeNode;
+use arbor_graph::ArborGraph;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// What `LiveIndex::apply` did with a batch of changes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LiveUpdate {
+    /// Files reparsed in place.
+    pub reparsed: usize,
+    /// Files whose nodes were removed.
+    pub removed: usize,
+    /// Files renamed with unchanged content, whose nodes were re-keyed.
+    pub renamed: usize,
+    /// Files that failed to reparse, with the error. Their old nodes stay.
+    pub errors: Vec<(String, String)>,
+    /// A change needs a full re-index; pass its graph to `reset`.
+    pub needs_reindex: bool,
+}
+
+/// An index of one directory, updated file by file.
+pub struct LiveIndex {
+    /// Root as given to the initial index, so paths match its file keys.
+    root: PathBuf,
+    /// Canonical root, for events reported with absolute paths.
+    canonical_root: Option<PathBuf>,
+    graph: ArborGraph,
+    parser: IncrementalParser,
+}
+
+impl LiveIndex {
+    /// Starts from `graph`, an `index_directory` of `root`.
+    pub fn new(root: &Path, graph: ArborGraph) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().ok(),
+            graph,
+            parser: IncrementalParser::new(),
+        }
+    }
+
+    /// The current graph.
+    pub fn graph(&self) -> &ArborGraph {
+        &self.graph
+    }
+
+    /// Replaces the graph after a full re-index.
+    pub fn reset(&mut self, graph: ArborGraph) {
+        self.graph = graph;
+    }
+
+    /// Applies a batch of watcher events.
+    ///
+    /// Stops at the first change that needs a full re-index and reports it
+    /// in `needs_reindex`; the re-index covers the rest of the batch.
+    pub fn apply(&mut self, changes: impl IntoIterator<Item = FileChange>) -> LiveUpdate {
+        let mut update = LiveUpdate::default();
+        // New nodes per changed file; None removes the file
+        let mut changed: HashMap<String, Option<Vec<CodeNode>>> = HashMap::new();
+
+        for change in changes {
+            let change = self.walk_change(change);
+            match &change {
+                FileChange::Modified(path) => {
+                    let file = path.to_string_lossy().to_string();
+                    if !self.is_indexed(&file) && !changed.contains_key(&file) {
+                        update.needs_reindex = true;
+                        break;
+                    }
+                    match self.parser.handle(&change) {
+                        Some(Ok(nodes)) => {
+                            changed.insert(file, Some(nodes));
+                        }
+                        Some(Err(e)) => update.errors.push((file, e.to_string())),
+                        None => {}
+                    }
+                }
+                FileChange::Deleted(path) => {
+                    let file = path.to_string_lossy().to_string();
+                    if self.is_indexed(&file) || changed.contains_key(&file) {
+                        self.parser.handle(&change);
+                        changed.insert(file, None);
+                    }
+                }
+                FileChange::Renamed(from, to) => {
+                    let (from, to) = (
+                        from.to_string_lossy().to_string(),
+                        to.to_string_lossy().to_string(),
+                    );
+                    let pending = changed.contains_key(&from);
+                    if !self.is_indexed(&from) && !pending {
+                        update.needs_reindex = true;
+                        break;
+                    }
+                    // Nodes still waiting to be spliced in carry the old path
+                    match self.parser.handle(&change) {
+                        None if !pending => {
+                            self.graph.rename_file(&from, &to);
+                            update.renamed += 1;
+                        }
+                        result => {
+                            let path = PathBuf::from(&to);
+                            match result.unwrap_or_else(|| self.parser.reparse(&path)) {
+                                Ok(nodes) => {
+                                    changed.insert(to, Some(nodes));
+                                }
+                                Err(e) => update.errors.push((to, e.to_string())),
+                            }
+                            changed.insert(from, None);
+                        }
+                    }
+                }
+                FileChange::Created(_) => {
+                    update.needs_reindex = true;
+                    break;
+                }
+            }
+        }
+
+        if changed.is_empty() || update.needs_reindex {
+            return update;
+        }
+        update.reparsed = changed.values().filter(|nodes| nodes.is_some()).count();
+        update.removed = changed.len() - update.reparsed;
+
+        // Only edges that touch the changed files are resolved again
+        self.graph.update_files(changed);
+
+        update
+    }
+
+    fn is_indexed(&self, file: &str) -> bool {
+        !self.graph.find_by_file(file).is_empty()
+    }
+
+    /// Spells an event's paths the way the directory walk does.
+    fn walk_change(&self, change: FileChange) -> FileChange {
+        match change {
+            FileChange::Created(path) => FileChange::Created(self.walk_path(path)),
+            FileChange::Modified(path) => FileChange::Modified(self.walk_path(path)),
+            FileChange::Deleted(path) => FileChange::Deleted(self.walk_path(path)),
+            FileChange::Renamed(from, to) => {
+                FileChange::Renamed(self.walk_path(from), self.walk_path(to))
+            }
+        }
+    }
+
+    /// Rewrites an absolute event path under the canonical root as `root`
+    /// joined with the relative part, e.g. `./src/lib.rs`.
+    fn walk_path(&self, path: PathBuf) -> PathBuf {
+        if path.starts_with(&self.root) {
+            return path;
+        }
+        match self
+            .canonical_root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+        {
+            Some(relative) => self.root.join(relative),
+            None => path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index_directory, IndexOptions};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_modified_file_is_spliced_in() {
+        let dir = tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.rs"), dir.path().join("b.rs"));
+        fs::write(&a, "pub fn a() {}\n").unwrap();
+        fs::write(&b, "pub fn b() { a(); }\n").unwrap();
+
+        let result = index_directory(dir.path(), IndexOptions::default()).unwrap();
+        let mut live = LiveIndex::new(dir.path(), result.graph);
+        let edges = live.graph().edge_count();
+        assert!(edges > 0);
+
+        fs::write(&a, "pub fn a() {}\n\npub fn c() {}\n").unwrap();
+        let update = live.apply([FileChange::Modified(a.clone())]);
+        assert_eq!(update.reparsed, 1);
+        assert!(!update.needs_reindex);
+        assert_eq!(live.graph().find_by_name("c").len(), 1);
+        // b -> a is resolved again against the new nodes
+        assert_eq!(live.graph().edge_count(), edges);
+
+        let update = live.apply([FileChange::Deleted(b.clone())]);
+        assert_eq!(update.removed, 1);
+        assert!(live.graph().find_by_name("b").is_empty());
+        assert!(live.graph().edge_count() < edges);
+
+        // New files go through the walk's ignore rules
+        let update = live.apply([FileChange::Created(dir.path().join("d.rs"))]);
+        assert!(update.needs_reindex);
+    }
+
+    #[test]
+    fn test_rename_keeps_nodes_and_edges() {
+        let dir = tempdir().unwrap();
+        let (a, b) = (dir.path().join("a.rs"), dir.path().join("b.rs"));
+        fs::write(&a, "pub fn a() {}\n").unwrap();
+        fs::write(&b, "pub fn b() { a(); }\n").unwrap();
+
+        let result = index_directory(dir.path(), IndexOptions::default()).unwrap();
+        let mut live = LiveIndex::new(dir.path(), result.graph);
+        // The parser needs the file's last source to tell a pure rename
+        live.apply([FileChange::Modified(b.clone())]);
+        let edges = live.graph().edge_count();
+
+        let moved = dir.path().join("caller.rs");
+        fs::rename(&b, &moved).unwrap();
+        let update = live.apply([FileChange::Renamed(b.clone(), moved.clone())]);
+        assert_eq!(update.renamed, 1);
+        assert_eq!(update.reparsed, 0);
+
+        let node = live.graph().find_by_name("b")[0];
+        assert_eq!(node.file, moved.to_string_lossy());
+        assert_eq!(live.graph().edge_count(), edges);
+        assert!(live.graph().find_by_file(&b.to_string_lossy()).is_empty());
+
+        // A rename with an edit reparses under the new path
+        let renamed = dir.path().join("renamed.rs");
+        fs::rename(&moved, &renamed).unwrap();
+        fs::write(&renamed, "pub fn b() { a(); }\n\npub fn e() {}\n").unwrap();
+        let update = live.apply([FileChange::Renamed(moved.clone(), renamed.clone())]);
+        assert_eq!((update.renamed, update.reparsed, update.removed), (0, 1, 1));
+        assert_eq!(
+            live.graph().find_by_name("e")[0].file,
+            renamed.to_string_lossy()
+        );
+        assert!(live
+            .graph()
+            .find_by_file(&moved.to_string_lossy())
+            .is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! Project root detection.
+//!
+//! Commands like `query` and `explain` index "the project", but users
+//! run them from wherever their shell happens to be. Indexing only the
+//! current subdirectory gives a partial graph, so we walk up to the
+//! directory that looks like the project root instead.
+
+use std::path::{Path, PathBuf};
+
+/// Markers that identify a repository or Arbor project root.
+const ROOT_MARKERS: &[&str] = &[".arbor", ".git"];
+
+/// Manifest files that identify a package root.
+const MANIFEST_FILES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "pom.xml",
+    "build.gradle",
+    "pubspec.yaml",
+];
+
+/// Finds the project root for `start`.
+///
+/// Prefers the nearest ancestor holding `.arbor/` or `.git/`, since
+/// workspaces often nest one manifest per package below a single
+/// repository. Falls back to the nearest ancestor with a manifest file,
+/// then to `start` itself.
+pub fn find_project_root(start: &Path) -> PathBuf {
+    let has_any = |dir: &Path, names: &[&str]| names.iter().any(|name| dir.join(name).exists());
+
+    start
+        .ancestors()
+        .find(|dir| has_any(dir, ROOT_MARKERS))
+        .or_else(|| start.ancestors().find(|dir| has_any(dir, MANIFEST_FILES)))
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index_directory, IndexOptions};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_root_found_from_subdirectory() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path();
+        fs::create_dir(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("crates/api/src")).unwrap();
+        fs::create_dir_all(repo.join("crates/db/src")).unwrap();
+        // A nested manifest must not stop the walk at the package
+        fs::write(repo.join("crates/api/Cargo.toml"), "[package]\n").unwrap();
+        fs::write(repo.join("crates/api/src/lib.rs"), "fn handle() {}\n").unwrap();
+        fs::write(repo.join("crates/db/src/lib.rs"), "fn query() {}\n").unwrap();
+
+        let root = find_project_root(&repo.join("crates/api/src"));
+        assert_eq!(root, repo);
+
+        let result = index_directory(&root, IndexOptions::default()).unwrap();
+        assert_eq!(result.files_indexed, 2);
+        assert_eq!(result.graph.find_by_name("query").len(), 1);
+    }
+
+    #[test]
+    fn test_manifest_fallback() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("app");
+        fs::create_dir_all(package.join("src")).unwrap();
+        fs::write(package.join("package.json"), "{}").unwrap();
+
+        assert_eq!(find_project_root(&package.join("src")), package);
+    }
+}
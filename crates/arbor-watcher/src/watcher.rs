@@ -3,7 +3,8 @@
 //! Uses the notify crate to watch for file changes and trigger
 //! incremental re-indexing.
 
-use notify::{Event, RecursiveMode, Watcher};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver};
 use std::time::Duration;
@@ -15,6 +16,34 @@ pub enum FileChange {
     Created(PathBuf),
     Modified(PathBuf),
     Deleted(PathBuf),
+    /// A rename with both ends known, as (from, to). Backends that report
+    /// the two halves separately produce Deleted + Created instead.
+    Renamed(PathBuf, PathBuf),
+}
+
+/// Maps a rename event that carries both paths to a `FileChange`.
+///
+/// Renames into or out of a supported file type degrade to a creation or
+/// deletion, since only one side has nodes in the graph.
+fn rename_change(event: &Event) -> Option<FileChange> {
+    if event.kind != EventKind::Modify(ModifyKind::Name(RenameMode::Both)) {
+        return None;
+    }
+    let [from, to] = event.paths.as_slice() else {
+        return None;
+    };
+    let supported = |path: &Path| {
+        arbor_core::languages::is_supported(path.extension().and_then(|e| e.to_str()).unwrap_or(""))
+    };
+
+    let change = match (supported(from), supported(to)) {
+        (true, true) => FileChange::Renamed(from.clone(), to.clone()),
+        (true, false) => FileChange::Deleted(from.clone()),
+        (false, true) => FileChange::Created(to.clone()),
+        (false, false) => return None,
+    };
+    debug!("File renamed: {} -> {}", from.display(), to.display());
+    Some(change)
 }
 
 /// Watches a directory for file changes.
@@ -35,6 +64,13 @@ impl FileWatcher {
         let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
             match res {
                 Ok(event) => {
+                    if let Some(change) = rename_change(&event) {
+                        if tx.send(change).is_err() {
+                            warn!("Failed to send file change event");
+                        }
+                        return;
+                    }
+
                     for path in event.paths {
                         // Only care about supported source files
                         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
@@ -44,15 +80,20 @@ impl FileWatcher {
                         }
 
                         let change = match event.kind {
-                            notify::EventKind::Create(_) => {
+                            EventKind::Create(_)
+                            | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
                                 debug!("File created: {}", path.display());
                                 Some(FileChange::Created(path))
                             }
-                            notify::EventKind::Modify(_) => {
+                            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                                debug!("File deleted: {}", path.display());
+                                Some(FileChange::Deleted(path))
+                            }
+                            EventKind::Modify(_) => {
                                 debug!("File modified: {}", path.display());
                                 Some(FileChange::Modified(path))
                             }
-                            notify::EventKind::Remove(_) => {
+                            EventKind::Remove(_) => {
                                 debug!("File deleted: {}", path.display());
                                 Some(FileChange::Deleted(path))
                             }
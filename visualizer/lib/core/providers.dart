@@ -28,6 +28,9 @@ class GraphState {
   // Stats from server
   final int fileCount;
 
+  /// Bumped by the `f` shortcut; each change centers the selected node
+  final int focusRequest;
+
   // Settings
   final bool isLowGpuMode;
   final bool isFollowMode;
@@ -41,6 +44,7 @@ class GraphState {
     this.selectedNodeId,
     this.spotlightNodeId,
     this.fileCount = 0,
+    this.focusRequest = 0,
     this.isLowGpuMode = false,
     this.isFollowMode = true,
   });
@@ -54,6 +58,7 @@ class GraphState {
     String? selectedNodeId,
     String? spotlightNodeId,
     int? fileCount,
+    int? focusRequest,
     bool? isLowGpuMode,
     bool? isFollowMode,
   }) {
@@ -66,12 +71,82 @@ class GraphState {
       selectedNodeId: selectedNodeId ?? this.selectedNodeId,
       spotlightNodeId: spotlightNodeId,
       fileCount: fileCount ?? this.fileCount,
+      focusRequest: focusRequest ?? this.focusRequest,
       isLowGpuMode: isLowGpuMode ?? this.isLowGpuMode,
       isFollowMode: isFollowMode ?? this.isFollowMode,
     );
   }
 }
 
+/// One keyboard step along call edges.
+enum NavStep {
+  /// First caller of the selected node.
+  caller,
+
+  /// First callee of the selected node.
+  callee,
+
+  /// Next node in the list the selection was picked from.
+  next,
+
+  /// Previous node in that list.
+  previous,
+}
+
+/// The selected node and the callers or callees it was picked from.
+class NavSelection {
+  final String nodeId;
+
+  /// Ids of the neighbor list, sorted by name; empty when the node was
+  /// selected by searching or clicking.
+  final List<String> siblings;
+  final int index;
+
+  const NavSelection(this.nodeId, {this.siblings = const [], this.index = 0});
+}
+
+/// Applies [step] to [current] using the `calls` edges of the graph.
+///
+/// Returns null when there is nowhere to go.
+NavSelection? stepSelection(
+  List<GraphNode> nodes,
+  List<GraphEdge> edges,
+  NavSelection current,
+  NavStep step,
+) {
+  switch (step) {
+    case NavStep.caller:
+    case NavStep.callee:
+      final toCaller = step == NavStep.caller;
+      final names = {for (final n in nodes) n.id: n.name};
+      final ids = edges
+          .where((e) => e.kind == 'calls')
+          .where((e) => (toCaller ? e.target : e.source) == current.nodeId)
+          .map((e) => toCaller ? e.source : e.target)
+          .where(names.containsKey)
+          .toSet()
+          .toList()
+        ..sort((a, b) {
+          final byName = names[a]!.compareTo(names[b]!);
+          return byName != 0 ? byName : a.compareTo(b);
+        });
+      if (ids.isEmpty) return null;
+      return NavSelection(ids.first, siblings: ids);
+    case NavStep.next:
+    case NavStep.previous:
+      final count = current.siblings.length;
+      if (count < 2) return null;
+      final index = step == NavStep.next
+          ? (current.index + 1) % count
+          : (current.index + count - 1) % count;
+      return NavSelection(
+        current.siblings[index],
+        siblings: current.siblings,
+        index: index,
+      );
+  }
+}
+
 /// Provider for graph state management.
 class GraphNotifier extends StateNotifier<GraphState> {
   final WebSocketService _wsService;
@@ -95,6 +170,29 @@ class GraphNotifier extends StateNotifier<GraphState> {
     state = state.copyWith(selectedNodeId: id);
   }
 
+  /// NavSelection as of the last keyboard step.
+  NavSelection? _selection;
+
+  /// Moves the selection along a call edge (arrow keys).
+  void step(NavStep step) {
+    final selected = state.selectedNodeId;
+    if (selected == null) return;
+    // Selecting by search or click starts a fresh list of siblings
+    final current = _selection?.nodeId == selected
+        ? _selection!
+        : NavSelection(selected);
+    final next = stepSelection(state.nodes, state.edges, current, step);
+    if (next == null) return;
+    _selection = next;
+    state = state.copyWith(selectedNodeId: next.nodeId);
+  }
+
+  /// Centers the camera on the selected node (`f`).
+  void focusSelected() {
+    if (state.selectedNodeId == null) return;
+    state = state.copyWith(focusRequest: state.focusRequest + 1);
+  }
+
   void _handleMessage(BroadcastMessage message) {
     if (message is GraphUpdate) {
       if (message.nodes != null && message.edges != null) {
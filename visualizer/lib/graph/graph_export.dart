@@ -0,0 +1,25 @@
+import 'dart:io';
+import 'dart:ui' as ui;
+
+import 'package:flutter/rendering.dart';
+
+import 'graph_widget.dart';
+
+/// Captures the graph exactly as it is on screen (current zoom, pan and
+/// culled nodes) and writes it to [path] as PNG.
+Future<void> exportGraphPng(String path, {double pixelRatio = 2.0}) async {
+  final boundary = graphBoundaryKey.currentContext?.findRenderObject()
+      as RenderRepaintBoundary?;
+  if (boundary == null) {
+    throw StateError('Graph is not on screen');
+  }
+
+  final image = await boundary.toImage(pixelRatio: pixelRatio);
+  final bytes = await image.toByteData(format: ui.ImageByteFormat.png);
+  image.dispose();
+  if (bytes == null) {
+    throw StateError('Failed to encode PNG');
+  }
+
+  await File(path).writeAsBytes(bytes.buffer.asUint8List());
+}
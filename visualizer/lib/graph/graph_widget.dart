@@ -6,6 +6,9 @@ import '../core/providers.dart';
 import 'force_layout.dart';
 import 'graph_painter.dart';
 
+/// Wraps the painted graph so it can be captured for image export.
+final GlobalKey graphBoundaryKey = GlobalKey();
+
 /// Interactive graph widget.
 class GraphWidget extends ConsumerStatefulWidget {
   const GraphWidget({super.key});
@@ -36,6 +39,7 @@ class _GraphWidgetState extends ConsumerState<GraphWidget>
   GraphNode? _draggedNode;
   bool _hasCentered = false;
   String? _lastSpotlightId;
+  int _lastFocusRequest = 0;
 
   @override
   void initState() {
@@ -111,6 +115,17 @@ class _GraphWidgetState extends ConsumerState<GraphWidget>
       });
     }
     
+    // Keyboard focus (`f`): center the selected node
+    if (state.focusRequest != _lastFocusRequest) {
+      _lastFocusRequest = state.focusRequest;
+      final selected = state.selectedNodeId;
+      if (selected != null) {
+        WidgetsBinding.instance.addPostFrameCallback((_) {
+          _animateToNode(selected, state.nodes, MediaQuery.of(context).size);
+        });
+      }
+    }
+
     return Stack(
       children: [
         GestureDetector(
@@ -155,17 +170,20 @@ class _GraphWidgetState extends ConsumerState<GraphWidget>
           onTapUp: (details) => _handleTap(details, state),
           child: MouseRegion(
             onHover: (event) => _handleHover(event, state),
-            child: CustomPaint(
-              size: Size.infinite,
-              painter: GraphPainter(
-                nodes: state.nodes,
-                edges: state.edges,
-                selectedNodeId: state.selectedNodeId,
-                hoveredNodeId: _hoveredNodeId,
-                offset: _offset,
-                scale: _scale,
-                isLowGpuMode: state.isLowGpuMode,
-                interactionSpeed: _interactionSpeed,
+            child: RepaintBoundary(
+              key: graphBoundaryKey,
+              child: CustomPaint(
+                size: Size.infinite,
+                painter: GraphPainter(
+                  nodes: state.nodes,
+                  edges: state.edges,
+                  selectedNodeId: state.selectedNodeId,
+                  hoveredNodeId: _hoveredNodeId,
+                  offset: _offset,
+                  scale: _scale,
+                  isLowGpuMode: state.isLowGpuMode,
+                  interactionSpeed: _interactionSpeed,
+                ),
               ),
             ),
           ),
@@ -1,8 +1,10 @@
 import 'package:flutter/material.dart';
+import 'package:flutter/services.dart';
 import 'package:flutter_riverpod/flutter_riverpod.dart';
 
-import '../core/providers.dart';
+import '../core/providers.dart' hide FocusNode;
 import '../core/theme.dart';
+import '../graph/graph_export.dart';
 import '../graph/graph_widget.dart';
 
 /// The main visualization view - the Logic Forest.
@@ -19,81 +21,127 @@ class ForestView extends ConsumerStatefulWidget {
 class _ForestViewState extends ConsumerState<ForestView> {
   // Search
   final _searchController = TextEditingController();
+  final _searchFocus = FocusNode();
 
   @override
   void dispose() {
     _searchController.dispose();
+    _searchFocus.dispose();
     super.dispose();
   }
 
+  /// Keyboard shortcuts: `/` search, arrows along call edges, `f` focus.
+  ///
+  /// Ignored while typing in the search field.
+  KeyEventResult _handleKey(FocusNode node, KeyEvent event) {
+    if (event is! KeyDownEvent || _searchFocus.hasFocus) {
+      return KeyEventResult.ignored;
+    }
+    final notifier = ref.read(graphProvider.notifier);
+    final key = event.logicalKey;
+    if (key == LogicalKeyboardKey.slash) {
+      _searchFocus.requestFocus();
+    } else if (key == LogicalKeyboardKey.arrowLeft) {
+      notifier.step(NavStep.caller);
+    } else if (key == LogicalKeyboardKey.arrowRight) {
+      notifier.step(NavStep.callee);
+    } else if (key == LogicalKeyboardKey.arrowDown) {
+      notifier.step(NavStep.next);
+    } else if (key == LogicalKeyboardKey.arrowUp) {
+      notifier.step(NavStep.previous);
+    } else if (key == LogicalKeyboardKey.keyF) {
+      notifier.focusSelected();
+    } else {
+      return KeyEventResult.ignored;
+    }
+    return KeyEventResult.handled;
+  }
+
   void _handleSearch(String query) {
     if (query.isEmpty) return;
     ref.read(graphProvider.notifier).search(query);
+    // Hand the keyboard back to the shortcuts
+    _searchFocus.unfocus(disposition: UnfocusDisposition.previouslyFocusedChild);
+  }
+
+  Future<void> _exportImage() async {
+    final path = 'arbor-graph-${DateTime.now().millisecondsSinceEpoch}.png';
+    final messenger = ScaffoldMessenger.of(context);
+    try {
+      await exportGraphPng(path);
+      messenger.showSnackBar(SnackBar(content: Text('Exported $path')));
+    } catch (e) {
+      messenger.showSnackBar(SnackBar(content: Text('Export failed: $e')));
+    }
   }
 
   @override
   Widget build(BuildContext context) {
     final state = ref.watch(graphProvider);
 
-    return Scaffold(
-      backgroundColor: ArborTheme.background,
-      body: Stack(
-        children: [
-          // Background gradient for depth
-          _buildBackground(),
+    return Focus(
+      autofocus: true,
+      onKeyEvent: _handleKey,
+      child: Scaffold(
+        backgroundColor: ArborTheme.background,
+        body: Stack(
+          children: [
+            // Background gradient for depth
+            _buildBackground(),
 
-          // The Interactive Graph
-          const GraphWidget(),
+            // The Interactive Graph
+            const GraphWidget(),
 
-          // Top bar
-          _buildTopBar(state),
+            // Top bar
+            _buildTopBar(state),
 
-          // Node inspector (right panel)
-          if (state.selectedNodeId != null) _buildInspector(state),
+            // Node inspector (right panel)
+            if (state.selectedNodeId != null) _buildInspector(state),
 
-          // Status bar
-          _buildStatusBar(state),
+            // Status bar
+            _buildStatusBar(state),
 
-          // Settings Panel
-          _buildSettingsPanel(state),
+            // Settings Panel
+            _buildSettingsPanel(state),
 
-          // Loading overlay
-          if (state.isLoading)
-            Container(
-              color: ArborTheme.background.withOpacity(0.7),
-              child: const Center(
-                child: CircularProgressIndicator(
-                  color: ArborTheme.function,
+            // Loading overlay
+            if (state.isLoading)
+              Container(
+                color: ArborTheme.background.withOpacity(0.7),
+                child: const Center(
+                  child: CircularProgressIndicator(
+                    color: ArborTheme.function,
+                  ),
                 ),
               ),
-            ),
           
-          // Error overlay
-          if (state.error != null)
-             Positioned(
-               top: 80,
-               left: 20,
-               right: 20,
-               child: Material(
-                 color: Colors.red.withOpacity(0.9),
-                 borderRadius: BorderRadius.circular(8),
-                 child: Padding(
-                   padding: const EdgeInsets.all(12),
-                   child: Row(
-                     children: [
-                       const Icon(Icons.error_outline, color: Colors.white),
-                       const SizedBox(width: 12),
-                       Expanded(child: Text(state.error!, style: const TextStyle(color: Colors.white))),
-                       IconButton(
-                         icon: const Icon(Icons.close, color: Colors.white),
-                         onPressed: () => ref.read(graphProvider.notifier).connect(), // Retry
-                       )
-                     ],
+            // Error overlay
+            if (state.error != null)
+               Positioned(
+                 top: 80,
+                 left: 20,
+                 right: 20,
+                 child: Material(
+                   color: Colors.red.withOpacity(0.9),
+                   borderRadius: BorderRadius.circular(8),
+                   child: Padding(
+                     padding: const EdgeInsets.all(12),
+                     child: Row(
+                       children: [
+                         const Icon(Icons.error_outline, color: Colors.white),
+                         const SizedBox(width: 12),
+                         Expanded(child: Text(state.error!, style: const TextStyle(color: Colors.white))),
+                         IconButton(
+                           icon: const Icon(Icons.close, color: Colors.white),
+                           onPressed: () => ref.read(graphProvider.notifier).connect(), // Retry
+                         )
+                       ],
+                     ),
                    ),
                  ),
                ),
-             ),
-        ],
+          ],
+        ),
       ),
     );
   }
@@ -163,6 +211,7 @@ class _ForestViewState extends ConsumerState<ForestView> {
                 height: 40,
                 child: TextField(
                   controller: _searchController,
+                  focusNode: _searchFocus,
                   onSubmitted: _handleSearch,
                   decoration: InputDecoration(
                     hintText: 'Search for functions, classes...',
@@ -184,7 +233,16 @@ class _ForestViewState extends ConsumerState<ForestView> {
                 ),
               ),
             ),
-            const SizedBox(width: 32),
+            const SizedBox(width: 16),
+
+            // Export the current view as an image
+            IconButton(
+              icon: const Icon(Icons.image_outlined, size: 20),
+              color: ArborTheme.textMuted,
+              tooltip: 'Export image',
+              onPressed: _exportImage,
+            ),
+            const SizedBox(width: 16),
 
             // Connection indicator
             Container(
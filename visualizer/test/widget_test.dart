@@ -262,4 +262,44 @@ void main() {
       expect(status.currentFile, 'lib.rs');
     });
   });
+
+  group('stepSelection', () {
+    protocol.GraphNode node(String id) => protocol.GraphNode(
+          id: id,
+          name: id,
+          kind: 'function',
+          file: 'lib.rs',
+          lineStart: 1,
+          lineEnd: 2,
+        );
+    final nodes = [node('main'), node('parse'), node('check'), node('emit')];
+    final edges = [
+      for (final callee in ['parse', 'check', 'emit'])
+        protocol.GraphEdge(source: 'main', target: callee, kind: 'calls'),
+      protocol.GraphEdge(source: 'check', target: 'parse', kind: 'calls'),
+    ];
+
+    test('next callee steps through callees by name', () {
+      final callee = stepSelection(
+          nodes, edges, const NavSelection('main'), NavStep.callee)!;
+      expect(callee.nodeId, 'check');
+
+      final next = stepSelection(nodes, edges, callee, NavStep.next)!;
+      expect(next.nodeId, 'emit');
+      expect(stepSelection(nodes, edges, callee, NavStep.previous)!.nodeId,
+          'parse');
+    });
+
+    test('caller steps up and returns null when there is none', () {
+      final caller = stepSelection(
+          nodes, edges, const NavSelection('parse'), NavStep.caller)!;
+      expect(caller.nodeId, 'check');
+      expect(stepSelection(nodes, edges, caller, NavStep.next)!.nodeId, 'main');
+
+      expect(
+        stepSelection(nodes, edges, const NavSelection('main'), NavStep.caller),
+        isNull,
+      );
+    });
+  });
 }